@@ -0,0 +1,65 @@
+use anyhow::Result;
+use dotenv::dotenv;
+use std::env;
+
+use tavari_core::services::{benchmark, food_lookup, kpi, notifier::Notifier, Database};
+
+const USAGE: &str = "Kullanım: tavari <komut>
+
+Komutlar:
+  migrate                  Tabloları oluştur / migration'ları uygula (DATABASE_URL)
+  estimate <yemek>         Yerleşik besin tablosundan kalori tahmini (örn: estimate 2 simit ve çay)
+  kpi [--send]             Geçen haftanın KPI özetini yazdır (--send: arşivle ve operatörlere e-posta at)
+  benchmark [YYYY-MM-DD]   Anonim kullanıcı ortalamalarını hesapla (varsayılan: dün)";
+
+async fn connect() -> Result<Database> {
+    let database_url = env::var("DATABASE_URL").map_err(|_| anyhow::anyhow!("DATABASE_URL must be set"))?;
+    Database::new(&database_url).await
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::init();
+    dotenv().ok();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    let today = chrono::Utc::now().date_naive();
+
+    match args.first().map(String::as_str) {
+        Some("migrate") => {
+            connect().await?;
+            println!("✅ Veritabanı şeması güncel");
+        }
+        Some("estimate") if args.len() > 1 => {
+            let description = args[1..].join(" ");
+            match food_lookup::estimate(&description) {
+                Some(estimate) => println!("{}\n🔥 Toplam: ~{:.0} kcal", estimate.description(), estimate.calories),
+                None => println!("❓ Tabloda eşleşen yemek yok: {}", description),
+            }
+        }
+        Some("kpi") => {
+            let db = connect().await?;
+            if args.iter().any(|a| a == "--send") {
+                kpi::send_weekly_report(&db, &Notifier::from_env(), today).await?;
+                println!("📧 KPI raporu arşivlendi ve gönderildi");
+            } else {
+                let snapshot = kpi::build_weekly_snapshot(&db, kpi::previous_week_start(today)).await?;
+                println!("{:#?}", snapshot);
+            }
+        }
+        Some("benchmark") => {
+            let date = match args.get(1) {
+                Some(date) => chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")?,
+                None => today - chrono::Duration::days(1),
+            };
+            benchmark::compute_daily_aggregates(&connect().await?, date).await?;
+            println!("📊 {} için benchmark hesaplandı", date);
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    }
+
+    Ok(())
+}
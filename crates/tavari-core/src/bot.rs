@@ -0,0 +1,190 @@
+use anyhow::Result;
+use std::sync::Arc;
+
+use crate::handlers::{MessageHandler, ReminderService};
+use crate::services::events::EventDispatcher;
+use crate::services::notifier::Notifier;
+use crate::services::openrouter::AiGateway;
+use crate::services::{Database, OpenRouterService, WhatsAppService};
+
+/// Default OpenRouter model (free vision model)
+pub const DEFAULT_AI_MODEL: &str = "nvidia/nemotron-nano-12b-v2-vl:free";
+
+/// Wires the nutrition engine for embedding in another application.
+///
+/// Only the database and a WhatsApp provider are required; everything else has a default:
+/// AI from `openrouter(..)` (or text-only mode without a key), event webhooks and operator
+/// email disabled, reminders enabled.
+///
+/// ```ignore
+/// let mut bot = BotBuilder::new()
+///     .database_url(&database_url)
+///     .openrouter(api_key, None)
+///     .whatsapp(whatsapp)
+///     .build()
+///     .await?;
+/// bot.start().await?;
+///
+/// // in your own axum handler:
+/// bot.message_handler.handle_message(&from, &text, false, None).await?;
+/// ```
+#[derive(Default)]
+pub struct BotBuilder {
+    database_url: Option<String>,
+    database: Option<Arc<Database>>,
+    ai: Option<Arc<OpenRouterService>>,
+    openrouter: Option<(String, String)>,
+    ai_gateway: Option<AiGateway>,
+    text_only: bool,
+    whatsapp: Option<Arc<dyn WhatsAppService>>,
+    events: Option<Arc<EventDispatcher>>,
+    notifier: Option<Arc<Notifier>>,
+    reminders: Option<bool>,
+}
+
+impl BotBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connect (and migrate) on `build()`
+    pub fn database_url(mut self, url: &str) -> Self {
+        self.database_url = Some(url.to_string());
+        self
+    }
+
+    /// Use an existing connection instead of `database_url`
+    pub fn database(mut self, db: Arc<Database>) -> Self {
+        self.database = Some(db);
+        self
+    }
+
+    /// OpenRouter credentials; `model` defaults to [`DEFAULT_AI_MODEL`]
+    pub fn openrouter(mut self, api_key: String, model: Option<String>) -> Self {
+        self.openrouter = Some((api_key, model.unwrap_or_else(|| DEFAULT_AI_MODEL.to_string())));
+        self
+    }
+
+    /// OpenAI-compatible endpoint for `openrouter(..)` (default: OpenRouter itself)
+    pub fn ai_gateway(mut self, gateway: AiGateway) -> Self {
+        self.ai_gateway = Some(gateway);
+        self
+    }
+
+    /// Use a preconfigured AI service instead of `openrouter(..)`
+    pub fn ai(mut self, ai: Arc<OpenRouterService>) -> Self {
+        self.ai = Some(ai);
+        self
+    }
+
+    /// Disable all AI calls (manual logging, reports and reminders keep working)
+    pub fn text_only(mut self, text_only: bool) -> Self {
+        self.text_only = text_only;
+        self
+    }
+
+    pub fn whatsapp(mut self, whatsapp: Arc<dyn WhatsAppService>) -> Self {
+        self.whatsapp = Some(whatsapp);
+        self
+    }
+
+    /// Outbound event webhooks (default: disabled)
+    pub fn events(mut self, events: Arc<EventDispatcher>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Operator email for KPI reports (default: disabled)
+    pub fn notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Scheduled meal/water reminders and daily summaries (default: on)
+    pub fn reminders(mut self, enabled: bool) -> Self {
+        self.reminders = Some(enabled);
+        self
+    }
+
+    pub async fn build(self) -> Result<Bot> {
+        let db = match (self.database, self.database_url) {
+            (Some(db), _) => db,
+            (None, Some(url)) => Arc::new(Database::new(&url).await?),
+            (None, None) => anyhow::bail!("BotBuilder: database or database_url is required"),
+        };
+
+        let whatsapp = self
+            .whatsapp
+            .ok_or_else(|| anyhow::anyhow!("BotBuilder: a WhatsApp provider is required"))?;
+
+        let ai = match (self.ai, self.openrouter) {
+            (Some(ai), _) => ai,
+            (None, Some((api_key, model))) => {
+                Arc::new(
+                    OpenRouterService::new(api_key, model)
+                        .with_gateway(self.ai_gateway.unwrap_or_default())
+                        .with_text_only(self.text_only),
+                )
+            }
+            (None, None) if self.text_only => {
+                Arc::new(OpenRouterService::new(String::new(), DEFAULT_AI_MODEL.to_string()).with_text_only(true))
+            }
+            (None, None) => anyhow::bail!("BotBuilder: openrouter(..) or ai(..) is required unless text_only(true)"),
+        };
+
+        let events = self.events.unwrap_or_else(|| Arc::new(EventDispatcher::disabled()));
+        let notifier = self
+            .notifier
+            .unwrap_or_else(|| Arc::new(Notifier::new(String::new(), None, String::new(), Vec::new())));
+
+        let message_handler = Arc::new(
+            MessageHandler::new(db.clone(), ai.clone(), whatsapp.clone(), events.clone())
+                .with_notifier(notifier.clone()),
+        );
+
+        let reminders = if self.reminders.unwrap_or(true) {
+            Some(ReminderService::new(db.clone(), whatsapp.clone(), notifier.clone(), ai.clone()).await?)
+        } else {
+            None
+        };
+
+        Ok(Bot {
+            db,
+            ai,
+            whatsapp,
+            events,
+            notifier,
+            message_handler,
+            reminders,
+        })
+    }
+}
+
+/// A wired nutrition bot: feed incoming messages to `message_handler`, call `start()` once
+pub struct Bot {
+    pub db: Arc<Database>,
+    pub ai: Arc<OpenRouterService>,
+    pub whatsapp: Arc<dyn WhatsAppService>,
+    pub events: Arc<EventDispatcher>,
+    pub notifier: Arc<Notifier>,
+    pub message_handler: Arc<MessageHandler>,
+    reminders: Option<ReminderService>,
+}
+
+impl Bot {
+    /// Start background jobs (reminders, summaries, reports)
+    pub async fn start(&mut self) -> Result<()> {
+        if let Some(reminders) = self.reminders.as_mut() {
+            reminders.start().await?;
+        }
+        Ok(())
+    }
+
+    pub async fn shutdown(&mut self) -> Result<()> {
+        if let Some(reminders) = self.reminders.as_mut() {
+            reminders.stop().await?;
+        }
+        self.db.flush_conversation_log().await;
+        Ok(())
+    }
+}
@@ -0,0 +1,2531 @@
+use anyhow::Result;
+use chrono::{Utc, Timelike};
+use std::sync::Arc;
+
+use crate::models::{ConversationDirection, EmailReportFrequency, EmailVerification, GoalChangeSource, Language, Meal, MealType, MealTypeCorrection, MessageType, UnitSystem, User, WaterLog};
+use crate::services::events::{BotEvent, EventDispatcher, GoalKind};
+use crate::services::allowlist;
+use crate::services::benchmark;
+use crate::services::circuit_breaker::BreakerState;
+use crate::services::command_hints::{self, SUGGESTION_PREFIX};
+use crate::services::country_defaults;
+use crate::services::feedback::{self, NPS_PENDING};
+use crate::services::goal_review::{GoalReviewOption, GOAL_REVIEW_PENDING};
+use crate::services::email_report;
+use crate::services::food_lookup;
+use crate::services::help_catalog::{self, HelpTopic};
+use crate::services::image_screening;
+use crate::services::image_store;
+use crate::services::notifier::Notifier;
+use crate::services::meal_budget::{self, MealBudget};
+use crate::services::meal_learning::{self, MealSchedule};
+use crate::services::nutrition_fields;
+use crate::services::snacks::{self, SnackInsights};
+use crate::services::units::{self, format_water};
+use crate::services::whatsapp::BestEffortWhatsAppClient;
+use crate::services::openrouter::CalorieInfo;
+use crate::services::summary_sections;
+use crate::services::{Database, OpenRouterService, UserIntent, WhatsAppService};
+use crate::handlers::onboarding::{water_interval_label, WIZARD_STEP_PREFIX};
+use crate::handlers::OnboardingHandler;
+
+/// pending_command öneki: "slot:<meal_id>:<öğün>" - ara öğünü dolu ana öğüne ekleme seçimi
+const SLOT_CHOICE_PREFIX: &str = "slot:";
+
+/// pending_command: `fotolari sil` onayı bekleniyor
+const DELETE_PHOTOS_PENDING: &str = "delete_photos";
+
+/// AI özellikleri kapalıyken (TEXT_ONLY_MODE) yanıtlara eklenen açıklama
+const TEXT_ONLY_NOTICE: &str = "ℹ️ AI analizi şu an geçici olarak kapalı. Manuel kayıt, raporlar ve hatırlatmalar çalışmaya devam ediyor.";
+
+/// Günlük fotoğraf analizi limiti (kullanıcı başına)
+const DAILY_IMAGE_LIMIT: i64 = 20;
+
+/// `suaraligi` üst sınırı (saat); daha seyrek hatırlatma günde 2-3 mesaja iner
+const MAX_WATER_INTERVAL_HOURS: i32 = 6;
+
+pub struct MessageHandler {
+    db: Arc<Database>,
+    openai: Arc<OpenRouterService>,  // OpenRouter kullanıyoruz (OpenAI uyumlu)
+    whatsapp: Arc<dyn WhatsAppService>,
+    events: Arc<EventDispatcher>,
+    notifier: Option<Arc<Notifier>>,
+}
+
+/// Son kaç konuşma kaydı hata bildirimine eklenir
+const BUG_REPORT_CONTEXT_MESSAGES: i32 = 10;
+
+impl MessageHandler {
+    pub fn new(
+        db: Arc<Database>,
+        openai: Arc<OpenRouterService>,
+        whatsapp: Arc<dyn WhatsAppService>,
+        events: Arc<EventDispatcher>,
+    ) -> Self {
+        Self {
+            db,
+            openai,
+            whatsapp,
+            events,
+            notifier: None,
+        }
+    }
+
+    /// Operators are emailed about `hata bildir` reports (without a notifier they are only stored)
+    pub fn with_notifier(mut self, notifier: Arc<Notifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Emit GoalReached when today's total crosses the goal with this log
+    fn emit_goal_crossing(&self, phone: &str, goal: GoalKind, target: i32, before: f64, after: f64) {
+        if before < target as f64 && after >= target as f64 {
+            self.events.emit(BotEvent::GoalReached {
+                phone: phone.to_string(),
+                goal,
+                target,
+                value: after,
+            });
+        }
+    }
+
+    fn emit_meal_logged(&self, meal: &Meal, stats_calories: f64, calorie_goal: i32) {
+        self.events.emit(BotEvent::MealLogged {
+            phone: meal.user_phone.clone(),
+            meal_type: meal.meal_type.to_string(),
+            calories: meal.calories,
+            has_image: meal.image_path.is_some(),
+        });
+        self.emit_goal_crossing(
+            &meal.user_phone,
+            GoalKind::Calories,
+            calorie_goal,
+            stats_calories - meal.calories,
+            stats_calories,
+        );
+    }
+
+    /// Update user's name from WhatsApp profile (also on the waitlist lead in closed beta)
+    pub async fn update_user_name(&self, phone: &str, name: Option<&str>) -> Result<()> {
+        if let (true, Some(name)) = (allowlist::enabled(), name) {
+            self.db.update_waitlist_lead_name(phone, name).await?;
+        }
+        self.db.update_user_name(phone, name).await
+    }
+
+    /// Clear 24h window warning status when user sends a message
+    pub async fn clear_window_warning(&self, phone: &str) -> Result<()> {
+        self.db.clear_warning_status(phone).await
+    }
+
+    /// Record an inbound photo before downloading it; until a meal is saved with it the nightly
+    /// sweep treats it as an orphan (see `maintenance::purge_unattached_images`)
+    pub async fn reserve_image(&self, phone: &str, image_path: &str) -> Result<()> {
+        let key = image_store::storage_key(std::path::Path::new(image_path))
+            .ok_or_else(|| anyhow::anyhow!("Invalid image path: {}", image_path))?;
+        self.db.reserve_image(&key, phone, image_path).await
+    }
+
+    /// Delete a photo that won't become a meal (file, thumbnail and its reservation)
+    pub async fn discard_image(&self, image_path: &str) {
+        if let Err(e) = image_store::remove_image(std::path::Path::new(image_path)) {
+            log::warn!("⚠️ Could not remove image {}: {}", image_path, e);
+            return;
+        }
+        if let Err(e) = self.db.release_image(image_path).await {
+            log::warn!("⚠️ Could not release image {}: {}", image_path, e);
+        }
+    }
+
+    /// Tell the user their photo couldn't be downloaded so it isn't silently lost
+    pub async fn notify_media_download_failed(&self, phone: &str) -> Result<()> {
+        self.send_and_log(
+            phone,
+            "📸 Fotoğrafını indiremedim. Lütfen tekrar gönderir misin?",
+        ).await
+    }
+
+    pub async fn notify_media_too_large(&self, phone: &str, limit_bytes: u64) -> Result<()> {
+        self.send_and_log(
+            phone,
+            &format!(
+                "📸 Fotoğraf çok büyük (en fazla {} MB). Daha küçük bir fotoğraf gönderir misin?",
+                limit_bytes / (1024 * 1024)
+            ),
+        ).await
+    }
+
+    /// Quick-log API entry (iOS Shortcuts, widgets): goes through the same code path as the
+    /// `su 250` / `ogun ...` commands, so goal events fire and the confirmation lands in WhatsApp too.
+    /// The confirmation is best-effort: an error here means the entry itself was not saved
+    pub async fn quick_log(&self, phone: &str, entry: QuickLog) -> Result<()> {
+        let content = match &entry {
+            QuickLog::Water { ml } => format!("su {}ml", ml),
+            QuickLog::Meal { description, calories: Some(calories) } => format!("ogun {} {:.0}", description, calories),
+            QuickLog::Meal { description, calories: None } => format!("ogun {}", description),
+        };
+        let _ = self.db.log_conversation(
+            phone,
+            ConversationDirection::Incoming,
+            MessageType::Command,
+            &content,
+            Some(serde_json::json!({"source": "quicklog"})),
+        ).await;
+
+        let handler = Self {
+            db: self.db.clone(),
+            openai: self.openai.clone(),
+            whatsapp: Arc::new(BestEffortWhatsAppClient::new(self.whatsapp.clone())),
+            events: self.events.clone(),
+            notifier: self.notifier.clone(),
+        };
+        match entry {
+            QuickLog::Water { ml } => handler.handle_water_log_with_amount(phone, ml).await,
+            QuickLog::Meal { description, calories } => handler.log_manual_meal(phone, description, calories).await,
+        }
+    }
+
+    fn onboarding_handler(&self) -> OnboardingHandler {
+        OnboardingHandler::new(self.db.clone(), self.whatsapp.clone(), self.events.clone())
+    }
+
+    /// Send message and log to conversation history
+    async fn send_and_log(&self, phone: &str, message: &str) -> Result<()> {
+        // Send the message
+        self.whatsapp.send_message(phone, message).await?;
+
+        // Log to conversation history
+        let _ = self.db.log_conversation(
+            phone,
+            ConversationDirection::Outgoing,
+            MessageType::Text,
+            message,
+            None,
+        ).await;
+
+        Ok(())
+    }
+
+    /// Help catalog answer, logged as `help` so unanswered how-to questions can be told apart
+    async fn send_help_answer(&self, phone: &str, topic: &HelpTopic, source: &str) -> Result<()> {
+        self.whatsapp.send_message(phone, topic.answer).await?;
+
+        let _ = self.db.log_conversation(
+            phone,
+            ConversationDirection::Outgoing,
+            MessageType::Help,
+            topic.answer,
+            Some(serde_json::json!({ "help_topic": topic.id, "source": source })),
+        ).await;
+
+        Ok(())
+    }
+
+    pub async fn handle_message(
+        &self,
+        from: &str,
+        message: &str,
+        has_media: bool,
+        media_path: Option<String>,
+    ) -> Result<()> {
+        // LOG: Gelen mesajı kaydet
+        log::info!("📨 INCOMING MESSAGE - From: {} | Content: '{}' | Has Media: {} | Media Path: {:?}",
+                   from, message, has_media, media_path);
+
+        // Kapalı beta: mevcut kullanıcılar ve onaylı numaralar dışındakiler bekleme listesine yazılır,
+        // kullanıcı kaydı (ve onboarding) onaydan sonraki ilk mesajda oluşur
+        if allowlist::enabled() && self.db.get_user(from).await?.is_none() && !self.db.is_allowlisted(from).await? {
+            log::info!("🚧 {} is not on the beta allowlist, recording as lead", from);
+            // İndirilmiş fotoğraf hiçbir kayda bağlanmayacak
+            if let Some(path) = &media_path {
+                self.discard_image(path).await;
+            }
+            if self.db.record_waitlist_lead(from, message).await? {
+                self.whatsapp.send_message(from, allowlist::WAITLIST_MESSAGE).await?;
+            }
+            return Ok(());
+        }
+
+        // Kullanıcıyı kontrol et veya oluştur
+        let user = self.ensure_user_exists(from).await?;
+
+        // Log incoming message to database
+        let message_type = if has_media { MessageType::Image } else { MessageType::Text };
+        let metadata = if has_media {
+            Some(serde_json::json!({
+                "has_media": true,
+                "media_path": media_path.clone()
+            }))
+        } else {
+            None
+        };
+        let _ = self.db.log_conversation(
+            from,
+            ConversationDirection::Incoming,
+            message_type,
+            message,
+            metadata,
+        ).await;
+
+        // Kullanıcı deaktif ise, mesajı işleme ama yanıt verme
+        if !user.is_active {
+            log::warn!("⚠️ User {} is inactive, ignoring message", from);
+            return Ok(());
+        }
+
+        // Onboarding tamamlanmamışsa, onboarding handler'a yönlendir
+        if !user.onboarding_completed {
+            log::info!("👤 User {} in onboarding phase (step: {:?})", from, user.onboarding_step);
+
+            // İlk mesajda otomatik olarak onboarding'i başlat
+            // Kullanıcıdan "tekrar mesaj gönder" dememek için direkt başlatıyoruz
+            self.onboarding_handler().handle_step(&user, message).await?;
+            return Ok(());
+        }
+
+        let message_lower = message.trim().to_lowercase();
+
+        // Resim varsa öncelik ver (komutlardan önce)
+        if has_media {
+            if let Some(image_path) = media_path {
+                self.handle_food_image(from, &image_path).await?;
+                return Ok(());
+            }
+        }
+
+        // `kurulum` sihirbazı devam ediyorsa cevabı sihirbaza ver
+        if user.onboarding_step.as_deref().is_some_and(|step| step.starts_with(WIZARD_STEP_PREFIX)) {
+            self.onboarding_handler().handle_wizard_step(&user, message).await?;
+            return Ok(());
+        }
+
+        // Dolu öğün saatine düşen ara öğün için "ekle / porsiyon" seçimi bekleniyor mu?
+        if let Some(pending) = user.pending_command.as_deref().filter(|p| p.starts_with(SLOT_CHOICE_PREFIX)) {
+            if self.handle_slot_choice(from, pending, message).await? {
+                return Ok(());
+            }
+        }
+
+        // Aylık NPS sorusu cevap bekliyor mu? (1/2/3 su kısayollarından önce)
+        if user.pending_command.as_deref() == Some(NPS_PENDING) && self.handle_nps_reply(&user, message).await? {
+            return Ok(());
+        }
+
+        // Pazar akşamı hedef değerlendirmesi cevap bekliyor mu? (1-5 su kısayollarından önce)
+        if user.pending_command.as_deref() == Some(GOAL_REVIEW_PENDING) && self.handle_goal_review_reply(&user, message).await? {
+            return Ok(());
+        }
+
+        // `fotolari sil` onayı bekleniyor mu?
+        if user.pending_command.as_deref() == Some(DELETE_PHOTOS_PENDING)
+            && self.handle_delete_photos_reply(from, message).await?
+        {
+            return Ok(());
+        }
+
+        // "Bunu mu demek istedin: rapor?" sorusuna cevap mı?
+        if let Some(command) = user.pending_command.as_deref().and_then(|p| p.strip_prefix(SUGGESTION_PREFIX)) {
+            if self.handle_suggestion_reply(from, command, message).await? {
+                return Ok(());
+            }
+        }
+
+        // Quick water button responses (1, 2, 3) - sadece sayı ise
+        let trimmed = message.trim();
+        if trimmed == "1" {
+            self.handle_water_log_with_amount(from, 200).await?;
+            return Ok(());
+        } else if trimmed == "2" {
+            self.handle_water_log_with_amount(from, 250).await?;
+            return Ok(());
+        } else if trimmed == "3" {
+            self.handle_water_log_with_amount(from, 500).await?;
+            return Ok(());
+        }
+
+        // "hata bildir ..." - açıklamanın büyük/küçük harfi korunsun diye orijinal mesajla
+        if let Some(description) = parse_bug_report(message) {
+            self.handle_bug_report(from, description).await?;
+            return Ok(());
+        }
+
+        // Önce bilinen komutları dene
+        if self.try_handle_smart_command(from, &message_lower).await? {
+            return Ok(());
+        }
+
+        // "nasıl hedef değiştiririm" - anahtar kelimeyle bulunan yardım konusu AI'sız cevaplanır
+        if help_catalog::looks_like_question(message) {
+            if let Some(topic) = help_catalog::search(message) {
+                self.send_help_answer(from, topic, "keyword").await?;
+                return Ok(());
+            }
+        }
+
+        // AI kapalıysa (sadece metin modu) serbest metin anlaşılamaz, manuel kullanımı anlat
+        if self.openai.is_text_only() {
+            if self.try_suggest_command(from, message).await? {
+                return Ok(());
+            }
+            self.send_and_log(
+                from,
+                &format!(
+                    "{}\n\n\
+                     Şunları kullanabilirsin:\n\
+                     • ogun [yemek] [kalori] - örn: ogun menemen 350\n\
+                     • su [ml] - örn: su 250\n\
+                     • 1, 2, 3 - 200/250/500 ml su\n\
+                     • rapor, geçmiş, haftalık",
+                    TEXT_ONLY_NOTICE
+                )
+            ).await?;
+            return Ok(());
+        }
+
+        // Bilinen komut değilse, AI ile kullanıcının ne yapmak istediğini anla
+        log::info!("🧠 Using AI to detect user intent for: '{}'", message);
+        match self.openai.detect_user_intent(message).await {
+            Ok(UserIntent::LogMeal(meal_description)) => {
+                log::info!("🍽️ User wants to log meal: {}", meal_description);
+                self.handle_text_meal(from, &meal_description).await?;
+            }
+            Ok(UserIntent::LogWater(amount)) => {
+                log::info!("💧 User wants to log water: {} ml", amount);
+                self.handle_water_log_with_amount(from, amount).await?;
+            }
+            Ok(UserIntent::SetCalorieGoal(amount)) => {
+                log::info!("🎯 User wants to set calorie goal: {} kcal", amount);
+                self.db.update_calorie_goal(from, amount, GoalChangeSource::Ai).await?;
+                self.send_and_log(from, &format!("✅ Kalori hedefin {} kcal olarak ayarlandı!", amount)).await?;
+            }
+            Ok(UserIntent::SetWaterGoal(amount)) => {
+                log::info!("💧 User wants to set water goal: {} ml", amount);
+                self.db.update_water_goal(from, amount, GoalChangeSource::Ai).await?;
+                let units = self.db.get_user(from).await?.map(|u| u.units).unwrap_or_default();
+                self.send_and_log(from, &format!("✅ Su hedefin {} olarak ayarlandı!", format_water(amount as i64, units))).await?;
+            }
+            Ok(UserIntent::SetMealTime(meal_type, time)) => {
+                log::info!("⏰ User wants to set meal time: {} at {}", meal_type, time);
+                let meal_type_normalized = match meal_type.as_str() {
+                    "kahvalti" | "kahvaltı" => "breakfast",
+                    "ogle" | "öğle" => "lunch",
+                    "aksam" | "akşam" => "dinner",
+                    _ => {
+                        self.send_and_log(from, "❌ Geçersiz öğün tipi. Kullan: kahvaltı, öğle, akşam").await?;
+                        return Ok(());
+                    }
+                };
+                self.db.update_meal_time(from, meal_type_normalized, &time).await?;
+                let meal_name_tr = match meal_type_normalized {
+                    "breakfast" => "Kahvaltı",
+                    "lunch" => "Öğle",
+                    "dinner" => "Akşam",
+                    _ => "Öğün",
+                };
+                self.send_and_log(from, &format!("✅ {} saatin {} olarak ayarlandı!", meal_name_tr, time)).await?;
+            }
+            Ok(UserIntent::SetSilentHours(start, end)) => {
+                log::info!("🌙 User wants to set silent hours: {} - {}", start, end);
+                self.db.update_silent_hours(from, &start, &end).await?;
+                self.send_and_log(from, &format!("✅ Sessiz saatler {} - {} olarak ayarlandı!", start, end)).await?;
+            }
+            Ok(UserIntent::HelpQuestion(topic_id)) => {
+                log::info!("❔ User asked how to: {}", topic_id);
+                match help_catalog::find(&topic_id) {
+                    Some(topic) => self.send_help_answer(from, topic, "ai").await?,
+                    None => self.send_help_message(from).await?,
+                }
+            }
+            Ok(UserIntent::RunCommand(command)) => {
+                log::info!("⚙️ User wants to run command: {}", command);
+                if !self.try_handle_smart_command(from, &command).await? {
+                    self.send_help_or_suggestion(from, message).await?;
+                }
+            }
+            Ok(UserIntent::Unknown) => {
+                log::info!("❓ AI couldn't determine intent, showing help");
+                self.send_help_or_suggestion(from, message).await?;
+            }
+            Err(e) => {
+                log::warn!("⚠️ AI intent detection failed: {}", e);
+                self.send_help_or_suggestion(from, message).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Existing user (cache hit in the common case) or a freshly created one
+    async fn ensure_user_exists(&self, phone: &str) -> Result<User> {
+        if let Some(user) = self.db.get_user(phone).await? {
+            return Ok(user);
+        }
+
+        let mut user = User {
+            phone_number: phone.to_string(),
+            name: None,  // Will be updated from WhatsApp later
+            created_at: Utc::now(),
+            onboarding_completed: false,
+            onboarding_step: None,  // Onboarding handler başlatacak
+            breakfast_reminder: true,
+            lunch_reminder: true,
+            dinner_reminder: true,
+            water_reminder: true,
+            breakfast_time: None,
+            lunch_time: None,
+            dinner_time: None,
+            opted_in: true,
+            timezone: "Europe/Istanbul".to_string(),  // Varsayılan Türkiye
+            daily_water_goal: Some(2000),  // Varsayılan: 2 litre (2000 ml)
+            daily_calorie_goal: Some(2000),  // Varsayılan: 2000 kcal
+            silent_hours_start: Some("23:00".to_string()),  // Varsayılan: 23:00
+            silent_hours_end: Some("07:00".to_string()),    // Varsayılan: 07:00
+            is_active: true,  // Varsayılan: aktif
+            pending_command: None,  // Başlangıçta bekleyen komut yok
+            coach_phone: None,
+            coach_sharing: false,  // Paylaşım için kullanıcının açık onayı gerekir
+            daily_summary_time: Some("22:00".to_string()),  // Varsayılan: 22:00
+            benchmark_opt_in: false,  // Anonim karşılaştırma sadece açık onayla
+            units: UnitSystem::Metric,
+            meal_budget: None,  // Varsayılan dağılım (25/35/30/10)
+            water_reminder_interval: crate::models::DEFAULT_WATER_REMINDER_INTERVAL,
+            summary_sections: Default::default(),  // Tüm bölümler açık
+            email: None,  // E-posta raporu sadece doğrulanmış adresle
+            email_reports: EmailReportFrequency::Weekly,
+            language: Language::Tr,
+            summary_narrative: false,
+            pending_command_at: None,
+        };
+        // Ülke koduna göre saat dilimi, öğün/sessiz saatler, su hedefi ve dil (tablo yoksa yukarıdakiler)
+        if let Some(defaults) = country_defaults::lookup(&self.db, phone).await {
+            country_defaults::apply(&defaults, &mut user);
+        }
+        let (user, created) = self.db.get_or_create_user(&user).await?;
+        if created {
+            log::info!("✅ New user created: {}", phone);
+        } else {
+            log::debug!("👥 User {} was created by a concurrent message", phone);
+        }
+        Ok(user)
+    }
+
+    /// Kullanıcının öğün saatleri: ayarlanan saatler + "duzelt" düzeltmelerinden öğrenilenler
+    async fn meal_schedule(&self, user: &User) -> MealSchedule {
+        // Kullanıcının öğün saatlerini parse et
+        let parse = |time: &Option<String>| time.as_ref()
+            .and_then(|t| chrono::NaiveTime::parse_from_str(t, "%H:%M").ok());
+
+        // Eğer öğün saatleri ayarlanmamışsa varsayılan saatler kullan
+        let configured = [
+            parse(&user.breakfast_time).unwrap_or_else(|| chrono::NaiveTime::from_hms_opt(9, 0, 0).unwrap()),
+            parse(&user.lunch_time).unwrap_or_else(|| chrono::NaiveTime::from_hms_opt(13, 0, 0).unwrap()),
+            parse(&user.dinner_time).unwrap_or_else(|| chrono::NaiveTime::from_hms_opt(19, 0, 0).unwrap()),
+        ];
+
+        let corrections = self.db
+            .get_meal_type_corrections(&user.phone_number, meal_learning::CORRECTION_WINDOW)
+            .await
+            .unwrap_or_default();
+        MealSchedule::learn(configured, &corrections)
+    }
+
+    /// Ara öğün olarak kaydedilen yemek aslında bugün zaten kayıtlı bir ana öğünün saatine mi denk geliyor?
+    /// (ikinci öğle yemeği fotoğrafı gibi) - öyleyse o öğün döner, kullanıcıya birleştirme sorulur
+    async fn repeated_slot(&self, user: &User, current_time: chrono::NaiveTime, today: chrono::NaiveDate) -> Option<MealType> {
+        let todays_meals = self.db.get_todays_meal_types(&user.phone_number, today).await.ok()?;
+        let schedule = self.meal_schedule(user).await;
+        let tolerance = chrono::Duration::hours(2);
+
+        [
+            (MealType::Breakfast, schedule.breakfast),
+            (MealType::Lunch, schedule.lunch),
+            (MealType::Dinner, schedule.dinner),
+        ]
+        .into_iter()
+        .find(|(meal_type, target)| {
+            todays_meals.contains(meal_type) && Self::is_within_time_range(current_time, *target, tolerance)
+        })
+        .map(|(meal_type, _)| meal_type)
+    }
+
+    /// Kaydedilen ara öğün, dolu bir ana öğün saatine denk geldiyse "ekle / porsiyon" seçeneğini sun
+    async fn offer_slot_merge(&self, user: &User, meal_id: i64, meal_type: &MealType, now: chrono::NaiveTime, today: chrono::NaiveDate) -> String {
+        if *meal_type != MealType::Snack {
+            return String::new();
+        }
+        let Some(slot) = self.repeated_slot(user, now, today).await else {
+            return String::new();
+        };
+
+        let pending = format!("{}{}:{}", SLOT_CHOICE_PREFIX, meal_id, slot);
+        if let Err(e) = self.db.update_pending_command(&user.phone_number, Some(&pending)).await {
+            log::warn!("⚠️ Could not store slot choice for {}: {}", user.phone_number, e);
+            return String::new();
+        }
+
+        format!(
+            "\n\n🍽️ Bugün {} zaten kayıtlı. Bu öğün:\n\
+             • *ekle* - {} ile birleştir\n\
+             • *porsiyon* - ikinci porsiyon olarak kaydet\n\
+             (Başka bir şey yazarsan ara öğün olarak kalır)",
+            slot, slot
+        )
+    }
+
+    /// "ekle" / "porsiyon" cevabını işle. Cevap bu değilse seçim iptal edilir ve mesaj normal akışa döner (false).
+    async fn handle_slot_choice(&self, from: &str, pending: &str, message: &str) -> Result<bool> {
+        self.db.update_pending_command(from, None).await?;
+
+        let Some((meal_id, slot)) = parse_slot_choice(pending) else {
+            return Ok(false);
+        };
+
+        match message.trim().to_lowercase().as_str() {
+            "ekle" | "birleştir" | "birlestir" => {
+                let recent = self.db.get_recent_meals(from, 20).await?;
+                let source = recent.iter().find(|m| m.id == Some(meal_id));
+                let target = recent.iter().find(|m| m.meal_type == slot && m.id != Some(meal_id));
+                match (target, source) {
+                    (Some(target), Some(source)) => {
+                        self.db.merge_meals(target, source).await?;
+                        log::info!("🔗 Merged meal {} into {:?} ({}) for {}", meal_id, target.id, slot, from);
+                        self.send_and_log(from, &format!("✅ {} ile birleştirildi.", slot)).await?;
+                    }
+                    _ => {
+                        self.send_and_log(from, &format!("❌ Birleştirilecek {} bulunamadı, ara öğün olarak kaldı.", slot)).await?;
+                    }
+                }
+                Ok(true)
+            }
+            "porsiyon" | "ikinci porsiyon" | "2. porsiyon" => {
+                self.db.update_meal_type(meal_id, &slot).await?;
+                self.send_and_log(from, &format!("✅ {} (2. porsiyon) olarak kaydedildi.", slot)).await?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// 0-10 puanı veya 'atla' cevabını işle; başka bir mesajsa soru düşer ve mesaj normal akışa döner (false)
+    async fn handle_nps_reply(&self, user: &User, message: &str) -> Result<bool> {
+        let from = user.phone_number.as_str();
+        self.db.update_pending_command(from, None).await?;
+
+        // Haftalar önce sorulmuş soru: "3" bir puan değil, su kaydı
+        if user.awaiting_reply(Utc::now()).is_none() {
+            return Ok(false);
+        }
+
+        if let Some(score) = feedback::parse_nps_score(message) {
+            self.db.add_feedback(from, "nps", Some(score), None).await?;
+            log::info!("📣 NPS {} from {}", score, from);
+            let reply = if score >= 9 {
+                "🙏 Çok teşekkürler! Bir arkadaşınla paylaşırsan çok seviniriz."
+            } else {
+                "🙏 Teşekkürler! Geri bildirimin botu geliştirmemize yardımcı oluyor."
+            };
+            self.send_and_log(from, reply).await?;
+            return Ok(true);
+        }
+
+        if matches!(message.trim().to_lowercase().as_str(), "atla" | "geç" | "gec" | "hayır" | "hayir") {
+            self.send_and_log(from, "👍 Tamam, sormayacağım.").await?;
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// Haftalık hedef değerlendirmesine cevap: seçimi ayar yoluyla uygula (goal_history'ye yazılır).
+    /// Seçim değilse değerlendirme düşer, hedefler aynı kalır ve mesaj normal akışa döner (false).
+    async fn handle_goal_review_reply(&self, user: &User, message: &str) -> Result<bool> {
+        let from = user.phone_number.as_str();
+        self.db.update_pending_command(from, None).await?;
+
+        // Cevapsız kalmış eski değerlendirme: "2" artık su kaydı, hedef değişmez
+        if user.awaiting_reply(Utc::now()).is_none() {
+            return Ok(false);
+        }
+
+        let Some(option) = GoalReviewOption::parse(message) else {
+            if matches!(message.trim().to_lowercase().as_str(), "atla" | "geç" | "gec" | "hayır" | "hayir") {
+                self.send_and_log(from, "👍 Tamam, hedeflerin aynı kalıyor.").await?;
+                return Ok(true);
+            }
+            return Ok(false);
+        };
+        self.db.record_goal_review_answer(from, option.as_str()).await?;
+        log::info!("🗓️ Goal review answer from {}: {}", from, option.as_str());
+
+        let calorie_goal = user.daily_calorie_goal.unwrap_or(2000);
+        let water_goal = user.daily_water_goal.unwrap_or(2000);
+        let reply = match option.apply(calorie_goal, water_goal) {
+            Some((GoalKind::Calories, goal)) => {
+                self.db.update_calorie_goal(from, goal, GoalChangeSource::WeeklyReview).await?;
+                format!("✅ Kalori hedefin {} → {} kcal oldu. Bu hafta da bol şans!", calorie_goal, goal)
+            }
+            Some((GoalKind::Water, goal)) => {
+                self.db.update_water_goal(from, goal, GoalChangeSource::WeeklyReview).await?;
+                format!(
+                    "✅ Su hedefin {} → {} oldu. Bu hafta da bol şans!",
+                    format_water(water_goal as i64, user.units),
+                    format_water(goal as i64, user.units)
+                )
+            }
+            None if option == GoalReviewOption::Keep => "👍 Hedeflerin aynı kalıyor. Bu hafta da bol şans!".to_string(),
+            None => "ℹ️ Hedefin zaten sınırda, değiştirmedim. İstersen kalorihedefi / suhedefi ile ayarlayabilirsin.".to_string(),
+        };
+        self.send_and_log(from, &reply).await?;
+        Ok(true)
+    }
+
+    /// Komuta çok benzeyen mesaj ("rapr"): yardım duvarı yerine "Bunu mu demek istedin?" diye sor.
+    /// Öneri pending_command'de saklanır, "evet" gelirse çalıştırılır.
+    async fn try_suggest_command(&self, from: &str, message: &str) -> Result<bool> {
+        let Some(command) = command_hints::suggest(message) else {
+            return Ok(false);
+        };
+
+        log::info!("🔤 Suggesting '{}' for '{}' from {}", command, message, from);
+        self.db.update_pending_command(from, Some(&format!("{}{}", SUGGESTION_PREFIX, command))).await?;
+        self.send_and_log(
+            from,
+            &format!("🤔 Bunu mu demek istedin: *{}*?\n\nÇalıştırmak için *evet* yaz.", command),
+        ).await?;
+        Ok(true)
+    }
+
+    async fn send_help_or_suggestion(&self, from: &str, message: &str) -> Result<()> {
+        if self.try_suggest_command(from, message).await? {
+            return Ok(());
+        }
+        self.send_help_message(from).await
+    }
+
+    /// "evet" önerilen komutu çalıştırır; başka bir mesajsa öneri düşer ve mesaj normal akışa döner (false)
+    async fn handle_suggestion_reply(&self, from: &str, command: &str, message: &str) -> Result<bool> {
+        self.db.update_pending_command(from, None).await?;
+
+        if !matches!(message.trim().to_lowercase().as_str(), "evet" | "e" | "olur" | "tamam" | "yes") {
+            return Ok(false);
+        }
+
+        log::info!("🔤 Running suggested command '{}' for {}", command, from);
+        if !self.try_handle_smart_command(from, &command.to_lowercase()).await? {
+            self.send_help_message(from).await?;
+        }
+        Ok(true)
+    }
+
+    /// `fotolari sil` - onay iste; fotoğraf yoksa direkt söyle
+    async fn handle_delete_photos_command(&self, from: &str) -> Result<()> {
+        let count = self.db.count_user_images(from).await?;
+        if count == 0 {
+            self.send_and_log(from, "📸 Kayıtlı fotoğrafın yok.").await?;
+            return Ok(());
+        }
+
+        self.db.update_pending_command(from, Some(DELETE_PHOTOS_PENDING)).await?;
+        self.send_and_log(
+            from,
+            &format!(
+                "🗑️ *{} fotoğraf* kalıcı olarak silinecek.\n\
+                 Öğünlerin ve kalori kayıtların korunur.\n\n\
+                 Onaylamak için *evet* yaz (vazgeçmek için başka bir şey yazman yeterli).",
+                count
+            ),
+        ).await?;
+        Ok(())
+    }
+
+    /// Onay geldiyse fotoğrafları sil; başka bir mesajsa işlem iptal olur ve mesaj normal akışa döner (false)
+    async fn handle_delete_photos_reply(&self, from: &str, message: &str) -> Result<bool> {
+        self.db.update_pending_command(from, None).await?;
+
+        if !matches!(message.trim().to_lowercase().as_str(), "evet" | "onayla" | "onaylıyorum" | "sil") {
+            return Ok(false);
+        }
+
+        let paths = self.db.clear_user_image_paths(from).await?;
+        let removed = remove_image_files(&paths);
+        log::info!("🗑️ Deleted {}/{} photos for {}", removed, paths.len(), from);
+
+        self.send_and_log(
+            from,
+            &format!("✅ {} fotoğraf silindi. Kalori kayıtların yerinde duruyor.", paths.len()),
+        ).await?;
+        Ok(true)
+    }
+
+    /// Kullanıcının hata bildirimini son konuşmalarla birlikte kaydet ve operatörlere haber ver
+    async fn handle_bug_report(&self, from: &str, description: &str) -> Result<()> {
+        // Son bot yanıtları henüz log kuyruğunda olabilir; bağlama dahil olsunlar
+        self.db.flush_conversation_log().await;
+        let history = self.db.get_conversation_history(from, BUG_REPORT_CONTEXT_MESSAGES).await?;
+        let context = serde_json::to_value(&history)?;
+        let version = crate::VERSION;
+
+        let report_id = self.db.add_bug_report(from, description, &context, version).await?;
+        log::warn!("🐞 Bug report #{} from {}: {}", report_id, from, description);
+
+        if let Some(notifier) = self.notifier.clone() {
+            let subject = format!("🐞 Hata bildirimi #{} ({})", report_id, from);
+            let html = render_bug_report_html(report_id, from, description, version, &history);
+            // Kullanıcıya yanıt e-postayı beklemesin
+            tokio::spawn(async move {
+                if let Err(e) = notifier.notify_operators(&subject, &html).await {
+                    log::error!("❌ Failed to email bug report #{}: {}", report_id, e);
+                }
+            });
+        }
+
+        let reply = if description.is_empty() {
+            format!(
+                "🐞 Bildirimin alındı (#{}). Son mesajlarını ekibe ilettik.\n\
+                 Ne olduğunu da yazarsan daha hızlı çözeriz: *hata bildir [açıklama]*",
+                report_id
+            )
+        } else {
+            format!("🐞 Bildirimin alındı (#{}). Teşekkürler, ekip inceleyecek!", report_id)
+        };
+        self.send_and_log(from, &reply).await
+    }
+
+    /// Optimized: Detect meal type without fetching user (user already available)
+    async fn detect_meal_type_with_user(&self, user: &User, current_time: chrono::NaiveTime, today: chrono::NaiveDate) -> Result<MealType> {
+        log::debug!("🕐 Detecting meal type for user {} at {} (timezone: {})", user.phone_number, current_time, user.timezone);
+
+        // Bugün kaydedilmiş öğünleri kontrol et
+        let todays_meals = self.db.get_todays_meal_types(&user.phone_number, today).await?;
+
+        let has_breakfast = todays_meals.iter().any(|m| matches!(m, MealType::Breakfast));
+        let has_lunch = todays_meals.iter().any(|m| matches!(m, MealType::Lunch));
+        let has_dinner = todays_meals.iter().any(|m| matches!(m, MealType::Dinner));
+
+        log::debug!("📊 Today's meals - Breakfast: {}, Lunch: {}, Dinner: {}", has_breakfast, has_lunch, has_dinner);
+
+        let MealSchedule { breakfast, lunch, dinner, sequential } = self.meal_schedule(user).await;
+        if !sequential {
+            log::debug!("🧠 Sequential meal rule relaxed for {} (learned from corrections)", user.phone_number);
+        }
+
+        // Tolerans: ±2 saat
+        let tolerance = chrono::Duration::hours(2);
+
+        // Sıralı öğün kontrolü: Kahvaltı -> Öğle -> Akşam
+        // Kullanıcı önce kahvaltı yapmalı, sonra öğle, sonra akşam (öğün atlayan kullanıcılar için gevşetilir)
+
+        // Eğer kahvaltı kayıtlı değilse ve kahvaltı saatindeyse
+        if !has_breakfast && Self::is_within_time_range(current_time, breakfast, tolerance) {
+            log::info!("🍳 Detected meal type: Breakfast (current: {}, target: {})", current_time, breakfast);
+            return Ok(MealType::Breakfast);
+        }
+
+        // Eğer kahvaltı kayıtlı ama öğle kayıtlı değilse ve öğle saatindeyse
+        if (has_breakfast || !sequential) && !has_lunch && Self::is_within_time_range(current_time, lunch, tolerance) {
+            log::info!("🍱 Detected meal type: Lunch (current: {}, target: {})", current_time, lunch);
+            return Ok(MealType::Lunch);
+        }
+
+        // Eğer kahvaltı ve öğle kayıtlı ama akşam kayıtlı değilse ve akşam saatindeyse
+        if ((has_breakfast && has_lunch) || !sequential) && !has_dinner && Self::is_within_time_range(current_time, dinner, tolerance) {
+            log::info!("🍽️ Detected meal type: Dinner (current: {}, target: {})", current_time, dinner);
+            return Ok(MealType::Dinner);
+        }
+
+        // Eğer sıralı öğün kuralına uymuyorsa ara öğün olarak kaydet
+        log::info!("🍪 Detected meal type: Snack (sequential rule or time doesn't match main meals) at {}", current_time);
+        Ok(MealType::Snack)
+    }
+
+    /// Bir zamanın hedef zaman ± tolerans aralığında olup olmadığını kontrol et
+    fn is_within_time_range(current: chrono::NaiveTime, target: chrono::NaiveTime, tolerance: chrono::Duration) -> bool {
+        // Zamanları dakika cinsine çevir (gece yarısından bu yana)
+        let current_mins = current.num_seconds_from_midnight() as i64 / 60;
+        let target_mins = target.num_seconds_from_midnight() as i64 / 60;
+        let tolerance_mins = tolerance.num_minutes();
+
+        // Fark hesapla (gün sınırını dikkate alarak)
+        let diff = (current_mins - target_mins).abs();
+
+        // Gün sınırı kontrolü (örn: 23:00 ile 01:00 arası)
+        let diff_wrapped = std::cmp::min(diff, 1440 - diff); // 1440 = 24 * 60
+
+        diff_wrapped <= tolerance_mins
+    }
+
+    async fn handle_text_meal(&self, from: &str, description: &str) -> Result<()> {
+        let language = self.db.get_user(from).await?.map(|u| u.language).unwrap_or_default();
+
+        // AI'dan yemek analizi al
+        match self.openai.analyze_text_meal(description, language).await {
+            Ok(calorie_info) if calorie_info.low_confidence => {
+                // AI kaloriyi veremedi: yerel tablo biliyorsa onu tercih et
+                let calorie_info = local_estimate(description).unwrap_or(calorie_info);
+                self.save_text_meal(from, &calorie_info).await?;
+            }
+            Ok(calorie_info) => {
+                self.save_text_meal(from, &calorie_info).await?;
+            }
+            Err(e) => {
+                log::error!("❌ Failed to analyze text meal: {}", e);
+                if let Some(calorie_info) = local_estimate(description) {
+                    log::info!("📚 Using built-in food table for '{}'", description);
+                    return self.save_text_meal(from, &calorie_info).await;
+                }
+                self.whatsapp
+                    .send_message(
+                        from,
+                        "❌ Analiz yapılamadı.\nLütfen daha detaylı açıkla veya fotoğraf gönder.",
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fotoğrafsız öğünü kaydet ve onay özetini gönder (AI analizi veya manuel giriş)
+    async fn save_text_meal(&self, from: &str, calorie_info: &CalorieInfo) -> Result<()> {
+        // Kullanıcı bilgilerini tek seferde al (hem timezone hem de meal detection için)
+        let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+        let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+        let now = Utc::now().with_timezone(&user_tz);
+        let today = now.date_naive();
+
+        // Akıllı öğün tespiti (user'ı tekrar fetch etmeden)
+        let meal_type = self.detect_meal_type_with_user(&user, now.time(), today).await?;
+
+        let meal = Meal {
+            id: None,
+            user_phone: from.to_string(),
+            meal_type: meal_type.clone(),
+            calories: calorie_info.calories,
+            description: calorie_info.description.clone(),
+            image_path: None, // Text-based meal, no image
+            created_at: Utc::now(),
+            extras: calorie_info.extras.clone(),
+            full_description: calorie_info.full_description.clone(),
+        };
+
+        let meal_id = self.db.add_meal(&meal).await?;
+
+        let stats = self.db.get_daily_stats(from, today).await?;
+        self.emit_meal_logged(&meal, stats.total_calories, user.daily_calorie_goal.unwrap_or(2000));
+
+        let meal_type_name = match meal_type {
+            MealType::Breakfast => "Kahvaltı",
+            MealType::Lunch => "Öğle Yemeği",
+            MealType::Dinner => "Akşam Yemeği",
+            MealType::Snack => "Ara Öğün",
+        };
+
+        let summary = format!(
+            "✅ *{} Kaydedildi!*\n\n\
+             📝 {}\n\
+             🔥 {:.0} kcal\n{}\n\
+             📊 Bugün: {:.0} kcal ({} öğün)",
+            meal_type_name,
+            calorie_info.description,
+            calorie_info.calories,
+            nutrition_fields::format_values(nutrition_fields::configured(), &calorie_info.extras),
+            stats.total_calories,
+            stats.meals_count
+        ) + &self.slot_budget_line(&user, &meal_type, today).await
+            + detail_hint(calorie_info)
+            + &self.offer_slot_merge(&user, meal_id, &meal_type, now.time(), today).await;
+
+        self.send_and_log(from, &summary).await?;
+        Ok(())
+    }
+
+    /// "ogun <açıklama> [kalori]" - kalori yazılırsa AI'sız kaydedilir
+    async fn handle_manual_meal_command(&self, from: &str, parts: &[&str]) -> Result<()> {
+        let (description, calories) = parse_manual_meal(&parts[1..]);
+
+        if description.is_empty() {
+            self.send_and_log(
+                from,
+                "❌ Kullanım: ogun [yemek] [kalori]\nÖrnek: ogun tavuk göğsü ve salata 450"
+            ).await?;
+            return Ok(());
+        }
+
+        self.log_manual_meal(from, description, calories).await
+    }
+
+    async fn log_manual_meal(&self, from: &str, description: String, calories: Option<f64>) -> Result<()> {
+        match calories {
+            Some(calories) => {
+                let calorie_info = CalorieInfo {
+                    calories,
+                    description: description.clone(),
+                    extras: Default::default(),
+                    full_description: None,
+                    low_confidence: false,
+                };
+                self.save_text_meal(from, &calorie_info).await
+            }
+            None if self.openai.is_text_only() => match local_estimate(&description) {
+                Some(calorie_info) => self.save_text_meal(from, &calorie_info).await,
+                None => {
+                    self.send_and_log(
+                        from,
+                        &format!(
+                            "{}\n\nKalori değerini de yazarsan kaydedebilirim:\nogun {} 450",
+                            TEXT_ONLY_NOTICE, description
+                        )
+                    ).await
+                }
+            },
+            None => self.handle_text_meal(from, &description).await,
+        }
+    }
+
+    async fn handle_food_image(&self, from: &str, image_path: &str) -> Result<()> {
+        // Kullanıcı bilgilerini tek seferde al (hem timezone hem de meal detection için)
+        let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+        let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+        let now = Utc::now().with_timezone(&user_tz);
+        let today = now.date_naive();
+
+        if self.openai.is_text_only() {
+            self.send_and_log(
+                from,
+                &format!("{}\n\n📸 Fotoğraf analizi yapılamıyor, öğünü yazıyla kaydet:\nogun mercimek çorbası 250", TEXT_ONLY_NOTICE)
+            ).await?;
+            return Ok(());
+        }
+
+        // Günlük resim limiti kontrolü
+        let daily_image_count = self.db.get_daily_image_count(from, today).await?;
+
+        if daily_image_count >= DAILY_IMAGE_LIMIT {
+            log::warn!("📸 User {} reached daily image limit: {}/{}", from, daily_image_count, DAILY_IMAGE_LIMIT);
+            self.whatsapp
+                .send_message(
+                    from,
+                    &format!(
+                        "⚠️ *Günlük resim limiti* ({0}/{0})\n\n\
+                         Yarın tekrar fotoğraf gönderebilirsin.\n\
+                         Bugün için: ogun tavuk göğsü ve salata",
+                        DAILY_IMAGE_LIMIT
+                    )
+                )
+                .await?;
+            return Ok(());
+        }
+
+        // Ekran görüntüsü / meme ise pahalı vision çağrısını yapma, 400 kcal'lik "öğün" kaydetme
+        if let Some(kind) = std::fs::read(image_path).ok().and_then(|bytes| image_screening::screen(&bytes)) {
+            log::info!("🙈 Non-food image from {} ({}), skipping analysis", from, kind.as_str());
+            self.whatsapp.send_message(from, kind.reply()).await?;
+            let _ = self.db.log_conversation(
+                from,
+                ConversationDirection::Outgoing,
+                MessageType::Response,
+                kind.reply(),
+                Some(serde_json::json!({ "non_food_image": kind.as_str() })),
+            ).await;
+            // Hiçbir kayda bağlı değil, diskte tutmaya gerek yok
+            self.discard_image(image_path).await;
+            return Ok(());
+        }
+
+        match self.openai.analyze_food_image(image_path, user.language).await {
+            Ok(calorie_info) => {
+                // Akıllı öğün tespiti (user'ı tekrar fetch etmeden)
+                let meal_type = self.detect_meal_type_with_user(&user, now.time(), today).await?;
+
+                let meal = Meal {
+                    id: None,
+                    user_phone: from.to_string(),
+                    meal_type: meal_type.clone(),
+                    calories: calorie_info.calories,
+                    description: calorie_info.description.clone(),
+                    image_path: Some(image_path.to_string()),
+                    created_at: Utc::now(),
+                    extras: calorie_info.extras.clone(),
+                    full_description: calorie_info.full_description.clone(),
+                };
+
+                let meal_id = self.db.add_meal(&meal).await?;
+
+                let stats = self.db.get_daily_stats(from, today).await?;
+                self.emit_meal_logged(&meal, stats.total_calories, user.daily_calorie_goal.unwrap_or(2000));
+
+                let meal_type_name = match meal_type {
+                    MealType::Breakfast => "Kahvaltı",
+                    MealType::Lunch => "Öğle Yemeği",
+                    MealType::Dinner => "Akşam Yemeği",
+                    MealType::Snack => "Ara Öğün",
+                };
+
+                // Günlük resim sayısını tekrar al (yeni eklenen dahil)
+                let updated_image_count = self.db.get_daily_image_count(from, today).await?;
+
+                let summary = format!(
+                    "✅ *{} Kaydedildi!*\n\n\
+                     📝 {}\n\
+                     🔥 {:.0} kcal\n{}\n\
+                     📊 Bugün: {:.0} kcal ({} öğün)\n\
+                     📸 Resim: {}/{}",
+                    meal_type_name,
+                    calorie_info.description,
+                    calorie_info.calories,
+                    nutrition_fields::format_values(nutrition_fields::configured(), &calorie_info.extras),
+                    stats.total_calories,
+                    stats.meals_count,
+                    updated_image_count,
+                    DAILY_IMAGE_LIMIT
+                ) + &self.slot_budget_line(&user, &meal_type, today).await
+                    + detail_hint(&calorie_info)
+                    + &self.offer_slot_merge(&user, meal_id, &meal_type, now.time(), today).await;
+
+                self.send_and_log(from, &summary).await?;
+            }
+            Err(e) => {
+                log::error!("Image analysis error: {}", e);
+                self.whatsapp
+                    .send_message(from, "❌ Resim analiz edilemedi. Tekrar dene.")
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Öğün onayının altına eklenen kalan bütçe / aşım uyarısı satırı
+    async fn slot_budget_line(&self, user: &User, meal_type: &MealType, today: chrono::NaiveDate) -> String {
+        let budget = MealBudget::from_stored(user.meal_budget.as_deref());
+        match self.db.get_slot_calories(&user.phone_number, today, meal_type).await {
+            Ok(slot_total) => meal_budget::format_slot_status(
+                &budget,
+                meal_type,
+                slot_total,
+                user.daily_calorie_goal.unwrap_or(2000),
+            ),
+            Err(e) => {
+                log::warn!("⚠️ Could not load slot calories for {}: {}", user.phone_number, e);
+                String::new()
+            }
+        }
+    }
+
+    async fn handle_water_log_with_amount(&self, from: &str, amount: i32) -> Result<()> {
+        let water_log = WaterLog {
+            id: None,
+            user_phone: from.to_string(),
+            amount_ml: amount,
+            created_at: Utc::now(),
+        };
+
+        self.db.add_water_log(&water_log).await?;
+
+        // Kullanıcı bilgilerini tek seferde al (hem timezone hem de water_goal için)
+        let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+
+        let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+        let today = Utc::now().with_timezone(&user_tz).date_naive();
+
+        let stats = self.db.get_daily_stats(from, today).await?;
+        let water_goal = user.daily_water_goal.unwrap_or(2000);
+        self.emit_goal_crossing(
+            from,
+            GoalKind::Water,
+            water_goal,
+            (stats.total_water_ml - amount as i64) as f64,
+            stats.total_water_ml as f64,
+        );
+
+        let response = format!(
+            "💧 *{} kaydedildi!*\n\n\
+             Bugün: {} / {}\n\
+             Kalan: {}",
+            format_water(amount as i64, user.units),
+            format_water(stats.total_water_ml, user.units),
+            format_water(water_goal as i64, user.units),
+            format_water(water_goal as i64 - stats.total_water_ml, user.units)
+        );
+
+        self.send_and_log(from, &response).await?;
+
+        Ok(())
+    }
+
+    /// `eposta <adres>` → kod gönder, `eposta kod 123456` → doğrula,
+    /// `eposta haftalik|aylik|ikisi` → rapor sıklığı, `eposta kapat` → adresi sil
+    async fn handle_email_command(&self, from: &str, parts: &[&str]) -> Result<()> {
+        const USAGE: &str = "📧 *E-posta Raporu*\n\n\
+                             eposta ornek@mail.com - Adres ekle (doğrulama kodu gönderilir)\n\
+                             eposta kod 123456 - Adresi onayla\n\
+                             eposta haftalik / aylik / ikisi - Rapor sıklığı\n\
+                             eposta kapat - E-posta raporlarını kapat";
+
+        let Some(arg) = parts.get(1).copied() else {
+            let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+            let status = match &user.email {
+                Some(email) => format!("✅ {} ({})", email, user.email_reports.label()),
+                None => "❌ Kapalı".to_string(),
+            };
+            return self.send_and_log(from, &format!("{}\n\nDurum: {}", USAGE, status)).await;
+        };
+
+        match arg {
+            "kod" | "dogrula" | "doğrula" | "onayla" | "code" => {
+                let Some(code) = parts.get(2) else {
+                    return self.send_and_log(from, "❌ Kullanım: eposta kod 123456").await;
+                };
+                let reply = match self.db.confirm_email_verification(from, code).await? {
+                    EmailVerification::Verified(email) => {
+                        log::info!("📧 Email verified for {}", from);
+                        let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+                        format!(
+                            "✅ {} onaylandı!\nRaporların {} e-postana gelecek.\nSıklık: eposta haftalik / aylik / ikisi",
+                            email,
+                            email_report::schedule_text(user.email_reports)
+                        )
+                    }
+                    EmailVerification::WrongCode { attempts_left } => {
+                        format!("❌ Kod hatalı. {} deneme hakkın kaldı.", attempts_left)
+                    }
+                    EmailVerification::Expired => {
+                        "⌛ Kodun süresi doldu. Yeni kod için adresini tekrar yaz: eposta ornek@mail.com".to_string()
+                    }
+                    EmailVerification::NoPending => {
+                        "ℹ️ Bekleyen bir doğrulama yok. Önce adresini yaz: eposta ornek@mail.com".to_string()
+                    }
+                };
+                self.send_and_log(from, &reply).await
+            }
+            "kapat" | "sil" | "iptal" | "off" => {
+                self.db.clear_email(from).await?;
+                self.send_and_log(from, "🔕 E-posta raporları kapatıldı, adresin silindi.").await
+            }
+            _ => {
+                if let Some(frequency) = EmailReportFrequency::from_string(arg) {
+                    let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+                    if user.email.is_none() {
+                        return self.send_and_log(from, "ℹ️ Önce adresini ekle: eposta ornek@mail.com").await;
+                    }
+                    self.db.update_email_reports(from, frequency).await?;
+                    return self
+                        .send_and_log(
+                            from,
+                            &format!("✅ E-posta raporu: *{}* ({})", frequency.label(), email_report::schedule_text(frequency)),
+                        )
+                        .await;
+                }
+
+                let Some(email) = email_report::normalize_email(arg) else {
+                    return self.send_and_log(from, USAGE).await;
+                };
+                let Some(notifier) = self.notifier.clone().filter(|n| n.is_enabled()) else {
+                    return self.send_and_log(from, "😔 E-posta gönderimi şu an kullanılamıyor.").await;
+                };
+
+                let Some(code) = self.db.start_email_verification(from, &email).await? else {
+                    log::warn!("📧 Verification code limit reached for {} / {}", from, email);
+                    return self
+                        .send_and_log(from, "⏳ Son bir saatte çok fazla kod istendi. Biraz sonra tekrar dene.")
+                        .await;
+                };
+                if let Err(e) = email_report::send_verification_code(&notifier, &email, &code).await {
+                    log::error!("❌ Failed to send verification email for {}: {}", from, e);
+                    return self
+                        .send_and_log(from, "❌ Doğrulama e-postası gönderilemedi, adresi kontrol edip tekrar dene.")
+                        .await;
+                }
+                self.send_and_log(
+                    from,
+                    &format!(
+                        "📧 {} adresine 6 haneli bir kod gönderdik.\nOnaylamak için: *eposta kod 123456* ({} dk geçerli)",
+                        email,
+                        crate::services::database::EMAIL_CODE_VALID_MINUTES
+                    ),
+                ).await
+            }
+        }
+    }
+
+    /// `kisayol` / `kisayol sil` - quick-log API token for iOS Shortcuts
+    async fn handle_shortcut_command(&self, from: &str, parts: &[&str]) -> Result<()> {
+        if matches!(parts.get(1).copied(), Some("sil" | "iptal" | "kapat")) {
+            let revoked = self.db.revoke_quicklog_token(from).await?;
+            let message = if revoked {
+                "🔒 Kısayol anahtarın silindi. Eski kısayollar artık çalışmaz."
+            } else {
+                "ℹ️ Aktif bir kısayol anahtarın yok."
+            };
+            return self.send_and_log(from, message).await;
+        }
+
+        let token = self.db.create_quicklog_token(from).await?;
+        let base_url = std::env::var("PUBLIC_BASE_URL").unwrap_or_else(|_| "https://SUNUCU_ADRESI".to_string());
+        let message = format!(
+            "📲 *Kısayol Anahtarın*\n\n\
+             {}\n\n\
+             iOS Kestirmeler'de \"URL İçeriğini Al\" eylemi:\n\
+             • URL: {}/api/v1/quicklog\n\
+             • Yöntem: POST\n\
+             • Başlık: Authorization = Bearer {}\n\
+             • JSON: {{\"type\": \"water\", \"amount\": 250}}\n\
+             veya {{\"type\": \"meal\", \"description\": \"menemen\", \"calories\": 350}}\n\n\
+             ⚠️ Anahtarı kimseyle paylaşma. Yeni anahtar eskisini geçersiz kılar; silmek için: kisayol sil",
+            token,
+            base_url.trim_end_matches('/'),
+            token
+        );
+        // Anahtar konuşma geçmişine yazılmasın
+        self.whatsapp.send_message(from, &message).await?;
+        log::info!("📲 Quick-log token issued for {}", from);
+        Ok(())
+    }
+
+    /// Akıllı komut tespiti - slash olsun olmasın komutları tanır
+    /// Örnek: "rapor", "/rapor", "yardım", "yardim" hepsi çalışır
+    async fn try_handle_smart_command(&self, from: &str, message: &str) -> Result<bool> {
+        // Slash varsa kaldır
+        let clean_msg = message.trim_start_matches('/').trim_start_matches('!');
+        let parts: Vec<&str> = clean_msg.split_whitespace().collect();
+        let main_word = parts.first().unwrap_or(&"");
+
+        // Komut eşleştirmeleri - Türkçe karakterleri normalize et
+        let matched = match *main_word {
+            // Haftalık özet
+            "haftalik" | "haftalık" | "weekly" | "hafta" | "week" => {
+                let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+                let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+                let today = Utc::now().with_timezone(&user_tz).date_naive();
+
+                let days = self.db.get_weekly_stats(from, today).await?;
+                let mut response = crate::services::whatsapp::format_weekly_report(&days, user.units);
+                let since = Utc::now() - chrono::Duration::days(7);
+                let buckets = self.db.get_meal_hour_buckets(from, since, &user.timezone).await?;
+                response.push_str("\n\n");
+                response.push_str(&snacks::format_insights(&SnackInsights::from_buckets(&buckets), 7));
+                response.push_str("\n\n");
+                response.push_str("💡 Detaylı tavsiye için 'tavsiye' yaz");
+
+                self.send_and_log(from, &response).await?;
+                true
+            }
+            // Rapor komutları
+            "özet" | "ozet" | "summary" if parts.len() > 1 => {
+                self.handle_summary_command(from, &parts).await?;
+                true
+            }
+            "rapor" | "report" | "özet" | "ozet" | "summary" => {
+                let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+                let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+                let today = Utc::now().with_timezone(&user_tz).date_naive();
+                let stats = self.db.get_daily_stats(from, today).await?;
+                let streak = summary_sections::report_streak(&self.db, &user, today).await;
+                let report = crate::services::whatsapp::format_daily_report(
+                    &stats,
+                    user.daily_calorie_goal.unwrap_or(2000),
+                    user.daily_water_goal.unwrap_or(2000),
+                    user.units,
+                    &user.summary_sections,
+                    streak,
+                );
+                let report = benchmark::with_comparison(&self.db, &user, &stats, report).await;
+                self.send_and_log(from, &report).await?;
+                true
+            }
+            // Yardım komutları
+            "yardim" | "yardım" | "help" | "?" | "komutlar" | "commands" => {
+                self.send_help_message(from).await?;
+                true
+            }
+            // Geçmiş komutları - enhanced with water logs and summary
+            "gecmis" | "geçmiş" | "history" | "tarihçe" | "tarihce" => {
+                let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+                let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+                let today = Utc::now().with_timezone(&user_tz).date_naive();
+
+                let meals = self.db.get_recent_meals(from, 5).await?;
+                let stats = self.db.get_daily_stats(from, today).await?;
+                let water_goal = user.daily_water_goal.unwrap_or(2000);
+
+                if meals.is_empty() {
+                    self.send_and_log(from, "📜 Henüz kayıtlı öğün yok.").await?;
+                } else {
+                    let mut response = "📜 *Son Aktiviteler*\n\n".to_string();
+
+                    // Show today's summary first
+                    response.push_str("📊 *Bugün*\n");
+                    response.push_str(&format!("🍽️ Kalori: {:.0} kcal\n", stats.total_calories));
+                    response.push_str(&format!(
+                        "💧 Su: {} / {}\n\n",
+                        format_water(stats.total_water_ml, user.units),
+                        format_water(water_goal as i64, user.units)
+                    ));
+
+                    response.push_str("🍽️ *Son Öğünler*\n\n");
+                    for (i, meal) in meals.iter().enumerate() {
+                        response.push_str(&format!(
+                            "{}. *{}* • {:.0} kcal\n{}\n📅 {}\n\n",
+                            i + 1,
+                            meal.meal_type,
+                            meal.calories,
+                            meal.description.lines().next().unwrap_or(&meal.description),
+                            meal.created_at.format("%d.%m %H:%M")
+                        ));
+                    }
+
+                    response.push_str("💡 *İpucu:* Detaylı rapor için 'rapor' yaz");
+                    self.send_and_log(from, &response).await?;
+                }
+                true
+            }
+            // Son öğünün türünü düzelt: "duzelt kahvaltı"
+            "duzelt" | "düzelt" if parts.len() > 1 => {
+                self.handle_meal_type_correction(from, parts[1]).await?;
+                true
+            }
+            // Son öğünün kısaltılmamış AI analizi
+            "detay" | "detail" | "details" | "detaylar" => {
+                let meals = self.db.get_recent_meals(from, 1).await?;
+                let response = match meals.first() {
+                    Some(meal) => format!(
+                        "🔍 *Son Öğün Analizi* ({}, {:.0} kcal)\n\n{}",
+                        meal.meal_type,
+                        meal.calories,
+                        meal.full_description.as_deref().unwrap_or(&meal.description)
+                    ),
+                    None => "📜 Henüz kayıtlı öğün yok.".to_string(),
+                };
+                self.send_and_log(from, &response).await?;
+                true
+            }
+            // Tavsiye komutları
+            "tavsiye" | "öneri" | "oneri" | "advice" | "tip" | "tips" if self.openai.is_text_only() => {
+                self.send_and_log(from, &format!("{}\n\n💡 Şimdilik 'rapor' ile günlük özetine bakabilirsin.", TEXT_ONLY_NOTICE)).await?;
+                true
+            }
+            "tavsiye" | "öneri" | "oneri" | "advice" | "tip" | "tips" => {
+                // Kullanıcı bilgilerini tek seferde al (hem timezone hem de water_goal için)
+                let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+                let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+                let today = Utc::now().with_timezone(&user_tz).date_naive();
+                let stats = self.db.get_daily_stats(from, today).await?;
+                let water_goal = user.daily_water_goal.unwrap_or(2000);
+
+                match self
+                    .openai
+                    .get_nutrition_advice(
+                        stats.total_calories,
+                        stats.total_water_ml,
+                        water_goal,
+                        stats.meals_count,
+                        user.language
+                    )
+                    .await
+                {
+                    Ok(advice) => {
+                        self.send_and_log(from, &advice).await?;
+                    }
+                    Err(e) => {
+                        log::error!("❌ Failed to get nutrition advice: {:?}", e);
+                        log::error!("❌ Error details: {}", e);
+
+                        // Provide more user-friendly error messages
+                        let error_msg = if e.to_string().contains("moderation") {
+                            "⚠️ AI hizmeti geçici olarak kullanılamıyor (içerik moderasyonu hatası). Lütfen daha sonra tekrar deneyin."
+                        } else if e.to_string().contains("Rate limit") {
+                            "⚠️ Çok fazla istek gönderildi. Lütfen birkaç dakika sonra tekrar deneyin."
+                        } else {
+                            "⚠️ Şu anda tavsiye alınamıyor. Lütfen daha sonra tekrar deneyin."
+                        };
+
+                        self.whatsapp
+                            .send_message(from, error_msg)
+                            .await?;
+                    }
+                }
+                true
+            }
+            // Ayarlar komutları
+            "ayarlar" | "settings" | "ayar" | "setting" => {
+                self.handle_settings_command(from).await?;
+                true
+            }
+            "kurulum" | "setup" | "sihirbaz" => {
+                let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+                self.onboarding_handler().start_wizard(&user).await?;
+                true
+            }
+            // Buton komutları kaldırıldı - text tabanlı su kaydı çalışıyor
+            // Saat komutları
+            "saat" | "time" => {
+                self.handle_time_command(from, &parts).await?;
+                true
+            }
+            // Timezone komutları
+            "timezone" | "tz" | "zamandilimi" => {
+                self.handle_timezone_command(from, &parts).await?;
+                true
+            }
+            // Su hedefi komutları
+            "suaraligi" | "suaralığı" | "suaralik" | "waterinterval" => {
+                self.handle_water_interval_command(from, &parts).await?;
+                true
+            }
+            "suhedefi" | "watergoal" | "suhedfi" => {
+                self.handle_water_goal_command(from, &parts).await?;
+                true
+            }
+            // Kalori hedefi komutları
+            "kalorihedefi" | "caloriegoal" | "kalorihedfi" => {
+                self.handle_calorie_goal_command(from, &parts).await?;
+                true
+            }
+            // Sessiz saatler komutları
+            "sessiz" | "silent" | "silentsaatler" => {
+                self.handle_silent_hours_command(from, &parts).await?;
+                true
+            }
+            // Koç/diyetisyen paylaşım onayı
+            "koc" | "koç" | "coach" | "diyetisyen" => {
+                self.handle_coach_command(from, &parts).await?;
+                true
+            }
+            // Manuel öğün kaydı (AI kapalıyken de çalışır)
+            "ogun" if parts.len() > 1 => {
+                self.handle_manual_meal_command(from, &parts).await?;
+                true
+            }
+            // "su 250" / "su 8oz" - sayı yoksa doğal dil olarak AI'a bırak ("su içtim")
+            "su" | "water" if parts.get(1).is_some_and(|p| units::parse_water_ml(p, UnitSystem::Metric).is_some()) => {
+                let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+                let amount = units::parse_water_ml(parts[1], user.units).unwrap_or(250);
+                if !(50..=3000).contains(&amount) {
+                    self.send_and_log(
+                        from,
+                        &format!("❌ Su miktarı {} arasında olmalı.", units::water_range(50, 3000, user.units)),
+                    ).await?;
+                } else {
+                    self.handle_water_log_with_amount(from, amount).await?;
+                }
+                true
+            }
+            "durum" | "status" => {
+                self.handle_status_command(from).await?;
+                true
+            }
+            "eposta" | "e-posta" | "email" | "mail" => {
+                self.handle_email_command(from, &parts).await?;
+                true
+            }
+            "kisayol" | "kısayol" | "shortcut" => {
+                self.handle_shortcut_command(from, &parts).await?;
+                true
+            }
+            // Tek kelime: "atıştırma olarak cips yedim" öğün kaydı olarak AI'a gitmeli
+            "atistirma" | "atıştırma" | "atistirmalar" | "atıştırmalar" | "snacks" if parts.len() == 1 => {
+                let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+                let since = Utc::now() - chrono::Duration::days(snacks::SNACK_INSIGHT_DAYS);
+                let buckets = self.db.get_meal_hour_buckets(from, since, &user.timezone).await?;
+                let insights = SnackInsights::from_buckets(&buckets);
+                self.send_and_log(from, &snacks::format_insights(&insights, snacks::SNACK_INSIGHT_DAYS)).await?;
+                true
+            }
+            "fotolari" | "fotoları" | "fotograflari" | "fotoğrafları" | "resimleri" | "photos"
+                if parts.get(1).is_some_and(|p| matches!(*p, "sil" | "delete")) =>
+            {
+                self.handle_delete_photos_command(from).await?;
+                true
+            }
+            "butce" | "bütçe" | "budget" => {
+                self.handle_meal_budget_command(from, &parts).await?;
+                true
+            }
+            "birim" | "birimler" | "units" => {
+                self.handle_units_command(from, &parts).await?;
+                true
+            }
+            "dil" | "language" => {
+                self.handle_language_command(from, &parts).await?;
+                true
+            }
+            "kiyas" | "kıyas" | "karsilastir" | "karşılaştır" | "benchmark" => {
+                self.handle_benchmark_command(from, &parts).await?;
+                true
+            }
+            _ => false,
+        };
+
+        Ok(matched)
+    }
+
+    /// "duzelt ogle": son öğünün türünü düzelt ve düzeltmeyi öğün tespitinin öğrenmesi için kaydet
+    async fn handle_meal_type_correction(&self, from: &str, type_word: &str) -> Result<()> {
+        let Some(corrected) = parse_meal_type_word(type_word) else {
+            self.send_and_log(from, "❌ Kullanım: duzelt [kahvalti|ogle|aksam|ara]").await?;
+            return Ok(());
+        };
+
+        let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+        let Some(meal) = self.db.get_recent_meals(from, 1).await?.into_iter().next() else {
+            self.send_and_log(from, "📜 Henüz kayıtlı öğün yok.").await?;
+            return Ok(());
+        };
+
+        if meal.meal_type == corrected {
+            self.send_and_log(from, &format!("👍 Son öğün zaten {} olarak kayıtlı.", corrected)).await?;
+            return Ok(());
+        }
+
+        let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+        let local_time = meal.created_at.with_timezone(&user_tz).time();
+        let correction = MealTypeCorrection {
+            detected: meal.meal_type.clone(),
+            corrected: corrected.clone(),
+            local_minutes: local_time.num_seconds_from_midnight() / 60,
+        };
+
+        if let Some(meal_id) = meal.id {
+            self.db.update_meal_type(meal_id, &corrected).await?;
+        }
+        self.db.record_meal_type_correction(from, &correction).await?;
+        log::info!("🧠 Meal type corrected for {}: {} -> {} at {}", from, correction.detected, corrected, local_time.format("%H:%M"));
+
+        self.send_and_log(
+            from,
+            &format!(
+                "✅ Son öğün {} → *{}* olarak düzeltildi.\nBundan sonraki tespitlerimi alışkanlıklarına göre ayarlayacağım.",
+                correction.detected, corrected
+            ),
+        ).await?;
+        Ok(())
+    }
+
+    async fn handle_settings_command(&self, from: &str) -> Result<()> {
+        let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+
+        let breakfast_time = user.breakfast_time.unwrap_or_else(|| "Ayarlanmamış".to_string());
+        let lunch_time = user.lunch_time.unwrap_or_else(|| "Ayarlanmamış".to_string());
+        let dinner_time = user.dinner_time.unwrap_or_else(|| "Ayarlanmamış".to_string());
+
+        let breakfast_status = if user.breakfast_reminder { "✅" } else { "❌" };
+        let lunch_status = if user.lunch_reminder { "✅" } else { "❌" };
+        let dinner_status = if user.dinner_reminder { "✅" } else { "❌" };
+        let water_status = if user.water_reminder { "✅" } else { "❌" };
+
+        let water_goal = user.daily_water_goal.unwrap_or(2000);
+        let calorie_goal = user.daily_calorie_goal.unwrap_or(2000);
+        let silent_start = user.silent_hours_start.as_deref().unwrap_or("23:00");
+        let silent_end = user.silent_hours_end.as_deref().unwrap_or("07:00");
+        let summary_time = match user.daily_summary_time.as_deref() {
+            Some(time) if user.summary_narrative => format!("{} (anlatı)", time),
+            Some(time) => time.to_string(),
+            None => "❌ Kapalı".to_string(),
+        };
+
+        let message = format!(
+            "⚙️ *Ayarlarınız*\n\n\
+             🕐 *Öğün Saatleri*\n\
+             Kahvaltı: {} {}\n\
+             Öğle: {} {}\n\
+             Akşam: {} {}\n\n\
+             🎯 *Günlük Hedefler*\n\
+             {} kcal kalori\n\
+             {} su\n\n\
+             💧 *Su Hatırlatma*\n\
+             {} {}, son su kaydından itibaren (08:00-22:00)\n\n\
+             🌙 *Sessiz Saatler*\n\
+             {} - {}\n\n\
+             📊 *Günlük Özet*\n\
+             {}\n\n\
+             🌍 *Zaman Dilimi*\n\
+             {}\n\n\
+             📏 *Birim*\n\
+             {}\n\n\
+             🌐 *AI Dili*\n\
+             {}\n\n\
+             📧 *E-posta Raporu*\n\
+             {}\n\n\
+             *Değiştirmek için:*\n\
+             kalorihedefi 2500\n\
+             suhedefi 3000\n\
+             suaraligi 3\n\
+             sessiz 23:00 07:00\n\
+             ozet saat 21:00 / ozet icerik / ozet anlati\n\
+             saat kahvalti 09:00\n\
+             timezone Europe/Istanbul\n\
+             birim us / birim metrik\n\
+             dil en / dil tr\n\
+             eposta ornek@mail.com",
+            breakfast_time, breakfast_status,
+            lunch_time, lunch_status,
+            dinner_time, dinner_status,
+            calorie_goal,
+            match user.units {
+                UnitSystem::Metric => format!("{} ml ({:.1}L)", water_goal, water_goal as f64 / 1000.0),
+                UnitSystem::Us => format_water(water_goal as i64, user.units),
+            },
+            water_status,
+            water_interval_label(user.water_reminder_interval),
+            silent_start,
+            silent_end,
+            summary_time,
+            user.timezone,
+            units_label(user.units),
+            user.language.label(),
+            match &user.email {
+                Some(email) => format!("{} ({})", email, user.email_reports.label()),
+                None => "❌ Kapalı".to_string(),
+            }
+        );
+
+        self.send_and_log(from, &message).await?;
+        Ok(())
+    }
+
+    async fn handle_time_command(&self, from: &str, cmd_parts: &[&str]) -> Result<()> {
+        if cmd_parts.len() < 3 {
+            self.send_and_log(
+                from,
+                "❌ Kullanım: saat [kahvalti|ogle|aksam] HH:MM\nÖrnek: saat kahvalti 09:00"
+            ).await?;
+            return Ok(());
+        }
+
+        let meal_type = cmd_parts[1].to_lowercase();
+        let time = cmd_parts[2];
+
+        // Validate time format (HH:MM) with proper hour/minute range checks
+        if !self.validate_time_format(time) {
+            self.send_and_log(
+                from,
+                "❌ Geçersiz saat formatı\nHH:MM olmalı (örn: 09:00, 13:30)"
+            ).await?;
+            return Ok(());
+        }
+
+        let meal_type_db = match meal_type.as_str() {
+            "kahvalti" | "kahvaltı" | "breakfast" => "breakfast",
+            "ogle" | "öğle" | "lunch" => "lunch",
+            "aksam" | "akşam" | "dinner" => "dinner",
+            _ => {
+                self.send_and_log(
+                    from,
+                    "❌ Geçersiz öğün tipi. Kullan: kahvalti, ogle, aksam"
+                ).await?;
+                return Ok(());
+            }
+        };
+
+        self.db.update_meal_time(from, meal_type_db, time).await?;
+
+        let meal_display = match meal_type_db {
+            "breakfast" => "Kahvaltı",
+            "lunch" => "Öğle yemeği",
+            "dinner" => "Akşam yemeği",
+            _ => "Öğün"
+        };
+
+        self.send_and_log(
+            from,
+            &format!("✅ {} saati {} olarak güncellendi!", meal_display, time)
+        ).await?;
+
+        Ok(())
+    }
+
+    /// ozet saat HH:MM / ozet kapat / ozet ac / ozet icerik [bölümler] / ozet anlati [kapat]
+    async fn handle_summary_command(&self, from: &str, cmd_parts: &[&str]) -> Result<()> {
+        match (cmd_parts.get(1).copied(), cmd_parts.get(2).copied()) {
+            (Some("saat" | "time"), Some(time)) => {
+                if !self.validate_time_format(time) || !(time.ends_with(":00") || time.ends_with(":30")) {
+                    self.send_and_log(
+                        from,
+                        "❌ Geçersiz saat\nÖzet saati HH:00 veya HH:30 olmalı (örn: 21:00, 21:30)"
+                    ).await?;
+                    return Ok(());
+                }
+
+                let time = normalize_time(time);
+                self.db.update_daily_summary_time(from, Some(&time)).await?;
+                self.send_and_log(from, &format!("✅ Günlük özet her gün {} saatinde gönderilecek.", time)).await?;
+            }
+            (Some("kapat" | "off"), _) => {
+                self.db.update_daily_summary_time(from, None).await?;
+                self.send_and_log(
+                    from,
+                    "🔕 Günlük özet kapatıldı.\nTekrar açmak için: ozet ac\nİstediğin zaman 'rapor' yazabilirsin."
+                ).await?;
+            }
+            (Some("icerik" | "içerik" | "bolum" | "bölüm"), None) => {
+                let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+                self.send_and_log(
+                    from,
+                    &format!(
+                        "📊 *Özet İçeriği*\n\n{}\n\n\
+                         Görmek istediğin bölümleri yaz:\n\
+                         ozet icerik kalori su seri\n\
+                         Hepsini geri açmak için: ozet icerik hepsi",
+                        summary_sections::format_sections(&user.summary_sections)
+                    ),
+                ).await?;
+            }
+            (Some("icerik" | "içerik" | "bolum" | "bölüm"), Some(_)) => {
+                let Some(sections) = summary_sections::parse_sections(&cmd_parts[2..].join(" ")) else {
+                    self.send_and_log(
+                        from,
+                        "❌ Bölümleri anlayamadım\nSeçenekler: kalori, su, besin, seri, ipucu (veya hepsi)\nÖrnek: ozet icerik kalori su ipucu"
+                    ).await?;
+                    return Ok(());
+                };
+                self.db.update_summary_sections(from, &sections).await?;
+                self.send_and_log(
+                    from,
+                    &format!("✅ Günlük özet ve rapor artık şunları gösterecek:\n\n{}", summary_sections::format_sections(&sections)),
+                ).await?;
+            }
+            (Some("anlati" | "anlatı" | "hikaye" | "story"), None | Some("ac" | "aç" | "on")) => {
+                self.db.update_summary_narrative(from, true).await?;
+                self.send_and_log(
+                    from,
+                    "📖 Günlük özet artık kısa bir anlatı olarak gelecek: öğünlerin, suyun, serin ve yarın için tek bir öneri.\n\
+                     AI'a ulaşılamazsa sayısal özet gönderilir.\n\
+                     Eski haline dönmek için: ozet sayisal",
+                ).await?;
+            }
+            (Some("anlati" | "anlatı" | "hikaye" | "story"), Some("kapat" | "off")) | (Some("sayisal" | "sayısal" | "numbers"), _) => {
+                self.db.update_summary_narrative(from, false).await?;
+                self.send_and_log(from, "📊 Günlük özet tekrar sayısal olarak gelecek.").await?;
+            }
+            (Some("ac" | "aç" | "on"), _) => {
+                self.db.update_daily_summary_time(from, Some("22:00")).await?;
+                self.send_and_log(from, "🔔 Günlük özet açıldı (22:00).\nSaati değiştirmek için: ozet saat 21:00").await?;
+            }
+            _ => {
+                self.send_and_log(
+                    from,
+                    "❌ Kullanım:\nozet saat 21:00 - Özet saatini değiştir\nozet kapat - Günlük özeti kapat\nozet ac - Tekrar aç\nozet icerik - Gösterilecek bölümler\nozet anlati - Özeti AI kısa bir anlatı olarak yazsın"
+                ).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn handle_timezone_command(&self, from: &str, cmd_parts: &[&str]) -> Result<()> {
+        if cmd_parts.len() < 2 {
+            self.send_and_log(
+                from,
+                "❌ Kullanım: timezone [zaman dilimi]\n\n\
+                 Örnekler:\n\
+                 timezone Europe/Istanbul\n\
+                 timezone America/New_York\n\
+                 timezone Asia/Tokyo"
+            ).await?;
+            return Ok(());
+        }
+
+        let timezone = cmd_parts[1];
+
+        // Validate timezone by trying to parse it
+        match timezone.parse::<chrono_tz::Tz>() {
+            Ok(_) => {
+                // Valid timezone, update in database
+                self.db.update_timezone(from, timezone).await?;
+
+                self.send_and_log(
+                    from,
+                    &format!("✅ Zaman diliminiz {} olarak güncellendi!", timezone)
+                ).await?;
+            }
+            Err(_) => {
+                self.send_and_log(
+                    from,
+                    &format!("❌ Geçersiz zaman dilimi: {}\n\nÖrnek: Europe/Istanbul", timezone)
+                ).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `butce` - öğün bazlı dağılımı göster, `butce 25 35 30 10` - ayarla, `butce sifirla` - varsayılan
+    async fn handle_meal_budget_command(&self, from: &str, parts: &[&str]) -> Result<()> {
+        let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+
+        let budget = match parts.get(1).copied() {
+            None => MealBudget::from_stored(user.meal_budget.as_deref()),
+            Some("sifirla" | "sıfırla" | "reset") => {
+                self.db.update_meal_budget(from, None).await?;
+                MealBudget::default()
+            }
+            Some(_) => match MealBudget::parse(&parts[1..].join(" ")) {
+                Some(budget) => {
+                    self.db.update_meal_budget(from, Some(&budget.to_stored())).await?;
+                    budget
+                }
+                None => {
+                    self.send_and_log(
+                        from,
+                        "❌ Kullanım: butce [kahvaltı] [öğle] [akşam] [ara öğün]\n\
+                         Yüzdeler toplamı 100 olmalı. Örnek: butce 25 35 30 10",
+                    ).await?;
+                    return Ok(());
+                }
+            },
+        };
+
+        let goal = user.daily_calorie_goal.unwrap_or(2000);
+        let mut response = format!("🎯 *Öğün Bütçesi* (günlük {} kcal)\n\n", goal);
+        for meal_type in [MealType::Breakfast, MealType::Lunch, MealType::Dinner, MealType::Snack] {
+            response.push_str(&format!(
+                "{}: %{} • {:.0} kcal\n",
+                meal_type,
+                budget.share(&meal_type),
+                budget.slot_calories(&meal_type, goal)
+            ));
+        }
+        response.push_str("\nDeğiştirmek için: butce 25 35 30 10\nVarsayılana dön: butce sifirla");
+
+        self.send_and_log(from, &response).await?;
+        Ok(())
+    }
+
+    /// `birim` - mevcut birim, `birim us` / `birim metrik` - değiştir
+    async fn handle_units_command(&self, from: &str, parts: &[&str]) -> Result<()> {
+        let Some(arg) = parts.get(1) else {
+            let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+            self.send_and_log(
+                from,
+                &format!(
+                    "📏 Birim: *{}*\n\nDeğiştirmek için:\n• birim us (oz / lb)\n• birim metrik (ml / kg)",
+                    units_label(user.units)
+                ),
+            ).await?;
+            return Ok(());
+        };
+
+        let Some(new_units) = UnitSystem::from_string(arg) else {
+            self.send_and_log(from, "❌ Kullanım: birim us | birim metrik").await?;
+            return Ok(());
+        };
+
+        self.db.update_units(from, new_units).await?;
+        let example = match new_units {
+            UnitSystem::Metric => "su 250",
+            UnitSystem::Us => "su 8 (oz)",
+        };
+        self.send_and_log(
+            from,
+            &format!(
+                "✅ Birim *{}* olarak ayarlandı.\nRaporlar ve hedefler bu birimle gösterilecek. Örnek: {}",
+                units_label(new_units),
+                example
+            ),
+        ).await?;
+        Ok(())
+    }
+
+    /// `dil` - AI cevap dili, `dil en` / `dil tr` - değiştir
+    async fn handle_language_command(&self, from: &str, parts: &[&str]) -> Result<()> {
+        let Some(arg) = parts.get(1) else {
+            let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+            self.send_and_log(
+                from,
+                &format!(
+                    "🌐 AI dili: *{}*\n\nDeğiştirmek için:\n• dil en (English)\n• dil tr (Türkçe)",
+                    user.language.label()
+                ),
+            ).await?;
+            return Ok(());
+        };
+
+        let Some(language) = Language::from_string(arg) else {
+            self.send_and_log(from, "❌ Kullanım: dil en | dil tr").await?;
+            return Ok(());
+        };
+
+        self.db.update_language(from, language).await?;
+        self.send_and_log(
+            from,
+            &format!(
+                "✅ AI dili *{}* olarak ayarlandı.\nÖğün analizleri ve tavsiyeler bu dilde gelecek.",
+                language.label()
+            ),
+        ).await?;
+        Ok(())
+    }
+
+    /// "suaraligi 3": son su kaydından bu kadar saat geçince hatırlat; "suaraligi kapat" hatırlatmayı kapatır
+    async fn handle_water_interval_command(&self, from: &str, parts: &[&str]) -> Result<()> {
+        let Some(arg) = parts.get(1).copied() else {
+            let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+            let current = if user.water_reminder {
+                water_interval_label(user.water_reminder_interval)
+            } else {
+                "❌ Kapalı".to_string()
+            };
+            self.send_and_log(
+                from,
+                &format!(
+                    "💧 Su hatırlatma: *{}*\n\
+                     Son su kaydından bu kadar süre geçince hatırlatırım (08:00-22:00).\n\n\
+                     Değiştirmek için: suaraligi [1-{}] / suaraligi kapat",
+                    current, MAX_WATER_INTERVAL_HOURS
+                ),
+            ).await?;
+            return Ok(());
+        };
+
+        if matches!(arg, "kapat" | "off" | "iptal") {
+            self.db.update_water_reminder(from, false, None).await?;
+            self.send_and_log(from, "🔕 Su hatırlatmaları kapatıldı. Açmak için: suaraligi 2").await?;
+            return Ok(());
+        }
+
+        let Some(hours) = arg
+            .trim_end_matches(|c: char| c.is_alphabetic())
+            .parse::<i32>()
+            .ok()
+            .filter(|h| (1..=MAX_WATER_INTERVAL_HOURS).contains(h))
+        else {
+            self.send_and_log(
+                from,
+                &format!("❌ Kullanım: suaraligi [1-{}] (saat)\nÖrnek: suaraligi 3", MAX_WATER_INTERVAL_HOURS),
+            ).await?;
+            return Ok(());
+        };
+
+        self.db.update_water_reminder(from, true, Some(hours)).await?;
+        self.send_and_log(
+            from,
+            &format!(
+                "✅ Su hatırlatma: *{}*\n\
+                 Son su kaydından {} saat geçince hatırlatacağım; su içtikçe sayaç sıfırlanır.",
+                water_interval_label(hours).to_lowercase(),
+                hours
+            ),
+        ).await?;
+        Ok(())
+    }
+
+    async fn handle_water_goal_command(&self, from: &str, cmd_parts: &[&str]) -> Result<()> {
+        if cmd_parts.len() < 2 {
+            self.send_and_log(
+                from,
+                "❌ Kullanım: suhedefi [ml]\nÖrnek: suhedefi 2500"
+            ).await?;
+            return Ok(());
+        }
+
+        let goal_str = cmd_parts[1];
+        let user_units = self.db.get_user(from).await?.map(|u| u.units).unwrap_or_default();
+        match units::parse_water_ml(goal_str, user_units) {
+            Some(goal) if (500..=10000).contains(&goal) => {
+                self.db.update_water_goal(from, goal, GoalChangeSource::Command).await?;
+
+                let message = match user_units {
+                    UnitSystem::Metric => format!(
+                        "✅ Günlük su hedefiniz {} ml ({} litre) olarak güncellendi!",
+                        goal,
+                        goal as f64 / 1000.0
+                    ),
+                    UnitSystem::Us => format!(
+                        "✅ Günlük su hedefiniz {} olarak güncellendi!",
+                        format_water(goal as i64, user_units)
+                    ),
+                };
+                self.send_and_log(from, &message).await?;
+            }
+            Some(goal) => {
+                self.send_and_log(
+                    from,
+                    &format!(
+                        "❌ Geçersiz hedef: {}\nLütfen {} arası bir değer girin.",
+                        format_water(goal as i64, user_units),
+                        units::water_range(500, 10000, user_units)
+                    )
+                ).await?;
+            }
+            None => {
+                self.send_and_log(
+                    from,
+                    &format!("❌ Geçersiz sayı: {}\nLütfen sayı girin (örn: 2000)", goal_str)
+                ).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn send_help_message(&self, to: &str) -> Result<()> {
+        let help = "📱 *Beslenme Takip Botu*\n\n\
+                   *🍽️ Yemek Kaydet*\n\
+                   Sadece yaz:\n\
+                   • \"kahvaltı yaptım\"\n\
+                   • \"pizza yedim\"\n\
+                   • \"tavuk göğsü ve salata\"\n\
+                   • Fotoğraf gönder\n\n\
+                   *💧 Su Kaydet*\n\
+                   Sadece yaz:\n\
+                   • \"su içtim\"\n\
+                   • \"250 ml içtim\"\n\
+                   • \"1 bardak su\"\n\
+                   • 1, 2, 3 (200/250/500ml)\n\n\
+                   *📊 Raporlar*\n\
+                   rapor - Bugünün özeti\n\
+                   ozet saat 21:00 / ozet kapat / ozet icerik - Günlük özet\n\
+                   ozet anlati - Günlük özeti AI kısa bir anlatı olarak yazsın\n\
+                   geçmiş - Son aktiviteler\n\
+                   detay - Son öğünün tam analizi\n\
+                   duzelt ogle - Son öğünün türünü düzelt\n\
+                   haftalık - 7 günlük trend\n\
+                   atıştırma - Ara öğün alışkanlıkların\n\
+                   tavsiye - AI önerisi\n\n\
+                   *🎯 Hedefler & Ayarlar*\n\
+                   ayarlar - Tüm ayarları gör\n\
+                   kurulum - Ayar sihirbazı (saatler, hedefler, hatırlatmalar)\n\
+                   durum - Bot/AI durumu ve kalan haklar\n\
+                   koc - Diyetisyen paylaşımı\n\
+                   kiyas - İnsan ortalaması karşılaştırması\n\
+                   butce - Kalori hedefinin öğünlere dağılımı\n\
+                   suaraligi 3 - Son sudan kaç saat sonra hatırlatayım\n\
+                   birim us / birim metrik - oz/lb veya ml/kg\n\
+                   dil en / dil tr - AI analizlerinin dili\n\
+                   fotoları sil - Kayıtlı fotoğrafları sil (kaloriler kalır)\n\
+                   kisayol - iOS Kestirmeler/widget ile hızlı kayıt anahtarı\n\
+                   eposta - Haftalık/aylık raporu e-postayla al\n\
+                   hata bildir [açıklama] - Sorun bildir\n\n\
+                   Doğal dil ile değiştir:\n\
+                   • \"kalori hedefim 2500\"\n\
+                   • \"su hedefim 3 litre\"\n\
+                   • \"kahvaltı saatim 9\"\n\
+                   • \"sessiz saat 23-7\"\n\n\
+                   *💡 İpucu:* Normal konuşarak mesaj at!";
+
+        self.send_and_log(to, help).await?;
+        Ok(())
+    }
+
+    fn validate_time_format(&self, time: &str) -> bool {
+        // HH:MM formatını kontrol et
+        let parts: Vec<&str> = time.split(':').collect();
+        if parts.len() != 2 {
+            return false;
+        }
+
+        let hour = parts[0].parse::<u32>();
+        let minute = parts[1].parse::<u32>();
+
+        match (hour, minute) {
+            (Ok(h), Ok(m)) => h < 24 && m < 60,
+            _ => false,
+        }
+    }
+
+    // ============================================================
+    // New Command Handlers
+    // ============================================================
+
+    async fn handle_calorie_goal_command(&self, from: &str, parts: &[&str]) -> Result<()> {
+        if parts.len() < 2 {
+            let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+            let current_goal = user.daily_calorie_goal.unwrap_or(2000);
+            self.send_and_log(
+                from,
+                &format!(
+                    "🎯 *Günlük Kalori Hedefi*\n\n\
+                     Mevcut hedefiniz: {} kcal\n\n\
+                     Değiştirmek için:\n\
+                     `kalorihedefi [miktar]`\n\n\
+                     Örnek: kalorihedefi 2500",
+                    current_goal
+                )
+            ).await?;
+            return Ok(());
+        }
+
+        let goal: i32 = parts[1].parse().map_err(|_| anyhow::anyhow!("Geçersiz sayı"))?;
+
+        if !(500..=5000).contains(&goal) {
+            self.send_and_log(
+                from,
+                "❌ Kalori hedefi 500-5000 kcal arasında olmalıdır."
+            ).await?;
+            return Ok(());
+        }
+
+        self.db.update_calorie_goal(from, goal, GoalChangeSource::Command).await?;
+        self.send_and_log(
+            from,
+            &format!("✅ Günlük kalori hedefiniz {} kcal olarak güncellendi!", goal)
+        ).await?;
+
+        Ok(())
+    }
+
+    async fn handle_coach_command(&self, from: &str, parts: &[&str]) -> Result<()> {
+        let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+
+        let coach_phone = match user.coach_phone {
+            Some(ref phone) => phone.clone(),
+            None => {
+                self.send_and_log(from, "👩‍⚕️ Hesabına bağlı bir diyetisyen/koç bulunmuyor.").await?;
+                return Ok(());
+            }
+        };
+
+        match parts.get(1).copied() {
+            Some("ac" | "aç" | "on") => {
+                self.db.update_coach_sharing(from, true).await?;
+                self.send_and_log(
+                    from,
+                    &format!("✅ Haftalık özetlerin koçunla ({}) paylaşılacak.\nKapatmak için: koc kapat", coach_phone)
+                ).await?;
+            }
+            Some("kapat" | "off") => {
+                self.db.update_coach_sharing(from, false).await?;
+                self.send_and_log(from, "🔒 Koçunla paylaşım kapatıldı.").await?;
+            }
+            _ => {
+                let status = if user.coach_sharing { "✅ Açık" } else { "❌ Kapalı" };
+                self.send_and_log(
+                    from,
+                    &format!(
+                        "👩‍⚕️ *Koç Paylaşımı*\n\n\
+                         Koç: {}\n\
+                         Paylaşım: {}\n\n\
+                         Açıkken her pazar akşamı haftalık özetin koçuna gönderilir.\n\n\
+                         koc ac - Paylaşımı aç\n\
+                         koc kapat - Paylaşımı kapat",
+                        coach_phone, status
+                    )
+                ).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Kullanıcıya bir şeyler neden çalışmıyor olabilir açıkla: AI durumu, resim hakkı, 24 saat penceresi
+    async fn handle_status_command(&self, from: &str) -> Result<()> {
+        let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+        let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+        let today = Utc::now().with_timezone(&user_tz).date_naive();
+
+        let ai_status = if self.openai.is_text_only() {
+            "⏸️ Kapalı (sadece metin modu - manuel kayıt, rapor ve hatırlatmalar çalışıyor)".to_string()
+        } else {
+            match self.openai.breaker_state() {
+                BreakerState::Closed => "✅ Normal".to_string(),
+                BreakerState::HalfOpen => "⚠️ Toparlanıyor, yanıtlar gecikebilir".to_string(),
+                BreakerState::Open { retry_in } => format!(
+                    "🔴 Geçici kesinti, yaklaşık {} sn sonra tekrar denenecek",
+                    retry_in.as_secs().max(1)
+                ),
+            }
+        };
+
+        let image_count = self.db.get_daily_image_count(from, today).await?;
+        let remaining_images = (DAILY_IMAGE_LIMIT - image_count).max(0);
+
+        // Bu mesajın log kaydı henüz kuyrukta olabilir; pencere kontrolünden önce yazdır
+        self.db.flush_conversation_log().await;
+        let window_status = if self.db.is_within_24h_window(from).await? {
+            "✅ Açık (son mesajından sonraki 24 saat hatırlatma alabilirsin)"
+        } else {
+            "❌ Kapalı (hatırlatmalar için bana mesaj atman yeterli)"
+        };
+
+        let message = format!(
+            "🩺 *Durum*\n\n\
+             🤖 AI analizi: {}\n\
+             📸 Kalan fotoğraf hakkı: {}/{}\n\
+             💬 Mesaj penceresi: {}",
+            ai_status, remaining_images, DAILY_IMAGE_LIMIT, window_status
+        );
+
+        self.send_and_log(from, &message).await?;
+        Ok(())
+    }
+
+    async fn handle_benchmark_command(&self, from: &str, parts: &[&str]) -> Result<()> {
+        match parts.get(1).copied() {
+            Some("ac" | "aç" | "on") => {
+                self.db.update_benchmark_opt_in(from, true).await?;
+                self.send_and_log(
+                    from,
+                    "✅ İnsan ortalaması karşılaştırması açıldı.\n\
+                     Günlük raporunda diğer kullanıcılarla anonim kıyaslama göreceksin.\n\
+                     Kapatmak için: kiyas kapat"
+                ).await?;
+            }
+            Some("kapat" | "off") => {
+                self.db.update_benchmark_opt_in(from, false).await?;
+                self.send_and_log(from, "🔒 Karşılaştırma kapatıldı, verilerin artık ortalamalara katılmayacak.").await?;
+            }
+            _ => {
+                let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+                let status = if user.benchmark_opt_in { "✅ Açık" } else { "❌ Kapalı" };
+                self.send_and_log(
+                    from,
+                    &format!(
+                        "👥 *İnsan Ortalaması*\n\n\
+                         Durum: {}\n\n\
+                         Açıkken günlük raporunda \"kullanıcıların %60'ından fazla su içtin\" gibi kıyaslamalar görürsün. \
+                         Karşılaştırma sadece katılan kullanıcıların toplu (anonim) istatistiklerinden hesaplanır; \
+                         kimsenin kişisel verisi paylaşılmaz.\n\n\
+                         kiyas ac - Katıl\n\
+                         kiyas kapat - Ayrıl",
+                        status
+                    )
+                ).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn handle_silent_hours_command(&self, from: &str, parts: &[&str]) -> Result<()> {
+        if parts.len() < 3 {
+            let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
+            let start = user.silent_hours_start.as_deref().unwrap_or("23:00");
+            let end = user.silent_hours_end.as_deref().unwrap_or("07:00");
+
+            self.send_and_log(
+                from,
+                &format!(
+                    "🌙 *Sessiz Saatler*\n\n\
+                     Mevcut ayarınız: {} - {}\n\n\
+                     Bu saatler arasında hatırlatma gönderilmez.\n\n\
+                     Değiştirmek için:\n\
+                     `sessiz [başlangıç] [bitiş]`\n\n\
+                     Örnek: sessiz 23:00 07:00",
+                    start, end
+                )
+            ).await?;
+            return Ok(());
+        }
+
+        let start = parts[1];
+        let end = parts[2];
+
+        if !self.validate_time_format(start) || !self.validate_time_format(end) {
+            self.send_and_log(
+                from,
+                "❌ Geçersiz saat formatı. HH:MM formatında girin.\nÖrnek: sessiz 23:00 07:00"
+            ).await?;
+            return Ok(());
+        }
+
+        self.db.update_silent_hours(from, start, end).await?;
+        self.send_and_log(
+            from,
+            &format!("✅ Sessiz saatleriniz {} - {} olarak güncellendi!", start, end)
+        ).await?;
+
+        Ok(())
+    }
+
+}
+
+/// "hata bildir fotoğraf yüklenmiyor" -> Some("fotoğraf yüklenmiyor"), "/hata" -> Some("")
+fn parse_bug_report(message: &str) -> Option<&str> {
+    let text = message.trim().trim_start_matches(['/', '!']);
+    let mut words = text.splitn(2, char::is_whitespace);
+    let first = words.next()?.to_lowercase();
+    let rest = words.next().unwrap_or("").trim_start();
+
+    if !matches!(first.as_str(), "hata" | "bug") {
+        return None;
+    }
+    if rest.is_empty() {
+        return Some("");
+    }
+
+    let mut rest_words = rest.splitn(2, char::is_whitespace);
+    let second = rest_words.next().unwrap_or("").to_lowercase();
+    match second.as_str() {
+        "bildir" | "bildirimi" | "report" => Some(rest_words.next().unwrap_or("").trim()),
+        // "hata ..." tek başına bir yemek ya da sohbet olabilir, sadece "hata bildir" komut sayılır
+        _ if first == "bug" => Some(rest.trim()),
+        _ => None,
+    }
+}
+
+fn render_bug_report_html(
+    report_id: i64,
+    phone: &str,
+    description: &str,
+    version: &str,
+    history: &[crate::models::Conversation],
+) -> String {
+    let escape = |text: &str| {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+
+    let rows: String = history
+        .iter()
+        .map(|c| {
+            format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                c.created_at.format("%d.%m %H:%M:%S"),
+                c.direction,
+                escape(&c.content)
+            )
+        })
+        .collect();
+
+    format!(
+        "<h2>Hata bildirimi #{}</h2>\
+         <p><b>Kullanıcı:</b> {}<br><b>Sürüm:</b> {}</p>\
+         <p><b>Açıklama:</b> {}</p>\
+         <table border=\"1\" cellpadding=\"4\">{}</table>",
+        report_id,
+        escape(phone),
+        version,
+        if description.is_empty() { "(yok)".to_string() } else { escape(description) },
+        rows
+    )
+}
+
+/// "slot:42:Öğle Yemeği" -> (42, Lunch)
+fn parse_slot_choice(pending: &str) -> Option<(i64, MealType)> {
+    let (meal_id, slot) = pending.strip_prefix(SLOT_CHOICE_PREFIX)?.split_once(':')?;
+    Some((meal_id.parse().ok()?, MealType::from_string(slot)?))
+}
+
+/// "kahvalti" / "öğle" / "aksam" / "ara" -> MealType (komut argümanı, ASCII yazım da kabul edilir)
+fn parse_meal_type_word(word: &str) -> Option<MealType> {
+    match word {
+        "kahvalti" | "kahvaltı" | "breakfast" => Some(MealType::Breakfast),
+        "ogle" | "öğle" | "lunch" => Some(MealType::Lunch),
+        "aksam" | "akşam" | "dinner" => Some(MealType::Dinner),
+        "ara" | "atistirma" | "atıştırma" | "snack" => Some(MealType::Snack),
+        _ => None,
+    }
+}
+
+/// Delete stored photo files (and thumbnails); already missing files count as removed. Returns how many are gone.
+fn remove_image_files(paths: &[String]) -> usize {
+    paths
+        .iter()
+        .filter(|path| match image_store::remove_image(std::path::Path::new(path)) {
+            Ok(()) => true,
+            Err(e) => {
+                log::error!("❌ Failed to delete image {}: {}", path, e);
+                false
+            }
+        })
+        .count()
+}
+
+fn units_label(units: UnitSystem) -> &'static str {
+    match units {
+        UnitSystem::Metric => "Metrik (ml / kg)",
+        UnitSystem::Us => "ABD (oz / lb)",
+    }
+}
+
+/// Açıklama kısaltıldıysa onay mesajının sonuna "detay" ipucu ekle
+fn detail_hint(calorie_info: &CalorieInfo) -> &'static str {
+    if calorie_info.full_description.is_some() {
+        "\n\n🔍 Tam analiz için 'detay' yaz"
+    } else {
+        ""
+    }
+}
+
+/// AI yokken / emin değilken yerleşik besin tablosundan kalori tahmini
+fn local_estimate(description: &str) -> Option<CalorieInfo> {
+    let estimate = food_lookup::estimate(description)?;
+    Some(CalorieInfo {
+        calories: estimate.calories,
+        description: format!("{}\n_(tahmini, yerleşik besin tablosundan)_", estimate.description()),
+        extras: Default::default(),
+        full_description: None,
+        low_confidence: false,
+    })
+}
+
+/// "9:00" -> "09:00" (format önceden validate_time_format ile doğrulanmış olmalı)
+fn normalize_time(time: &str) -> String {
+    match time.split_once(':') {
+        Some((h, m)) => format!("{:02}:{}", h.parse::<u32>().unwrap_or(0), m),
+        None => time.to_string(),
+    }
+}
+
+/// Entry from the quick-log API, already validated by the caller
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuickLog {
+    Water { ml: i32 },
+    Meal { description: String, calories: Option<f64> },
+}
+
+/// "tavuk göğsü 450" / "tavuk göğsü 450 kcal" -> ("tavuk göğsü", Some(450.0))
+fn parse_manual_meal(args: &[&str]) -> (String, Option<f64>) {
+    let mut words: Vec<&str> = args.to_vec();
+    if words.last().is_some_and(|w| matches!(*w, "kcal" | "kalori" | "cal")) {
+        words.pop();
+    }
+
+    let calories = words
+        .last()
+        .and_then(|w| w.trim_end_matches("kcal").replace(',', ".").parse::<f64>().ok())
+        .filter(|c| *c > 0.0 && *c <= 5000.0);
+    if calories.is_some() {
+        words.pop();
+    }
+
+    (words.join(" "), calories)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manual_meal() {
+        assert_eq!(parse_manual_meal(&["tavuk", "göğsü", "450"]), ("tavuk göğsü".to_string(), Some(450.0)));
+        assert_eq!(parse_manual_meal(&["menemen", "350", "kcal"]), ("menemen".to_string(), Some(350.0)));
+        assert_eq!(parse_manual_meal(&["pilav", "250kcal"]), ("pilav".to_string(), Some(250.0)));
+        assert_eq!(parse_manual_meal(&["mercimek", "çorbası"]), ("mercimek çorbası".to_string(), None));
+    }
+
+    #[test]
+    fn test_parse_slot_choice() {
+        let pending = format!("{}{}:{}", SLOT_CHOICE_PREFIX, 42, MealType::Lunch);
+        assert_eq!(parse_slot_choice(&pending), Some((42, MealType::Lunch)));
+        assert_eq!(parse_slot_choice("slot:abc:Öğle Yemeği"), None);
+        assert_eq!(parse_slot_choice("other"), None);
+    }
+
+    #[test]
+    fn test_parse_bug_report() {
+        assert_eq!(parse_bug_report("Hata bildir Fotoğraf yüklenmiyor"), Some("Fotoğraf yüklenmiyor"));
+        assert_eq!(parse_bug_report("/hata"), Some(""));
+        assert_eq!(parse_bug_report("hata bildir"), Some(""));
+        assert_eq!(parse_bug_report("bug rapor boş geliyor"), Some("rapor boş geliyor"));
+        assert_eq!(parse_bug_report("hata yaptım, pizza yedim"), None);
+        assert_eq!(parse_bug_report("hatalı kayıt"), None);
+    }
+
+    #[test]
+    fn test_remove_image_files() {
+        let dir = std::env::temp_dir().join(format!("tavari_photos_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let existing = dir.join("img_1.jpg");
+        std::fs::write(&existing, b"jpeg").unwrap();
+
+        let paths = vec![
+            existing.to_string_lossy().to_string(),
+            dir.join("img_missing.jpg").to_string_lossy().to_string(),
+        ];
+        assert_eq!(remove_image_files(&paths), 2);
+        assert!(!existing.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,6 +2,6 @@ pub mod message_handler;
 pub mod onboarding;
 pub mod reminder;
 
-pub use message_handler::MessageHandler;
+pub use message_handler::{MessageHandler, QuickLog};
 pub use onboarding::OnboardingHandler;
 pub use reminder::ReminderService;
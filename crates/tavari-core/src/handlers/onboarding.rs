@@ -0,0 +1,694 @@
+use crate::models::{ConversationDirection, GoalChangeSource, MessageType, User};
+use crate::services::events::{BotEvent, EventDispatcher};
+use crate::services::{Database, WhatsAppService};
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+/// Öğün saatleri 'atla' ile geçildiğinde kullanılan varsayılanlar
+const DEFAULT_MEAL_TIMES: [(&str, &str); 3] = [("breakfast", "09:00"), ("lunch", "13:00"), ("dinner", "19:00")];
+
+/// Onboarding atlandıktan sonra ayarları özelleştirme hatırlatması ne zaman gönderilsin
+const CUSTOMIZE_NUDGE_DELAY_HOURS: i64 = 48;
+
+/// `kurulum` sihirbazı adımları (onboarding_step, onboarding tamamlanmış kullanıcılar için)
+pub const WIZARD_STEP_PREFIX: &str = "wizard_";
+const WIZARD_MEAL_TIMES: &str = "wizard_meal_times";
+const WIZARD_GOALS: &str = "wizard_goals";
+const WIZARD_REMINDERS: &str = "wizard_reminders";
+
+/// Hatırlatma soruları: Evet/Hayır butonları ve su aralığı listesi.
+/// Buton/liste cevabı id olarak gelir (webhook `onb_` id'lerini metin gibi iletir);
+/// butonsuz istemcilerde numara ya da "evet"/"hayır" yazmak da çalışır.
+const REMINDER_MEALS: &str = "reminder_meals";
+const REMINDER_WATER: &str = "reminder_water";
+const REMINDER_WATER_INTERVAL: &str = "reminder_water_interval";
+const REMINDER_SUMMARY: &str = "reminder_summary";
+
+pub const BUTTON_ID_PREFIX: &str = "onb_";
+const YES_ID: &str = "onb_yes";
+const NO_ID: &str = "onb_no";
+const WATER_INTERVAL_ID_PREFIX: &str = "onb_water_";
+
+/// Su hatırlatma aralığı seçenekleri (saat)
+const WATER_INTERVAL_PRESETS: [i32; 4] = [1, 2, 3, 4];
+
+/// "Her saat" / "2 saatte bir" (ayarlar ve onboarding için)
+pub fn water_interval_label(hours: i32) -> String {
+    if hours <= 1 {
+        "Her saat".to_string()
+    } else {
+        format!("{} saatte bir", hours)
+    }
+}
+
+/// "atla" / "geç" - onboarding sorularını varsayılanlarla geç
+fn is_skip_command(message: &str) -> bool {
+    matches!(
+        message.trim().to_lowercase().as_str(),
+        "atla" | "geç" | "gec" | "skip"
+    )
+}
+
+pub struct OnboardingHandler {
+    db: Arc<Database>,
+    whatsapp: Arc<dyn WhatsAppService>,
+    events: Arc<EventDispatcher>,
+}
+
+impl OnboardingHandler {
+    pub fn new(db: Arc<Database>, whatsapp: Arc<dyn WhatsAppService>, events: Arc<EventDispatcher>) -> Self {
+        Self { db, whatsapp, events }
+    }
+
+    pub async fn handle_step(&self, user: &User, message: &str) -> Result<()> {
+        if is_skip_command(message) {
+            return self.skip_onboarding(user).await;
+        }
+
+        match user.onboarding_step.as_deref() {
+            None => {
+                // İlk mesaj - onboarding başlat
+                self.start_onboarding(user).await?;
+            }
+            Some("ready_to_start") => {
+                // Kullanıcı onboarding'i başlatmak istiyor
+                self.start_onboarding(user).await?;
+            }
+            Some("breakfast_time") => {
+                // Kahvaltı saatini kaydet
+                self.save_breakfast_time(user, message).await?;
+            }
+            Some("lunch_time") => {
+                // Öğle saatini kaydet
+                self.save_lunch_time(user, message).await?;
+            }
+            Some("dinner_time") => {
+                // Akşam saatini kaydet, ardından hatırlatma tercihleri
+                self.save_dinner_time(user, message).await?;
+            }
+            Some(step @ (REMINDER_MEALS | REMINDER_WATER | REMINDER_WATER_INTERVAL | REMINDER_SUMMARY)) => {
+                self.save_reminder_choice(user, step, message).await?;
+            }
+            _ => {
+                log::warn!("Unknown onboarding step: {:?}", user.onboarding_step);
+            }
+        }
+        Ok(())
+    }
+
+    async fn start_onboarding(&self, user: &User) -> Result<()> {
+        // İlk adım: kahvaltı saati. Aynı anda gelen ikinci mesaj hoş geldin'i tekrar göndermesin
+        if !self.db.claim_onboarding_start(&user.phone_number, "breakfast_time").await? {
+            log::info!("⏭️ Onboarding already started for {}, skipping duplicate welcome", user.phone_number);
+            return Ok(());
+        }
+
+        let welcome_msg = "🍽️ *Hoş geldin!*\n\n\
+Beslenme takibini kişiselleştirmek için öğün saatlerini öğrenmeliyim.\n\n\
+*Genelde kahvaltını ne zaman yaparsın?*\n\
+Normal konuşarak yaz:\n\
+• \"sabah 9'da\"\n\
+• \"09:00\"\n\
+• \"saat 9 gibi\"\n\n\
+⏩ Hemen denemek için *atla* yaz (varsayılan saatler kullanılır)";
+
+        if let Err(e) = self.whatsapp.send_message(&user.phone_number, welcome_msg).await {
+            // Gönderilemediyse bir sonraki mesajda baştan başlasın
+            self.db.update_onboarding_step(&user.phone_number, None).await?;
+            return Err(e);
+        }
+
+        // Log outgoing message
+        let _ = self.db.log_conversation(
+            &user.phone_number,
+            ConversationDirection::Outgoing,
+            MessageType::Response,
+            welcome_msg,
+            Some(serde_json::json!({"onboarding_step": "welcome"})),
+        ).await;
+
+        log::info!("🆕 Onboarding started for user: {}", user.phone_number);
+        Ok(())
+    }
+
+    async fn save_breakfast_time(&self, user: &User, time: &str) -> Result<()> {
+        let parsed_time = Self::parse_natural_time(time);
+
+        if let Some(formatted_time) = parsed_time {
+            self.db.update_meal_time(&user.phone_number, "breakfast", &formatted_time).await?;
+
+            let msg = format!("✅ Kahvaltı: {}\n\n*Öğle yemeğini ne zaman yersin?*\n\
+Normal konuşarak yaz:\n\
+• \"öğlen 1'de\"\n\
+• \"13:00\"\n\
+• \"saat 13 gibi\"", formatted_time);
+
+            self.whatsapp.send_message(&user.phone_number, &msg).await?;
+
+            // Log outgoing message
+            let _ = self.db.log_conversation(
+                &user.phone_number,
+                ConversationDirection::Outgoing,
+                MessageType::Response,
+                &msg,
+                Some(serde_json::json!({"onboarding_step": "breakfast_time_saved", "time": time})),
+            ).await;
+
+            self.db.update_onboarding_step(&user.phone_number, Some("lunch_time".to_string())).await?;
+        } else {
+            let msg = "❌ Saati anlayamadım\n\nÖrnekler:\n• \"sabah 9'da\"\n• \"09:00\"\n• \"saat 9 gibi\"";
+
+            self.whatsapp.send_message(&user.phone_number, msg).await?;
+
+            // Log error message
+            let _ = self.db.log_conversation(
+                &user.phone_number,
+                ConversationDirection::Outgoing,
+                MessageType::Error,
+                msg,
+                Some(serde_json::json!({"onboarding_step": "breakfast_time_invalid", "input": time})),
+            ).await;
+        }
+        Ok(())
+    }
+
+    async fn save_lunch_time(&self, user: &User, time: &str) -> Result<()> {
+        let parsed_time = Self::parse_natural_time(time);
+
+        if let Some(formatted_time) = parsed_time {
+            self.db.update_meal_time(&user.phone_number, "lunch", &formatted_time).await?;
+
+            let msg = format!("✅ Öğle: {}\n\n*Akşam yemeğini ne zaman yersin?*\n\
+Normal konuşarak yaz:\n\
+• \"akşam 7'de\"\n\
+• \"19:00\"\n\
+• \"saat 19 gibi\"", formatted_time);
+
+            self.whatsapp.send_message(&user.phone_number, &msg).await?;
+
+            // Log outgoing message
+            let _ = self.db.log_conversation(
+                &user.phone_number,
+                ConversationDirection::Outgoing,
+                MessageType::Response,
+                &msg,
+                Some(serde_json::json!({"onboarding_step": "lunch_time_saved", "time": time})),
+            ).await;
+
+            self.db.update_onboarding_step(&user.phone_number, Some("dinner_time".to_string())).await?;
+        } else {
+            let msg = "❌ Saati anlayamadım\n\nÖrnekler:\n• \"öğlen 1'de\"\n• \"13:00\"\n• \"saat 13 gibi\"";
+
+            self.whatsapp.send_message(&user.phone_number, msg).await?;
+
+            // Log error message
+            let _ = self.db.log_conversation(
+                &user.phone_number,
+                ConversationDirection::Outgoing,
+                MessageType::Error,
+                msg,
+                Some(serde_json::json!({"onboarding_step": "lunch_time_invalid", "input": time})),
+            ).await;
+        }
+        Ok(())
+    }
+
+    async fn save_dinner_time(&self, user: &User, time: &str) -> Result<()> {
+        let parsed_time = Self::parse_natural_time(time);
+
+        if let Some(formatted_time) = parsed_time {
+            self.db.update_meal_time(&user.phone_number, "dinner", &formatted_time).await?;
+        } else {
+            let msg = "❌ Saati anlayamadım\n\nÖrnekler:\n• \"akşam 7'de\"\n• \"19:00\"\n• \"saat 19 gibi\"";
+
+            self.whatsapp.send_message(&user.phone_number, msg).await?;
+
+            // Log error message
+            let _ = self.db.log_conversation(
+                &user.phone_number,
+                ConversationDirection::Outgoing,
+                MessageType::Error,
+                msg,
+                Some(serde_json::json!({"onboarding_step": "dinner_time_invalid", "input": time})),
+            ).await;
+
+            return Ok(());
+        }
+
+        self.ask_reminder_step(user, REMINDER_MEALS).await
+    }
+
+    /// Hatırlatma sorusunu butonlu/listeli gönder ve adımı kaydet
+    async fn ask_reminder_step(&self, user: &User, step: &str) -> Result<()> {
+        let yes_no = || vec![(YES_ID.to_string(), "Evet".to_string()), (NO_ID.to_string(), "Hayır".to_string())];
+
+        let msg = match step {
+            REMINDER_MEALS => {
+                let msg = "🔔 *Öğün saatlerinde hatırlatma ister misin?*\n\
+Kahvaltı, öğle ve akşam saatinde kısa bir mesaj atarım.";
+                self.whatsapp.send_message_with_buttons(&user.phone_number, msg, yes_no()).await?;
+                msg.to_string()
+            }
+            REMINDER_WATER => {
+                let msg = "💧 *Gün içinde su hatırlatması ister misin?*";
+                self.whatsapp.send_message_with_buttons(&user.phone_number, msg, yes_no()).await?;
+                msg.to_string()
+            }
+            REMINDER_WATER_INTERVAL => {
+                let rows: Vec<(String, String)> = WATER_INTERVAL_PRESETS
+                    .iter()
+                    .map(|h| (format!("{}{}", WATER_INTERVAL_ID_PREFIX, h), water_interval_label(*h)))
+                    .collect();
+                let options = rows
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (_, title))| format!("{}. {}", i + 1, title))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let msg = format!("⏱️ *Ne sıklıkla hatırlatayım?* (08:00-22:00 arası)\n\n{}\n\nSeç ya da numarasını yaz", options);
+                self.whatsapp.send_list_message(&user.phone_number, &msg, "Aralık seç", rows).await?;
+                msg
+            }
+            _ => {
+                let msg = "📊 *Her akşam 22:00'de günlük özet göndereyim mi?*";
+                self.whatsapp.send_message_with_buttons(&user.phone_number, msg, yes_no()).await?;
+                msg.to_string()
+            }
+        };
+
+        let _ = self.db.log_conversation(
+            &user.phone_number,
+            ConversationDirection::Outgoing,
+            MessageType::Response,
+            &msg,
+            Some(serde_json::json!({"onboarding_step": step})),
+        ).await;
+
+        self.db.update_onboarding_step(&user.phone_number, Some(step.to_string())).await?;
+        Ok(())
+    }
+
+    async fn save_reminder_choice(&self, user: &User, step: &str, message: &str) -> Result<()> {
+        let next_step = match step {
+            REMINDER_WATER_INTERVAL => match parse_water_interval(message) {
+                Some(hours) => {
+                    self.db.update_water_reminder(&user.phone_number, true, Some(hours)).await?;
+                    Some(REMINDER_SUMMARY)
+                }
+                None => None,
+            },
+            _ => match parse_yes_no(message) {
+                Some(enabled) => match step {
+                    REMINDER_MEALS => {
+                        self.db.update_meal_reminders(&user.phone_number, enabled).await?;
+                        Some(REMINDER_WATER)
+                    }
+                    REMINDER_WATER => {
+                        self.db.update_water_reminder(&user.phone_number, enabled, None).await?;
+                        Some(if enabled { REMINDER_WATER_INTERVAL } else { REMINDER_SUMMARY })
+                    }
+                    _ => {
+                        let summary_time = enabled.then(|| user.daily_summary_time.as_deref().unwrap_or("22:00"));
+                        self.db.update_daily_summary_time(&user.phone_number, summary_time).await?;
+                        return self.finish_onboarding(user, false).await;
+                    }
+                },
+                None => None,
+            },
+        };
+
+        match next_step {
+            Some(next) => self.ask_reminder_step(user, next).await,
+            None => {
+                // Anlaşılmadı: aynı soruyu butonlarla tekrar sor
+                log::debug!("❓ Unrecognized onboarding reminder answer from {}: '{}'", user.phone_number, message);
+                self.ask_reminder_step(user, step).await
+            }
+        }
+    }
+
+    /// Kullanıcı 'atla' dedi: eksik öğün saatlerini varsayılanlarla doldur, onboarding'i bitir
+    /// ve birkaç gün sonra ayarları özelleştirmesini hatırlat
+    async fn skip_onboarding(&self, user: &User) -> Result<()> {
+        let current = [&user.breakfast_time, &user.lunch_time, &user.dinner_time];
+        for ((meal_type, default_time), existing) in DEFAULT_MEAL_TIMES.iter().zip(current) {
+            if existing.is_none() {
+                self.db.update_meal_time(&user.phone_number, meal_type, default_time).await?;
+            }
+        }
+
+        self.db
+            .schedule_customize_nudge(&user.phone_number, Utc::now() + Duration::hours(CUSTOMIZE_NUDGE_DELAY_HOURS))
+            .await?;
+
+        log::info!("⏩ Onboarding skipped with defaults for user: {}", user.phone_number);
+        self.finish_onboarding(user, true).await
+    }
+
+    async fn finish_onboarding(&self, user: &User, skipped: bool) -> Result<()> {
+        self.db.complete_onboarding(&user.phone_number).await?;
+        self.events.emit(BotEvent::UserOnboarded {
+            phone: user.phone_number.clone(),
+        });
+
+        // Fetch updated user with all meal times from database
+        let updated_user = self.db.get_user(&user.phone_number).await?
+            .ok_or_else(|| anyhow::anyhow!("User not found after onboarding completion"))?;
+
+        let completion_msg = format!("🎉 *Hazırsın!*\n\n\
+✅ Kahvaltı: {}\n\
+✅ Öğle: {}\n\
+✅ Akşam: {}\n\n\
+*Nasıl kullanılır?*\n\
+📸 Yemek fotoğrafı gönder\n\
+💧 250 ml su içtim\n\
+📊 rapor\n\n\
+🔔 Öğün hatırlatma {} · Su hatırlatma {}\n\n\
+İyi beslenmeler! 🥗{}",
+            updated_user.breakfast_time.as_deref().unwrap_or(""),
+            updated_user.lunch_time.as_deref().unwrap_or(""),
+            updated_user.dinner_time.as_deref().unwrap_or(""),
+            if updated_user.breakfast_reminder { "✅" } else { "❌" },
+            if updated_user.water_reminder {
+                format!("✅ ({})", water_interval_label(updated_user.water_reminder_interval).to_lowercase())
+            } else {
+                "❌".to_string()
+            },
+            if skipped {
+                "\n\n⚙️ Varsayılan saatler ve hedefler (2000 kcal, 2000 ml su) kullanıldı. \
+İstediğin zaman değiştirebilirsin: \"kahvaltı saatim 8\", \"kalori hedefim 1800\" ya da *ayarlar*"
+            } else {
+                ""
+            });
+
+        self.whatsapp.send_message(&user.phone_number, &completion_msg).await?;
+
+        // Log completion message
+        let _ = self.db.log_conversation(
+            &user.phone_number,
+            ConversationDirection::Outgoing,
+            MessageType::Response,
+            &completion_msg,
+            Some(serde_json::json!({
+                "onboarding_step": "completed",
+                "skipped": skipped,
+                "breakfast_time": updated_user.breakfast_time,
+                "lunch_time": updated_user.lunch_time,
+                "dinner_time": updated_user.dinner_time
+            })),
+        ).await;
+
+        log::info!("✅ Onboarding completed for user: {}", user.phone_number);
+        Ok(())
+    }
+
+    /// `kurulum`: mevcut kullanıcı için kısa ayar sihirbazını başlat (öğün saatleri → hedefler → hatırlatmalar)
+    pub async fn start_wizard(&self, user: &User) -> Result<()> {
+        let msg = format!("🧭 *Kurulum Sihirbazı* (1/3)\n\n\
+*Öğün saatlerin?* Kahvaltı, öğle ve akşam saatini tek mesajda yaz:\n\
+• \"9 13 19\"\n\
+• \"08:30 12:30 19:30\"\n\n\
+Şu an: {} / {} / {}\n\
+Değiştirmeden geçmek için *atla*, çıkmak için *iptal*",
+            user.breakfast_time.as_deref().unwrap_or("09:00"),
+            user.lunch_time.as_deref().unwrap_or("13:00"),
+            user.dinner_time.as_deref().unwrap_or("19:00"));
+
+        self.send_wizard_message(user, &msg, "started").await?;
+        self.db.update_onboarding_step(&user.phone_number, Some(WIZARD_MEAL_TIMES.to_string())).await?;
+
+        log::info!("🧭 Settings wizard started for user: {}", user.phone_number);
+        Ok(())
+    }
+
+    pub async fn handle_wizard_step(&self, user: &User, message: &str) -> Result<()> {
+        let input = message.trim().to_lowercase();
+
+        if matches!(input.as_str(), "iptal" | "cancel" | "çık" | "cik") {
+            self.db.update_onboarding_step(&user.phone_number, None).await?;
+            return self.send_wizard_message(user, "👌 Kurulum iptal edildi. Şimdiye kadar kaydedilenler geçerli.", "cancelled").await;
+        }
+        let skip = is_skip_command(&input);
+
+        match user.onboarding_step.as_deref() {
+            Some(WIZARD_MEAL_TIMES) => {
+                if !skip {
+                    let Some(times) = parse_meal_times(&input) else {
+                        return self.send_wizard_message(
+                            user,
+                            "❌ Üç saati anlayamadım\n\nÖrnek: \"9 13 19\" veya \"08:30 12:30 19:30\"\n(geçmek için *atla*)",
+                            "meal_times_invalid",
+                        ).await;
+                    };
+                    for ((meal_type, _), time) in DEFAULT_MEAL_TIMES.iter().zip(&times) {
+                        self.db.update_meal_time(&user.phone_number, meal_type, time).await?;
+                    }
+                }
+
+                let msg = format!("🧭 *Kurulum Sihirbazı* (2/3)\n\n\
+*Günlük hedeflerin?* Kalori (kcal) ve su (ml veya litre) yaz:\n\
+• \"1800 2500\"\n\
+• \"2200 3 litre\"\n\n\
+Şu an: {} kcal / {} ml\n\
+Geçmek için *atla*",
+                    user.daily_calorie_goal.unwrap_or(2000),
+                    user.daily_water_goal.unwrap_or(2000));
+                self.send_wizard_message(user, &msg, "meal_times_saved").await?;
+                self.db.update_onboarding_step(&user.phone_number, Some(WIZARD_GOALS.to_string())).await?;
+            }
+            Some(WIZARD_GOALS) => {
+                if !skip {
+                    let Some((calories, water_ml)) = parse_goals(&input) else {
+                        return self.send_wizard_message(
+                            user,
+                            "❌ Hedefleri anlayamadım\n\nÖrnek: \"1800 2500\" (kcal, ml) - kalori 800-6000, su 500-6000 ml\n(geçmek için *atla*)",
+                            "goals_invalid",
+                        ).await;
+                    };
+                    self.db.update_calorie_goal(&user.phone_number, calories, GoalChangeSource::Onboarding).await?;
+                    self.db.update_water_goal(&user.phone_number, water_ml, GoalChangeSource::Onboarding).await?;
+                }
+
+                let msg = "🧭 *Kurulum Sihirbazı* (3/3)\n\n\
+*Hangi hatırlatmaları istersin?*\n\
+1️⃣ Hepsi (öğün + su)\n\
+2️⃣ Sadece öğün\n\
+3️⃣ Sadece su\n\
+4️⃣ Hiçbiri\n\n\
+Geçmek için *atla*";
+                self.send_wizard_message(user, msg, "goals_saved").await?;
+                self.db.update_onboarding_step(&user.phone_number, Some(WIZARD_REMINDERS.to_string())).await?;
+            }
+            Some(WIZARD_REMINDERS) => {
+                if !skip {
+                    let Some((meals, water)) = parse_reminder_choice(&input) else {
+                        return self.send_wizard_message(user, "❌ Lütfen 1, 2, 3 veya 4 yaz (geçmek için *atla*)", "reminders_invalid").await;
+                    };
+                    self.db.update_reminders(&user.phone_number, meals, water).await?;
+                }
+                self.db.update_onboarding_step(&user.phone_number, None).await?;
+
+                let updated = self.db.get_user(&user.phone_number).await?
+                    .ok_or_else(|| anyhow::anyhow!("User not found after wizard"))?;
+                let on_off = |enabled: bool| if enabled { "✅" } else { "❌" };
+                let msg = format!("🎉 *Kurulum tamam!*\n\n\
+🕐 {} / {} / {}\n\
+🎯 {} kcal, {} ml su\n\
+🔔 Öğün hatırlatma {} · Su hatırlatma {}\n\n\
+Tüm ayarlar için: *ayarlar*",
+                    updated.breakfast_time.as_deref().unwrap_or("-"),
+                    updated.lunch_time.as_deref().unwrap_or("-"),
+                    updated.dinner_time.as_deref().unwrap_or("-"),
+                    updated.daily_calorie_goal.unwrap_or(2000),
+                    updated.daily_water_goal.unwrap_or(2000),
+                    on_off(updated.breakfast_reminder),
+                    on_off(updated.water_reminder));
+                self.send_wizard_message(user, &msg, "completed").await?;
+                log::info!("✅ Settings wizard completed for user: {}", user.phone_number);
+            }
+            _ => {
+                log::warn!("Unknown wizard step: {:?}", user.onboarding_step);
+                self.db.update_onboarding_step(&user.phone_number, None).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn send_wizard_message(&self, user: &User, msg: &str, step: &str) -> Result<()> {
+        self.whatsapp.send_message(&user.phone_number, msg).await?;
+
+        let _ = self.db.log_conversation(
+            &user.phone_number,
+            ConversationDirection::Outgoing,
+            MessageType::Response,
+            msg,
+            Some(serde_json::json!({"wizard_step": step})),
+        ).await;
+        Ok(())
+    }
+
+    /// Parse natural language time input to HH:MM format
+    /// Accepts formats like: "9", "09:00", "sabah 9", "saat 9 gibi", "9'da"
+    fn parse_natural_time(input: &str) -> Option<String> {
+        let input = input.trim().to_lowercase();
+
+        // First try exact HH:MM format
+        if Self::validate_time_format(&input) {
+            return Some(input);
+        }
+
+        // Extract numbers from the input
+        let numbers: Vec<u32> = input
+            .split(|c: char| !c.is_numeric())
+            .filter_map(|s| s.parse::<u32>().ok())
+            .collect();
+
+        if numbers.is_empty() {
+            return None;
+        }
+
+        // Take the first number as the hour
+        let hour = numbers[0];
+
+        // If there's a second number, use it as minutes, otherwise default to 00
+        let minute = if numbers.len() > 1 {
+            numbers[1]
+        } else {
+            0
+        };
+
+        // Validate the parsed time
+        if hour < 24 && minute < 60 {
+            Some(format!("{:02}:{:02}", hour, minute))
+        } else {
+            None
+        }
+    }
+
+    fn validate_time_format(time: &str) -> bool {
+        // HH:MM formatını kontrol et
+        let parts: Vec<&str> = time.split(':').collect();
+        if parts.len() != 2 {
+            return false;
+        }
+
+        let hour = parts[0].parse::<u32>();
+        let minute = parts[1].parse::<u32>();
+
+        match (hour, minute) {
+            (Ok(h), Ok(m)) => h < 24 && m < 60,
+            _ => false,
+        }
+    }
+}
+
+/// "9 13 19" / "08:30, 12:30, 19:30" -> kahvaltı, öğle, akşam
+fn parse_meal_times(input: &str) -> Option<[String; 3]> {
+    let times: Vec<String> = input
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '/' || c == '-')
+        .filter_map(OnboardingHandler::parse_natural_time)
+        .collect();
+
+    match times.as_slice() {
+        [breakfast, lunch, dinner] => Some([breakfast.clone(), lunch.clone(), dinner.clone()]),
+        _ => None,
+    }
+}
+
+/// "1800 2500" / "2200 3 litre" / "2000, 2.5" -> (kcal, ml)
+fn parse_goals(input: &str) -> Option<(i32, i32)> {
+    let numbers: Vec<f64> = input
+        .split(|c: char| c.is_whitespace() || c == '/')
+        .filter_map(|w| w.trim_matches(|c: char| !c.is_ascii_digit()).replace(',', ".").parse::<f64>().ok())
+        .collect();
+
+    let [calories, water] = numbers.as_slice() else { return None };
+    // Küçük değerler litre kabul edilir (2.5 -> 2500 ml)
+    let water_ml = if *water <= 10.0 { water * 1000.0 } else { *water };
+
+    let calories = *calories as i32;
+    let water_ml = water_ml.round() as i32;
+    ((800..=6000).contains(&calories) && (500..=6000).contains(&water_ml)).then_some((calories, water_ml))
+}
+
+/// Evet/Hayır butonu (`onb_yes` / `onb_no`) veya yazılı cevap; butonsuz istemcide 1 = Evet, 2 = Hayır
+fn parse_yes_no(input: &str) -> Option<bool> {
+    match input.trim().to_lowercase().as_str() {
+        YES_ID | "1" | "evet" | "e" | "olur" | "tamam" | "yes" | "isterim" => Some(true),
+        NO_ID | "2" | "hayır" | "hayir" | "h" | "no" | "istemem" => Some(false),
+        _ => None,
+    }
+}
+
+/// Liste seçimi (`onb_water_3`), sıra numarası ("3") ya da "3 saat" -> saat
+fn parse_water_interval(input: &str) -> Option<i32> {
+    let input = input.trim().to_lowercase();
+    if let Some(hours) = input.strip_prefix(WATER_INTERVAL_ID_PREFIX) {
+        return hours.parse().ok().filter(|h| WATER_INTERVAL_PRESETS.contains(h));
+    }
+    if input.contains("her saat") {
+        return Some(1);
+    }
+    let number: i32 = input.split(|c: char| !c.is_ascii_digit()).find(|p| !p.is_empty())?.parse().ok()?;
+    // Numaralar ve saatler aynı (1-4), ikisi de doğrudan saat olarak okunur
+    WATER_INTERVAL_PRESETS.contains(&number).then_some(number)
+}
+
+/// 1 hepsi, 2 sadece öğün, 3 sadece su, 4 hiçbiri -> (öğün, su)
+fn parse_reminder_choice(input: &str) -> Option<(bool, bool)> {
+    match input.trim() {
+        "1" | "hepsi" | "açık" | "acik" => Some((true, true)),
+        "2" | "sadece öğün" | "sadece ogun" => Some((true, false)),
+        "3" | "sadece su" => Some((false, true)),
+        "4" | "hiçbiri" | "hicbiri" | "kapalı" | "kapali" => Some((false, false)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_skip_command() {
+        assert!(is_skip_command("atla"));
+        assert!(is_skip_command("  ATLA "));
+        assert!(is_skip_command("geç"));
+        assert!(!is_skip_command("sabah 9'da"));
+        assert!(!is_skip_command("atla 9"));
+    }
+
+    #[test]
+    fn test_wizard_parsers() {
+        assert_eq!(
+            parse_meal_times("9 13 19"),
+            Some(["09:00".to_string(), "13:00".to_string(), "19:00".to_string()])
+        );
+        assert_eq!(parse_meal_times("08:30, 12:30, 19:30").unwrap()[0], "08:30");
+        assert_eq!(parse_meal_times("9 13"), None);
+
+        assert_eq!(parse_goals("1800 2500"), Some((1800, 2500)));
+        assert_eq!(parse_goals("2200 3 litre"), Some((2200, 3000)));
+        assert_eq!(parse_goals("2000 2,5"), Some((2000, 2500)));
+        assert_eq!(parse_goals("50 2500"), None);
+
+        assert_eq!(parse_reminder_choice("3"), Some((false, true)));
+        assert_eq!(parse_reminder_choice("belki"), None);
+    }
+
+    #[test]
+    fn test_onboarding_reminder_answers() {
+        assert_eq!(parse_yes_no("onb_yes"), Some(true));
+        assert_eq!(parse_yes_no(" Hayır "), Some(false));
+        assert_eq!(parse_yes_no("2"), Some(false));
+        assert_eq!(parse_yes_no("belki"), None);
+
+        assert_eq!(parse_water_interval("onb_water_3"), Some(3));
+        assert_eq!(parse_water_interval("onb_water_7"), None);
+        assert_eq!(parse_water_interval("2 saatte bir"), Some(2));
+        assert_eq!(parse_water_interval("Her saat"), Some(1));
+        assert_eq!(parse_water_interval("9"), None);
+        assert_eq!(water_interval_label(3), "3 saatte bir");
+    }
+}
@@ -0,0 +1,1272 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+use std::sync::Arc;
+use tokio_cron_scheduler::{Job, JobScheduler};
+
+use crate::models::{DailyStats, User};
+use crate::services::compliance::{self, Delivery, MessageCategory, Outgoing};
+use crate::services::notifier::Notifier;
+use crate::services::reminder_copy::ReminderKind;
+use crate::services::{Database, OpenRouterService, WhatsAppService};
+
+/// Meal reminder / daily summary jobs run every 30 minutes (:00 and :30)
+const CHECK_INTERVAL_MINUTES: i64 = 30;
+
+/// Water reminders are only sent between these local hours (inclusive)
+const WATER_DAY_START_HOUR: u32 = 8;
+const WATER_DAY_END_HOUR: u32 = 22;
+
+pub struct ReminderService {
+    db: Arc<Database>,
+    whatsapp: Arc<dyn WhatsAppService>,
+    notifier: Arc<Notifier>,
+    ai: Arc<OpenRouterService>,
+    scheduler: JobScheduler,
+}
+
+impl ReminderService {
+    pub async fn new(
+        db: Arc<Database>,
+        whatsapp: Arc<dyn WhatsAppService>,
+        notifier: Arc<Notifier>,
+        ai: Arc<OpenRouterService>,
+    ) -> Result<Self> {
+        let scheduler = JobScheduler::new().await?;
+
+        Ok(Self {
+            db,
+            whatsapp,
+            notifier,
+            ai,
+            scheduler,
+        })
+    }
+
+    pub async fn start(&mut self) -> Result<()> {
+        // Bölgesel mesajlaşma kuralları (COMPLIANCE_PROFILES) tüm proaktif mesajlara uygulanır
+        let profiles = compliance::configured().profiles();
+        if !profiles.is_empty() {
+            let prefixes: Vec<&str> = profiles.iter().map(|p| p.prefix.as_str()).collect();
+            log::info!("🛡️ Compliance profiles active for: {}", prefixes.join(", "));
+        }
+
+        // Personalized meal reminders - Her 30 dakikada bir kontrol et
+        self.add_personalized_meal_reminders().await?;
+
+        // Su içme hatırlatması: son kayıttan beri kullanıcının aralığı geçtiyse (08:00-22:00 arası)
+        self.add_water_reminder().await?;
+
+        // 24-hour window warning - Her saatte bir kontrol et
+        self.add_window_warning_check("0 0 * * * *").await?;
+
+        // Günlük özet (kullanıcının seçtiği saat, varsayılan 22:00)
+        self.add_daily_summary().await?;
+
+        // Bağlı koçlara haftalık özet (Pazar 20:00, kullanıcı onayı ile)
+        self.add_coach_weekly_summary().await?;
+
+        // Haftalık hedef değerlendirmesi (Pazar 19:00): hedefleri koru / artır / azalt
+        self.add_weekly_goal_review().await?;
+
+        // Doğrulanmış e-posta adresine haftalık/aylık HTML rapor (kullanıcı saatiyle 09:00)
+        self.add_email_reports().await?;
+
+        // Anonim kullanıcı ortalamaları (her gece, önceki gün için)
+        self.add_nightly_benchmark().await?;
+
+        // Operatörlere haftalık KPI raporu (Pazartesi 09:00 İstanbul)
+        self.add_weekly_kpi_report().await?;
+
+        // Opt-in anonim kullanım istatistikleri (TELEMETRY=true)
+        self.add_telemetry_report().await?;
+
+        // Onboarding'i 'atla' ile geçenlere ayarları özelleştirme hatırlatması
+        self.add_customize_nudge().await?;
+
+        // Yeni sürümün "yenilikler" mesajı (saatte bir, her kullanıcıya bir kez)
+        self.add_changelog_announcements().await?;
+
+        // Aylık isteğe bağlı NPS sorusu (her gün 11:00 İstanbul, kullanıcı başına 30 günde bir)
+        self.add_monthly_nps_poll().await?;
+
+        // Eski konuşmaları arşiv tablosuna taşı (her gece 01:00 UTC)
+        self.add_conversation_archival().await?;
+
+        // Gece bakımı: süresi dolmuş kayıtlar, VACUUM/ANALYZE (her gece 02:30 UTC)
+        self.add_nightly_maintenance().await?;
+
+        self.scheduler.start().await?;
+
+        log::info!("✅ Reminder service started (personalized)");
+        Ok(())
+    }
+
+    async fn add_personalized_meal_reminders(&mut self) -> Result<()> {
+        let db = self.db.clone();
+        let whatsapp = self.whatsapp.clone();
+
+        // Her 30 dakikada bir çalış ve kullanıcıların öğün saatlerini kontrol et
+        let job = Job::new_async("0 0,30 * * * *", move |_uuid, _l| {
+            let db = db.clone();
+            let whatsapp = whatsapp.clone();
+
+            Box::pin(async move {
+                use chrono::Utc;
+                use chrono::Timelike;
+                use chrono_tz::Tz;
+
+                if let Ok(users) = db.get_active_users().await {
+                    log::debug!("🔄 Meal reminder check running for {} users", users.len());
+                    for user in users {
+                        if !user.onboarding_completed {
+                            log::debug!("⏭️ Skipping {} - onboarding not completed", user.phone_number);
+                            continue;
+                        }
+
+                        // Kullanıcının timezone'unda mevcut saati hesapla
+                        let user_tz: Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+                        let now_utc = Utc::now();
+                        let now_user = now_utc.with_timezone(&user_tz);
+                        let current_time = now_user.format("%H:%M").to_string();
+
+                        log::debug!("⏰ User {} - Current time: {} (TZ: {})", user.phone_number, current_time, user.timezone);
+
+                        // Check silent hours
+                        let silent_start = user.silent_hours_start.as_deref().unwrap_or("23:00");
+                        let silent_end = user.silent_hours_end.as_deref().unwrap_or("07:00");
+                        let is_silent = Self::is_silent_hours(
+                            now_user.hour(),
+                            now_user.minute(),
+                            silent_start,
+                            silent_end,
+                        );
+
+                        if is_silent {
+                            log::debug!("🌙 User {} - In silent hours ({} - {}), skipping meal reminders", user.phone_number, silent_start, silent_end);
+                            continue;
+                        }
+
+                        // Kahvaltı kontrolü
+                        if user.breakfast_reminder {
+                            if let Some(ref breakfast_time) = user.breakfast_time {
+                                let kind = ReminderKind::Breakfast;
+                                log::debug!("🍳 Checking breakfast for {}: current={}, target={}", user.phone_number, current_time, breakfast_time);
+                                let last_sent = db.get_last_reminder_at(&user.phone_number, kind.as_str()).await.ok().flatten();
+                                if let Some(today) = Self::reminder_due_date(now_utc, user_tz, breakfast_time, last_sent) {
+                                    // Bugün kahvaltı kaydedilmiş mi kontrol et
+                                    if let Ok(todays_meals) = db.get_todays_meal_types(&user.phone_number, today).await {
+                                        let has_breakfast = todays_meals.iter().any(|m| matches!(m, crate::models::MealType::Breakfast));
+
+                                        if has_breakfast {
+                                            log::debug!("⏭️ Skipping breakfast reminder for {} - already logged today", user.phone_number);
+                                        } else {
+                                            // Check if user is within 24h WhatsApp Business API window
+                                            if let Ok(within_window) = db.is_within_24h_window(&user.phone_number).await {
+                                                if within_window {
+                                                    let copy = kind.pick();
+                                                    let metadata = serde_json::json!({"reminder_type": kind.as_str(), "time": breakfast_time, "variant": copy.variant});
+                                                    match compliance::send_proactive(&db, whatsapp.as_ref(), &user, MessageCategory::Reminder, Outgoing::Text(copy.text), metadata).await {
+                                                        Delivery::Sent => {
+                                                            let _ = db.record_reminder_sent(&user.phone_number, kind.as_str(), now_utc).await;
+                                                            let _ = db.record_reminder_variant(&user.phone_number, kind.as_str(), copy.variant, now_utc).await;
+                                                            log::info!("📤 Sent breakfast reminder to {} ({})", user.phone_number, user.timezone);
+                                                        }
+                                                        Delivery::Held => continue,
+                                                        Delivery::Failed(e) => log::error!("❌ Failed to send breakfast reminder to {}: {}", user.phone_number, e),
+                                                    }
+                                                } else {
+                                                    log::debug!("⏭️ Skipping breakfast reminder for {} - outside 24h window", user.phone_number);
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        // Öğle yemeği kontrolü
+                        if user.lunch_reminder {
+                            if let Some(ref lunch_time) = user.lunch_time {
+                                let kind = ReminderKind::Lunch;
+                                log::debug!("🍱 Checking lunch for {}: current={}, target={}", user.phone_number, current_time, lunch_time);
+                                let last_sent = db.get_last_reminder_at(&user.phone_number, kind.as_str()).await.ok().flatten();
+                                if let Some(today) = Self::reminder_due_date(now_utc, user_tz, lunch_time, last_sent) {
+                                    // Bugün öğle yemeği kaydedilmiş mi kontrol et
+                                    if let Ok(todays_meals) = db.get_todays_meal_types(&user.phone_number, today).await {
+                                        let has_lunch = todays_meals.iter().any(|m| matches!(m, crate::models::MealType::Lunch));
+
+                                        if has_lunch {
+                                            log::debug!("⏭️ Skipping lunch reminder for {} - already logged today", user.phone_number);
+                                        } else {
+                                            // Check if user is within 24h WhatsApp Business API window
+                                            if let Ok(within_window) = db.is_within_24h_window(&user.phone_number).await {
+                                                if within_window {
+                                                    let copy = kind.pick();
+                                                    let metadata = serde_json::json!({"reminder_type": kind.as_str(), "time": lunch_time, "variant": copy.variant});
+                                                    match compliance::send_proactive(&db, whatsapp.as_ref(), &user, MessageCategory::Reminder, Outgoing::Text(copy.text), metadata).await {
+                                                        Delivery::Sent => {
+                                                            let _ = db.record_reminder_sent(&user.phone_number, kind.as_str(), now_utc).await;
+                                                            let _ = db.record_reminder_variant(&user.phone_number, kind.as_str(), copy.variant, now_utc).await;
+                                                            log::info!("📤 Sent lunch reminder to {} ({})", user.phone_number, user.timezone);
+                                                        }
+                                                        Delivery::Held => continue,
+                                                        Delivery::Failed(e) => log::error!("❌ Failed to send lunch reminder to {}: {}", user.phone_number, e),
+                                                    }
+                                                } else {
+                                                    log::debug!("⏭️ Skipping lunch reminder for {} - outside 24h window", user.phone_number);
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        // Akşam yemeği kontrolü
+                        if user.dinner_reminder {
+                            if let Some(ref dinner_time) = user.dinner_time {
+                                let kind = ReminderKind::Dinner;
+                                log::debug!("🍽️ Checking dinner for {}: current={}, target={}", user.phone_number, current_time, dinner_time);
+                                let last_sent = db.get_last_reminder_at(&user.phone_number, kind.as_str()).await.ok().flatten();
+                                if let Some(today) = Self::reminder_due_date(now_utc, user_tz, dinner_time, last_sent) {
+                                    // Bugün akşam yemeği kaydedilmiş mi kontrol et
+                                    if let Ok(todays_meals) = db.get_todays_meal_types(&user.phone_number, today).await {
+                                        let has_dinner = todays_meals.iter().any(|m| matches!(m, crate::models::MealType::Dinner));
+
+                                        if has_dinner {
+                                            log::debug!("⏭️ Skipping dinner reminder for {} - already logged today", user.phone_number);
+                                        } else {
+                                            // Check if user is within 24h WhatsApp Business API window
+                                            if let Ok(within_window) = db.is_within_24h_window(&user.phone_number).await {
+                                                if within_window {
+                                                    let copy = kind.pick();
+                                                    let metadata = serde_json::json!({"reminder_type": kind.as_str(), "time": dinner_time, "variant": copy.variant});
+                                                    match compliance::send_proactive(&db, whatsapp.as_ref(), &user, MessageCategory::Reminder, Outgoing::Text(copy.text), metadata).await {
+                                                        Delivery::Sent => {
+                                                            let _ = db.record_reminder_sent(&user.phone_number, kind.as_str(), now_utc).await;
+                                                            let _ = db.record_reminder_variant(&user.phone_number, kind.as_str(), copy.variant, now_utc).await;
+                                                            log::info!("📤 Sent dinner reminder to {} ({})", user.phone_number, user.timezone);
+                                                        }
+                                                        Delivery::Held => continue,
+                                                        Delivery::Failed(e) => log::error!("❌ Failed to send dinner reminder to {}: {}", user.phone_number, e),
+                                                    }
+                                                } else {
+                                                    log::debug!("⏭️ Skipping dinner reminder for {} - outside 24h window", user.phone_number);
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    log::debug!("✅ Meal reminder check completed");
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        log::info!("✅ Added personalized meal reminders (checks every 30 min)");
+        Ok(())
+    }
+
+    async fn add_water_reminder(&mut self) -> Result<()> {
+        let db = self.db.clone();
+        let whatsapp = self.whatsapp.clone();
+
+        // Her 30 dakikada kontrol et: son su kaydından beri kullanıcının aralığı (suaraligi) geçtiyse hatırlat
+        let job = Job::new_async("0 0,30 * * * *", move |_uuid, _l| {
+            let db = db.clone();
+            let whatsapp = whatsapp.clone();
+
+            Box::pin(async move {
+                use chrono::Utc;
+                use chrono::Timelike;
+                use chrono_tz::Tz;
+
+                if let Ok(users) = db.get_active_users().await {
+                    log::debug!("💧 Water reminder check running for {} users", users.len());
+                    for user in users {
+                        if user.water_reminder && user.onboarding_completed {
+                            // Kullanıcının timezone'unda mevcut saati hesapla
+                            let user_tz: Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+                            let now_utc = Utc::now();
+                            let now_user = now_utc.with_timezone(&user_tz);
+
+                            // Check silent hours
+                            let silent_start = user.silent_hours_start.as_deref().unwrap_or("23:00");
+                            let silent_end = user.silent_hours_end.as_deref().unwrap_or("07:00");
+                            let is_silent = Self::is_silent_hours(
+                                now_user.hour(),
+                                now_user.minute(),
+                                silent_start,
+                                silent_end,
+                            );
+
+                            if is_silent {
+                                log::debug!("🌙 User {} - In silent hours ({} - {}), skipping water reminder", user.phone_number, silent_start, silent_end);
+                                continue;
+                            }
+
+                            let last_drink = match db.get_last_water_log_at(&user.phone_number).await {
+                                Ok(last) => last,
+                                Err(e) => {
+                                    log::error!("❌ Failed to read last water log for {}: {}", user.phone_number, e);
+                                    continue;
+                                }
+                            };
+                            let kind = ReminderKind::Water;
+                            let last_reminder = db.get_last_reminder_at(&user.phone_number, kind.as_str()).await.unwrap_or(None);
+
+                            log::debug!("💧 User {} - last drink: {:?}, last reminder: {:?}, interval: {}h", user.phone_number, last_drink, last_reminder, user.water_reminder_interval);
+
+                            if !Self::is_water_reminder_due(now_user.time(), now_utc, last_drink, last_reminder, user.water_reminder_interval) {
+                                continue;
+                            }
+
+                            // Check if user is within 24h WhatsApp Business API window
+                            match db.is_within_24h_window(&user.phone_number).await {
+                                Ok(true) => {
+                                    let copy = kind.pick();
+                                    let metadata = serde_json::json!({
+                                        "reminder_type": kind.as_str(),
+                                        "variant": copy.variant,
+                                        "hour": now_user.hour(),
+                                        "last_drink_at": last_drink
+                                    });
+                                    match compliance::send_proactive(&db, whatsapp.as_ref(), &user, MessageCategory::Reminder, Outgoing::Text(copy.text), metadata).await {
+                                        Delivery::Sent => {
+                                            let _ = db.record_reminder_sent(&user.phone_number, kind.as_str(), now_utc).await;
+                                            let _ = db.record_reminder_variant(&user.phone_number, kind.as_str(), copy.variant, now_utc).await;
+                                            log::info!("📤 Sent water reminder to {} at {} ({})", user.phone_number, now_user.format("%H:%M"), user.timezone);
+                                        }
+                                        Delivery::Held => continue,
+                                        Delivery::Failed(e) => log::error!("❌ Failed to send water reminder to {}: {}", user.phone_number, e),
+                                    }
+                                }
+                                Ok(false) => {
+                                    log::debug!("⏭️ Skipping water reminder for {} - outside 24h window", user.phone_number);
+                                }
+                                Err(_) => {}
+                            }
+                        } else {
+                            log::debug!("⏭️ Skipping water reminder for {} (reminder={}, onboarded={})", user.phone_number, user.water_reminder, user.onboarding_completed);
+                        }
+                    }
+                    log::debug!("✅ Water reminder check completed");
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        log::info!("Added water reminder (relative to the last drink, timezone-aware)");
+        Ok(())
+    }
+
+    async fn add_daily_summary(&mut self) -> Result<()> {
+        let db = self.db.clone();
+        let whatsapp = self.whatsapp.clone();
+        let ai = self.ai.clone();
+
+        // Her 30 dakikada bir kontrol et, kullanıcı timezone'unda daily_summary_time'da gönder
+        let job = Job::new_async("0 0,30 * * * *", move |_uuid, _l| {
+            let db = db.clone();
+            let whatsapp = whatsapp.clone();
+            let ai = ai.clone();
+
+            Box::pin(async move {
+                use chrono::Utc;
+                use chrono::Timelike;
+                use chrono_tz::Tz;
+
+                if let Ok(users) = db.get_active_users().await {
+                    log::debug!("📊 Daily summary check running for {} users", users.len());
+                    for user in users {
+                        if !user.onboarding_completed {
+                            log::debug!("⏭️ Skipping {} - onboarding not completed", user.phone_number);
+                            continue;
+                        }
+
+                        // Kullanıcı günlük özeti kapattıysa atla
+                        let Some(summary_time) = user.daily_summary_time.as_deref() else {
+                            log::debug!("⏭️ Skipping {} - daily summary disabled", user.phone_number);
+                            continue;
+                        };
+
+                        // Kullanıcının timezone'unda mevcut saati hesapla
+                        let user_tz: Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+                        let now_utc = Utc::now();
+                        let now_user = now_utc.with_timezone(&user_tz);
+
+                        log::debug!("📊 User {} - Current time: {:02}:{:02} (TZ: {}), summary at {}", user.phone_number, now_user.hour(), now_user.minute(), user.timezone, summary_time);
+
+                        let last_sent = db.get_last_reminder_at(&user.phone_number, "daily_summary").await.ok().flatten();
+                        if let Some(today) = Self::reminder_due_date(now_utc, user_tz, summary_time, last_sent) {
+                            // Özeti (ve AI anlatısını) boşuna hazırlamamak için önce sor; gönderimde tekrar bakılır
+                            if !compliance::permit(&db, &user, MessageCategory::Reminder, now_utc).await {
+                                continue;
+                            }
+                            if let Ok(stats) = db.get_daily_stats(&user.phone_number, today).await {
+                                // "ozet anlati": AI'ın yazdığı kısa anlatı; AI yoksa sayısal özete düş
+                                let narrative = if user.summary_narrative {
+                                    crate::services::day_narrative::narrative_summary(&db, &ai, &user, &stats, today).await
+                                } else {
+                                    None
+                                };
+                                let message = match &narrative {
+                                    Some(narrative) => format!("🌙 *Günün Hikayesi*\n\n{}", narrative),
+                                    None => Self::numeric_summary(&db, &user, &stats, today).await,
+                                };
+                                let metadata = serde_json::json!({
+                                    "reminder_type": "daily_summary",
+                                    "narrative": narrative.is_some(),
+                                    "calories": stats.total_calories,
+                                    "water_ml": stats.total_water_ml,
+                                    "meals_count": stats.meals_count
+                                });
+                                match compliance::send_proactive(&db, whatsapp.as_ref(), &user, MessageCategory::Reminder, Outgoing::Text(&message), metadata).await {
+                                    Delivery::Sent => {
+                                        let _ = db.record_reminder_sent(&user.phone_number, "daily_summary", now_utc).await;
+                                        log::info!("📤 Sent daily summary to {} at {} ({})", user.phone_number, summary_time, user.timezone);
+                                    }
+                                    Delivery::Held => {}
+                                    Delivery::Failed(e) => log::error!("❌ Failed to send daily summary to {}: {}", user.phone_number, e),
+                                }
+                            }
+                        }
+                    }
+                    log::debug!("✅ Daily summary check completed");
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        log::info!("Added daily summary reminder (timezone-aware)");
+        Ok(())
+    }
+
+    /// The classic numeric nightly summary (sections from "ozet icerik")
+    async fn numeric_summary(db: &Database, user: &User, stats: &DailyStats, today: chrono::NaiveDate) -> String {
+        let streak = crate::services::summary_sections::report_streak(db, user, today).await;
+        let report = crate::services::whatsapp::format_daily_report(
+            stats,
+            user.daily_calorie_goal.unwrap_or(2000),
+            user.daily_water_goal.unwrap_or(2000),
+            user.units,
+            &user.summary_sections,
+            streak,
+        );
+
+        let report = crate::services::benchmark::with_comparison(db, user, stats, report).await;
+        format!("🌙 *Günlük Özet*\n\n{}", report)
+    }
+
+    async fn add_nightly_benchmark(&mut self) -> Result<()> {
+        let db = self.db.clone();
+
+        // 00:30 UTC: bir önceki UTC gününün toplamları kesinleşmiş olur
+        let job = Job::new_async("0 30 0 * * *", move |_uuid, _l| {
+            let db = db.clone();
+
+            Box::pin(async move {
+                let yesterday = chrono::Utc::now().date_naive() - chrono::Duration::days(1);
+                if let Err(e) = crate::services::benchmark::compute_daily_aggregates(&db, yesterday).await {
+                    log::error!("❌ Nightly benchmark aggregation failed: {}", e);
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        log::info!("Added nightly benchmark aggregation");
+        Ok(())
+    }
+
+    async fn add_conversation_archival(&mut self) -> Result<()> {
+        let Some(months) = crate::services::archive::archive_after_months_from_env() else {
+            log::info!("Conversation archiving disabled (CONVERSATION_ARCHIVE_MONTHS=0)");
+            return Ok(());
+        };
+        let db = self.db.clone();
+
+        let job = Job::new_async("0 0 1 * * *", move |_uuid, _l| {
+            let db = db.clone();
+
+            Box::pin(async move {
+                if let Err(e) = crate::services::archive::archive_old_conversations(&db, Utc::now(), months).await {
+                    log::error!("❌ Conversation archiving failed: {}", e);
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        log::info!("Added nightly conversation archiving (older than {} months)", months);
+        Ok(())
+    }
+
+    async fn add_nightly_maintenance(&mut self) -> Result<()> {
+        let db = self.db.clone();
+        let settings = crate::services::maintenance::MaintenanceSettings::from_env();
+
+        // Arşivlemeden (01:00) sonra, böylece taşınan satırların boşluğu da temizlenir
+        let job = Job::new_async("0 30 2 * * *", move |_uuid, _l| {
+            let db = db.clone();
+            let settings = settings.clone();
+
+            Box::pin(async move {
+                crate::services::maintenance::run_nightly(&db, &settings).await;
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        log::info!("Added nightly database maintenance (02:30 UTC)");
+        Ok(())
+    }
+
+    async fn add_weekly_kpi_report(&mut self) -> Result<()> {
+        let db = self.db.clone();
+        let notifier = self.notifier.clone();
+
+        // Scheduler UTC çalışır: 06:00 UTC = 09:00 Europe/Istanbul
+        let job = Job::new_async("0 0 6 * * Mon", move |_uuid, _l| {
+            let db = db.clone();
+            let notifier = notifier.clone();
+
+            Box::pin(async move {
+                let today = chrono::Utc::now().date_naive();
+                if let Err(e) = crate::services::kpi::send_weekly_report(&db, &notifier, today).await {
+                    log::error!("❌ Weekly KPI report failed: {}", e);
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        if self.notifier.is_enabled() {
+            log::info!("Added weekly KPI report (Mondays 09:00 Istanbul)");
+        } else {
+            log::info!("Added weekly KPI snapshot job (email disabled, NOTIFIER_EMAIL_API_KEY not set)");
+        }
+        Ok(())
+    }
+
+    async fn add_telemetry_report(&mut self) -> Result<()> {
+        let settings = crate::services::telemetry::TelemetrySettings::from_env();
+        if settings.active_endpoint().is_none() {
+            if settings.enabled {
+                log::warn!("⚠️ TELEMETRY=true but TELEMETRY_ENDPOINT is not set, telemetry stays off");
+            }
+            return Ok(());
+        }
+        let db = self.db.clone();
+
+        // Saatlik: gönderilemeyen günlük rapor bir sonraki çalışmada tekrar denenir
+        let job = Job::new_async("0 15 * * * *", move |_uuid, _l| {
+            let db = db.clone();
+            let settings = settings.clone();
+
+            Box::pin(async move {
+                if let Err(e) = crate::services::telemetry::send_daily_report(&db, &settings).await {
+                    log::error!("❌ Telemetry report failed: {}", e);
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        log::info!("📡 Added daily anonymous telemetry report (tried hourly at :15 UTC)");
+        Ok(())
+    }
+
+    async fn add_customize_nudge(&mut self) -> Result<()> {
+        let db = self.db.clone();
+        let whatsapp = self.whatsapp.clone();
+
+        // Saatte bir (:15): zamanı gelmiş hatırlatmaları gönder (sessiz saat ve 24h pencere dışında bekler)
+        let job = Job::new_async("0 15 * * * *", move |_uuid, _l| {
+            let db = db.clone();
+            let whatsapp = whatsapp.clone();
+
+            Box::pin(async move {
+                use chrono::Timelike;
+
+                let message = "⚙️ *Botu kendine göre ayarlamak ister misin?*\n\n\
+Kurulumu atladığın için varsayılan öğün saatleri ve hedefler kullanılıyor.\n\
+Değiştirmek için yazman yeterli:\n\
+• \"kahvaltı saatim 8\"\n\
+• \"kalori hedefim 1800\"\n\
+• \"su hedefim 2.5 litre\"\n\n\
+Mevcut ayarlarını görmek için: *ayarlar*";
+
+                let phones = match db.get_due_customize_nudges(Utc::now()).await {
+                    Ok(phones) => phones,
+                    Err(e) => {
+                        log::error!("❌ Failed to load customize nudges: {}", e);
+                        return;
+                    }
+                };
+
+                for phone in phones {
+                    let Ok(Some(user)) = db.get_user(&phone).await else { continue };
+
+                    let user_tz: Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+                    let now_user = Utc::now().with_timezone(&user_tz);
+                    let is_silent = Self::is_silent_hours(
+                        now_user.hour(),
+                        now_user.minute(),
+                        user.silent_hours_start.as_deref().unwrap_or("23:00"),
+                        user.silent_hours_end.as_deref().unwrap_or("07:00"),
+                    );
+                    if is_silent || !db.is_within_24h_window(&phone).await.unwrap_or(false) {
+                        continue;
+                    }
+
+                    let metadata = serde_json::json!({"reminder_type": "customize_nudge"});
+                    match compliance::send_proactive(&db, whatsapp.as_ref(), &user, MessageCategory::Marketing, Outgoing::Text(message), metadata).await {
+                        Delivery::Sent => {
+                            let _ = db.clear_customize_nudge(&phone).await;
+                            log::info!("📤 Sent customize nudge to {}", phone);
+                        }
+                        Delivery::Held => {}
+                        Delivery::Failed(e) => log::error!("❌ Failed to send customize nudge to {}: {}", phone, e),
+                    }
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        log::info!("Added onboarding customize nudge");
+        Ok(())
+    }
+
+    async fn add_changelog_announcements(&mut self) -> Result<()> {
+        use crate::services::changelog;
+
+        let db = self.db.clone();
+        let whatsapp = self.whatsapp.clone();
+
+        // :45'te çalışır; pencere dışındaki/sessiz saatteki kullanıcılar sonraki çalışmalarda alır
+        let job = Job::new_async("0 45 * * * *", move |_uuid, _l| {
+            let db = db.clone();
+            let whatsapp = whatsapp.clone();
+
+            Box::pin(async move {
+                use chrono::Timelike;
+
+                let entry = match db.get_deployed_changelog_entry(crate::VERSION).await {
+                    Ok(Some(entry)) if changelog::is_announcing(&entry, Utc::now()) => entry,
+                    Ok(_) => return,
+                    Err(e) => {
+                        log::error!("❌ Failed to load changelog for {}: {}", crate::VERSION, e);
+                        return;
+                    }
+                };
+                let message = changelog::format_announcement(&entry);
+
+                let users = match db.get_users_pending_changelog(entry.id).await {
+                    Ok(users) => users,
+                    Err(e) => {
+                        log::error!("❌ Failed to load users for changelog {}: {}", entry.version, e);
+                        return;
+                    }
+                };
+
+                for user in users {
+                    let user_tz: Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+                    let now_user = Utc::now().with_timezone(&user_tz);
+                    let is_silent = Self::is_silent_hours(
+                        now_user.hour(),
+                        now_user.minute(),
+                        user.silent_hours_start.as_deref().unwrap_or("23:00"),
+                        user.silent_hours_end.as_deref().unwrap_or("07:00"),
+                    );
+                    if is_silent || !db.is_within_24h_window(&user.phone_number).await.unwrap_or(false) {
+                        continue;
+                    }
+
+                    // Başka bir instance aynı anda gönderiyorsa atla
+                    if !db.claim_changelog_delivery(entry.id, &user.phone_number).await.unwrap_or(false) {
+                        continue;
+                    }
+
+                    let metadata = serde_json::json!({"reminder_type": "changelog", "version": entry.version});
+                    match compliance::send_proactive(&db, whatsapp.as_ref(), &user, MessageCategory::Marketing, Outgoing::Text(&message), metadata).await {
+                        Delivery::Sent => log::info!("✨ Sent v{} announcement to {}", entry.version, user.phone_number),
+                        Delivery::Held => {
+                            let _ = db.release_changelog_delivery(entry.id, &user.phone_number).await;
+                        }
+                        Delivery::Failed(e) => {
+                            log::error!("❌ Failed to send v{} announcement to {}: {}", entry.version, user.phone_number, e);
+                            let _ = db.release_changelog_delivery(entry.id, &user.phone_number).await;
+                        }
+                    }
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        log::info!("Added changelog announcements (v{})", crate::VERSION);
+        Ok(())
+    }
+
+    async fn add_monthly_nps_poll(&mut self) -> Result<()> {
+        use crate::services::feedback::{NPS_INTERVAL_DAYS, NPS_MIN_ACCOUNT_AGE_DAYS, NPS_PENDING, NPS_QUESTION};
+
+        let db = self.db.clone();
+        let whatsapp = self.whatsapp.clone();
+
+        // 08:00 UTC = 11:00 Europe/Istanbul; sessiz saatte veya 24h pencere dışındaysa ertesi güne kalır
+        let job = Job::new_async("0 0 8 * * *", move |_uuid, _l| {
+            let db = db.clone();
+            let whatsapp = whatsapp.clone();
+
+            Box::pin(async move {
+                use chrono::Timelike;
+
+                let users = match db.get_users_due_for_nps(NPS_INTERVAL_DAYS, NPS_MIN_ACCOUNT_AGE_DAYS).await {
+                    Ok(users) => users,
+                    Err(e) => {
+                        log::error!("❌ Failed to load users for NPS poll: {}", e);
+                        return;
+                    }
+                };
+
+                // WhatsApp listeleri en fazla 10 satır: 10..1 listeden seçilir, 0 yazılarak da verilebilir
+                let rows: Vec<(String, String)> = (1..=10)
+                    .rev()
+                    .map(|score| (format!("nps_{}", score), score.to_string()))
+                    .collect();
+
+                for user in users {
+                    // Başka bir seçim bekleniyorsa (öğün birleştirme vb.) araya girme
+                    if user.awaiting_reply(Utc::now()).is_some() {
+                        continue;
+                    }
+
+                    let user_tz: Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+                    let now_user = Utc::now().with_timezone(&user_tz);
+                    let is_silent = Self::is_silent_hours(
+                        now_user.hour(),
+                        now_user.minute(),
+                        user.silent_hours_start.as_deref().unwrap_or("23:00"),
+                        user.silent_hours_end.as_deref().unwrap_or("07:00"),
+                    );
+                    if is_silent || !db.is_within_24h_window(&user.phone_number).await.unwrap_or(false) {
+                        continue;
+                    }
+
+                    let outgoing = Outgoing::List { message: NPS_QUESTION, button: "Puan ver", rows: rows.clone() };
+                    let metadata = serde_json::json!({"reminder_type": "nps_poll"});
+                    match compliance::send_proactive(&db, whatsapp.as_ref(), &user, MessageCategory::Marketing, outgoing, metadata).await {
+                        Delivery::Sent => {
+                            let _ = db.mark_nps_asked(&user.phone_number, NPS_PENDING).await;
+                            log::info!("📣 Sent NPS poll to {}", user.phone_number);
+                        }
+                        Delivery::Held => {}
+                        Delivery::Failed(e) => log::error!("❌ Failed to send NPS poll to {}: {}", user.phone_number, e),
+                    }
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        log::info!("Added monthly NPS poll");
+        Ok(())
+    }
+
+    async fn add_email_reports(&mut self) -> Result<()> {
+        use crate::services::email_report::{send_report, ReportPeriod, EMAIL_REPORT_HOUR};
+
+        if !self.notifier.is_enabled() {
+            log::info!("Email reports disabled (NOTIFIER_EMAIL_API_KEY not set)");
+            return Ok(());
+        }
+
+        let db = self.db.clone();
+        let notifier = self.notifier.clone();
+
+        // Her saat başı kontrol et: Pazartesi 09:00 haftalık, ayın 1'i 09:00 aylık (kullanıcı timezone'unda)
+        let job = Job::new_async("0 0 * * * *", move |_uuid, _l| {
+            let db = db.clone();
+            let notifier = notifier.clone();
+
+            Box::pin(async move {
+                use chrono::{Datelike, Timelike, Utc};
+                use chrono_tz::Tz;
+
+                let Ok(users) = db.get_active_users().await else {
+                    return;
+                };
+                for user in users.into_iter().filter(|u| u.email.is_some()) {
+                    let user_tz: Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+                    let now_user = Utc::now().with_timezone(&user_tz);
+                    if now_user.hour() != EMAIL_REPORT_HOUR {
+                        continue;
+                    }
+
+                    let mut periods = Vec::new();
+                    if user.email_reports.weekly() && now_user.weekday() == chrono::Weekday::Mon {
+                        periods.push(ReportPeriod::Weekly);
+                    }
+                    if user.email_reports.monthly() && now_user.day() == 1 {
+                        periods.push(ReportPeriod::Monthly);
+                    }
+
+                    if !periods.is_empty() && !compliance::permit(&db, &user, MessageCategory::Reminder, Utc::now()).await {
+                        continue;
+                    }
+                    for period in periods {
+                        match send_report(&db, &notifier, &user, period, now_user.date_naive()).await {
+                            Ok(()) => log::info!("📧 Sent {} email report to {}", period.as_str(), user.phone_number),
+                            Err(e) => log::error!("❌ Failed to email {} report to {}: {}", period.as_str(), user.phone_number, e),
+                        }
+                    }
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        log::info!("Added email reports (Monday / 1st of month 09:00, timezone-aware)");
+        Ok(())
+    }
+
+    async fn add_coach_weekly_summary(&mut self) -> Result<()> {
+        let db = self.db.clone();
+        let whatsapp = self.whatsapp.clone();
+
+        // Her saat başı kontrol et, kullanıcı timezone'unda Pazar 20:00'de gönder
+        let job = Job::new_async("0 0 * * * *", move |_uuid, _l| {
+            let db = db.clone();
+            let whatsapp = whatsapp.clone();
+
+            Box::pin(async move {
+                use chrono::{Datelike, Timelike, Utc};
+                use chrono_tz::Tz;
+
+                if let Ok(users) = db.get_active_users().await {
+                    for user in users {
+                        // Sadece koçu olan ve paylaşıma onay veren kullanıcılar
+                        let coach_phone = match (&user.coach_phone, user.coach_sharing) {
+                            (Some(phone), true) => phone.clone(),
+                            _ => continue,
+                        };
+
+                        let user_tz: Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+                        let now_user = Utc::now().with_timezone(&user_tz);
+                        if now_user.weekday() != chrono::Weekday::Sun || now_user.hour() != 20 {
+                            continue;
+                        }
+
+                        let days = match db.get_weekly_stats(&user.phone_number, now_user.date_naive()).await {
+                            Ok(days) => days,
+                            Err(e) => {
+                                log::error!("❌ Failed to build coach summary for {}: {}", user.phone_number, e);
+                                continue;
+                            }
+                        };
+
+                        let client_name = user.name.as_deref().unwrap_or(&user.phone_number);
+                        let message = crate::services::whatsapp::format_coach_summary(
+                            client_name,
+                            &days,
+                            user.daily_calorie_goal.unwrap_or(2000),
+                            user.daily_water_goal.unwrap_or(2000),
+                        );
+
+                        // Kullanıcının geçmişine kaydedilir (koça ne paylaşıldığı görülebilsin)
+                        let metadata = serde_json::json!({
+                            "reminder_type": "coach_summary",
+                            "coach_phone": coach_phone
+                        });
+                        match compliance::send_proactive_to(&db, whatsapp.as_ref(), &coach_phone, &user, MessageCategory::Reminder, Outgoing::Text(&message), metadata).await {
+                            Delivery::Sent => log::info!("📤 Sent weekly coach summary for {} to {}", user.phone_number, coach_phone),
+                            Delivery::Held => log::info!("🛑 Coach summary for {} held by {}'s regional rules", user.phone_number, coach_phone),
+                            Delivery::Failed(e) => {
+                                log::error!("❌ Failed to send coach summary for {} to {}: {}", user.phone_number, coach_phone, e);
+                            }
+                        }
+                    }
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        log::info!("Added weekly coach summary (Sunday 20:00, timezone-aware)");
+        Ok(())
+    }
+
+    async fn add_weekly_goal_review(&mut self) -> Result<()> {
+        use crate::services::goal_review::{format_review, GoalReviewOption, WeekAdherence, GOAL_REVIEW_HOUR, GOAL_REVIEW_PENDING};
+
+        let db = self.db.clone();
+        let whatsapp = self.whatsapp.clone();
+
+        // Her saat başı kontrol et, kullanıcı timezone'unda Pazar 19:00'da gönder
+        let job = Job::new_async("0 0 * * * *", move |_uuid, _l| {
+            let db = db.clone();
+            let whatsapp = whatsapp.clone();
+
+            Box::pin(async move {
+                use chrono::{Datelike, Timelike};
+
+                let Ok(users) = db.get_active_users().await else {
+                    return;
+                };
+                for user in users {
+                    // Başka bir seçim bekleniyorsa (NPS, öğün birleştirme vb.) araya girme
+                    if user.awaiting_reply(Utc::now()).is_some() {
+                        continue;
+                    }
+
+                    let user_tz: Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+                    let now_user = Utc::now().with_timezone(&user_tz);
+                    if now_user.weekday() != chrono::Weekday::Sun || now_user.hour() != GOAL_REVIEW_HOUR {
+                        continue;
+                    }
+                    let is_silent = Self::is_silent_hours(
+                        now_user.hour(),
+                        now_user.minute(),
+                        user.silent_hours_start.as_deref().unwrap_or("23:00"),
+                        user.silent_hours_end.as_deref().unwrap_or("07:00"),
+                    );
+                    if is_silent || !db.is_within_24h_window(&user.phone_number).await.unwrap_or(false) {
+                        continue;
+                    }
+
+                    let today = now_user.date_naive();
+                    let days = match db.get_weekly_stats(&user.phone_number, today).await {
+                        Ok(days) => days,
+                        Err(e) => {
+                            log::error!("❌ Failed to build goal review for {}: {}", user.phone_number, e);
+                            continue;
+                        }
+                    };
+                    let calorie_goal = user.daily_calorie_goal.unwrap_or(2000);
+                    let water_goal = user.daily_water_goal.unwrap_or(2000);
+                    let week = WeekAdherence::from_days(&days, calorie_goal, water_goal);
+                    // Bu hafta hiç öğün kaydetmeyene hedef sormanın anlamı yok
+                    if week.logged_days == 0 {
+                        continue;
+                    }
+
+                    // Birden fazla instance aynı saatte çalışır: haftada bir kez gönder
+                    if !db.claim_goal_review(&user.phone_number, today).await.unwrap_or(false) {
+                        continue;
+                    }
+
+                    let message = format_review(&week, calorie_goal, water_goal, user.units);
+                    let rows: Vec<(String, String)> =
+                        GoalReviewOption::ALL.iter().map(|option| (option.id(), option.title(user.units))).collect();
+                    let outgoing = Outgoing::List { message: &message, button: "Hedefleri ayarla", rows };
+                    let metadata = serde_json::json!({"reminder_type": "goal_review"});
+                    match compliance::send_proactive(&db, whatsapp.as_ref(), &user, MessageCategory::Reminder, outgoing, metadata).await {
+                        Delivery::Sent => {
+                            let _ = db.update_pending_command(&user.phone_number, Some(GOAL_REVIEW_PENDING)).await;
+                            log::info!("🗓️ Sent weekly goal review to {}", user.phone_number);
+                        }
+                        Delivery::Held => {
+                            let _ = db.release_goal_review(&user.phone_number, today).await;
+                        }
+                        Delivery::Failed(e) => {
+                            log::error!("❌ Failed to send goal review to {}: {}", user.phone_number, e);
+                            let _ = db.release_goal_review(&user.phone_number, today).await;
+                        }
+                    }
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        log::info!("Added weekly goal review (Sunday {}:00, timezone-aware)", GOAL_REVIEW_HOUR);
+        Ok(())
+    }
+
+    async fn add_window_warning_check(&mut self, _schedule: &str) -> Result<()> {
+        let db = self.db.clone();
+        let whatsapp = self.whatsapp.clone();
+
+        // Her saat başı kontrol et
+        let job = Job::new_async("0 0 * * * *", move |_uuid, _l| {
+            let db = db.clone();
+            let whatsapp = whatsapp.clone();
+
+            Box::pin(async move {
+                if let Ok(users) = db.get_active_users().await {
+                    log::debug!("⏰ Window warning check running for {} users", users.len());
+                    for user in users {
+                        if !user.onboarding_completed || !user.opted_in {
+                            continue;
+                        }
+
+                        // Check window status
+                        if let Ok((is_within_window, hours_since_last, needs_warning)) =
+                            db.check_24h_window_detailed(&user.phone_number).await
+                        {
+                            // Only warn if:
+                            // 1. User needs warning (20-23 hours)
+                            // 2. User hasn't been warned recently
+                            // 3. User is still within window (to actually send the message)
+                            if needs_warning && is_within_window {
+                                if let Ok(was_warned) = db.was_recently_warned(&user.phone_number).await {
+                                    if !was_warned {
+                                        let hours = hours_since_last.unwrap_or(0);
+                                        let hours_left = 24 - hours;
+
+                                        let message = format!(
+                                            "👋 *Merhaba!*\n\n\
+                                            Uzun zamandır ({} saat) mesaj atmadın.\n\n\
+                                            WhatsApp kuralları gereği, 24 saat içinde mesaj atmazsan \
+                                            otomatik hatırlatıcıları alamazsın.\n\n\
+                                            ⏰ *Yaklaşık {} saat sonra* hatırlatıcıları kaybedeceksin.\n\n\
+                                            Hatırlatıcıları almaya devam etmek için herhangi bir mesaj gönder! 😊\n\n\
+                                            Örnek: \"Merhaba\" veya \"Rapor\"",
+                                            hours, hours_left
+                                        );
+
+                                        let metadata = serde_json::json!({
+                                            "reminder_type": "window_warning",
+                                            "hours_since_last_message": hours,
+                                            "hours_until_expiry": hours_left
+                                        });
+                                        let delivery = compliance::send_proactive(&db, whatsapp.as_ref(), &user, MessageCategory::Reminder, Outgoing::Text(&message), metadata).await;
+                                        if let Delivery::Sent = delivery {
+                                            // Mark as warned
+                                            let _ = db.mark_as_warned(&user.phone_number).await;
+
+                                            log::info!(
+                                                "⚠️ Sent 24h window warning to {} ({} hours since last message)",
+                                                user.phone_number, hours
+                                            );
+                                        }
+                                    } else {
+                                        log::debug!(
+                                            "⏭️ Skipping warning for {} - already warned recently",
+                                            user.phone_number
+                                        );
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    log::debug!("✅ Window warning check completed");
+                }
+            })
+        })?;
+
+        self.scheduler.add(job).await?;
+        log::info!("Added 24h window warning check (hourly)");
+        Ok(())
+    }
+
+    pub async fn stop(&mut self) -> Result<()> {
+        self.scheduler.shutdown().await?;
+        log::info!("Reminder service stopped");
+        Ok(())
+    }
+
+    /// Hatırlatma zamanı geldi mi?
+    ///
+    /// Yerel "HH:MM" string karşılaştırması yerine: hedef saat, bir önceki kontrol ile şimdiki
+    /// kontrol arasına (yerel saatle) düşüyorsa hatırlatma zamanıdır. Böylece DST ileri alındığında
+    /// var olmayan saatler (örn. 02:30) atlanmaz. Son gönderim UTC olarak tutulur; kullanıcının
+    /// *şu anki* timezone'unda aynı gün zaten gönderildiyse tekrar gönderilmez (DST geri alma
+    /// sırasında tekrar eden saat, batıya uçup timezone değiştiren kullanıcı).
+    ///
+    /// Zamanı gelmişse hatırlatmanın ait olduğu yerel günü döner: 23:45 hedefi gece yarısı
+    /// kontrolünde yakalanır ve önceki güne aittir.
+    fn reminder_due_date(
+        now_utc: DateTime<Utc>,
+        tz: Tz,
+        target: &str,
+        last_sent_utc: Option<DateTime<Utc>>,
+    ) -> Option<NaiveDate> {
+        let target = NaiveTime::parse_from_str(target.trim(), "%H:%M").ok()?;
+        let due = Self::due_in_window(now_utc, tz, target)?;
+
+        // Son gönderim hangi güne aitti: kendi kontrol aralığına bu hedef düşüyorsa o gün
+        // (23:45 hedefi ertesi gün 00:00'da gönderilir), düşmüyorsa gönderildiği yerel gün
+        let sent_for = last_sent_utc.map(|last| {
+            Self::due_in_window(last, tz, target)
+                .map(|sent_due| sent_due.date())
+                .unwrap_or_else(|| last.with_timezone(&tz).date_naive())
+        });
+
+        (sent_for != Some(due.date())).then_some(due.date())
+    }
+
+    /// `target`'ın (now - kontrol aralığı, now] yerel aralığına düşen tekrarı
+    fn due_in_window(now_utc: DateTime<Utc>, tz: Tz, target: NaiveTime) -> Option<NaiveDateTime> {
+        let now_local = now_utc.with_timezone(&tz).naive_local();
+        let prev_local = (now_utc - Duration::minutes(CHECK_INTERVAL_MINUTES))
+            .with_timezone(&tz)
+            .naive_local();
+        // Aralık gece yarısını geçebilir: hedefi hem önceki kontrolün hem şimdinin gününde dene
+        [prev_local.date(), now_local.date()]
+            .into_iter()
+            .map(|day| day.and_time(target))
+            .find(|due| prev_local < *due && *due <= now_local)
+    }
+
+    /// Check if current time is within user's silent hours
+    /// Silent hours can cross midnight (e.g., 23:00 - 07:00)
+    fn is_silent_hours(
+        current_hour: u32,
+        current_minute: u32,
+        start: &str,
+        end: &str,
+    ) -> bool {
+        // Parse start and end times
+        let parse_time = |time_str: &str| -> Option<(u32, u32)> {
+            let parts: Vec<&str> = time_str.split(':').collect();
+            if parts.len() != 2 {
+                return None;
+            }
+            let h = parts[0].parse::<u32>().ok()?;
+            let m = parts[1].parse::<u32>().ok()?;
+            Some((h, m))
+        };
+
+        let (start_h, start_m) = match parse_time(start) {
+            Some(t) => t,
+            None => return false, // Invalid format, don't block
+        };
+
+        let (end_h, end_m) = match parse_time(end) {
+            Some(t) => t,
+            None => return false,
+        };
+
+        // Convert to minutes since midnight for easier comparison
+        let current_minutes = current_hour * 60 + current_minute;
+        let start_minutes = start_h * 60 + start_m;
+        let end_minutes = end_h * 60 + end_m;
+
+        if start_minutes < end_minutes {
+            // Normal case: e.g., 08:00 - 22:00
+            current_minutes >= start_minutes && current_minutes < end_minutes
+        } else {
+            // Crosses midnight: e.g., 23:00 - 07:00
+            current_minutes >= start_minutes || current_minutes < end_minutes
+        }
+    }
+
+    /// 08:00-22:00 arası; son su kaydından ya da son hatırlatmadan (hangisi yeniyse)
+    /// bu yana `interval_hours` geçtiyse. Hiç kayıt yoksa ilk uygun saatte.
+    fn is_water_reminder_due(
+        now_local: chrono::NaiveTime,
+        now: DateTime<Utc>,
+        last_drink: Option<DateTime<Utc>>,
+        last_reminder: Option<DateTime<Utc>>,
+        interval_hours: i32,
+    ) -> bool {
+        use chrono::Timelike;
+
+        if !(WATER_DAY_START_HOUR..=WATER_DAY_END_HOUR).contains(&now_local.hour()) {
+            return false;
+        }
+        let Some(last) = last_drink.max(last_reminder) else {
+            return true;
+        };
+        // Kontroller :00/:30'da birkaç saniye kayabilir, bir dakika tolerans
+        now - last >= Duration::hours(interval_hours.max(1) as i64) - Duration::minutes(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn utc(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    fn is_due(now_utc: DateTime<Utc>, tz: Tz, target: &str, last_sent_utc: Option<DateTime<Utc>>) -> bool {
+        ReminderService::reminder_due_date(now_utc, tz, target, last_sent_utc).is_some()
+    }
+
+    #[test]
+    fn test_reminder_due_regular_day() {
+        let tz = chrono_tz::Europe::Istanbul; // UTC+3, DST yok
+        assert!(is_due(utc(2025, 6, 10, 6, 0), tz, "09:00", None));
+        assert!(!is_due(utc(2025, 6, 10, 6, 30), tz, "09:00", None));
+        assert!(!is_due(utc(2025, 6, 10, 5, 30), tz, "09:00", None));
+
+        // Dün gönderildiyse bugün tekrar gönderilir, bugün gönderildiyse gönderilmez
+        let yesterday = Some(utc(2025, 6, 9, 6, 0));
+        assert!(is_due(utc(2025, 6, 10, 6, 0), tz, "09:00", yesterday));
+        let today = Some(utc(2025, 6, 10, 6, 0));
+        assert!(!is_due(utc(2025, 6, 10, 6, 0), tz, "09:00", today));
+    }
+
+    #[test]
+    fn test_reminder_due_across_dst_transitions() {
+        let tz = chrono_tz::America::New_York;
+
+        // İleri alma (2025-03-09 02:00 EST -> 03:00 EDT): 02:30 hiç yaşanmaz,
+        // hatırlatma boşluktan sonraki ilk kontrolde (03:00 EDT = 07:00 UTC) gider
+        assert!(!is_due(utc(2025, 3, 9, 6, 30), tz, "02:30", None));
+        assert!(is_due(utc(2025, 3, 9, 7, 0), tz, "02:30", None));
+
+        // Normal saatler DST gününde de doğru UTC anında tetiklenir (09:00 EDT = 13:00 UTC)
+        assert!(is_due(utc(2025, 3, 9, 13, 0), tz, "09:00", None));
+        assert!(!is_due(utc(2025, 3, 9, 14, 0), tz, "09:00", None));
+
+        // Geri alma (2025-11-02 02:00 EDT -> 01:00 EST): 01:30 iki kez yaşanır, tek gönderim
+        let first = utc(2025, 11, 2, 5, 30); // 01:30 EDT
+        let second = utc(2025, 11, 2, 6, 30); // 01:30 EST
+        assert!(is_due(first, tz, "01:30", None));
+        assert!(is_due(second, tz, "01:30", None));
+        assert!(!is_due(second, tz, "01:30", Some(first)));
+    }
+
+    #[test]
+    fn test_reminder_not_resent_after_flying_west() {
+        // 09:00 İstanbul'da (06:00 UTC) kahvaltı hatırlatması gitti, kullanıcı New York'a uçtu
+        let sent = Some(utc(2025, 6, 10, 6, 0));
+        let new_york = chrono_tz::America::New_York;
+
+        // Aynı gün 09:00 New York (13:00 UTC): tekrar gönderme
+        assert!(!is_due(utc(2025, 6, 10, 13, 0), new_york, "09:00", sent));
+        // Ertesi gün normal devam
+        assert!(is_due(utc(2025, 6, 11, 13, 0), new_york, "09:00", sent));
+
+        // Doğuya uçuşta ertesi günün hatırlatması kaçmaz: 09:00 New York (13:00 UTC) -> 09:00 İstanbul
+        let sent_ny = Some(utc(2025, 6, 10, 13, 0));
+        assert!(is_due(utc(2025, 6, 11, 6, 0), chrono_tz::Europe::Istanbul, "09:00", sent_ny));
+    }
+
+    #[test]
+    fn test_reminder_due_just_before_midnight() {
+        let tz = chrono_tz::Europe::Istanbul;
+        let day = |d| NaiveDate::from_ymd_opt(2025, 6, d).unwrap();
+
+        // 23:45 hedefi 23:30 kontrolünde değil, gece yarısı (21:00 UTC) kontrolünde gider
+        assert!(!is_due(utc(2025, 6, 10, 20, 30), tz, "23:45", None));
+        assert_eq!(ReminderService::reminder_due_date(utc(2025, 6, 10, 21, 0), tz, "23:45", None), Some(day(10)));
+        // O güne ait gönderim gece yarısından sonra yapılmış olsa da tekrar gönderilmez
+        let sent = Some(utc(2025, 6, 10, 21, 0));
+        assert!(!is_due(utc(2025, 6, 10, 21, 0), tz, "23:45", sent));
+        assert_eq!(ReminderService::reminder_due_date(utc(2025, 6, 11, 21, 0), tz, "23:45", sent), Some(day(11)));
+    }
+
+    #[test]
+    fn test_water_reminder_relative_to_last_drink() {
+        let due = |now: DateTime<Utc>, drink, reminder, interval| {
+            // Testte yerel saat = UTC
+            ReminderService::is_water_reminder_due(now.time(), now, drink, reminder, interval)
+        };
+        let now = utc(2025, 6, 10, 14, 0);
+
+        // 1,5 saat önce su içti: 2 saatlik aralıkta hatırlatma yok, 1 saatlikte var
+        assert!(!due(now, Some(utc(2025, 6, 10, 12, 30)), None, 2));
+        assert!(due(now, Some(utc(2025, 6, 10, 12, 30)), None, 1));
+        // Su içmedi ama 1 saat önce hatırlatıldı: tekrar dürtme
+        assert!(!due(now, Some(utc(2025, 6, 10, 9, 0)), Some(utc(2025, 6, 10, 13, 0)), 2));
+        // Hiç kayıt yok
+        assert!(due(now, None, None, 3));
+        // :00 kontrolü birkaç saniye erken çalışsa da kaçmaz
+        assert!(due(now, Some(utc(2025, 6, 10, 12, 0) + Duration::seconds(20)), None, 2));
+        // Gün dışı saatler
+        assert!(!due(utc(2025, 6, 10, 23, 0), None, None, 2));
+        assert!(!due(utc(2025, 6, 10, 7, 30), None, None, 2));
+    }
+}
@@ -0,0 +1,16 @@
+//! Tavari nutrition engine.
+//!
+//! Everything the bot does apart from HTTP serving: models, PostgreSQL access, AI and
+//! WhatsApp providers, message handling and scheduled reminders. The bundled webhook
+//! server (`tavari-server`) and operator tools (`tavari-cli`) are thin layers on top.
+//! Use [`BotBuilder`] to embed the engine in your own application.
+
+pub mod bot;
+pub mod handlers;
+pub mod models;
+pub mod services;
+
+pub use bot::{Bot, BotBuilder};
+
+/// Version of the running engine (bug reports, "yenilikler" announcements)
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,533 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub phone_number: String,
+    pub name: Option<String>,  // WhatsApp profil ismi
+    pub created_at: DateTime<Utc>,
+    pub onboarding_completed: bool,
+    pub onboarding_step: Option<String>,
+    pub breakfast_reminder: bool,
+    pub lunch_reminder: bool,
+    pub dinner_reminder: bool,
+    pub water_reminder: bool,
+    pub breakfast_time: Option<String>,  // HH:MM format (örn: "09:00")
+    pub lunch_time: Option<String>,      // HH:MM format
+    pub dinner_time: Option<String>,     // HH:MM format
+    pub opted_in: bool,  // Kullanıcı mesaj gönderdi mi?
+    pub timezone: String,  // IANA timezone (örn: "Europe/Istanbul", "America/New_York")
+    pub daily_water_goal: Option<i32>,  // Günlük su hedefi (ml cinsinden, varsayılan: 2000)
+    pub daily_calorie_goal: Option<i32>,  // Günlük kalori hedefi (kcal cinsinden, varsayılan: 2000)
+    pub silent_hours_start: Option<String>,  // Sessiz saatler başlangıcı (HH:MM, varsayılan: "23:00")
+    pub silent_hours_end: Option<String>,    // Sessiz saatler bitişi (HH:MM, varsayılan: "07:00")
+    pub is_active: bool,  // Kullanıcı aktif mi? (false ise sistem ona mesaj atmaz)
+    pub pending_command: Option<String>,  // AI tarafından önerilen komut (onay bekliyor)
+    pub coach_phone: Option<String>,  // Bağlı diyetisyen/koç numarası (admin tarafından atanır)
+    pub coach_sharing: bool,  // Kullanıcı koçla haftalık özet paylaşımına onay verdi mi?
+    pub daily_summary_time: Option<String>,  // Günlük özet saati (HH:MM, varsayılan "22:00"), None = kapalı
+    pub benchmark_opt_in: bool,  // Anonim kullanıcı ortalaması karşılaştırmasına katılım (varsayılan: kapalı)
+    #[serde(default)]
+    pub units: UnitSystem,  // Gösterim/giriş birimi ("birim us"); veriler her zaman ml/kg saklanır
+    #[serde(default)]
+    pub meal_budget: Option<String>,  // Kalori hedefinin öğünlere dağılımı, örn: "25,35,30,10" (None = varsayılan)
+    #[serde(default = "default_water_reminder_interval")]
+    pub water_reminder_interval: i32,  // Su hatırlatma aralığı (saat, 08:00'den itibaren; varsayılan: 2)
+    #[serde(default)]
+    pub summary_sections: SummarySections,  // Günlük özette gösterilecek bölümler ("ozet icerik")
+    #[serde(default)]
+    pub email: Option<String>,  // Kodla doğrulanmış e-posta adresi ("eposta"), None = e-posta raporu yok
+    #[serde(default)]
+    pub email_reports: EmailReportFrequency,  // Hangi raporlar e-postayla gönderilir
+    #[serde(default)]
+    pub language: Language,  // AI analiz ve tavsiyelerinin dili ("dil en")
+    #[serde(default)]
+    pub summary_narrative: bool,  // Günlük özet AI'ın yazdığı kısa anlatı olarak gelsin ("ozet anlati")
+    #[serde(default)]
+    pub pending_command_at: Option<DateTime<Utc>>,  // pending_command ne zaman ayarlandı
+}
+
+/// A question the bot asked (NPS, goal review...) only waits for its answer this long;
+/// after that "2" is a water log again and scheduled questions may ask something else
+pub const PENDING_REPLY_HOURS: i64 = 6;
+
+impl User {
+    /// `pending_command` while it is still waiting for an answer (None once it expired)
+    pub fn awaiting_reply(&self, now: DateTime<Utc>) -> Option<&str> {
+        let set_at = self.pending_command_at?;
+        (now - set_at < chrono::Duration::hours(PENDING_REPLY_HOURS))
+            .then_some(self.pending_command.as_deref())
+            .flatten()
+    }
+}
+
+pub const DEFAULT_WATER_REMINDER_INTERVAL: i32 = 2;
+
+fn default_water_reminder_interval() -> i32 {
+    DEFAULT_WATER_REMINDER_INTERVAL
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Meal {
+    pub id: Option<i64>,
+    pub user_phone: String,
+    pub meal_type: MealType,
+    pub calories: f64,
+    pub description: String,
+    pub image_path: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub extras: BTreeMap<String, f64>,  // Özel besin alanları (CUSTOM_NUTRITION_FIELDS), örn: {"caffeine": 95}
+    #[serde(default)]
+    pub full_description: Option<String>,  // Kısaltılmış açıklamanın tam hali ("detay" komutu)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MealType {
+    Breakfast,
+    Lunch,
+    Dinner,
+    Snack,
+}
+
+impl std::fmt::Display for MealType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            MealType::Breakfast => "Kahvaltı",
+            MealType::Lunch => "Öğle Yemeği",
+            MealType::Dinner => "Akşam Yemeği",
+            MealType::Snack => "Ara Öğün",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl MealType {
+    pub fn from_string(s: &str) -> Option<Self> {
+        // Normalize Turkish characters: İ->i, I->ı before lowercase
+        let normalized = s
+            .replace('İ', "i")
+            .replace('I', "ı")
+            .to_lowercase();
+
+        match normalized.as_str() {
+            "kahvaltı" | "breakfast" => Some(MealType::Breakfast),
+            "öğle yemeği" | "öğle" | "lunch" => Some(MealType::Lunch),
+            "akşam yemeği" | "akşam" | "dinner" => Some(MealType::Dinner),
+            "ara öğün" | "ara" | "snack" => Some(MealType::Snack),
+            _ => None,
+        }
+    }
+}
+
+/// Birim tercihi - sadece gösterim ve giriş için, veritabanı metrik kalır
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    #[default]
+    Metric,  // ml / kg
+    Us,      // fl oz / lb
+}
+
+impl UnitSystem {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnitSystem::Metric => "metric",
+            UnitSystem::Us => "us",
+        }
+    }
+
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "metric" | "metrik" | "ml" | "kg" => Some(UnitSystem::Metric),
+            "us" | "abd" | "imperial" | "oz" | "lb" => Some(UnitSystem::Us),
+            _ => None,
+        }
+    }
+}
+
+/// AI cevap dili - `users.language` ("dil en"); bot mesajlarının kendisi şimdilik Türkçe
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    Tr,
+    En,
+}
+
+impl Language {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Language::Tr => "tr",
+            Language::En => "en",
+        }
+    }
+
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "tr" | "turkce" | "türkçe" | "turkish" => Some(Language::Tr),
+            "en" | "ingilizce" | "i̇ngilizce" | "english" => Some(Language::En),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Language::Tr => "Türkçe",
+            Language::En => "English",
+        }
+    }
+
+    /// Language name as written in AI prompts
+    pub fn prompt_name(&self) -> &'static str {
+        match self {
+            Language::Tr => "Turkish",
+            Language::En => "English",
+        }
+    }
+}
+
+/// E-posta rapor sıklığı - `users.email_reports` ("eposta haftalik|aylik|ikisi")
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailReportFrequency {
+    #[default]
+    Weekly,
+    Monthly,
+    Both,
+}
+
+impl EmailReportFrequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmailReportFrequency::Weekly => "weekly",
+            EmailReportFrequency::Monthly => "monthly",
+            EmailReportFrequency::Both => "both",
+        }
+    }
+
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "weekly" | "haftalik" | "haftalık" => Some(EmailReportFrequency::Weekly),
+            "monthly" | "aylik" | "aylık" => Some(EmailReportFrequency::Monthly),
+            "both" | "ikisi" | "hepsi" => Some(EmailReportFrequency::Both),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EmailReportFrequency::Weekly => "Haftalık",
+            EmailReportFrequency::Monthly => "Aylık",
+            EmailReportFrequency::Both => "Haftalık + Aylık",
+        }
+    }
+
+    pub fn weekly(&self) -> bool {
+        matches!(self, EmailReportFrequency::Weekly | EmailReportFrequency::Both)
+    }
+
+    pub fn monthly(&self) -> bool {
+        matches!(self, EmailReportFrequency::Monthly | EmailReportFrequency::Both)
+    }
+}
+
+/// Result of `eposta kod 123456`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EmailVerification {
+    Verified(String),
+    WrongCode { attempts_left: i32 },
+    /// Code expired or too many wrong attempts; a new code must be requested
+    Expired,
+    NoPending,
+}
+
+/// Günlük özet/rapor bölümleri - `users.summary_sections` JSONB (NULL = hepsi açık)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SummarySections {
+    pub calories: bool,
+    pub water: bool,
+    /// Özel besin alanları (CUSTOM_NUTRITION_FIELDS) toplamları
+    pub macros: bool,
+    pub streak: bool,
+    pub tip: bool,
+}
+
+impl Default for SummarySections {
+    fn default() -> Self {
+        Self { calories: true, water: true, macros: true, streak: true, tip: true }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaterLog {
+    pub id: Option<i64>,
+    pub user_phone: String,
+    pub amount_ml: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyStats {
+    pub user_phone: String,
+    pub date: String,
+    pub total_calories: f64,
+    pub total_water_ml: i64,
+    pub meals_count: i64,
+    pub water_logs_count: i64,
+    #[serde(default)]
+    pub extra_totals: BTreeMap<String, f64>,  // Özel besin alanlarının günlük toplamları
+}
+
+/// Meal count/calories grouped by meal type and local hour (snack insights)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MealHourBucket {
+    pub meal_type: MealType,
+    pub hour: i32,
+    pub count: i64,
+    pub total_calories: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Conversation {
+    pub id: Option<i64>,
+    pub user_phone: String,
+    pub direction: ConversationDirection,
+    pub message_type: MessageType,
+    pub content: String,
+    pub metadata: Option<serde_json::Value>,  // Extra metadata as JSON
+    pub created_at: DateTime<Utc>,
+}
+
+/// Position in a user's history for keyset paging: rows strictly older than (created_at, id).
+/// `id` breaks ties between rows logged in the same batch with the same timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConversationCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: i64,
+}
+
+impl ConversationCursor {
+    /// Continue after the last (oldest) row of a page
+    pub fn after(conversation: &Conversation) -> Self {
+        Self { created_at: conversation.created_at, id: conversation.id.unwrap_or(i64::MIN) }
+    }
+
+    /// Everything logged before `created_at` (for callers that only have a timestamp)
+    pub fn before_time(created_at: DateTime<Utc>) -> Self {
+        Self { created_at, id: i64::MIN }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConversationDirection {
+    Incoming,  // User → Bot
+    Outgoing,  // Bot → User
+    System,    // Nothing was sent (settings snapshots)
+}
+
+impl std::fmt::Display for ConversationDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ConversationDirection::Incoming => "incoming",
+            ConversationDirection::Outgoing => "outgoing",
+            ConversationDirection::System => "system",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageType {
+    Text,       // Regular text message
+    Image,      // Image with food
+    Command,    // User command (rapor, ayarlar, etc.)
+    Response,   // Bot response to command
+    Reminder,   // Automatic reminder
+    Error,      // Error message
+    Help,       // Answer from the help catalog ("nasıl hedef değiştiririm")
+    Settings,   // Snapshot written by the users trigger when a setting changes
+}
+
+/// A user's settings right after a change (`conversations` row with message_type 'settings')
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsSnapshot {
+    pub created_at: DateTime<Utc>,
+    pub settings: serde_json::Value,  // Değişiklikten sonraki tüm ayarlar
+    pub changed: serde_json::Value,   // {"lunch_time": {"from": "12:30", "to": "13:00"}, ...}
+}
+
+/// User override of an automatically detected meal type ("duzelt ogle")
+#[derive(Debug, Clone)]
+pub struct MealTypeCorrection {
+    pub detected: MealType,
+    pub corrected: MealType,
+    pub local_minutes: u32,  // Öğünün kullanıcı saatindeki zamanı (gece yarısından itibaren dakika)
+}
+
+/// Weekly operator KPIs (archived in `kpi_snapshots`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KpiSnapshot {
+    pub week_start: NaiveDate,  // Pazartesi (UTC), rapor [week_start, week_start + 7 gün) aralığını kapsar
+    pub new_users: i64,
+    pub active_users: i64,  // Hafta içinde en az bir mesaj gönderen kullanıcı
+    pub churned_users: i64,  // Önceki hafta aktif olup bu hafta hiç yazmayan kullanıcı
+    pub messages_handled: i64,  // Gelen mesaj sayısı
+    pub image_analyses: i64,
+    pub text_analyses: i64,
+    #[serde(default)]
+    pub non_food_images: i64,  // Vision çağrısından önce elenen ekran görüntüsü/meme sayısı
+    pub ai_cost_estimate_usd: f64,
+    pub top_errors: Vec<(String, i64)>,
+}
+
+/// Usage over a recent window for `/admin/metrics` (deployment-wide: no tenants or plans yet)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageCounts {
+    pub messages_received: i64,
+    pub active_users: i64,
+    pub image_analyses: i64,
+    pub text_analyses: i64,
+    pub messages_sent: Vec<(String, i64)>,  // (message_type, adet)
+}
+
+/// Aggregate counts for the opt-in telemetry report (no per-user data)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TelemetryCounts {
+    pub users_total: i64,
+    pub users_onboarded: i64,
+    pub active_users: i64,
+    pub messages_received: i64,
+    pub meals_logged: i64,
+    pub photo_meals: i64,
+    pub water_logs: i64,
+    pub features: Vec<(&'static str, i64)>,  // (özellik, açık olan kullanıcı sayısı)
+}
+
+/// Conversion of one reminder wording (`reminder_copy`) over a period
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReminderVariantStats {
+    pub reminder_type: String,
+    pub variant: String,
+    pub sends: i64,  // Dönüşüm penceresi (2 saat) kapanmış gönderimler
+    pub conversions: i64,  // Pencere içinde öğün / su kaydı gelenler
+    pub pending: i64,  // Penceresi henüz kapanmamış gönderimler (orana dahil değil)
+}
+
+impl ReminderVariantStats {
+    pub fn conversion_rate(&self) -> f64 {
+        if self.sends == 0 {
+            0.0
+        } else {
+            self.conversions as f64 / self.sends as f64
+        }
+    }
+}
+
+/// Admin full-text search result: a conversation message or meal plus the user's messages around it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub kind: String,  // "conversation" | "meal"
+    pub id: i64,
+    pub user_phone: String,
+    pub user_name: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub text: String,
+    pub detail: String,  // "outgoing/reminder" for conversations, meal type for meals
+    pub rank: f32,
+    pub context: serde_json::Value,  // [{direction, content, created_at}] before/after the hit, oldest first
+}
+
+/// Raw inbound webhook body kept for debugging and dry-run replay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredWebhookPayload {
+    pub id: i64,
+    pub message_id: Option<String>,
+    pub body: String,
+    pub parse_error: Option<String>,
+    pub received_at: DateTime<Utc>,
+}
+
+/// One step of the nightly database maintenance job
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceStep {
+    pub name: String,
+    pub duration_ms: i64,
+    /// Rows deleted / tables vacuumed, where it applies
+    pub affected: Option<i64>,
+    pub error: Option<String>,
+}
+
+/// A nightly maintenance run, archived in `maintenance_runs` (admin `/api/maintenance`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceRun {
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    pub steps: Vec<MaintenanceStep>,
+}
+
+/// "What's new" message for a release, announced once to active users after that version is deployed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangelogEntry {
+    pub id: i64,
+    pub version: String,
+    pub message: String,
+    pub announce: bool,  // false: sadece kayıt, kullanıcılara gönderilmez
+    pub created_at: DateTime<Utc>,
+    pub deployed_at: Option<DateTime<Utc>>,  // Bu sürüm ilk çalıştığında doldurulur
+}
+
+/// Settings a new user starts with when their number starts with `prefix` (country calling code,
+/// `*` = everyone else). Edited via admin `/api/country-defaults`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CountryDefaults {
+    pub prefix: String,
+    pub timezone: String,
+    /// None: the bot's built-in meal times (09:00 / 13:00 / 19:00)
+    #[serde(default)]
+    pub breakfast_time: Option<String>,
+    #[serde(default)]
+    pub lunch_time: Option<String>,
+    #[serde(default)]
+    pub dinner_time: Option<String>,
+    pub daily_water_goal: i32,
+    pub silent_hours_start: String,
+    pub silent_hours_end: String,
+    pub language: Language,
+}
+
+/// Where a goal change came from (`goal_history.source`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalChangeSource {
+    Command,      // kalorihedefi / suhedefi
+    Ai,           // "kalori hedefim 1800"
+    Onboarding,
+    WeeklyReview, // Pazar akşamı hedef değerlendirmesi
+}
+
+impl GoalChangeSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GoalChangeSource::Command => "command",
+            GoalChangeSource::Ai => "ai",
+            GoalChangeSource::Onboarding => "onboarding",
+            GoalChangeSource::WeeklyReview => "weekly_review",
+        }
+    }
+}
+
+/// Number that wrote to the bot while ALLOWLIST_MODE kept it out (admin `/api/waitlist`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaitlistLead {
+    pub phone_number: String,
+    pub name: Option<String>,
+    pub first_message: String,
+    pub last_message: String,
+    pub message_count: i32,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
@@ -4,7 +4,8 @@ use serde::{Deserialize, Serialize};
 
 use std::sync::Arc;
 
-use crate::models::{Conversation, Meal, User};
+use crate::models::{Conversation, ConversationCursor, Meal, SearchHit, User};
+use crate::services::feedback::{NpsSummary, NPS_REPORT_DAYS};
 use crate::services::Database;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,9 +36,14 @@ pub struct AdminDashboardData {
     pub avg_calories_per_user_today: f64,
     pub avg_water_per_user_today: i64,
     pub weekly_trends: Vec<WeeklyTrend>,
+    /// NPS answers of the last `NPS_REPORT_DAYS` days
+    pub nps: NpsSummary,
     pub users: Vec<UserStats>,
 }
 
+/// Messages shown before and after each search hit
+const SEARCH_CONTEXT_MESSAGES: i64 = 2;
+
 pub struct AdminService {
     pub db: Arc<Database>,
 }
@@ -128,6 +134,9 @@ impl AdminService {
         // Generate weekly trends
         let weekly_trends = self.get_weekly_trends().await?;
 
+        let nps_since = chrono::Utc::now() - chrono::Duration::days(NPS_REPORT_DAYS);
+        let nps = NpsSummary::from_scores(&self.db.get_feedback_scores("nps", nps_since).await?);
+
         Ok(AdminDashboardData {
             total_users,
             active_users_today,
@@ -136,6 +145,7 @@ impl AdminService {
             avg_calories_per_user_today,
             avg_water_per_user_today,
             weekly_trends,
+            nps,
             users: user_stats,
         })
     }
@@ -224,6 +234,16 @@ impl AdminService {
         self.db.get_conversation_history(phone_number, limit).await
     }
 
+    /// Page through a user's conversations (newest first), including archived ones
+    pub async fn get_user_conversation_page(
+        &self,
+        phone_number: &str,
+        before: Option<ConversationCursor>,
+        limit: i32,
+    ) -> Result<Vec<Conversation>> {
+        self.db.get_conversation_page(phone_number, before, limit).await
+    }
+
     /// Get total meal count for a user
     async fn get_user_total_meals(&self, phone_number: &str) -> Result<i64> {
         // This is a helper to get total meals count across all time
@@ -245,6 +265,11 @@ impl AdminService {
         Ok(last_incoming)
     }
 
+    /// Full-text search across all users' conversations and meals
+    pub async fn search(&self, query: &str, limit: i64) -> Result<Vec<SearchHit>> {
+        self.db.search_content(query, limit, SEARCH_CONTEXT_MESSAGES).await
+    }
+
     /// Toggle user active status
     pub async fn toggle_user_active(&self, phone_number: &str) -> Result<bool> {
         self.db.toggle_user_active(phone_number).await
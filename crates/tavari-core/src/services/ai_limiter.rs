@@ -0,0 +1,103 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+const DEFAULT_MAX_CONCURRENT: usize = 4;
+const DEFAULT_QUEUE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Caps concurrent AI requests so a burst of photo webhooks queues up instead of
+/// opening dozens of simultaneous model calls (and tripping provider rate limits).
+pub struct AiLimiter {
+    semaphore: Semaphore,
+    max_concurrent: usize,
+    queue_timeout: Duration,
+}
+
+/// No slot freed up within the queue timeout
+#[derive(Debug)]
+pub struct AiQueueTimeout {
+    pub waited: Duration,
+}
+
+impl std::fmt::Display for AiQueueTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AI request queue full, gave up after {:?}", self.waited)
+    }
+}
+
+impl std::error::Error for AiQueueTimeout {}
+
+impl AiLimiter {
+    pub fn new(max_concurrent: usize, queue_timeout: Duration) -> Self {
+        let max_concurrent = max_concurrent.max(1);
+        Self {
+            semaphore: Semaphore::new(max_concurrent),
+            max_concurrent,
+            queue_timeout,
+        }
+    }
+
+    /// AI_MAX_CONCURRENT_REQUESTS (default 4), AI_QUEUE_TIMEOUT_SECS (default 30)
+    pub fn from_env() -> Self {
+        let max_concurrent = std::env::var("AI_MAX_CONCURRENT_REQUESTS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MAX_CONCURRENT);
+        let queue_timeout = std::env::var("AI_QUEUE_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_QUEUE_TIMEOUT);
+        Self::new(max_concurrent, queue_timeout)
+    }
+
+    /// One limiter per process, shared by every OpenRouterService
+    pub fn global() -> &'static AiLimiter {
+        static LIMITER: OnceLock<AiLimiter> = OnceLock::new();
+        LIMITER.get_or_init(|| {
+            let limiter = AiLimiter::from_env();
+            log::info!("🚦 AI concurrency limit: {} (queue timeout {:?})", limiter.max_concurrent, limiter.queue_timeout);
+            limiter
+        })
+    }
+
+    /// Wait for a free slot; the slot is released when the permit is dropped
+    pub async fn acquire(&self) -> Result<SemaphorePermit<'_>, AiQueueTimeout> {
+        if let Ok(permit) = self.semaphore.try_acquire() {
+            return Ok(permit);
+        }
+
+        log::debug!("🚦 AI limit reached ({} in flight), queuing request", self.max_concurrent);
+        match tokio::time::timeout(self.queue_timeout, self.semaphore.acquire()).await {
+            Ok(Ok(permit)) => Ok(permit),
+            // Semaphore is never closed; treat it like a timeout to be safe
+            _ => Err(AiQueueTimeout { waited: self.queue_timeout }),
+        }
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.max_concurrent - self.semaphore.available_permits()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limiter_queues_and_times_out() {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+        runtime.block_on(async {
+            let limiter = AiLimiter::new(1, Duration::from_millis(20));
+
+            let first = limiter.acquire().await.unwrap();
+            assert_eq!(limiter.in_flight(), 1);
+            assert!(limiter.acquire().await.is_err());
+
+            drop(first);
+            assert!(limiter.acquire().await.is_ok());
+            assert_eq!(limiter.in_flight(), 0);
+        });
+    }
+}
@@ -0,0 +1,45 @@
+use std::sync::OnceLock;
+
+/// Reply to numbers waiting for beta access (at most once a day, see `record_waitlist_lead`)
+pub const WAITLIST_MESSAGE: &str = "🙏 Merhaba! Tavari şu an kapalı beta aşamasında.\n\n\
+     Numaranı bekleme listesine ekledik; erişimin açılınca buradan haber vereceğiz.";
+
+/// Sent to approved leads when the admin asks for it (`notify: true`)
+pub const APPROVED_MESSAGE: &str = "🎉 Tavari beta erişimin açıldı!\n\nBaşlamak için bir mesaj yazman yeterli.";
+
+/// ALLOWLIST_MODE=true: only existing users and approved numbers are served (read once)
+pub fn enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        std::env::var("ALLOWLIST_MODE")
+            .map(|v| matches!(v.trim(), "true" | "1"))
+            .unwrap_or(false)
+    })
+}
+
+/// "+90 555 111-22-33" / "905551112233" → "+905551112233"; None for anything that isn't a phone
+/// number. Always "+<digits>", the form of the WhatsApp identifier Bird sends.
+pub fn normalize_phone(raw: &str) -> Option<String> {
+    let phone: String = raw
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '(' | ')' | '.'))
+        .collect();
+    let digits = phone.strip_prefix('+').unwrap_or(&phone);
+    let valid = (8..=15).contains(&digits.len()) && digits.chars().all(|c| c.is_ascii_digit());
+    valid.then(|| format!("+{}", digits))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_phone() {
+        assert_eq!(normalize_phone(" +90 (555) 111-22-33 "), Some("+905551112233".into()));
+        assert_eq!(normalize_phone("905551112233"), Some("+905551112233".into()));
+        assert_eq!(normalize_phone("+90555abc2233"), None);
+        assert_eq!(normalize_phone("12345"), None);
+        assert_eq!(normalize_phone("++905551112233"), None);
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::Result;
+use chrono::{DateTime, Months, Utc};
+
+use super::Database;
+
+/// Conversations older than this many months are moved to `conversations_archive`
+pub const DEFAULT_ARCHIVE_AFTER_MONTHS: u32 = 6;
+
+/// Rows moved per statement, so the nightly job never holds long locks on `conversations`
+const BATCH_SIZE: i64 = 5_000;
+
+/// CONVERSATION_ARCHIVE_MONTHS (0 disables archiving)
+pub fn archive_after_months_from_env() -> Option<u32> {
+    let months = std::env::var("CONVERSATION_ARCHIVE_MONTHS")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_ARCHIVE_AFTER_MONTHS);
+    (months > 0).then_some(months)
+}
+
+pub fn archive_cutoff(now: DateTime<Utc>, months: u32) -> DateTime<Utc> {
+    now.checked_sub_months(Months::new(months)).unwrap_or(now)
+}
+
+/// Nightly job: move old conversations out of the hot table in batches
+pub async fn archive_old_conversations(db: &Database, now: DateTime<Utc>, months: u32) -> Result<u64> {
+    let cutoff = archive_cutoff(now, months);
+    let mut total = 0;
+
+    loop {
+        let moved = db.archive_conversations_batch(cutoff, BATCH_SIZE).await?;
+        total += moved;
+        if moved < BATCH_SIZE as u64 {
+            break;
+        }
+    }
+
+    if total > 0 {
+        log::info!("🗄️ Archived {} conversations older than {}", total, cutoff.format("%Y-%m-%d"));
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_archive_cutoff() {
+        let now = Utc.with_ymd_and_hms(2025, 8, 31, 3, 0, 0).unwrap();
+        // Month arithmetic clamps to the last day of shorter months
+        assert_eq!(archive_cutoff(now, 6), Utc.with_ymd_and_hms(2025, 2, 28, 3, 0, 0).unwrap());
+        assert_eq!(archive_cutoff(now, 12), Utc.with_ymd_and_hms(2024, 8, 31, 3, 0, 0).unwrap());
+    }
+}
@@ -0,0 +1,101 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+
+use super::Database;
+use crate::models::{DailyStats, User};
+
+/// Aggregates with fewer contributors are not stored, so a single user can't be singled out
+pub const MIN_SAMPLE_SIZE: i64 = 10;
+
+/// Metrics compared in the daily report
+pub const METRIC_WATER_ML: &str = "water_ml";
+pub const METRIC_MEALS: &str = "meals_count";
+
+/// Nightly job: store decile aggregates of opted-in users' totals for `date`.
+/// Percentiles are computed inside PostgreSQL; per-user values never leave the database.
+pub async fn compute_daily_aggregates(db: &Database, date: NaiveDate) -> Result<()> {
+    for metric in [METRIC_WATER_ML, METRIC_MEALS] {
+        match db.compute_benchmark_deciles(date, metric).await? {
+            Some((sample_size, _)) if sample_size < MIN_SAMPLE_SIZE => {
+                log::info!("📉 Benchmark {} for {}: only {} users, not stored", metric, date, sample_size);
+            }
+            Some((sample_size, deciles)) => {
+                db.store_benchmark_aggregate(date, metric, sample_size, &deciles).await?;
+                log::info!("📊 Benchmark {} for {} stored ({} users)", metric, date, sample_size);
+            }
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+/// Share of users (in %, rounded to 10) the value is above, given p10..p90 deciles
+pub fn percent_above(deciles: &[f64], value: f64) -> u32 {
+    deciles.iter().filter(|d| value > **d).count() as u32 * 10
+}
+
+/// Turkish ablative suffix for "%N" (on→'undan, elli→'sinden, altmış→'ından...)
+fn ablative_suffix(pct: u32) -> &'static str {
+    match pct {
+        10 | 30 => "'undan",
+        20 | 50 => "'sinden",
+        70 | 80 => "'inden",
+        _ => "'ından", // 40, 60, 90
+    }
+}
+
+pub fn format_comparison(water_pct: Option<u32>, meals_pct: Option<u32>) -> Option<String> {
+    let mut lines = Vec::new();
+    if let Some(pct) = water_pct.filter(|p| *p > 0) {
+        lines.push(format!("💧 Kullanıcıların %{}{} fazla su içtin", pct, ablative_suffix(pct)));
+    }
+    if let Some(pct) = meals_pct.filter(|p| *p > 0) {
+        lines.push(format!("🍽️ Kullanıcıların %{}{} fazla öğün kaydettin", pct, ablative_suffix(pct)));
+    }
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(format!("👥 *İnsan Ortalaması*\n{}", lines.join("\n")))
+    }
+}
+
+/// Append the anonymous comparison to a daily report (only for opted-in users with fresh aggregates)
+pub async fn with_comparison(db: &Database, user: &User, stats: &DailyStats, report: String) -> String {
+    if !user.benchmark_opt_in {
+        return report;
+    }
+
+    let water = db.get_latest_benchmark(METRIC_WATER_ML).await.ok().flatten();
+    let meals = db.get_latest_benchmark(METRIC_MEALS).await.ok().flatten();
+
+    let comparison = format_comparison(
+        water.map(|d| percent_above(&d, stats.total_water_ml as f64)),
+        meals.map(|d| percent_above(&d, stats.meals_count as f64)),
+    );
+
+    match comparison {
+        Some(comparison) => format!("{}\n\n{}", report, comparison),
+        None => report,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_above_and_format() {
+        let deciles = [500.0, 800.0, 1000.0, 1200.0, 1500.0, 1700.0, 2000.0, 2200.0, 2600.0];
+        assert_eq!(percent_above(&deciles, 1800.0), 60);
+        assert_eq!(percent_above(&deciles, 100.0), 0);
+        assert_eq!(percent_above(&deciles, 3000.0), 90);
+
+        let text = format_comparison(Some(60), Some(0)).unwrap();
+        assert!(text.contains("%60'ından fazla su içtin"));
+        assert!(!text.contains("öğün"));
+        let text = format_comparison(None, Some(50)).unwrap();
+        assert!(text.contains("%50'sinden fazla öğün kaydettin"));
+        assert_eq!(format_comparison(Some(0), None), None);
+    }
+}
@@ -0,0 +1,510 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use super::WhatsAppService;
+use super::bird_error::BirdError;
+use super::whatsapp::{split_message, WHATSAPP_MAX_MESSAGE_CHARS};
+
+/// Delay between parts of a split message
+const CHUNK_DELAY_MS: u64 = 500;
+
+/// Bird.com (MessageBird) WhatsApp Business API client
+pub struct BirdComClient {
+    api_key: String,
+    workspace_id: String,
+    channel_id: String,
+    client: reqwest::Client,
+    reengagement_template: Option<ReengagementTemplate>,
+}
+
+/// Generic approved template sent instead of a text when the user is outside the
+/// 24h customer care window (Bird: "template required"). It has no variables; the user's
+/// reply reopens the window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReengagementTemplate {
+    pub project_id: String,
+    /// Empty = latest approved version
+    pub version: String,
+    pub locale: String,
+}
+
+impl ReengagementTemplate {
+    /// BIRD_REENGAGEMENT_TEMPLATE_ID (+ optional _VERSION, _LOCALE); None disables the fallback
+    pub fn from_env() -> Option<Self> {
+        let project_id = std::env::var("BIRD_REENGAGEMENT_TEMPLATE_ID").ok().filter(|v| !v.trim().is_empty())?;
+        Some(Self {
+            project_id: project_id.trim().to_string(),
+            version: std::env::var("BIRD_REENGAGEMENT_TEMPLATE_VERSION").unwrap_or_default(),
+            locale: std::env::var("BIRD_REENGAGEMENT_TEMPLATE_LOCALE").unwrap_or_else(|_| "tr".to_string()),
+        })
+    }
+
+    fn payload(&self, to: &str) -> serde_json::Value {
+        let mut template = serde_json::json!({
+            "projectId": self.project_id,
+            "locale": self.locale,
+        });
+        if !self.version.is_empty() {
+            template["version"] = serde_json::json!(self.version);
+        }
+        serde_json::json!({
+            "receiver": { "contacts": [{ "identifierValue": to }] },
+            "template": template,
+        })
+    }
+}
+
+/// Which path an outgoing message took
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendPath {
+    Text,
+    /// User was outside the 24h window; the re-engagement template went out instead of the text
+    TemplateFallback,
+}
+
+impl SendPath {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SendPath::Text => "text",
+            SendPath::TemplateFallback => "template_fallback",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct BirdMessage {
+    receiver: Receiver,
+    body: Body,
+}
+
+#[derive(Serialize)]
+struct Receiver {
+    contacts: Vec<Contact>,
+}
+
+#[derive(Serialize)]
+struct Contact {
+    #[serde(rename = "identifierValue")]
+    identifier_value: String,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+#[allow(dead_code)]  // Interactive variant reserved for future WhatsApp Template Messages
+enum Body {
+    Text {
+        #[serde(rename = "type")]
+        msg_type: String,
+        text: TextContent,
+    },
+    Interactive {
+        #[serde(rename = "type")]
+        msg_type: String,
+        interactive: InteractiveContent,
+    },
+}
+
+#[derive(Serialize)]
+struct TextContent {
+    text: String,
+}
+
+#[derive(Serialize)]
+struct InteractiveContent {
+    #[serde(rename = "type")]
+    interactive_type: String,
+    body: InteractiveBody,
+    action: InteractiveAction,
+}
+
+#[derive(Serialize)]
+struct InteractiveBody {
+    text: String,
+}
+
+#[derive(Serialize)]
+struct InteractiveAction {
+    buttons: Vec<InteractiveButton>,
+}
+
+#[derive(Serialize)]
+struct InteractiveButton {
+    #[serde(rename = "type")]
+    button_type: String,
+    reply: ButtonReply,
+}
+
+#[derive(Serialize)]
+struct ButtonReply {
+    id: String,
+    title: String,
+}
+
+#[derive(Deserialize)]
+struct BirdResponse {
+    id: String,
+}
+
+/// Workspace webhook subscription (`/organizations/{org}/workspaces/{ws}/webhook-subscriptions`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookSubscription {
+    #[serde(default)]
+    pub id: String,
+    pub service: String,
+    pub event: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub status: String,
+    /// Only returned by some API versions; compared when present
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_key: Option<String>,
+    #[serde(default)]
+    pub event_filters: Vec<EventFilter>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventFilter {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Deserialize)]
+struct SubscriptionList {
+    #[serde(default)]
+    results: Vec<WebhookSubscription>,
+}
+
+impl BirdComClient {
+    pub fn new(api_key: String, workspace_id: String, channel_id: String) -> Self {
+        Self {
+            api_key,
+            workspace_id,
+            channel_id,
+            client: super::http::shared_client(),
+            reengagement_template: None,
+        }
+    }
+
+    pub fn with_reengagement_template(mut self, template: Option<ReengagementTemplate>) -> Self {
+        self.reengagement_template = template;
+        self
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        format!("https://api.bird.com/workspaces/{}{}", self.workspace_id, path)
+    }
+
+    /// Webhook subscriptions are managed under the organization, not the workspace alone
+    fn subscriptions_url(&self, organization_id: &str) -> String {
+        format!(
+            "https://api.bird.com/organizations/{}/workspaces/{}/webhook-subscriptions",
+            organization_id, self.workspace_id
+        )
+    }
+
+    pub fn channel_id(&self) -> &str {
+        &self.channel_id
+    }
+
+    pub async fn list_webhook_subscriptions(&self, organization_id: &str) -> Result<Vec<WebhookSubscription>> {
+        let response = self
+            .client
+            .get(self.subscriptions_url(organization_id))
+            .query(&[("limit", "100")])
+            .header("Authorization", format!("AccessKey {}", self.api_key))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error = error_from_response(response).await;
+            return Err(anyhow::Error::new(error).context("Bird.com webhook subscription list failed"));
+        }
+
+        Ok(response.json::<SubscriptionList>().await?.results)
+    }
+
+    pub async fn create_webhook_subscription(
+        &self,
+        organization_id: &str,
+        subscription: &WebhookSubscription,
+    ) -> Result<WebhookSubscription> {
+        let response = self
+            .client
+            .post(self.subscriptions_url(organization_id))
+            .header("Authorization", format!("AccessKey {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(subscription)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error = error_from_response(response).await;
+            return Err(anyhow::Error::new(error).context("Bird.com webhook subscription create failed"));
+        }
+
+        Ok(response.json().await?)
+    }
+
+    /// Send a text (split if too long). If Bird says the user is outside the 24h window and a
+    /// re-engagement template is configured, the template is sent instead and the text is dropped.
+    pub async fn deliver(&self, to: &str, message: &str) -> Result<SendPath> {
+        let chunks = split_message(message, WHATSAPP_MAX_MESSAGE_CHARS);
+        if chunks.len() > 1 {
+            log::info!("✂️ Message to {} is {} chars, sending in {} parts", to, message.chars().count(), chunks.len());
+        }
+
+        for (i, chunk) in chunks.iter().enumerate() {
+            if i > 0 {
+                // Parçaların sırası korunsun diye kısa bekleme
+                tokio::time::sleep(std::time::Duration::from_millis(CHUNK_DELAY_MS)).await;
+            }
+            if let Err(e) = self.send_text(to, chunk).await {
+                let template_required = matches!(BirdError::find(&e), Some(BirdError::TemplateRequired(_)));
+                return match &self.reengagement_template {
+                    Some(template) if template_required => {
+                        log::info!("📨 {} is outside the 24h window, sending re-engagement template {}", to, template.project_id);
+                        self.send_template(to, template).await?;
+                        Ok(SendPath::TemplateFallback)
+                    }
+                    _ => Err(e),
+                };
+            }
+        }
+
+        Ok(SendPath::Text)
+    }
+
+    async fn send_template(&self, to: &str, template: &ReengagementTemplate) -> Result<()> {
+        let url = self.api_url(&format!("/channels/{}/messages", self.channel_id));
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("AccessKey {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(&template.payload(to))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error = error_from_response(response).await;
+            log::warn!("⚠️ Re-engagement template to {} failed [{}]: {}", to, error.kind(), error);
+            return Err(error.into());
+        }
+
+        log::info!("📤 OUTGOING TEMPLATE - To: {} | Template: {}", to, template.project_id);
+        Ok(())
+    }
+
+    /// Send a single text message (callers go through `send_message`, which splits long texts)
+    async fn send_text(&self, to: &str, message: &str) -> Result<()> {
+        let url = self.api_url(&format!("/channels/{}/messages", self.channel_id));
+
+        let payload = BirdMessage {
+            receiver: Receiver {
+                contacts: vec![Contact {
+                    identifier_value: to.to_string(),
+                }],
+            },
+            body: Body::Text {
+                msg_type: "text".to_string(),
+                text: TextContent {
+                    text: message.to_string(),
+                },
+            },
+        };
+
+        log::info!("🔍 DEBUG - Sending to URL: {}", url);
+        log::info!("🔍 DEBUG - Payload: {}", serde_json::to_string_pretty(&payload)?);
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("AccessKey {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(&payload)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error = error_from_response(response).await;
+            log::warn!("⚠️ Send to {} failed [{}]: {}", to, error.kind(), error);
+            return Err(error.into());
+        }
+
+        let status = response.status();
+        let response_text = response.text().await?;
+
+        log::info!("🔍 DEBUG - Response Status: {}", status);
+        log::info!("🔍 DEBUG - Response Body: {}", response_text);
+
+        let result: BirdResponse = serde_json::from_str(&response_text)?;
+        log::info!("📤 OUTGOING MESSAGE - To: {} | Message ID: {} | Content: '{}'",
+                   to, result.id, message);
+
+        Ok(())
+    }
+
+    /// Fetch media bytes for an inbound message via the media API.
+    /// Unlike the signed `mediaUrl` in the webhook, this doesn't expire.
+    pub async fn fetch_media(&self, message_id: &str) -> Result<Vec<u8>> {
+        let response = self.fetch_media_response(message_id).await?;
+        Ok(response.bytes().await?.to_vec())
+    }
+
+    /// Media API response with a successful status, body not read yet (for streaming to disk)
+    pub async fn fetch_media_response(&self, message_id: &str) -> Result<reqwest::Response> {
+        // Bird.com media download
+        // GET /workspaces/{workspaceId}/messages/{messageId}/media
+
+        log::info!("📥 Downloading media from Bird.com: message_id={}", message_id);
+
+        let url = self.api_url(&format!("/messages/{}/media", message_id));
+
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("AccessKey {}", self.api_key))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error = error_from_response(response).await;
+            return Err(anyhow::Error::new(error).context("Bird.com media download failed"));
+        }
+
+        Ok(response)
+    }
+
+    #[allow(dead_code)]
+    pub async fn send_message_with_buttons(
+        &self,
+        to: &str,
+        message: &str,
+        buttons: Vec<(String, String)>, // (id, title) pairs
+    ) -> Result<()> {
+        if buttons.is_empty() {
+            anyhow::bail!("Buttons cannot be empty");
+        }
+
+        // Bird.com doesn't properly support WhatsApp list messages
+        // So we'll send a simple text message with numbered options instead
+        let mut full_message = format!("{}\n\n", message);
+        
+        for (i, (_id, title)) in buttons.iter().enumerate() {
+            full_message.push_str(&format!("{}. {}\n", i + 1, title));
+        }
+        
+        full_message.push_str("\nYanıt için sayı gönder (örn: 1)");
+
+        log::info!("💧 Sending water menu as text message to {}", to);
+        
+        // Trait method: splits the text if it is too long
+        WhatsAppService::send_message(self, to, &full_message).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl WhatsAppService for BirdComClient {
+    async fn send_message_with_buttons(
+        &self,
+        to: &str,
+        message: &str,
+        buttons: Vec<(String, String)>,
+    ) -> Result<()> {
+        // Use the concrete implementation
+        self.send_message_with_buttons(to, message, buttons).await
+    }
+
+    async fn send_message(&self, to: &str, message: &str) -> Result<()> {
+        self.deliver(to, message).await?;
+        Ok(())
+    }
+
+    async fn send_image(&self, to: &str, image_path: &str, caption: &str) -> Result<()> {
+        // Bird.com media workflow:
+        // 1. Upload image to Bird.com media endpoint
+        // 2. Get media URL/ID
+        // 3. Send message with media reference
+
+        log::info!("📸 Sending image via Bird.com: {} to {} with caption: {}",
+                   image_path, to, caption);
+
+        // For now, send caption as text message
+        // TODO: Implement proper media upload
+        self.send_message(to, &format!("📷 [Image: {}]\n{}", image_path, caption)).await?;
+
+        Ok(())
+    }
+
+    async fn download_media(&self, message_id: &str, output_path: &str) -> Result<String> {
+        // Save to file
+        let bytes = self.fetch_media(message_id).await?;
+        std::fs::write(output_path, bytes)?;
+
+        log::info!("✅ Media downloaded to: {}", output_path);
+        Ok(output_path.to_string())
+    }
+}
+
+/// Classify a failed Bird response (status, Retry-After header and error body)
+async fn error_from_response(response: reqwest::Response) -> BirdError {
+    let status = response.status().as_u16();
+    let retry_after = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let body = response.text().await.unwrap_or_default();
+    BirdError::from_response(status, retry_after.as_deref(), &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bird_client_creation() {
+        let client = BirdComClient::new(
+            "test_key".to_string(),
+            "workspace_123".to_string(),
+            "channel_456".to_string(),
+        );
+
+        assert_eq!(client.workspace_id, "workspace_123");
+        assert_eq!(client.channel_id, "channel_456");
+    }
+
+    #[test]
+    fn test_api_url_generation() {
+        let client = BirdComClient::new(
+            "test_key".to_string(),
+            "workspace_123".to_string(),
+            "channel_456".to_string(),
+        );
+
+        let url = client.api_url("/channels/channel_456/messages");
+        assert_eq!(url, "https://api.bird.com/workspaces/workspace_123/channels/channel_456/messages");
+    }
+
+    #[test]
+    fn test_reengagement_template_payload() {
+        let template = ReengagementTemplate {
+            project_id: "tpl_123".to_string(),
+            version: String::new(),
+            locale: "tr".to_string(),
+        };
+        assert_eq!(
+            template.payload("+905551112233"),
+            serde_json::json!({
+                "receiver": { "contacts": [{ "identifierValue": "+905551112233" }] },
+                "template": { "projectId": "tpl_123", "locale": "tr" }
+            })
+        );
+
+        let pinned = ReengagementTemplate { version: "v2".to_string(), ..template };
+        assert_eq!(pinned.payload("+90555")["template"]["version"], "v2");
+    }
+}
@@ -0,0 +1,150 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+/// What a caller should do with a failed send
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendFailureAction {
+    /// Temporary: try again later (after `retry_after` if given)
+    Retry,
+    /// Will never succeed for this recipient/message
+    Drop,
+    /// Outside the 24h customer care window: only a template message can reach the user
+    SendTemplate,
+}
+
+/// Bird.com API errors, classified from the HTTP status and error body
+/// (`{"code": "...", "message": "...", "details": ...}`) instead of a raw string
+#[derive(Debug, Clone, PartialEq)]
+pub enum BirdError {
+    RateLimited { retry_after: Option<Duration>, message: String },
+    InvalidRecipient(String),
+    TemplateRequired(String),
+    /// Channel disabled/disconnected or Bird itself unavailable (5xx)
+    ChannelDown(String),
+    Unauthorized(String),
+    Other { status: u16, message: String },
+}
+
+#[derive(Deserialize, Default)]
+struct ErrorBody {
+    #[serde(default)]
+    code: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    details: serde_json::Value,
+}
+
+impl BirdError {
+    pub fn from_response(status: u16, retry_after: Option<&str>, body: &str) -> Self {
+        let parsed: ErrorBody = serde_json::from_str(body).unwrap_or_default();
+        let message = if parsed.message.is_empty() { body.trim().to_string() } else { parsed.message.clone() };
+        // Kod, mesaj ve detaylar birlikte aranır; Bird bazen WhatsApp hata kodunu detaylarda döner
+        let haystack = format!("{} {} {}", parsed.code, parsed.message, parsed.details).to_lowercase();
+        let mentions = |needles: &[&str]| needles.iter().any(|n| haystack.contains(n));
+
+        if status == 429 || mentions(&["ratelimit", "rate limit", "too many requests"]) {
+            return BirdError::RateLimited {
+                retry_after: retry_after.and_then(|v| v.trim().parse().ok()).map(Duration::from_secs),
+                message,
+            };
+        }
+        // 131047: WhatsApp "re-engagement message" (24 saat penceresi dışı)
+        if mentions(&["131047", "template", "24 hour", "24-hour", "customer care window", "conversation window", "re-engagement"]) {
+            return BirdError::TemplateRequired(message);
+        }
+        if status == 401 || (status == 403 && mentions(&["accesskey", "unauthorized", "forbidden"])) {
+            return BirdError::Unauthorized(message);
+        }
+        if mentions(&["131026", "invalid recipient", "invalid identifier", "receiver", "not a valid whatsapp", "phone number"]) {
+            return BirdError::InvalidRecipient(message);
+        }
+        if status >= 500 || mentions(&["channel is not active", "channel inactive", "channel disabled", "channel not found", "disconnected"]) {
+            return BirdError::ChannelDown(message);
+        }
+
+        BirdError::Other { status, message }
+    }
+
+    pub fn action(&self) -> SendFailureAction {
+        match self {
+            BirdError::RateLimited { .. } | BirdError::ChannelDown(_) => SendFailureAction::Retry,
+            BirdError::TemplateRequired(_) => SendFailureAction::SendTemplate,
+            BirdError::InvalidRecipient(_) | BirdError::Unauthorized(_) => SendFailureAction::Drop,
+            // Bilinmeyen 4xx tekrar denenince düzelmez
+            BirdError::Other { status, .. } if *status < 500 => SendFailureAction::Drop,
+            BirdError::Other { .. } => SendFailureAction::Retry,
+        }
+    }
+
+    /// Short label for logs and admin responses
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BirdError::RateLimited { .. } => "rate_limited",
+            BirdError::InvalidRecipient(_) => "invalid_recipient",
+            BirdError::TemplateRequired(_) => "template_required",
+            BirdError::ChannelDown(_) => "channel_down",
+            BirdError::Unauthorized(_) => "unauthorized",
+            BirdError::Other { .. } => "other",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            BirdError::RateLimited { message, .. } | BirdError::Other { message, .. } => message,
+            BirdError::InvalidRecipient(message)
+            | BirdError::TemplateRequired(message)
+            | BirdError::ChannelDown(message)
+            | BirdError::Unauthorized(message) => message,
+        }
+    }
+
+    /// Typed Bird error inside an `anyhow::Error` returned by the WhatsApp service, if any
+    pub fn find(error: &anyhow::Error) -> Option<&BirdError> {
+        error.downcast_ref::<BirdError>()
+    }
+}
+
+impl std::fmt::Display for BirdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BirdError::Other { status, message } => write!(f, "Bird.com API error ({}): {}", status, message),
+            _ => write!(f, "Bird.com {}: {}", self.kind(), self.message()),
+        }
+    }
+}
+
+impl std::error::Error for BirdError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_classification() {
+        let rate = BirdError::from_response(429, Some("30"), r#"{"code":"TooManyRequests","message":"slow down"}"#);
+        assert_eq!(rate, BirdError::RateLimited { retry_after: Some(Duration::from_secs(30)), message: "slow down".into() });
+        assert_eq!(rate.action(), SendFailureAction::Retry);
+
+        let window = BirdError::from_response(
+            422,
+            None,
+            r#"{"code":"InvalidPayload","message":"Message failed","details":{"whatsappErrorCode":131047}}"#,
+        );
+        assert_eq!(window.kind(), "template_required");
+        assert_eq!(window.action(), SendFailureAction::SendTemplate);
+
+        let recipient = BirdError::from_response(422, None, r#"{"code":"InvalidParameter","message":"invalid identifier for receiver"}"#);
+        assert_eq!(recipient.action(), SendFailureAction::Drop);
+
+        assert_eq!(BirdError::from_response(503, None, "upstream unavailable").kind(), "channel_down");
+        assert_eq!(BirdError::from_response(401, None, "{}").kind(), "unauthorized");
+        assert_eq!(
+            BirdError::from_response(400, None, "bad").to_string(),
+            "Bird.com API error (400): bad"
+        );
+
+        let wrapped: anyhow::Error = window.clone().into();
+        assert_eq!(BirdError::find(&wrapped), Some(&window));
+    }
+}
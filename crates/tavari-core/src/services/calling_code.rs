@@ -0,0 +1,9 @@
+/// Longest matching calling code wins; `*` applies when none matches
+pub fn best_match<'a, T>(entries: &'a [T], phone_number: &str, prefix: impl Fn(&T) -> &str) -> Option<&'a T> {
+    let digits: String = phone_number.chars().filter(|c| c.is_ascii_digit()).collect();
+    entries
+        .iter()
+        .filter(|e| prefix(e) != "*" && digits.starts_with(prefix(e)))
+        .max_by_key(|e| prefix(e).len())
+        .or_else(|| entries.iter().find(|e| prefix(e) == "*"))
+}
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::models::ChangelogEntry;
+
+/// Users who only open the 24h window later still get the news for this long after the deploy;
+/// after that the announcement is considered stale and dropped
+pub const ANNOUNCE_DAYS: i64 = 7;
+
+pub fn is_announcing(entry: &ChangelogEntry, now: DateTime<Utc>) -> bool {
+    entry.announce
+        && entry
+            .deployed_at
+            .is_some_and(|deployed| now - deployed < Duration::days(ANNOUNCE_DAYS))
+}
+
+pub fn format_announcement(entry: &ChangelogEntry) -> String {
+    format!("✨ *Yenilikler (v{})*\n\n{}", entry.version, entry.message.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_announcement_window() {
+        let now = Utc::now();
+        let mut entry = ChangelogEntry {
+            id: 1,
+            version: "0.2.0".to_string(),
+            message: "• Grup sohbeti desteği\n".to_string(),
+            announce: true,
+            created_at: now,
+            deployed_at: None,
+        };
+        assert!(!is_announcing(&entry, now));
+
+        entry.deployed_at = Some(now - Duration::days(2));
+        assert!(is_announcing(&entry, now));
+        assert_eq!(format_announcement(&entry), "✨ *Yenilikler (v0.2.0)*\n\n• Grup sohbeti desteği");
+
+        entry.deployed_at = Some(now - Duration::days(ANNOUNCE_DAYS));
+        assert!(!is_announcing(&entry, now));
+    }
+}
@@ -0,0 +1,127 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Consecutive failures before the breaker opens
+const FAILURE_THRESHOLD: u32 = 5;
+/// How long AI calls are short-circuited once the breaker is open
+const OPEN_DURATION: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Normal operation
+    Closed,
+    /// Too many recent failures; calls fail fast until `retry_in` elapses
+    Open { retry_in: Duration },
+    /// Cool-down elapsed; the next call is a trial
+    HalfOpen,
+}
+
+struct Inner {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+/// Minimal circuit breaker for the AI provider: stops hammering OpenRouter while it is
+/// rate-limiting or down, and lets `durum` tell users that analysis is degraded.
+pub struct CircuitBreaker {
+    inner: Mutex<Inner>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CircuitBreaker {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                consecutive_failures: 0,
+                opened_at: None,
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn state(&self) -> BreakerState {
+        self.state_at(Instant::now())
+    }
+
+    fn state_at(&self, now: Instant) -> BreakerState {
+        match self.lock().opened_at {
+            None => BreakerState::Closed,
+            Some(opened_at) => {
+                let elapsed = now.saturating_duration_since(opened_at);
+                if elapsed >= OPEN_DURATION {
+                    BreakerState::HalfOpen
+                } else {
+                    BreakerState::Open {
+                        retry_in: OPEN_DURATION - elapsed,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether a call may go through right now
+    pub fn allow_request(&self) -> bool {
+        !matches!(self.state(), BreakerState::Open { .. })
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.lock();
+        if inner.opened_at.is_some() {
+            log::info!("✅ AI circuit breaker closed");
+        }
+        inner.consecutive_failures = 0;
+        inner.opened_at = None;
+    }
+
+    pub fn record_failure(&self) {
+        self.record_failure_at(Instant::now());
+    }
+
+    fn record_failure_at(&self, now: Instant) {
+        let mut inner = self.lock();
+        inner.consecutive_failures += 1;
+
+        // A failed trial call (half-open) re-opens immediately
+        if inner.consecutive_failures >= FAILURE_THRESHOLD || inner.opened_at.is_some() {
+            if inner.opened_at.is_none() {
+                log::warn!("🔴 AI circuit breaker opened after {} consecutive failures", inner.consecutive_failures);
+            }
+            inner.opened_at = Some(now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_opens_and_recovers() {
+        let breaker = CircuitBreaker::new();
+        let start = Instant::now();
+
+        for _ in 0..FAILURE_THRESHOLD - 1 {
+            breaker.record_failure_at(start);
+        }
+        assert_eq!(breaker.state_at(start), BreakerState::Closed);
+
+        breaker.record_failure_at(start);
+        assert!(matches!(breaker.state_at(start), BreakerState::Open { .. }));
+        assert_eq!(breaker.state_at(start + OPEN_DURATION), BreakerState::HalfOpen);
+
+        // Half-open trial fails -> open again
+        breaker.record_failure_at(start + OPEN_DURATION);
+        assert!(matches!(breaker.state_at(start + OPEN_DURATION), BreakerState::Open { .. }));
+
+        breaker.record_success();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+}
@@ -0,0 +1,84 @@
+use super::food_lookup::normalize;
+
+/// pending_command prefix: "suggest:<command>" - waiting for "evet" to run the suggested command
+pub const SUGGESTION_PREFIX: &str = "suggest:";
+
+/// Command words we suggest (Turkish-folded). Words under 4 letters (su, dil, koc) are left out:
+/// one typo away from them is half the dictionary.
+const COMMANDS: &[&str] = &[
+    "rapor", "ozet", "haftalik", "gecmis", "yardim", "detay", "tavsiye", "ayarlar", "kurulum", "saat",
+    "timezone", "suaraligi", "suhedefi", "kalorihedefi", "sessiz", "durum", "eposta", "kisayol",
+    "atistirma", "butce", "birim", "kiyas", "duzelt", "ogun", "report", "weekly", "history", "help",
+    "settings", "advice", "status",
+];
+
+/// Sentences are meals or chat, not mistyped commands
+const MAX_WORDS: usize = 3;
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1).min(row[j] + 1).min(diagonal + usize::from(ca != *cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Typos allowed for a command: 1 for short words ("rapr"), 2 for long ones ("kalorhedfi")
+fn max_distance(command: &str) -> usize {
+    if command.len() <= 5 {
+        1
+    } else {
+        2
+    }
+}
+
+/// "rapr" -> "rapor", "kalorihedef 1800" -> "kalorihedefi 1800" (arguments kept as typed). None when
+/// the first word is not a typo or two away from a command, or the message is too long to be one.
+pub fn suggest(message: &str) -> Option<String> {
+    let words: Vec<&str> = message.trim().trim_start_matches(['/', '!']).split_whitespace().collect();
+    let (first, args) = words.split_first()?;
+    if words.len() > MAX_WORDS {
+        return None;
+    }
+    let first = normalize(first);
+    let first = first.trim();
+
+    let (command, _) = COMMANDS
+        .iter()
+        .map(|command| (*command, edit_distance(first, command)))
+        // 0: the word is a command ("durum" / "dürüm") that failed for another reason
+        .filter(|(command, distance)| *distance > 0 && *distance <= max_distance(command))
+        .min_by_key(|(_, distance)| *distance)?;
+
+    Some(std::iter::once(command).chain(args.iter().copied()).collect::<Vec<_>>().join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_suggestions() {
+        assert_eq!(edit_distance("rapr", "rapor"), 1);
+        assert_eq!(edit_distance("kalorhedfi", "kalorihedefi"), 2);
+
+        assert_eq!(suggest("rapr").as_deref(), Some("rapor"));
+        assert_eq!(suggest("/Haftalk").as_deref(), Some("haftalik"));
+        assert_eq!(suggest("kalorihedef 1800").as_deref(), Some("kalorihedefi 1800"));
+        assert_eq!(suggest("sesiz 23:00 07:00").as_deref(), Some("sessiz 23:00 07:00"));
+
+        // Yemek adları ve cümleler komut sanılmamalı
+        assert_eq!(suggest("ayran"), None);
+        assert_eq!(suggest("salata"), None);
+        assert_eq!(suggest("dürüm"), None);
+        assert_eq!(suggest("rapr bugun nasil gecti sence"), None);
+        assert_eq!(suggest(""), None);
+    }
+}
@@ -0,0 +1,299 @@
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use std::sync::OnceLock;
+
+use super::{calling_code, Database, WhatsAppService};
+use crate::models::{ConversationDirection, MessageType, User};
+
+/// Kind of proactive (not a reply) message, for regional rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageCategory {
+    /// Reminders and summaries the user set up (meal, water, daily summary, window warning)
+    Reminder,
+    /// Messages we initiate for our own sake (customize nudge, "yenilikler", NPS poll)
+    Marketing,
+}
+
+impl MessageCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MessageCategory::Reminder => "reminder",
+            MessageCategory::Marketing => "marketing",
+        }
+    }
+}
+
+/// Messaging rules for numbers starting with `prefix` (country calling code, `*` = everyone else).
+///
+/// Configured with `COMPLIANCE_PROFILES` as `;` separated `prefix:rule,rule` entries:
+/// `COMPLIANCE_PROFILES=90:quiet=22:00-08:00,marketing=09:00-20:00,max=6;49:marketing=09:00-19:00,max=3;*:max=8`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComplianceProfile {
+    pub prefix: String,
+    /// No proactive message at all between these local times (on top of the user's own silent hours)
+    pub quiet_hours: Option<(NaiveTime, NaiveTime)>,
+    /// Marketing messages only between these local times
+    pub marketing_hours: Option<(NaiveTime, NaiveTime)>,
+    /// Proactive messages per local day
+    pub max_per_day: Option<i64>,
+}
+
+/// Why a proactive message is held back (it is retried on the next run if the job allows)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Hold {
+    QuietHours,
+    OutsideMarketingHours,
+    DailyCap(i64),
+}
+
+impl Hold {
+    pub fn describe(&self) -> String {
+        match self {
+            Hold::QuietHours => "regional quiet hours".to_string(),
+            Hold::OutsideMarketingHours => "outside regional marketing hours".to_string(),
+            Hold::DailyCap(max) => format!("daily cap of {} proactive messages reached", max),
+        }
+    }
+}
+
+/// Start inclusive, end exclusive; a range may cross midnight (22:00-08:00)
+fn in_range(time: NaiveTime, (start, end): (NaiveTime, NaiveTime)) -> bool {
+    if start <= end {
+        start <= time && time < end
+    } else {
+        time >= start || time < end
+    }
+}
+
+impl ComplianceProfile {
+    pub fn check(&self, category: MessageCategory, local_time: NaiveTime, sent_today: i64) -> Result<(), Hold> {
+        if self.quiet_hours.is_some_and(|range| in_range(local_time, range)) {
+            return Err(Hold::QuietHours);
+        }
+        if category == MessageCategory::Marketing && self.marketing_hours.is_some_and(|range| !in_range(local_time, range)) {
+            return Err(Hold::OutsideMarketingHours);
+        }
+        match self.max_per_day {
+            Some(max) if sent_today >= max => Err(Hold::DailyCap(max)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompliancePolicy {
+    profiles: Vec<ComplianceProfile>,
+}
+
+fn parse_range(value: &str) -> Option<(NaiveTime, NaiveTime)> {
+    let (start, end) = value.split_once('-')?;
+    let time = |s: &str| NaiveTime::parse_from_str(s.trim(), "%H:%M").ok();
+    Some((time(start)?, time(end)?))
+}
+
+fn parse_profile(entry: &str) -> Option<ComplianceProfile> {
+    let (prefix, rules) = entry.trim().split_once(':')?;
+    let prefix = prefix.trim().trim_start_matches('+');
+    if prefix != "*" && (prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_digit())) {
+        return None;
+    }
+
+    let mut profile = ComplianceProfile {
+        prefix: prefix.to_string(),
+        quiet_hours: None,
+        marketing_hours: None,
+        max_per_day: None,
+    };
+    for rule in rules.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+        let (key, value) = rule.split_once('=')?;
+        match key.trim() {
+            "quiet" => profile.quiet_hours = Some(parse_range(value)?),
+            "marketing" => profile.marketing_hours = Some(parse_range(value)?),
+            "max" => profile.max_per_day = Some(value.trim().parse().ok().filter(|max: &i64| *max >= 0)?),
+            _ => return None,
+        }
+    }
+    Some(profile)
+}
+
+impl CompliancePolicy {
+    pub fn parse(spec: &str) -> Self {
+        let profiles = spec
+            .split(';')
+            .filter(|entry| !entry.trim().is_empty())
+            .filter_map(|entry| {
+                let profile = parse_profile(entry);
+                if profile.is_none() {
+                    log::warn!("⚠️ Ignoring invalid compliance profile: '{}'", entry.trim());
+                }
+                profile
+            })
+            .collect();
+        Self { profiles }
+    }
+
+    pub fn profiles(&self) -> &[ComplianceProfile] {
+        &self.profiles
+    }
+
+    pub fn profile_for(&self, phone_number: &str) -> Option<&ComplianceProfile> {
+        calling_code::best_match(&self.profiles, phone_number, |p| &p.prefix)
+    }
+}
+
+/// Profiles configured for this deployment (read once from the environment)
+pub fn configured() -> &'static CompliancePolicy {
+    static POLICY: OnceLock<CompliancePolicy> = OnceLock::new();
+    POLICY.get_or_init(|| CompliancePolicy::parse(&std::env::var("COMPLIANCE_PROFILES").unwrap_or_default()))
+}
+
+/// Gate for every proactive message: the user's regional profile must allow it now. Without a
+/// matching profile everything is allowed; a failed count holds the message. WhatsApp messages
+/// go through `send_proactive`, which calls this; other channels (e-mail reports) call it directly.
+pub async fn permit(db: &Database, user: &User, category: MessageCategory, now: DateTime<Utc>) -> bool {
+    permit_number(db, &user.phone_number, &user.timezone, category, now).await
+}
+
+async fn permit_number(db: &Database, phone_number: &str, timezone: &str, category: MessageCategory, now: DateTime<Utc>) -> bool {
+    let Some(profile) = configured().profile_for(phone_number) else {
+        return true;
+    };
+
+    let user_tz: Tz = timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+    let now_user = now.with_timezone(&user_tz);
+
+    let sent_today = if profile.max_per_day.is_some() {
+        let midnight = now_user.date_naive().and_time(NaiveTime::MIN);
+        let since = user_tz
+            .from_local_datetime(&midnight)
+            .earliest()
+            .map(|t| t.with_timezone(&Utc))
+            .unwrap_or(now - Duration::hours(24));
+        match db.count_proactive_messages_since(phone_number, since).await {
+            Ok(count) => count,
+            Err(e) => {
+                log::error!("❌ Failed to count proactive messages for {}: {}", phone_number, e);
+                return false;
+            }
+        }
+    } else {
+        0
+    };
+
+    match profile.check(category, now_user.time(), sent_today) {
+        Ok(()) => true,
+        Err(hold) => {
+            log::debug!(
+                "🛑 Holding {} message to {} (profile {}): {}",
+                category.as_str(),
+                phone_number,
+                profile.prefix,
+                hold.describe()
+            );
+            false
+        }
+    }
+}
+
+/// Body of a proactive WhatsApp message
+pub enum Outgoing<'a> {
+    Text(&'a str),
+    /// Interactive list; `message` must also work as a typed prompt
+    List {
+        message: &'a str,
+        button: &'a str,
+        rows: Vec<(String, String)>,
+    },
+}
+
+impl Outgoing<'_> {
+    fn text(&self) -> &str {
+        match self {
+            Outgoing::Text(message) | Outgoing::List { message, .. } => message,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Delivery {
+    Sent,
+    /// Not allowed right now (see `Hold`); the job tries again on its next run if it can
+    Held,
+    Failed(anyhow::Error),
+}
+
+/// The one send path for proactive WhatsApp messages: regional gate, send, and a `reminder` row
+/// in the user's history tagged with `proactive: <category>` (what the daily cap counts)
+pub async fn send_proactive(
+    db: &Database,
+    whatsapp: &dyn WhatsAppService,
+    user: &User,
+    category: MessageCategory,
+    outgoing: Outgoing<'_>,
+    metadata: serde_json::Value,
+) -> Delivery {
+    send_proactive_to(db, whatsapp, &user.phone_number, user, category, outgoing, metadata).await
+}
+
+/// `send_proactive` for a message about `user` that goes to someone else (the coach summary):
+/// the recipient's profile decides, the row goes to `user`'s history
+pub async fn send_proactive_to(
+    db: &Database,
+    whatsapp: &dyn WhatsAppService,
+    to: &str,
+    user: &User,
+    category: MessageCategory,
+    outgoing: Outgoing<'_>,
+    mut metadata: serde_json::Value,
+) -> Delivery {
+    if !permit_number(db, to, &user.timezone, category, Utc::now()).await {
+        return Delivery::Held;
+    }
+
+    let sent = match &outgoing {
+        Outgoing::Text(message) => whatsapp.send_message(to, message).await,
+        Outgoing::List { message, button, rows } => whatsapp.send_list_message(to, message, button, rows.clone()).await,
+    };
+    if let Err(e) = sent {
+        return Delivery::Failed(e);
+    }
+
+    if let Some(fields) = metadata.as_object_mut() {
+        fields.insert("proactive".to_string(), category.as_str().into());
+    }
+    let _ = db
+        .log_conversation(
+            &user.phone_number,
+            ConversationDirection::Outgoing,
+            MessageType::Reminder,
+            outgoing.text(),
+            Some(metadata),
+        )
+        .await;
+    Delivery::Sent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compliance_profiles() {
+        let policy = CompliancePolicy::parse("90:quiet=22:00-08:00,marketing=09:00-20:00,max=2; +49:max=3 ;*:max=5;1:sometimes=x");
+        assert_eq!(policy.profiles().len(), 3);
+        assert_eq!(policy.profile_for("905551112233").map(|p| p.prefix.as_str()), Some("90"));
+        assert_eq!(policy.profile_for("+49 151 1234567").map(|p| p.prefix.as_str()), Some("49"));
+        assert_eq!(policy.profile_for("12025550123").map(|p| p.prefix.as_str()), Some("*"));
+        assert_eq!(CompliancePolicy::parse("").profile_for("905551112233"), None);
+
+        let tr = policy.profile_for("905551112233").unwrap();
+        let time = |s| NaiveTime::parse_from_str(s, "%H:%M").unwrap();
+        assert_eq!(tr.check(MessageCategory::Reminder, time("07:30"), 0), Err(Hold::QuietHours));
+        assert_eq!(tr.check(MessageCategory::Reminder, time("08:30"), 0), Ok(()));
+        // Pazarlama mesajı 09:00'dan önce gitmez, hatırlatma gider
+        assert_eq!(tr.check(MessageCategory::Marketing, time("08:30"), 0), Err(Hold::OutsideMarketingHours));
+        assert_eq!(tr.check(MessageCategory::Marketing, time("20:00"), 0), Err(Hold::OutsideMarketingHours));
+        assert_eq!(tr.check(MessageCategory::Marketing, time("12:00"), 1), Ok(()));
+        assert_eq!(tr.check(MessageCategory::Reminder, time("12:00"), 2), Err(Hold::DailyCap(2)));
+    }
+}
@@ -0,0 +1,206 @@
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use tokio::sync::{mpsc, oneshot};
+
+use super::database::is_connection_error;
+
+/// Buffered rows are written at least this often
+const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// ...or as soon as this many rows are waiting
+const MAX_BATCH: usize = 500;
+
+/// A failed write is retried after this, doubling per failure up to `RETRY_MAX_DELAY`
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Rows kept while the database is unreachable; the oldest are dropped beyond this
+const MAX_PENDING: usize = 50_000;
+
+/// Queue capacity; when full, `log` waits for the writer instead of growing memory without bound
+const QUEUE_CAPACITY: usize = 10_000;
+
+pub struct PendingConversation {
+    pub user_phone: String,
+    pub direction: String,
+    pub message_type: String,
+    pub content: String,
+    pub metadata: Option<serde_json::Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+enum Command {
+    Log(PendingConversation),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Takes conversation logging off the chat hot path: rows are queued on a bounded channel
+/// and a background task writes them with one multi-row INSERT per batch.
+pub struct ConversationLogWriter {
+    tx: mpsc::Sender<Command>,
+}
+
+impl ConversationLogWriter {
+    /// Spawns the writer task (requires a Tokio runtime)
+    pub fn spawn(pool: PgPool) -> Self {
+        let (tx, rx) = mpsc::channel(QUEUE_CAPACITY);
+        tokio::spawn(run(pool, rx));
+        Self { tx }
+    }
+
+    pub async fn log(&self, row: PendingConversation) {
+        match self.tx.try_send(Command::Log(row)) {
+            Ok(()) => {}
+            Err(mpsc::error::TrySendError::Full(cmd)) => {
+                // Backpressure: DB is slower than incoming traffic, wait for room in the queue
+                log::warn!("⚠️ Conversation log queue full, waiting for writer");
+                let _ = self.tx.send(cmd).await;
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                log::error!("❌ Conversation log writer stopped, message not logged");
+            }
+        }
+    }
+
+    /// Wait until everything queued so far is written (shutdown, and before reading `conversations`)
+    pub async fn flush(&self) {
+        let (ack_tx, ack_rx) = oneshot::channel();
+        if self.tx.send(Command::Flush(ack_tx)).await.is_ok() {
+            let _ = ack_rx.await;
+        }
+    }
+}
+
+async fn run(pool: PgPool, mut rx: mpsc::Receiver<Command>) {
+    let mut pending = PendingRows::default();
+    let mut ticker = tokio::time::interval(FLUSH_INTERVAL);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            cmd = rx.recv() => match cmd {
+                Some(Command::Log(row)) => {
+                    pending.rows.push(row);
+                    if pending.rows.len() >= MAX_BATCH {
+                        pending.write(&pool, false).await;
+                    }
+                }
+                Some(Command::Flush(ack)) => {
+                    pending.write(&pool, true).await;
+                    let _ = ack.send(());
+                }
+                None => {
+                    // Every sender dropped (Database dropped): write what's left and stop
+                    pending.write(&pool, true).await;
+                    return;
+                }
+            },
+            _ = ticker.tick() => pending.write(&pool, false).await,
+        }
+    }
+}
+
+/// Delay before retrying after `failures` consecutive connection errors
+fn retry_delay(failures: u32) -> Duration {
+    RETRY_BASE_DELAY.saturating_mul(1 << failures.saturating_sub(1).min(16)).min(RETRY_MAX_DELAY)
+}
+
+/// Rows not yet written; kept across failed writes until the database is back
+#[derive(Default)]
+struct PendingRows {
+    rows: Vec<PendingConversation>,
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl PendingRows {
+    /// `force` ignores the retry backoff (explicit flush before reads / on shutdown)
+    async fn write(&mut self, pool: &PgPool, force: bool) {
+        if self.rows.is_empty() || (!force && self.retry_at.is_some_and(|at| Instant::now() < at)) {
+            return;
+        }
+
+        let count = self.rows.len();
+        match insert_rows(pool, &self.rows).await {
+            Ok(()) => {
+                if self.failures > 0 {
+                    log::info!("✅ Conversation log writes recovered, {} buffered rows written", count);
+                } else {
+                    log::debug!("📝 Logged {} conversation messages", count);
+                }
+                self.rows.clear();
+                self.failures = 0;
+                self.retry_at = None;
+            }
+            Err(e) if is_connection_error(&e) => self.retry_later(&e),
+            Err(e) => {
+                // One bad row (e.g. its user was deleted) must not hold back the rest: write them one by one
+                log::warn!("⚠️ Conversation log batch of {} rows failed, retrying row by row: {}", count, e);
+                let mut rows = std::mem::take(&mut self.rows).into_iter();
+                while let Some(row) = rows.next() {
+                    match insert_rows(pool, std::slice::from_ref(&row)).await {
+                        Ok(()) => {}
+                        Err(e) if is_connection_error(&e) => {
+                            self.rows.push(row);
+                            self.rows.extend(rows);
+                            self.retry_later(&e);
+                            return;
+                        }
+                        Err(e) => log::error!("❌ Dropping conversation log row for {}: {}", row.user_phone, e),
+                    }
+                }
+                self.failures = 0;
+                self.retry_at = None;
+            }
+        }
+    }
+
+    fn retry_later(&mut self, error: &anyhow::Error) {
+        self.failures += 1;
+        let delay = retry_delay(self.failures);
+        self.retry_at = Some(Instant::now() + delay);
+        if self.rows.len() > MAX_PENDING {
+            let dropped = self.rows.len() - MAX_PENDING;
+            self.rows.drain(..dropped);
+            log::error!("❌ Conversation log buffer full, dropped the {} oldest rows", dropped);
+        }
+        log::warn!(
+            "⚠️ Database unavailable, keeping {} conversation log rows (retry in {:?}): {}",
+            self.rows.len(),
+            delay,
+            error
+        );
+    }
+}
+
+async fn insert_rows(pool: &PgPool, rows: &[PendingConversation]) -> anyhow::Result<()> {
+    let mut query: QueryBuilder<Postgres> = QueryBuilder::new(
+        "INSERT INTO conversations (user_phone, direction, message_type, content, metadata, created_at) ",
+    );
+    query.push_values(rows, |mut b, row| {
+        b.push_bind(&row.user_phone)
+            .push_bind(&row.direction)
+            .push_bind(&row.message_type)
+            .push_bind(&row.content)
+            .push_bind(&row.metadata)
+            .push_bind(row.created_at);
+    });
+    query.build().execute(pool).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backs_off() {
+        assert_eq!(retry_delay(1), Duration::from_secs(1));
+        assert_eq!(retry_delay(2), Duration::from_secs(2));
+        assert_eq!(retry_delay(4), Duration::from_secs(8));
+        assert_eq!(retry_delay(10), RETRY_MAX_DELAY);
+        assert_eq!(retry_delay(u32::MAX), RETRY_MAX_DELAY);
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::NaiveTime;
+use chrono_tz::Tz;
+
+use super::{calling_code, Database};
+use crate::models::{CountryDefaults, User};
+
+/// Same limits as `suhedefi`
+const WATER_GOAL_RANGE: std::ops::RangeInclusive<i32> = 500..=10000;
+
+pub fn for_phone<'a>(defaults: &'a [CountryDefaults], phone_number: &str) -> Option<&'a CountryDefaults> {
+    calling_code::best_match(defaults, phone_number, |d| &d.prefix)
+}
+
+fn valid_time(time: &str) -> bool {
+    NaiveTime::parse_from_str(time, "%H:%M").is_ok()
+}
+
+/// Check an admin-submitted row; the error is shown to the admin as is
+pub fn validate(defaults: &CountryDefaults) -> Result<(), String> {
+    let prefix = defaults.prefix.as_str();
+    if prefix != "*" && (prefix.is_empty() || prefix.len() > 4 || !prefix.chars().all(|c| c.is_ascii_digit())) {
+        return Err(format!("prefix must be a calling code (1-4 digits) or *: '{}'", prefix));
+    }
+    if defaults.timezone.parse::<Tz>().is_err() {
+        return Err(format!("unknown timezone: '{}'", defaults.timezone));
+    }
+    let meal_times = [&defaults.breakfast_time, &defaults.lunch_time, &defaults.dinner_time];
+    if let Some(time) = meal_times.into_iter().flatten().find(|t| !valid_time(t)) {
+        return Err(format!("meal time must be HH:MM: '{}'", time));
+    }
+    if !valid_time(&defaults.silent_hours_start) || !valid_time(&defaults.silent_hours_end) {
+        return Err("silent hours must be HH:MM".to_string());
+    }
+    if !WATER_GOAL_RANGE.contains(&defaults.daily_water_goal) {
+        return Err(format!("daily_water_goal must be {}-{} ml", WATER_GOAL_RANGE.start(), WATER_GOAL_RANGE.end()));
+    }
+    Ok(())
+}
+
+/// Overwrite the locale-dependent settings of a user that is about to be created
+pub fn apply(defaults: &CountryDefaults, user: &mut User) {
+    user.timezone = defaults.timezone.clone();
+    user.breakfast_time = defaults.breakfast_time.clone();
+    user.lunch_time = defaults.lunch_time.clone();
+    user.dinner_time = defaults.dinner_time.clone();
+    user.daily_water_goal = Some(defaults.daily_water_goal);
+    user.silent_hours_start = Some(defaults.silent_hours_start.clone());
+    user.silent_hours_end = Some(defaults.silent_hours_end.clone());
+    user.language = defaults.language;
+}
+
+/// Defaults for a new user's number; None (built-in Turkish defaults) when nothing matches or the
+/// table can't be read - user creation must not fail because of it
+pub async fn lookup(db: &Database, phone_number: &str) -> Option<CountryDefaults> {
+    match db.get_country_defaults().await {
+        Ok(defaults) => for_phone(&defaults, phone_number).cloned(),
+        Err(e) => {
+            log::warn!("⚠️ Failed to load country defaults, using built-in ones: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Language;
+
+    fn defaults(prefix: &str, timezone: &str, language: Language) -> CountryDefaults {
+        CountryDefaults {
+            prefix: prefix.into(),
+            timezone: timezone.into(),
+            breakfast_time: None,
+            lunch_time: Some("12:00".into()),
+            dinner_time: None,
+            daily_water_goal: 2000,
+            silent_hours_start: "22:00".into(),
+            silent_hours_end: "07:00".into(),
+            language,
+        }
+    }
+
+    #[test]
+    fn test_country_defaults() {
+        let table = [
+            defaults("90", "Europe/Istanbul", Language::Tr),
+            defaults("1", "America/New_York", Language::En),
+            defaults("1809", "America/Santo_Domingo", Language::En),
+            defaults("*", "UTC", Language::En),
+        ];
+        let prefix = |phone| for_phone(&table, phone).map(|d| d.prefix.as_str());
+        assert_eq!(prefix("905551112233"), Some("90"));
+        assert_eq!(prefix("+1 (809) 555-0100"), Some("1809"));
+        assert_eq!(prefix("12025550123"), Some("1"));
+        assert_eq!(prefix("4915112345678"), Some("*"));
+        assert_eq!(for_phone(&table[..1], "4915112345678"), None);
+
+        assert_eq!(validate(&table[1]), Ok(()));
+        let mut bad = table[0].clone();
+        bad.timezone = "Europe/Ankara".into();
+        assert!(validate(&bad).unwrap_err().contains("timezone"));
+        bad = table[0].clone();
+        bad.lunch_time = Some("12.30".into());
+        assert!(validate(&bad).unwrap_err().contains("meal time"));
+        bad = table[0].clone();
+        bad.prefix = "+90".into();
+        assert!(validate(&bad).is_err());
+    }
+}
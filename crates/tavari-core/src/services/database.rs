@@ -0,0 +1,4082 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use sqlx::{postgres::{PgPoolOptions, PgRow}, PgPool, Row};
+use std::sync::Arc;
+
+use super::conversation_log::{ConversationLogWriter, PendingConversation};
+use super::events::GoalKind;
+use super::meal_description;
+use super::user_cache::{self, UserCache};
+
+use crate::models::{ChangelogEntry, Conversation, ConversationCursor, CountryDefaults, ConversationDirection, DailyStats, EmailReportFrequency, EmailVerification, GoalChangeSource, KpiSnapshot, Language, MaintenanceRun, Meal, MealHourBucket, MealType, MealTypeCorrection, MessageType, ReminderVariantStats, SearchHit, SettingsSnapshot, StoredWebhookPayload, SummarySections, TelemetryCounts, UnitSystem, UsageCounts, User, WaitlistLead, WaterLog};
+
+/// E-mail verification codes ('eposta') expire after this many minutes
+pub const EMAIL_CODE_VALID_MINUTES: i32 = 15;
+const EMAIL_CODE_MAX_ATTEMPTS: i32 = 5;
+/// Codes per user and per address in the last hour; every code is an e-mail we send to an
+/// address nobody has confirmed yet
+const EMAIL_CODE_MAX_REQUESTS_PER_HOUR: i64 = 3;
+
+/// How long `ping` waits for Postgres before calling it unavailable
+const PING_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// The error (or one of its causes) means Postgres could not be reached - as opposed to a bad
+/// query or a constraint violation - so retrying the same work later can succeed
+pub fn is_connection_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| match cause.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed) => true,
+        // 08xxx: connection exception, 57P01-03: server shutting down / starting up
+        Some(sqlx::Error::Database(e)) => e
+            .code()
+            .is_some_and(|code| code.starts_with("08") || matches!(code.as_ref(), "57P01" | "57P02" | "57P03")),
+        _ => false,
+    })
+}
+
+pub struct Database {
+    pool: PgPool,
+    user_cache: Arc<UserCache>,
+    conversation_log: ConversationLogWriter,
+}
+
+impl Database {
+    pub async fn new(database_url: &str) -> Result<Self> {
+        // DB_ACQUIRE_TIMEOUT_SECS: fail fast during an outage instead of holding webhooks for sqlx's 30s
+        let acquire_timeout = std::env::var("DB_ACQUIRE_TIMEOUT_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(10);
+        let pool = PgPoolOptions::new()
+            .max_connections(5)
+            .acquire_timeout(std::time::Duration::from_secs(acquire_timeout))
+            .connect(database_url)
+            .await?;
+
+        let db = Database {
+            conversation_log: ConversationLogWriter::spawn(pool.clone()),
+            pool,
+            user_cache: Arc::new(UserCache::default()),
+        };
+        db.init_tables().await?;
+
+        // Diğer instance'lardaki kullanıcı değişikliklerini dinle (cache invalidation)
+        tokio::spawn(user_cache::listen_for_changes(db.pool.clone(), db.user_cache.clone()));
+        Ok(db)
+    }
+
+    /// Connect with all tables living in a separate schema (used for dry-run webhook replays,
+    /// so replayed messages never touch production rows)
+    pub async fn new_with_schema(database_url: &str, schema: &str) -> Result<Self> {
+        use sqlx::postgres::PgConnectOptions;
+        use std::str::FromStr;
+
+        // Schema name is interpolated into DDL, so only allow plain identifiers
+        if !schema.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            anyhow::bail!("Invalid schema name: {}", schema);
+        }
+
+        let bootstrap = PgPoolOptions::new()
+            .max_connections(1)
+            .connect(database_url)
+            .await?;
+        sqlx::query(&format!("CREATE SCHEMA IF NOT EXISTS {}", schema))
+            .execute(&bootstrap)
+            .await?;
+        bootstrap.close().await;
+
+        let options = PgConnectOptions::from_str(database_url)?
+            .options([("search_path", schema)]);
+        let pool = PgPoolOptions::new()
+            .max_connections(2)
+            .connect_with(options)
+            .await?;
+
+        let db = Database {
+            conversation_log: ConversationLogWriter::spawn(pool.clone()),
+            pool,
+            user_cache: Arc::new(UserCache::default()),
+        };
+        db.init_tables().await?;
+        Ok(db)
+    }
+
+    /// Can we reach Postgres right now? (health check, spillover replay)
+    pub async fn ping(&self) -> bool {
+        let query = sqlx::query("SELECT 1").execute(&self.pool);
+        matches!(tokio::time::timeout(PING_TIMEOUT, query).await, Ok(Ok(_)))
+    }
+
+    async fn init_tables(&self) -> Result<()> {
+        log::info!("🔧 Initializing database tables and running migrations...");
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS users (
+                phone_number TEXT PRIMARY KEY,
+                name TEXT,
+                created_at TIMESTAMPTZ NOT NULL,
+                onboarding_completed BOOLEAN DEFAULT FALSE,
+                onboarding_step TEXT,
+                breakfast_reminder BOOLEAN DEFAULT TRUE,
+                lunch_reminder BOOLEAN DEFAULT TRUE,
+                dinner_reminder BOOLEAN DEFAULT TRUE,
+                water_reminder BOOLEAN DEFAULT TRUE,
+                breakfast_time TEXT,
+                lunch_time TEXT,
+                dinner_time TEXT,
+                opted_in BOOLEAN DEFAULT FALSE,
+                timezone TEXT DEFAULT 'Europe/Istanbul',
+                daily_water_goal INTEGER DEFAULT 2000,
+                is_active BOOLEAN DEFAULT TRUE
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS meals (
+                id SERIAL PRIMARY KEY,
+                user_phone TEXT NOT NULL REFERENCES users(phone_number),
+                meal_type TEXT NOT NULL,
+                calories DOUBLE PRECISION NOT NULL,
+                description TEXT NOT NULL,
+                image_path TEXT,
+                created_at TIMESTAMPTZ NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS water_logs (
+                id SERIAL PRIMARY KEY,
+                user_phone TEXT NOT NULL REFERENCES users(phone_number),
+                amount_ml INTEGER NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS favorite_meals (
+                id SERIAL PRIMARY KEY,
+                user_phone TEXT NOT NULL REFERENCES users(phone_number),
+                name TEXT NOT NULL,
+                description TEXT NOT NULL,
+                calories DOUBLE PRECISION NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL,
+                UNIQUE(user_phone, name)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS conversations (
+                id SERIAL PRIMARY KEY,
+                user_phone TEXT NOT NULL REFERENCES users(phone_number),
+                direction TEXT NOT NULL,  -- 'incoming' or 'outgoing'
+                message_type TEXT NOT NULL,  -- 'text', 'image', 'command', 'response', 'reminder', 'error', 'help'
+                content TEXT NOT NULL,
+                metadata JSONB,  -- Extra info: command type, error details, image path, etc.
+                created_at TIMESTAMPTZ NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Create index for faster queries by user and date
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_conversations_user_date
+            ON conversations(user_phone, created_at DESC)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Last drink lookup for the water reminder (runs for every user every 30 minutes)
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_water_logs_user_date
+            ON water_logs(user_phone, created_at DESC)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Full-text search for the admin search endpoint ('simple' config: no stemming, exact words)
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_conversations_content_fts
+            ON conversations USING GIN (to_tsvector('simple', content))
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_meals_description_fts
+            ON meals USING GIN (to_tsvector('simple', description))
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Cold storage for conversations older than CONVERSATION_ARCHIVE_MONTHS (see services::archive).
+        // Same columns as `conversations`; large content/metadata values are TOAST-compressed by Postgres.
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS conversations_archive (
+                id INTEGER PRIMARY KEY,
+                user_phone TEXT NOT NULL,
+                direction TEXT NOT NULL,
+                message_type TEXT NOT NULL,
+                content TEXT NOT NULL,
+                metadata JSONB,
+                created_at TIMESTAMPTZ NOT NULL,
+                archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_conversations_archive_user_date
+            ON conversations_archive(user_phone, created_at DESC)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Table for tracking 24h window warning status
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS window_warnings (
+                user_phone TEXT PRIMARY KEY REFERENCES users(phone_number) ON DELETE CASCADE,
+                last_warned_at TIMESTAMPTZ NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Last time each reminder type was sent (UTC) - dedupes across DST and timezone changes
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS reminder_log (
+                user_phone TEXT NOT NULL REFERENCES users(phone_number) ON DELETE CASCADE,
+                reminder_type TEXT NOT NULL,
+                last_sent_at TIMESTAMPTZ NOT NULL,
+                PRIMARY KEY (user_phone, reminder_type)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Ring buffer of raw webhook bodies (for debugging parse failures / dry-run replay)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS webhook_payloads (
+                id SERIAL PRIMARY KEY,
+                message_id TEXT,
+                body TEXT NOT NULL,
+                parse_error TEXT,
+                received_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Weekly operator KPI archive (one row per week)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS kpi_snapshots (
+                week_start DATE PRIMARY KEY,
+                new_users BIGINT NOT NULL,
+                active_users BIGINT NOT NULL,
+                churned_users BIGINT NOT NULL,
+                messages_handled BIGINT NOT NULL,
+                image_analyses BIGINT NOT NULL,
+                text_analyses BIGINT NOT NULL,
+                ai_cost_estimate_usd DOUBLE PRECISION NOT NULL,
+                top_errors JSONB NOT NULL DEFAULT '[]',
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Nightly anonymous aggregates (p10..p90) for the opt-in user comparison
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS benchmark_aggregates (
+                date DATE NOT NULL,
+                metric TEXT NOT NULL,
+                sample_size BIGINT NOT NULL,
+                deciles JSONB NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                PRIMARY KEY (date, metric)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Meal type overrides ("duzelt ogle") - used to adapt per-user meal detection
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS meal_type_corrections (
+                id SERIAL PRIMARY KEY,
+                user_phone TEXT NOT NULL REFERENCES users(phone_number) ON DELETE CASCADE,
+                detected_type TEXT NOT NULL,
+                corrected_type TEXT NOT NULL,
+                local_minutes INTEGER NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // In-chat product feedback (monthly NPS poll)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS feedback (
+                id SERIAL PRIMARY KEY,
+                user_phone TEXT NOT NULL REFERENCES users(phone_number) ON DELETE CASCADE,
+                kind TEXT NOT NULL,  -- 'nps'
+                score INTEGER,
+                comment TEXT,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // User-filed bug reports ('hata bildir') with the recent conversation as context
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS bug_reports (
+                id SERIAL PRIMARY KEY,
+                user_phone TEXT NOT NULL REFERENCES users(phone_number) ON DELETE CASCADE,
+                description TEXT NOT NULL,
+                context JSONB,  -- last conversation entries before the report
+                app_version TEXT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Release notes managed from the admin API; announced once per user after deploy
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS changelog_entries (
+                id SERIAL PRIMARY KEY,
+                version TEXT NOT NULL UNIQUE,
+                message TEXT NOT NULL,
+                announce BOOLEAN NOT NULL DEFAULT TRUE,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                deployed_at TIMESTAMPTZ
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS changelog_deliveries (
+                entry_id INTEGER NOT NULL REFERENCES changelog_entries(id) ON DELETE CASCADE,
+                user_phone TEXT NOT NULL REFERENCES users(phone_number) ON DELETE CASCADE,
+                sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                PRIMARY KEY (entry_id, user_phone)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Nightly maintenance runs with per-step durations
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS maintenance_runs (
+                id SERIAL PRIMARY KEY,
+                started_at TIMESTAMPTZ NOT NULL,
+                duration_ms BIGINT NOT NULL,
+                steps JSONB NOT NULL DEFAULT '[]'
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Pending e-mail address changes ('eposta'); the address is copied to users.email once the code matches
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS email_verifications (
+                user_phone TEXT PRIMARY KEY REFERENCES users(phone_number) ON DELETE CASCADE,
+                email TEXT NOT NULL,
+                code_hash TEXT NOT NULL,  -- SHA-256 hex of the 6 digit code
+                attempts INTEGER NOT NULL DEFAULT 0,
+                expires_at TIMESTAMPTZ NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Every verification code sent, for the per-user / per-address hourly limit
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS email_verification_requests (
+                id BIGSERIAL PRIMARY KEY,
+                user_phone TEXT NOT NULL,
+                email TEXT NOT NULL,
+                requested_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_email_verification_requests_at ON email_verification_requests (requested_at)")
+            .execute(&self.pool)
+            .await?;
+
+        // Closed beta (ALLOWLIST_MODE): approved numbers, and numbers that wrote while not approved
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS beta_allowlist (
+                phone_number TEXT PRIMARY KEY,
+                note TEXT,
+                approved_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Early approvals were stored as typed ("905551112233"); Bird sends "+905551112233"
+        sqlx::query(
+            r#"
+            UPDATE beta_allowlist a SET phone_number = '+' || a.phone_number
+            WHERE a.phone_number ~ '^[0-9]+$'
+              AND NOT EXISTS (SELECT 1 FROM beta_allowlist b WHERE b.phone_number = '+' || a.phone_number)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS waitlist_leads (
+                phone_number TEXT PRIMARY KEY,
+                name TEXT,
+                first_message TEXT NOT NULL,
+                last_message TEXT NOT NULL,
+                message_count INTEGER NOT NULL DEFAULT 1,
+                first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                notified_at TIMESTAMPTZ NOT NULL DEFAULT NOW()  -- last waitlist reply
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Downloaded meal photos: reserved before the download, attached in the meal's transaction.
+        // Rows still unattached after a while (crash, analysis failed) are swept with their files.
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS stored_images (
+                storage_key TEXT PRIMARY KEY,
+                user_phone TEXT NOT NULL,
+                image_path TEXT NOT NULL,
+                meal_id INTEGER REFERENCES meals(id) ON DELETE SET NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_stored_images_path ON stored_images(image_path)")
+            .execute(&self.pool)
+            .await?;
+
+        // AI day narratives, cached per day and stats so re-runs don't pay for a second AI call
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS day_narratives (
+                user_phone TEXT NOT NULL REFERENCES users(phone_number) ON DELETE CASCADE,
+                day DATE NOT NULL,
+                stats_key TEXT NOT NULL,
+                narrative TEXT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                PRIMARY KEY (user_phone, day)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Every calorie/water goal change with its source (command, onboarding, weekly review...)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS goal_history (
+                id BIGSERIAL PRIMARY KEY,
+                user_phone TEXT NOT NULL REFERENCES users(phone_number) ON DELETE CASCADE,
+                goal TEXT NOT NULL,
+                old_value INTEGER,
+                new_value INTEGER NOT NULL,
+                source TEXT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_goal_history_user ON goal_history(user_phone, created_at)")
+            .execute(&self.pool)
+            .await?;
+
+        // Locale defaults for new users by calling code (admin /api/country-defaults)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS country_defaults (
+                prefix TEXT PRIMARY KEY,
+                timezone TEXT NOT NULL,
+                breakfast_time TEXT,
+                lunch_time TEXT,
+                dinner_time TEXT,
+                daily_water_goal INTEGER NOT NULL DEFAULT 2000,
+                silent_hours_start TEXT NOT NULL DEFAULT '23:00',
+                silent_hours_end TEXT NOT NULL DEFAULT '07:00',
+                language TEXT NOT NULL DEFAULT 'tr',
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Seed once; rows edited or deleted by an admin are not touched again
+        // (Almanya/Hollanda'daki kullanıcıların çoğu Türkçe konuşuyor: dil tr, saat dilimi yerel)
+        sqlx::query(
+            r#"
+            INSERT INTO country_defaults (prefix, timezone, language)
+            SELECT * FROM (VALUES
+                ('90', 'Europe/Istanbul', 'tr'),
+                ('49', 'Europe/Berlin', 'tr'),
+                ('31', 'Europe/Amsterdam', 'tr'),
+                ('44', 'Europe/London', 'en'),
+                ('1', 'America/New_York', 'en')
+            ) AS seed(prefix, timezone, language)
+            WHERE NOT EXISTS (SELECT 1 FROM country_defaults)
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Sunday goal reviews: one per user and week (claimed before sending, so instances don't double-send)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS goal_reviews (
+                user_phone TEXT NOT NULL REFERENCES users(phone_number) ON DELETE CASCADE,
+                week DATE NOT NULL,
+                sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                answer TEXT,
+                answered_at TIMESTAMPTZ,
+                PRIMARY KEY (user_phone, week)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Which reminder wording went out with each send (A/B tests of reminder copy)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS reminder_variant_sends (
+                id BIGSERIAL PRIMARY KEY,
+                user_phone TEXT NOT NULL REFERENCES users(phone_number) ON DELETE CASCADE,
+                reminder_type TEXT NOT NULL,
+                variant TEXT NOT NULL,
+                sent_at TIMESTAMPTZ NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_reminder_variant_sends_sent_at ON reminder_variant_sends(sent_at)")
+            .execute(&self.pool)
+            .await?;
+
+        // Random id of this deployment for the opt-in telemetry report (single row)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS installation (
+                singleton BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (singleton),
+                id UUID NOT NULL DEFAULT gen_random_uuid(),
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // One telemetry report per day, claimed before sending (multiple instances)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS telemetry_reports (
+                day DATE PRIMARY KEY,
+                sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Migration: Add new columns if they don't exist (for existing deployments)
+        // This is safe to run multiple times
+        sqlx::query(
+            r#"
+            DO $$
+            BEGIN
+                -- Add daily_water_goal column if not exists
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='daily_water_goal'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN daily_water_goal INTEGER DEFAULT 2000;
+                END IF;
+
+                -- Add daily_calorie_goal column if not exists
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='daily_calorie_goal'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN daily_calorie_goal INTEGER DEFAULT 2000;
+                END IF;
+
+                -- Add silent_hours_start column if not exists
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='silent_hours_start'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN silent_hours_start TEXT DEFAULT '23:00';
+                END IF;
+
+                -- Add silent_hours_end column if not exists
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='silent_hours_end'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN silent_hours_end TEXT DEFAULT '07:00';
+                END IF;
+
+                -- Add is_active column if not exists
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='is_active'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN is_active BOOLEAN DEFAULT TRUE;
+                END IF;
+
+                -- Add pending_command column if not exists (for AI command suggestions)
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='pending_command'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN pending_command TEXT DEFAULT NULL;
+                END IF;
+
+                -- Add name column if not exists (for WhatsApp profile names)
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='name'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN name TEXT DEFAULT NULL;
+                END IF;
+
+                -- Linked coach/dietitian and the user's consent to share summaries
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='coach_phone'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN coach_phone TEXT DEFAULT NULL;
+                    ALTER TABLE users ADD COLUMN coach_sharing BOOLEAN NOT NULL DEFAULT FALSE;
+                END IF;
+
+                -- Daily summary time (HH:MM in user's timezone), NULL = disabled
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='daily_summary_time'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN daily_summary_time TEXT DEFAULT '22:00';
+                END IF;
+
+                -- Opt-in for anonymous aggregate benchmarking
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='benchmark_opt_in'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN benchmark_opt_in BOOLEAN NOT NULL DEFAULT FALSE;
+                END IF;
+
+                -- Display units ('metric' | 'us'); stored values stay ml/kg
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='units'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN units TEXT NOT NULL DEFAULT 'metric';
+                END IF;
+
+                -- Calorie goal split per meal slot ("25,35,30,10"), NULL = default split
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='meal_budget'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN meal_budget TEXT;
+                END IF;
+
+                -- Water reminder every N hours from 08:00 (onboarding preset)
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='water_reminder_interval'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN water_reminder_interval INTEGER NOT NULL DEFAULT 2;
+                END IF;
+
+                -- Daily summary sections ({"calories": true, "tip": false, ...}), NULL = all
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='summary_sections'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN summary_sections JSONB DEFAULT NULL;
+                END IF;
+
+                -- Verified e-mail address for weekly/monthly HTML reports
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='email'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN email TEXT DEFAULT NULL;
+                END IF;
+
+                -- 'weekly' | 'monthly' | 'both'
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='email_reports'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN email_reports TEXT NOT NULL DEFAULT 'weekly';
+                END IF;
+
+                -- AI answer language ('tr' | 'en')
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='language'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN language TEXT NOT NULL DEFAULT 'tr';
+                END IF;
+
+                -- Nightly summary as an AI-written narrative instead of the numeric block
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='summary_narrative'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN summary_narrative BOOLEAN NOT NULL DEFAULT FALSE;
+                END IF;
+
+                -- Quick-log API token (SHA-256 hex; the plain token is shown to the user once)
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='quicklog_token_hash'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN quicklog_token_hash TEXT UNIQUE;
+                END IF;
+
+                -- Onboarding skipped with 'atla': when to nudge the user to customize defaults
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='customize_nudge_at'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN customize_nudge_at TIMESTAMPTZ DEFAULT NULL;
+                END IF;
+
+                -- Last time the monthly NPS question was sent
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='nps_asked_at'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN nps_asked_at TIMESTAMPTZ DEFAULT NULL;
+                END IF;
+
+                -- Custom nutrition fields per meal (CUSTOM_NUTRITION_FIELDS)
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='meals' AND column_name='extras'
+                ) THEN
+                    ALTER TABLE meals ADD COLUMN extras JSONB DEFAULT NULL;
+                END IF;
+
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='meals' AND column_name='full_description'
+                ) THEN
+                    ALTER TABLE meals ADD COLUMN full_description TEXT DEFAULT NULL;
+                END IF;
+
+                -- meal_description::canonicalize(description); old rows are filled by nightly maintenance
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='meals' AND column_name='description_normalized'
+                ) THEN
+                    ALTER TABLE meals ADD COLUMN description_normalized TEXT DEFAULT NULL;
+                END IF;
+
+                -- Forwarded screenshots/memes rejected before the vision call
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='kpi_snapshots' AND column_name='non_food_images'
+                ) THEN
+                    ALTER TABLE kpi_snapshots ADD COLUMN non_food_images BIGINT NOT NULL DEFAULT 0;
+                END IF;
+
+                -- When pending_command was set; questions left unanswered expire (User::awaiting_reply)
+                IF NOT EXISTS (
+                    SELECT 1 FROM information_schema.columns
+                    WHERE table_schema = current_schema() AND table_name='users' AND column_name='pending_command_at'
+                ) THEN
+                    ALTER TABLE users ADD COLUMN pending_command_at TIMESTAMPTZ DEFAULT NULL;
+                END IF;
+            END $$;
+            "#,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_meals_description_normalized ON meals(user_phone, description_normalized)"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Publish every users row change so other instances can drop their cached copy
+        sqlx::query(&format!(
+            r#"
+            CREATE OR REPLACE FUNCTION users_notify_change() RETURNS trigger AS $$
+            BEGIN
+                IF TG_OP = 'DELETE' THEN
+                    PERFORM pg_notify('{channel}', OLD.phone_number);
+                ELSE
+                    PERFORM pg_notify('{channel}', NEW.phone_number);
+                END IF;
+                RETURN NULL;
+            END;
+            $$ LANGUAGE plpgsql
+            "#,
+            channel = user_cache::USER_CHANGED_CHANNEL
+        ))
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("DROP TRIGGER IF EXISTS users_notify_change ON users")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query(
+            "CREATE TRIGGER users_notify_change AFTER INSERT OR UPDATE OR DELETE ON users \
+             FOR EACH ROW EXECUTE FUNCTION users_notify_change()"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Settings snapshot into `conversations` on every change of a user-facing setting, whichever
+        // code path (command, onboarding, admin, SQL console) made it
+        sqlx::query(&format!(
+            r#"
+            CREATE OR REPLACE FUNCTION users_settings_snapshot() RETURNS trigger AS $$
+            DECLARE
+                new_settings JSONB := {new_settings};
+                old_settings JSONB := {old_settings};
+                changed JSONB;
+            BEGIN
+                IF new_settings IS NOT DISTINCT FROM old_settings THEN
+                    RETURN NULL;
+                END IF;
+                SELECT jsonb_object_agg(n.key, jsonb_build_object('from', old_settings -> n.key, 'to', n.value))
+                INTO changed
+                FROM jsonb_each(new_settings) n
+                WHERE n.value IS DISTINCT FROM old_settings -> n.key;
+
+                INSERT INTO conversations (user_phone, direction, message_type, content, metadata, created_at)
+                VALUES (
+                    NEW.phone_number, 'system', 'settings',
+                    'Ayarlar değişti: ' || (SELECT string_agg(k, ', ' ORDER BY k) FROM jsonb_object_keys(changed) k),
+                    jsonb_build_object('settings', new_settings, 'changed', changed),
+                    NOW()
+                );
+                RETURN NULL;
+            END;
+            $$ LANGUAGE plpgsql
+            "#,
+            new_settings = settings_json("NEW"),
+            old_settings = settings_json("OLD"),
+        ))
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query("DROP TRIGGER IF EXISTS users_settings_snapshot ON users")
+            .execute(&self.pool)
+            .await?;
+        sqlx::query(
+            "CREATE TRIGGER users_settings_snapshot AFTER UPDATE ON users \
+             FOR EACH ROW EXECUTE FUNCTION users_settings_snapshot()"
+        )
+        .execute(&self.pool)
+        .await?;
+
+        // Update existing users with NULL values to have defaults
+        sqlx::query("UPDATE users SET daily_water_goal = 2000 WHERE daily_water_goal IS NULL")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query("UPDATE users SET daily_calorie_goal = 2000 WHERE daily_calorie_goal IS NULL")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query("UPDATE users SET silent_hours_start = '23:00' WHERE silent_hours_start IS NULL")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query("UPDATE users SET silent_hours_end = '07:00' WHERE silent_hours_end IS NULL")
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query("UPDATE users SET is_active = TRUE WHERE is_active IS NULL")
+            .execute(&self.pool)
+            .await?;
+
+        log::info!("✅ Database initialization and migrations completed successfully");
+
+        Ok(())
+    }
+
+    pub async fn create_user(&self, user: &User) -> Result<()> {
+        let sql = format!(
+            "INSERT INTO users ({}) VALUES ({}) ON CONFLICT (phone_number) DO UPDATE SET name = EXCLUDED.name",
+            USER_COLUMNS,
+            user_placeholders()
+        );
+        bind_user(sqlx::query(&sql), user).execute(&self.pool).await?;
+
+        self.user_cache.invalidate(&user.phone_number);
+        Ok(())
+    }
+
+    /// Insert the user unless the phone number already exists, in one statement that returns the row.
+    /// `created` tells the caller whether this call made the user. A transaction-scoped advisory
+    /// lock on the phone number serializes webhooks that arrive together for a brand-new user.
+    pub async fn get_or_create_user(&self, user: &User) -> Result<(User, bool)> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+            .bind(&user.phone_number)
+            .execute(&mut *tx)
+            .await?;
+
+        // Every column, so country defaults (language, units...) set on `user` are kept
+        let sql = format!(
+            "INSERT INTO users ({0}) VALUES ({1}) ON CONFLICT (phone_number) DO NOTHING RETURNING {0}",
+            USER_COLUMNS,
+            user_placeholders()
+        );
+        let inserted = bind_user(sqlx::query(&sql), user).fetch_optional(&mut *tx).await?;
+
+        let (row, created) = match inserted {
+            Some(row) => (row, true),
+            None => {
+                let row = sqlx::query(&format!("SELECT {} FROM users WHERE phone_number = $1", USER_COLUMNS))
+                    .bind(&user.phone_number)
+                    .fetch_one(&mut *tx)
+                    .await?;
+                (row, false)
+            }
+        };
+        tx.commit().await?;
+
+        self.user_cache.invalidate(&user.phone_number);
+        Ok((user_from_row(&row), created))
+    }
+
+    pub async fn get_user(&self, phone_number: &str) -> Result<Option<User>> {
+        if let Some(user) = self.user_cache.get(phone_number) {
+            return Ok(Some(user));
+        }
+
+        let user_result = sqlx::query(&format!("SELECT {} FROM users WHERE phone_number = $1", USER_COLUMNS))
+            .bind(phone_number)
+            .fetch_optional(&self.pool)
+            .await;
+
+        // If query fails (column doesn't exist), fall back to the legacy column set
+        let user = match user_result {
+            Ok(row) => row.as_ref().map(user_from_row),
+            Err(e) if e.to_string().contains("column") => {
+                // Column doesn't exist yet, use legacy query (migration will add it on next restart)
+                log::debug!("User column missing, using legacy query: {}", e);
+                sqlx::query(&format!("SELECT {} FROM users WHERE phone_number = $1", LEGACY_USER_COLUMNS))
+                    .bind(phone_number)
+                    .fetch_optional(&self.pool)
+                    .await?
+                    .as_ref()
+                    .map(legacy_user_from_row)
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        if let Some(user) = &user {
+            self.user_cache.insert(user);
+        }
+        Ok(user)
+    }
+
+    pub async fn get_all_users(&self) -> Result<Vec<User>> {
+        self.query_users("").await
+    }
+
+    /// Load users with an optional WHERE clause, falling back to legacy columns
+    async fn query_users(&self, where_clause: &str) -> Result<Vec<User>> {
+        let result = sqlx::query(&format!("SELECT {} FROM users {}", USER_COLUMNS, where_clause))
+            .fetch_all(&self.pool)
+            .await;
+
+        let users = match result {
+            Ok(rows) => rows.iter().map(user_from_row).collect(),
+            Err(e) if e.to_string().contains("column") => {
+                // Column doesn't exist yet, use legacy query
+                log::debug!("User column missing, using legacy query: {}", e);
+                sqlx::query(&format!("SELECT {} FROM users {}", LEGACY_USER_COLUMNS, where_clause))
+                    .fetch_all(&self.pool)
+                    .await?
+                    .iter()
+                    .map(legacy_user_from_row)
+                    .collect()
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(users)
+    }
+
+    /// Insert a meal; a photo meal also attaches its `stored_images` row in the same transaction,
+    /// so a crash can't leave a meal pointing at an unreserved file or a reserved file without its meal
+    pub async fn add_meal(&self, meal: &Meal) -> Result<i64> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(
+            r#"
+            INSERT INTO meals (user_phone, meal_type, calories, description, image_path, created_at, extras, full_description, description_normalized)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id
+            "#,
+        )
+        .bind(&meal.user_phone)
+        .bind(meal.meal_type.to_string())
+        .bind(meal.calories)
+        .bind(&meal.description)
+        .bind(&meal.image_path)
+        .bind(meal.created_at)
+        .bind(if meal.extras.is_empty() { None } else { Some(serde_json::to_value(&meal.extras)?) })
+        .bind(&meal.full_description)
+        .bind(meal_description::canonicalize(&meal.description))
+        .fetch_one(&mut *tx)
+        .await?;
+        let id: i32 = result.get(0);
+
+        if let Some(image_path) = &meal.image_path {
+            sqlx::query("UPDATE stored_images SET meal_id = $1 WHERE image_path = $2")
+                .bind(id)
+                .bind(image_path)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(id as i64)
+    }
+
+    /// Record a photo before it is downloaded (`storage_key` = file name in the image directory)
+    pub async fn reserve_image(&self, storage_key: &str, user_phone: &str, image_path: &str) -> Result<()> {
+        sqlx::query("INSERT INTO stored_images (storage_key, user_phone, image_path) VALUES ($1, $2, $3)")
+            .bind(storage_key)
+            .bind(user_phone)
+            .bind(image_path)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Forget a photo whose file was deleted
+    pub async fn release_image(&self, image_path: &str) -> Result<()> {
+        sqlx::query("DELETE FROM stored_images WHERE image_path = $1")
+            .bind(image_path)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Photos reserved before `before` that never got a meal (or whose meal was deleted)
+    pub async fn get_unattached_images(&self, before: chrono::DateTime<chrono::Utc>) -> Result<Vec<String>> {
+        let paths = sqlx::query_scalar(
+            "SELECT image_path FROM stored_images WHERE meal_id IS NULL AND created_at < $1 ORDER BY created_at",
+        )
+        .bind(before)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(paths)
+    }
+
+    pub async fn add_water_log(&self, water_log: &WaterLog) -> Result<i64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO water_logs (user_phone, amount_ml, created_at)
+            VALUES ($1, $2, $3)
+            RETURNING id
+            "#,
+        )
+        .bind(&water_log.user_phone)
+        .bind(water_log.amount_ml)
+        .bind(water_log.created_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let id: i32 = result.get(0);
+        Ok(id as i64)
+    }
+
+    /// Time of the user's most recent water log
+    pub async fn get_last_water_log_at(&self, user_phone: &str) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+        let last = sqlx::query_scalar("SELECT MAX(created_at) FROM water_logs WHERE user_phone = $1")
+            .bind(user_phone)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(last)
+    }
+
+    pub async fn get_daily_stats(&self, user_phone: &str, date: NaiveDate) -> Result<DailyStats> {
+        let date_str = date.format("%Y-%m-%d").to_string();
+
+        // Optimized: Use CTEs and single pass aggregation (~40% faster)
+        let result = sqlx::query(
+            r#"
+            WITH meals_stats AS (
+                SELECT
+                    COALESCE(SUM(calories), 0.0) as total_calories,
+                    COUNT(*)::BIGINT as meals_count
+                FROM meals
+                WHERE user_phone = $1
+                    AND created_at >= $2::DATE
+                    AND created_at < ($2::DATE + INTERVAL '1 day')
+            ),
+            water_stats AS (
+                SELECT
+                    COALESCE(SUM(amount_ml)::BIGINT, 0) as total_water,
+                    COUNT(*)::BIGINT as water_count
+                FROM water_logs
+                WHERE user_phone = $1
+                    AND created_at >= $2::DATE
+                    AND created_at < ($2::DATE + INTERVAL '1 day')
+            )
+            SELECT
+                m.total_calories,
+                m.meals_count,
+                w.total_water,
+                w.water_count
+            FROM meals_stats m, water_stats w
+            "#,
+        )
+        .bind(user_phone)
+        .bind(date)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let total_calories: f64 = result.get(0);
+        let meals_count: i64 = result.get::<i64, _>(1);
+        let total_water_ml: i64 = result.get::<i64, _>(2);
+        let water_logs_count: i64 = result.get::<i64, _>(3);
+
+        log::debug!(
+            "🔍 DB daily_stats for {} on {}: calories={}, water={}ml, meals={}, water_logs={}",
+            user_phone,
+            date_str,
+            total_calories,
+            total_water_ml,
+            meals_count,
+            water_logs_count
+        );
+
+        // Custom nutrition field totals (only queried when the deployment defines fields)
+        let mut extra_totals = std::collections::BTreeMap::new();
+        if !crate::services::nutrition_fields::configured().is_empty() {
+            let rows = sqlx::query(
+                r#"
+                SELECT e.key, SUM(e.value::DOUBLE PRECISION)
+                FROM meals m, jsonb_each_text(m.extras) e
+                WHERE m.user_phone = $1
+                    AND m.extras IS NOT NULL
+                    AND m.created_at >= $2::DATE
+                    AND m.created_at < ($2::DATE + INTERVAL '1 day')
+                GROUP BY e.key
+                "#,
+            )
+            .bind(user_phone)
+            .bind(date)
+            .fetch_all(&self.pool)
+            .await?;
+
+            for row in rows {
+                extra_totals.insert(row.get::<String, _>(0), row.get::<f64, _>(1));
+            }
+        }
+
+        Ok(DailyStats {
+            user_phone: user_phone.to_string(),
+            date: date_str,
+            total_calories,
+            total_water_ml,
+            meals_count,
+            water_logs_count,
+            extra_totals,
+        })
+    }
+
+    /// Get meal types logged today (for sequential meal validation)
+    pub async fn get_todays_meal_types(&self, user_phone: &str, date: NaiveDate) -> Result<Vec<MealType>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT DISTINCT meal_type
+            FROM meals
+            WHERE user_phone = $1
+                AND created_at >= $2::DATE
+                AND created_at < ($2::DATE + INTERVAL '1 day')
+            ORDER BY meal_type
+            "#,
+        )
+        .bind(user_phone)
+        .bind(date)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let meal_types = rows
+            .into_iter()
+            .filter_map(|row| {
+                let meal_type_str: String = row.get(0);
+                MealType::from_string(&meal_type_str)
+            })
+            .collect();
+
+        Ok(meal_types)
+    }
+
+    pub async fn update_meal_type(&self, meal_id: i64, meal_type: &MealType) -> Result<()> {
+        sqlx::query("UPDATE meals SET meal_type = $1 WHERE id = $2")
+            .bind(meal_type.to_string())
+            .bind(meal_id as i32)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Fold `source` into `target` (calories, description and extras summed) and delete `source`.
+    /// A photo-less target takes over the source's photo.
+    pub async fn merge_meals(&self, target: &Meal, source: &Meal) -> Result<()> {
+        let (Some(target_id), Some(source_id)) = (target.id, source.id) else {
+            anyhow::bail!("Cannot merge unsaved meals");
+        };
+
+        let mut extras = target.extras.clone();
+        for (key, value) in &source.extras {
+            *extras.entry(key.clone()).or_insert(0.0) += value;
+        }
+        let description = format!("{}\n+ {}", target.description, source.description);
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "UPDATE meals SET calories = $1, description = $2, extras = $3, image_path = COALESCE(image_path, $5), \
+             description_normalized = $6 WHERE id = $4",
+        )
+            .bind(target.calories + source.calories)
+            .bind(&description)
+            .bind(if extras.is_empty() { None } else { Some(serde_json::to_value(&extras)?) })
+            .bind(target_id as i32)
+            .bind(&source.image_path)
+            .bind(meal_description::canonicalize(&description))
+            .execute(&mut *tx)
+            .await?;
+        if target.image_path.is_none() {
+            if let Some(image_path) = &source.image_path {
+                sqlx::query("UPDATE stored_images SET meal_id = $1 WHERE image_path = $2")
+                    .bind(target_id as i32)
+                    .bind(image_path)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+        sqlx::query("DELETE FROM meals WHERE id = $1")
+            .bind(source_id as i32)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn record_meal_type_correction(&self, user_phone: &str, correction: &MealTypeCorrection) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO meal_type_corrections (user_phone, detected_type, corrected_type, local_minutes) VALUES ($1, $2, $3, $4)"
+        )
+        .bind(user_phone)
+        .bind(correction.detected.to_string())
+        .bind(correction.corrected.to_string())
+        .bind(correction.local_minutes as i32)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Most recent meal type corrections of a user (newest first)
+    pub async fn get_meal_type_corrections(&self, user_phone: &str, limit: i64) -> Result<Vec<MealTypeCorrection>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT detected_type, corrected_type, local_minutes
+            FROM meal_type_corrections
+            WHERE user_phone = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_phone)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                Some(MealTypeCorrection {
+                    detected: MealType::from_string(&row.get::<String, _>(0))?,
+                    corrected: MealType::from_string(&row.get::<String, _>(1))?,
+                    local_minutes: row.get::<i32, _>(2) as u32,
+                })
+            })
+            .collect())
+    }
+
+    /// The user's meals with the same canonical description (`meal_description::canonicalize`),
+    /// newest first - for duplicate detection, favorites and reusing an earlier analysis
+    pub async fn find_meals_by_canonical_description(&self, user_phone: &str, description: &str, limit: i32) -> Result<Vec<Meal>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, user_phone, meal_type, calories, description, image_path, created_at, extras, full_description
+            FROM meals
+            WHERE user_phone = $1 AND description_normalized = $2
+            ORDER BY created_at DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(user_phone)
+        .bind(meal_description::canonicalize(description))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let meal_type_str: String = row.get(2);
+                let id_i32: i32 = row.get(0);
+                Meal {
+                    id: Some(id_i32 as i64),
+                    user_phone: row.get(1),
+                    meal_type: MealType::from_string(&meal_type_str).unwrap_or(MealType::Snack),
+                    calories: row.get(3),
+                    description: row.get(4),
+                    image_path: row.get(5),
+                    created_at: row.get(6),
+                    extras: row
+                        .get::<Option<serde_json::Value>, _>(7)
+                        .and_then(|v| serde_json::from_value(v).ok())
+                        .unwrap_or_default(),
+                    full_description: row.get(8),
+                }
+            })
+            .collect())
+    }
+
+    /// Fill `description_normalized` for up to `batch` meals written before the column existed.
+    /// Returns how many were updated (less than `batch` once everything is done).
+    pub async fn backfill_normalized_descriptions(&self, batch: i64) -> Result<u64> {
+        let rows = sqlx::query("SELECT id, description FROM meals WHERE description_normalized IS NULL LIMIT $1")
+            .bind(batch)
+            .fetch_all(&self.pool)
+            .await?;
+        let ids: Vec<i32> = rows.iter().map(|row| row.get(0)).collect();
+        let normalized: Vec<String> = rows
+            .iter()
+            .map(|row| meal_description::canonicalize(row.get::<&str, _>(1)))
+            .collect();
+
+        let result = sqlx::query(
+            r#"
+            UPDATE meals m SET description_normalized = v.normalized
+            FROM UNNEST($1::INTEGER[], $2::TEXT[]) AS v(id, normalized)
+            WHERE m.id = v.id
+            "#,
+        )
+        .bind(&ids)
+        .bind(&normalized)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn get_recent_meals(&self, user_phone: &str, limit: i32) -> Result<Vec<Meal>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, user_phone, meal_type, calories, description, image_path, created_at, extras, full_description
+            FROM meals
+            WHERE user_phone = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_phone)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let meals = rows
+            .into_iter()
+            .map(|row| {
+                let meal_type_str: String = row.get(2);
+                let meal_type = MealType::from_string(&meal_type_str)
+                    .unwrap_or_else(|| {
+                        log::warn!("Unknown meal type '{}', defaulting to Snack", meal_type_str);
+                        MealType::Snack
+                    });
+
+                let id_i32: i32 = row.get(0);
+                Meal {
+                    id: Some(id_i32 as i64),
+                    user_phone: row.get(1),
+                    meal_type,
+                    calories: row.get(3),
+                    description: row.get(4),
+                    image_path: row.get(5),
+                    created_at: row.get(6),
+                    extras: row
+                        .get::<Option<serde_json::Value>, _>(7)
+                        .and_then(|v| serde_json::from_value(v).ok())
+                        .unwrap_or_default(),
+                    full_description: row.get(8),
+                }
+            })
+            .collect();
+
+        Ok(meals)
+    }
+
+    /// Bekleyen tek adımlık seçim (ör. "slot:42:Öğle Yemeği"), None ile temizlenir
+    pub async fn update_pending_command(&self, phone_number: &str, pending: Option<&str>) -> Result<()> {
+        sqlx::query(
+            "UPDATE users SET pending_command = $1, \
+             pending_command_at = CASE WHEN $1::TEXT IS NULL THEN NULL ELSE NOW() END \
+             WHERE phone_number = $2",
+        )
+            .bind(pending)
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    // Onboarding related methods
+    /// Move a user who hasn't started onboarding to `first_step`. Returns false if another
+    /// message already did it, so the welcome is sent only once.
+    pub async fn claim_onboarding_start(&self, phone_number: &str, first_step: &str) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE users SET onboarding_step = $1
+            WHERE phone_number = $2
+                AND onboarding_completed = FALSE
+                AND (onboarding_step IS NULL OR onboarding_step = 'ready_to_start')
+            "#,
+        )
+        .bind(first_step)
+        .bind(phone_number)
+        .execute(&self.pool)
+        .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn update_onboarding_step(&self, phone_number: &str, step: Option<String>) -> Result<()> {
+        sqlx::query(
+            "UPDATE users SET onboarding_step = $1 WHERE phone_number = $2",
+        )
+        .bind(step)
+        .bind(phone_number)
+        .execute(&self.pool)
+        .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    pub async fn update_meal_time(&self, phone_number: &str, meal_type: &str, time: &str) -> Result<()> {
+        // Kullanıcı saatini kendisi ayarladıysa "varsayılanları özelleştir" hatırlatmasına gerek kalmaz
+        // Use separate queries instead of dynamic column names to prevent SQL injection
+        match meal_type {
+            "breakfast" => {
+                sqlx::query("UPDATE users SET breakfast_time = $1, customize_nudge_at = NULL WHERE phone_number = $2")
+                    .bind(time)
+                    .bind(phone_number)
+                    .execute(&self.pool)
+                    .await?;
+            }
+            "lunch" => {
+                sqlx::query("UPDATE users SET lunch_time = $1, customize_nudge_at = NULL WHERE phone_number = $2")
+                    .bind(time)
+                    .bind(phone_number)
+                    .execute(&self.pool)
+                    .await?;
+            }
+            "dinner" => {
+                sqlx::query("UPDATE users SET dinner_time = $1, customize_nudge_at = NULL WHERE phone_number = $2")
+                    .bind(time)
+                    .bind(phone_number)
+                    .execute(&self.pool)
+                    .await?;
+            }
+            _ => return Err(anyhow::anyhow!("Invalid meal type")),
+        }
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    pub async fn complete_onboarding(&self, phone_number: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE users SET onboarding_completed = TRUE, onboarding_step = NULL WHERE phone_number = $1",
+        )
+        .bind(phone_number)
+        .execute(&self.pool)
+        .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    /// Onboarding 'atla' ile geçildiyse, varsayılanları özelleştirme hatırlatmasını planla
+    pub async fn schedule_customize_nudge(&self, phone_number: &str, at: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        sqlx::query("UPDATE users SET customize_nudge_at = $1 WHERE phone_number = $2")
+            .bind(at)
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    /// Users whose customize nudge is due
+    pub async fn get_due_customize_nudges(&self, now: chrono::DateTime<chrono::Utc>) -> Result<Vec<String>> {
+        let rows = sqlx::query(
+            "SELECT phone_number FROM users WHERE customize_nudge_at IS NOT NULL AND customize_nudge_at <= $1"
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|r| r.get(0)).collect())
+    }
+
+    pub async fn clear_customize_nudge(&self, phone_number: &str) -> Result<()> {
+        sqlx::query("UPDATE users SET customize_nudge_at = NULL WHERE phone_number = $1")
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    /// Active, onboarded users old enough to be asked the NPS question and not asked recently
+    pub async fn get_users_due_for_nps(&self, interval_days: i64, min_account_age_days: i64) -> Result<Vec<User>> {
+        self.query_users(&format!(
+            "WHERE is_active = TRUE AND onboarding_completed = TRUE \
+             AND created_at < NOW() - INTERVAL '{} days' \
+             AND (nps_asked_at IS NULL OR nps_asked_at < NOW() - INTERVAL '{} days')",
+            min_account_age_days, interval_days
+        ))
+        .await
+    }
+
+    /// Record that the NPS question was sent and wait for the answer (`pending_command`)
+    pub async fn mark_nps_asked(&self, phone_number: &str, pending: &str) -> Result<()> {
+        sqlx::query("UPDATE users SET nps_asked_at = NOW(), pending_command = $1, pending_command_at = NOW() WHERE phone_number = $2")
+            .bind(pending)
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    pub async fn add_feedback(&self, user_phone: &str, kind: &str, score: Option<i32>, comment: Option<&str>) -> Result<()> {
+        sqlx::query("INSERT INTO feedback (user_phone, kind, score, comment) VALUES ($1, $2, $3, $4)")
+            .bind(user_phone)
+            .bind(kind)
+            .bind(score)
+            .bind(comment)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn add_bug_report(
+        &self,
+        user_phone: &str,
+        description: &str,
+        context: &serde_json::Value,
+        app_version: &str,
+    ) -> Result<i64> {
+        let row = sqlx::query(
+            "INSERT INTO bug_reports (user_phone, description, context, app_version) VALUES ($1, $2, $3, $4) RETURNING id"
+        )
+        .bind(user_phone)
+        .bind(description)
+        .bind(context)
+        .bind(app_version)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get::<i32, _>(0) as i64)
+    }
+
+    // ============================================================
+    // Changelog ("yenilikler") announcements
+    // ============================================================
+
+    /// Create the entry for `version`, or replace its text if it already exists
+    pub async fn upsert_changelog_entry(&self, version: &str, message: &str, announce: bool) -> Result<ChangelogEntry> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO changelog_entries (version, message, announce)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (version) DO UPDATE SET message = EXCLUDED.message, announce = EXCLUDED.announce
+            RETURNING id, version, message, announce, created_at, deployed_at
+            "#,
+        )
+        .bind(version)
+        .bind(message)
+        .bind(announce)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(changelog_from_row(&row))
+    }
+
+    /// All entries, newest first
+    pub async fn get_changelog_entries(&self) -> Result<Vec<ChangelogEntry>> {
+        let rows = sqlx::query(
+            "SELECT id, version, message, announce, created_at, deployed_at FROM changelog_entries ORDER BY created_at DESC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(changelog_from_row).collect())
+    }
+
+    pub async fn delete_changelog_entry(&self, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM changelog_entries WHERE id = $1")
+            .bind(id as i32)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Stamp the first time `version` runs and return its entry if it should be announced
+    pub async fn get_deployed_changelog_entry(&self, version: &str) -> Result<Option<ChangelogEntry>> {
+        let row = sqlx::query(
+            r#"
+            UPDATE changelog_entries SET deployed_at = COALESCE(deployed_at, NOW())
+            WHERE version = $1 AND announce = TRUE
+            RETURNING id, version, message, announce, created_at, deployed_at
+            "#,
+        )
+        .bind(version)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.as_ref().map(changelog_from_row))
+    }
+
+    /// Active, onboarded users that haven't received the entry yet
+    pub async fn get_users_pending_changelog(&self, entry_id: i64) -> Result<Vec<User>> {
+        self.query_users(&format!(
+            "WHERE is_active = TRUE AND onboarding_completed = TRUE \
+             AND phone_number NOT IN (SELECT user_phone FROM changelog_deliveries WHERE entry_id = {})",
+            entry_id
+        ))
+        .await
+    }
+
+    /// Reserve the delivery so each user gets the announcement once, even with several instances.
+    /// Returns false if it was already sent.
+    pub async fn claim_changelog_delivery(&self, entry_id: i64, user_phone: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT INTO changelog_deliveries (entry_id, user_phone) VALUES ($1, $2) ON CONFLICT DO NOTHING"
+        )
+        .bind(entry_id as i32)
+        .bind(user_phone)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Undo a claim when sending failed, so the next run retries
+    pub async fn release_changelog_delivery(&self, entry_id: i64, user_phone: &str) -> Result<()> {
+        sqlx::query("DELETE FROM changelog_deliveries WHERE entry_id = $1 AND user_phone = $2")
+            .bind(entry_id as i32)
+            .bind(user_phone)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Scores of a feedback kind received since `since`
+    pub async fn get_feedback_scores(&self, kind: &str, since: chrono::DateTime<chrono::Utc>) -> Result<Vec<i32>> {
+        let rows = sqlx::query(
+            "SELECT score FROM feedback WHERE kind = $1 AND score IS NOT NULL AND created_at >= $2"
+        )
+        .bind(kind)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|r| r.get(0)).collect())
+    }
+
+    /// Turn all meal reminders and the water reminder on/off (kurulum sihirbazı)
+    pub async fn update_reminders(&self, phone_number: &str, meals: bool, water: bool) -> Result<()> {
+        sqlx::query(
+            "UPDATE users SET breakfast_reminder = $1, lunch_reminder = $1, dinner_reminder = $1, water_reminder = $2 \
+             WHERE phone_number = $3",
+        )
+        .bind(meals)
+        .bind(water)
+        .bind(phone_number)
+        .execute(&self.pool)
+        .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    pub async fn update_meal_reminders(&self, phone_number: &str, enabled: bool) -> Result<()> {
+        sqlx::query(
+            "UPDATE users SET breakfast_reminder = $1, lunch_reminder = $1, dinner_reminder = $1 WHERE phone_number = $2",
+        )
+        .bind(enabled)
+        .bind(phone_number)
+        .execute(&self.pool)
+        .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    /// Su hatırlatmasını aç/kapat; aralık verilirse onu da kaydet
+    pub async fn update_water_reminder(&self, phone_number: &str, enabled: bool, interval_hours: Option<i32>) -> Result<()> {
+        sqlx::query(
+            "UPDATE users SET water_reminder = $1, water_reminder_interval = COALESCE($2, water_reminder_interval) \
+             WHERE phone_number = $3",
+        )
+        .bind(enabled)
+        .bind(interval_hours)
+        .bind(phone_number)
+        .execute(&self.pool)
+        .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    pub async fn update_timezone(&self, phone_number: &str, timezone: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE users SET timezone = $1 WHERE phone_number = $2",
+        )
+        .bind(timezone)
+        .bind(phone_number)
+        .execute(&self.pool)
+        .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    pub async fn update_water_goal(&self, phone_number: &str, goal_ml: i32, source: GoalChangeSource) -> Result<()> {
+        self.update_goal(phone_number, GoalKind::Water, goal_ml, source).await
+    }
+
+    /// Set a daily goal and record the change in `goal_history` (unchanged values are not recorded)
+    async fn update_goal(&self, phone_number: &str, kind: GoalKind, value: i32, source: GoalChangeSource) -> Result<()> {
+        let column = match kind {
+            GoalKind::Calories => "daily_calorie_goal",
+            GoalKind::Water => "daily_water_goal",
+        };
+
+        let mut tx = self.pool.begin().await?;
+        let old_value: Option<Option<i32>> = sqlx::query_scalar(&format!(
+            "SELECT {} FROM users WHERE phone_number = $1 FOR UPDATE",
+            column
+        ))
+        .bind(phone_number)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        sqlx::query(&format!("UPDATE users SET {} = $1 WHERE phone_number = $2", column))
+            .bind(value)
+            .bind(phone_number)
+            .execute(&mut *tx)
+            .await?;
+
+        let old_value = old_value.flatten();
+        if old_value != Some(value) {
+            sqlx::query(
+                "INSERT INTO goal_history (user_phone, goal, old_value, new_value, source) VALUES ($1, $2, $3, $4, $5)"
+            )
+            .bind(phone_number)
+            .bind(kind.as_str())
+            .bind(old_value)
+            .bind(value)
+            .bind(source.as_str())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    /// All per-country defaults, by prefix
+    pub async fn get_country_defaults(&self) -> Result<Vec<CountryDefaults>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT prefix, timezone, breakfast_time, lunch_time, dinner_time, daily_water_goal,
+                   silent_hours_start, silent_hours_end, language
+            FROM country_defaults ORDER BY prefix
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(country_defaults_from_row).collect())
+    }
+
+    pub async fn upsert_country_defaults(&self, defaults: &CountryDefaults) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO country_defaults (prefix, timezone, breakfast_time, lunch_time, dinner_time,
+                daily_water_goal, silent_hours_start, silent_hours_end, language)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (prefix) DO UPDATE SET
+                timezone = EXCLUDED.timezone,
+                breakfast_time = EXCLUDED.breakfast_time,
+                lunch_time = EXCLUDED.lunch_time,
+                dinner_time = EXCLUDED.dinner_time,
+                daily_water_goal = EXCLUDED.daily_water_goal,
+                silent_hours_start = EXCLUDED.silent_hours_start,
+                silent_hours_end = EXCLUDED.silent_hours_end,
+                language = EXCLUDED.language,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(&defaults.prefix)
+        .bind(&defaults.timezone)
+        .bind(&defaults.breakfast_time)
+        .bind(&defaults.lunch_time)
+        .bind(&defaults.dinner_time)
+        .bind(defaults.daily_water_goal)
+        .bind(&defaults.silent_hours_start)
+        .bind(&defaults.silent_hours_end)
+        .bind(defaults.language.as_str())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_country_defaults(&self, prefix: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM country_defaults WHERE prefix = $1")
+            .bind(prefix)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Claim this week's goal review for the user; false if another run/instance already sent it
+    pub async fn claim_goal_review(&self, user_phone: &str, week: NaiveDate) -> Result<bool> {
+        let result = sqlx::query("INSERT INTO goal_reviews (user_phone, week) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+            .bind(user_phone)
+            .bind(week)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Undo a claim when sending failed, so the next hourly run retries
+    pub async fn release_goal_review(&self, user_phone: &str, week: NaiveDate) -> Result<()> {
+        sqlx::query("DELETE FROM goal_reviews WHERE user_phone = $1 AND week = $2")
+            .bind(user_phone)
+            .bind(week)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Store the choice on the user's latest review
+    pub async fn record_goal_review_answer(&self, user_phone: &str, answer: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE goal_reviews SET answer = $2, answered_at = NOW()
+            WHERE user_phone = $1 AND week = (SELECT MAX(week) FROM goal_reviews WHERE user_phone = $1)
+            "#,
+        )
+        .bind(user_phone)
+        .bind(answer)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn count_user_images(&self, user_phone: &str) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*)::BIGINT FROM meals WHERE user_phone = $1 AND image_path IS NOT NULL",
+        )
+        .bind(user_phone)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
+    /// Detach every stored photo from the user's meals (calories stay) and return the old paths
+    pub async fn clear_user_image_paths(&self, user_phone: &str) -> Result<Vec<String>> {
+        let paths = sqlx::query_scalar(
+            r#"
+            UPDATE meals m
+            SET image_path = NULL
+            FROM (
+                SELECT id, image_path FROM meals
+                WHERE user_phone = $1 AND image_path IS NOT NULL
+                FOR UPDATE
+            ) old
+            WHERE m.id = old.id
+            RETURNING old.image_path
+            "#,
+        )
+        .bind(user_phone)
+        .fetch_all(&self.pool)
+        .await?;
+
+        sqlx::query("DELETE FROM stored_images WHERE image_path = ANY($1)")
+            .bind(&paths)
+            .execute(&self.pool)
+            .await?;
+        Ok(paths)
+    }
+
+    /// Get count of images (meals with image_path) for today
+    pub async fn get_daily_image_count(&self, user_phone: &str, date: chrono::NaiveDate) -> Result<i64> {
+        let result = sqlx::query(
+            r#"
+            SELECT COUNT(*)::BIGINT
+            FROM meals
+            WHERE user_phone = $1
+                AND image_path IS NOT NULL
+                AND created_at >= $2::DATE
+                AND created_at < ($2::DATE + INTERVAL '1 day')
+            "#,
+        )
+        .bind(user_phone)
+        .bind(date)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let count: i64 = result.get::<i64, _>(0);
+        Ok(count)
+    }
+
+    // ============================================================
+    // Favorite Meals (Removed in v2.1 - feature deprecated)
+    // Table kept for backward compatibility with existing data
+    // ============================================================
+
+    /// Update calorie goal for user
+    pub async fn update_calorie_goal(&self, phone_number: &str, goal_kcal: i32, source: GoalChangeSource) -> Result<()> {
+        self.update_goal(phone_number, GoalKind::Calories, goal_kcal, source).await
+    }
+
+    /// Update silent hours for user
+    pub async fn update_silent_hours(
+        &self,
+        phone_number: &str,
+        start: &str,
+        end: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE users SET silent_hours_start = $1, silent_hours_end = $2 WHERE phone_number = $3",
+        )
+        .bind(start)
+        .bind(end)
+        .bind(phone_number)
+        .execute(&self.pool)
+        .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    // ============================================================
+    // Conversation Logging Functions
+    // ============================================================
+
+    /// Log a conversation message (incoming from user or outgoing from bot).
+    /// The row is queued and written by the background batch writer within ~500ms.
+    pub async fn log_conversation(
+        &self,
+        user_phone: &str,
+        direction: ConversationDirection,
+        message_type: MessageType,
+        content: &str,
+        metadata: Option<serde_json::Value>,
+    ) -> Result<()> {
+        let message_type_str = serde_json::to_string(&message_type)?.trim_matches('"').to_string();
+
+        self.conversation_log
+            .log(PendingConversation {
+                user_phone: user_phone.to_string(),
+                direction: direction.to_string(),
+                message_type: message_type_str,
+                content: content.to_string(),
+                metadata,
+                created_at: chrono::Utc::now(),
+            })
+            .await;
+
+        Ok(())
+    }
+
+    /// Write all queued conversation log rows now (call before shutdown)
+    pub async fn flush_conversation_log(&self) {
+        self.conversation_log.flush().await;
+    }
+
+    /// Get recent conversation history for a user (last `limit` messages, oldest first).
+    /// Callers want the latest messages: bug-report context, the admin user page and last activity.
+    pub async fn get_conversation_history(
+        &self,
+        user_phone: &str,
+        limit: i32,
+    ) -> Result<Vec<Conversation>> {
+        let mut conversations = self.get_conversation_page(user_phone, None, limit).await?;
+        conversations.reverse();
+        Ok(conversations)
+    }
+
+    /// One page of a user's conversations, newest first, strictly after `before` in that order.
+    /// The hot table is searched first; the archive is only read when the page isn't full.
+    /// Archived rows keep their ids, so one cursor works across both tables.
+    pub async fn get_conversation_page(
+        &self,
+        user_phone: &str,
+        before: Option<ConversationCursor>,
+        limit: i32,
+    ) -> Result<Vec<Conversation>> {
+        self.conversation_log.flush().await;
+        let mut conversations = self.query_conversation_page("conversations", user_phone, before, limit).await?;
+
+        let remaining = limit - conversations.len() as i32;
+        if remaining > 0 {
+            let archive_before = conversations.last().map(ConversationCursor::after).or(before);
+            conversations.extend(
+                self.query_conversation_page("conversations_archive", user_phone, archive_before, remaining)
+                    .await?,
+            );
+        }
+
+        Ok(conversations)
+    }
+
+    async fn query_conversation_page(
+        &self,
+        table: &str,
+        user_phone: &str,
+        before: Option<ConversationCursor>,
+        limit: i32,
+    ) -> Result<Vec<Conversation>> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT id, user_phone, direction, message_type, content, metadata, created_at
+            FROM {}
+            WHERE user_phone = $1 AND ($2::TIMESTAMPTZ IS NULL OR (created_at, id) < ($2, $3::BIGINT))
+            ORDER BY created_at DESC, id DESC
+            LIMIT $4
+            "#,
+            table
+        ))
+        .bind(user_phone)
+        .bind(before.map(|c| c.created_at))
+        .bind(before.map(|c| c.id))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(conversation_from_row).collect())
+    }
+
+    /// Settings snapshots (newest first) taken at or before `at`, archived ones included
+    pub async fn get_settings_snapshots(
+        &self,
+        user_phone: &str,
+        at: Option<chrono::DateTime<chrono::Utc>>,
+        limit: i64,
+    ) -> Result<Vec<SettingsSnapshot>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT created_at, metadata FROM (
+                SELECT created_at, metadata FROM conversations
+                WHERE user_phone = $1 AND message_type = 'settings'
+                UNION ALL
+                SELECT created_at, metadata FROM conversations_archive
+                WHERE user_phone = $1 AND message_type = 'settings'
+            ) s
+            WHERE $2::TIMESTAMPTZ IS NULL OR created_at <= $2
+            ORDER BY created_at DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(user_phone)
+        .bind(at)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.iter().map(settings_snapshot_from_row).collect())
+    }
+
+    /// First settings snapshot after `at` (its `from` values are the settings before it)
+    pub async fn get_first_settings_snapshot_after(
+        &self,
+        user_phone: &str,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Option<SettingsSnapshot>> {
+        let row = sqlx::query(
+            r#"
+            SELECT created_at, metadata FROM (
+                SELECT created_at, metadata FROM conversations
+                WHERE user_phone = $1 AND message_type = 'settings' AND created_at > $2
+                UNION ALL
+                SELECT created_at, metadata FROM conversations_archive
+                WHERE user_phone = $1 AND message_type = 'settings' AND created_at > $2
+            ) s
+            ORDER BY created_at ASC
+            LIMIT 1
+            "#,
+        )
+        .bind(user_phone)
+        .bind(at)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.as_ref().map(settings_snapshot_from_row))
+    }
+
+    /// The user's settings today, in the same shape as a snapshot's `settings`
+    pub async fn get_current_settings(&self, user_phone: &str) -> Result<Option<serde_json::Value>> {
+        let settings = sqlx::query_scalar(&format!(
+            "SELECT {} FROM users u WHERE phone_number = $1",
+            settings_json("u")
+        ))
+        .bind(user_phone)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(settings)
+    }
+
+    /// Move up to `batch_size` conversations older than `cutoff` into `conversations_archive`.
+    /// Returns the number of rows moved (0 when nothing is left to archive).
+    pub async fn archive_conversations_batch(
+        &self,
+        cutoff: chrono::DateTime<chrono::Utc>,
+        batch_size: i64,
+    ) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            WITH moved AS (
+                DELETE FROM conversations
+                WHERE id IN (
+                    SELECT id FROM conversations
+                    WHERE created_at < $1
+                    ORDER BY id
+                    LIMIT $2
+                )
+                RETURNING id, user_phone, direction, message_type, content, metadata, created_at
+            )
+            INSERT INTO conversations_archive (id, user_phone, direction, message_type, content, metadata, created_at)
+            SELECT id, user_phone, direction, message_type, content, metadata, created_at FROM moved
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(cutoff)
+        .bind(batch_size)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Get conversation count for a user
+    pub async fn get_conversation_count(&self, user_phone: &str) -> Result<i64> {
+        self.conversation_log.flush().await;
+        let result = sqlx::query(
+            r#"
+            SELECT (SELECT COUNT(*) FROM conversations WHERE user_phone = $1)
+                 + (SELECT COUNT(*) FROM conversations_archive WHERE user_phone = $1)
+            "#,
+        )
+        .bind(user_phone)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let count: i64 = result.get::<i64, _>(0);
+        Ok(count)
+    }
+
+    /// Toggle user active status
+    /// Update user's name from WhatsApp profile
+    pub async fn update_user_name(&self, phone_number: &str, name: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE users SET name = $1 WHERE phone_number = $2")
+            .bind(name)
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache.invalidate(phone_number);
+        if let Some(n) = name {
+            log::debug!("Updated name for {}: {}", phone_number, n);
+        }
+        Ok(())
+    }
+
+    /// Check if user has sent a message in the last 24 hours (WhatsApp Business API window)
+    pub async fn is_within_24h_window(&self, phone_number: &str) -> Result<bool> {
+        use chrono::{Duration, Utc};
+
+        // The message that opened the window may still be queued in the log writer
+        self.conversation_log.flush().await;
+
+        let cutoff = Utc::now() - Duration::hours(24);
+
+        let result = sqlx::query(
+            r#"
+            SELECT created_at FROM conversations
+            WHERE user_phone = $1 AND direction = 'incoming'
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#
+        )
+        .bind(phone_number)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if let Some(row) = result {
+            let last_message: chrono::DateTime<Utc> = row.get(0);
+            Ok(last_message > cutoff)
+        } else {
+            // No incoming messages yet - not in window
+            Ok(false)
+        }
+    }
+
+    /// Check 24h window status and return hours since last message
+    /// Returns: (is_within_window, hours_since_last_message, needs_warning)
+    /// needs_warning is true if user is at 20-23 hours of inactivity
+    pub async fn check_24h_window_detailed(&self, phone_number: &str) -> Result<(bool, Option<i64>, bool)> {
+        use chrono::Utc;
+
+        self.conversation_log.flush().await;
+
+        let result = sqlx::query(
+            r#"
+            SELECT created_at FROM conversations
+            WHERE user_phone = $1 AND direction = 'incoming'
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#
+        )
+        .bind(phone_number)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if let Some(row) = result {
+            let last_message: chrono::DateTime<Utc> = row.get(0);
+            let now = Utc::now();
+            let duration = now.signed_duration_since(last_message);
+            let hours = duration.num_hours();
+
+            let is_within_window = hours < 24;
+            let needs_warning = (20..24).contains(&hours);
+
+            Ok((is_within_window, Some(hours), needs_warning))
+        } else {
+            // No incoming messages yet - not in window, no warning needed
+            Ok((false, None, false))
+        }
+    }
+
+    /// Check if user was already warned about 24h window expiration
+    /// Returns true if user was warned in the last 4 hours
+    pub async fn was_recently_warned(&self, phone_number: &str) -> Result<bool> {
+        use chrono::{Duration, Utc};
+
+        let cutoff = Utc::now() - Duration::hours(4);
+
+        let result = sqlx::query(
+            r#"
+            SELECT last_warned_at FROM window_warnings
+            WHERE user_phone = $1 AND last_warned_at > $2
+            "#
+        )
+        .bind(phone_number)
+        .bind(cutoff)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(result.is_some())
+    }
+
+    /// Mark user as warned about 24h window expiration
+    pub async fn mark_as_warned(&self, phone_number: &str) -> Result<()> {
+        use chrono::Utc;
+
+        sqlx::query(
+            r#"
+            INSERT INTO window_warnings (user_phone, last_warned_at)
+            VALUES ($1, $2)
+            ON CONFLICT (user_phone) DO UPDATE SET last_warned_at = EXCLUDED.last_warned_at
+            "#
+        )
+        .bind(phone_number)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Proactive messages (reminders, summaries, nudges, polls) logged for the user since `since`:
+    /// everything `compliance::send_proactive` tagged, plus untagged `reminder` rows from before.
+    /// Coach summaries are logged on the client's history but go to the coach, so they don't count.
+    pub async fn count_proactive_messages_since(&self, phone_number: &str, since: chrono::DateTime<chrono::Utc>) -> Result<i64> {
+        self.conversation_log.flush().await;
+        let count: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)::BIGINT FROM conversations
+            WHERE user_phone = $1 AND direction = 'outgoing'
+              AND (metadata ? 'proactive' OR message_type = 'reminder')
+              AND created_at >= $2
+              AND metadata->>'reminder_type' IS DISTINCT FROM 'coach_summary'
+            "#,
+        )
+        .bind(phone_number)
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
+    /// When the given reminder type ("breakfast", "daily_summary"...) was last sent to the user
+    pub async fn get_last_reminder_at(&self, phone_number: &str, reminder_type: &str) -> Result<Option<chrono::DateTime<chrono::Utc>>> {
+        let row = sqlx::query(
+            "SELECT last_sent_at FROM reminder_log WHERE user_phone = $1 AND reminder_type = $2"
+        )
+        .bind(phone_number)
+        .bind(reminder_type)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| r.get(0)))
+    }
+
+    pub async fn record_reminder_sent(&self, phone_number: &str, reminder_type: &str, sent_at: chrono::DateTime<chrono::Utc>) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO reminder_log (user_phone, reminder_type, last_sent_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_phone, reminder_type) DO UPDATE SET last_sent_at = EXCLUDED.last_sent_at
+            "#
+        )
+        .bind(phone_number)
+        .bind(reminder_type)
+        .bind(sent_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn record_reminder_variant(
+        &self,
+        phone_number: &str,
+        reminder_type: &str,
+        variant: &str,
+        sent_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO reminder_variant_sends (user_phone, reminder_type, variant, sent_at) VALUES ($1, $2, $3, $4)",
+        )
+        .bind(phone_number)
+        .bind(reminder_type)
+        .bind(variant)
+        .bind(sent_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Sends and conversions per reminder wording since `since`. A send converts when the user logs
+    /// a meal (meal reminders) or water (water reminder) within `window_hours`; sends whose window
+    /// is still open are only counted as pending.
+    pub async fn get_reminder_variant_stats(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        window_hours: i64,
+    ) -> Result<Vec<ReminderVariantStats>> {
+        let rows = sqlx::query(
+            r#"
+            WITH sends AS (
+                SELECT s.reminder_type, s.variant,
+                       s.sent_at + make_interval(hours => $2::INT) <= NOW() AS closed,
+                       CASE WHEN s.reminder_type = 'water' THEN EXISTS (
+                           SELECT 1 FROM water_logs w
+                           WHERE w.user_phone = s.user_phone
+                               AND w.created_at > s.sent_at
+                               AND w.created_at <= s.sent_at + make_interval(hours => $2::INT)
+                       ) ELSE EXISTS (
+                           SELECT 1 FROM meals m
+                           WHERE m.user_phone = s.user_phone
+                               AND m.created_at > s.sent_at
+                               AND m.created_at <= s.sent_at + make_interval(hours => $2::INT)
+                       ) END AS converted
+                FROM reminder_variant_sends s
+                WHERE s.sent_at >= $1
+            )
+            SELECT reminder_type, variant,
+                   COUNT(*) FILTER (WHERE closed) AS sends,
+                   COUNT(*) FILTER (WHERE closed AND converted) AS conversions,
+                   COUNT(*) FILTER (WHERE NOT closed) AS pending
+            FROM sends
+            GROUP BY reminder_type, variant
+            ORDER BY reminder_type, variant
+            "#,
+        )
+        .bind(since)
+        .bind(window_hours)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ReminderVariantStats {
+                reminder_type: row.get("reminder_type"),
+                variant: row.get("variant"),
+                sends: row.get("sends"),
+                conversions: row.get("conversions"),
+                pending: row.get("pending"),
+            })
+            .collect())
+    }
+
+    /// Clear warning status when user sends a new message (called when message received)
+    pub async fn clear_warning_status(&self, phone_number: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            DELETE FROM window_warnings WHERE user_phone = $1
+            "#
+        )
+        .bind(phone_number)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // ============================================================
+    // Closed beta allowlist
+    // ============================================================
+
+    pub async fn is_allowlisted(&self, phone_number: &str) -> Result<bool> {
+        let allowed: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM beta_allowlist WHERE phone_number = $1)")
+            .bind(phone_number)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(allowed)
+    }
+
+    /// Record a message from a number without access; true when the waitlist reply is due
+    /// (first message, then at most once every 24 hours)
+    pub async fn record_waitlist_lead(&self, phone_number: &str, message: &str) -> Result<bool> {
+        let notify: bool = sqlx::query_scalar(
+            r#"
+            INSERT INTO waitlist_leads (phone_number, first_message, last_message)
+            VALUES ($1, $2, $2)
+            ON CONFLICT (phone_number) DO UPDATE SET
+                last_message = EXCLUDED.last_message,
+                message_count = waitlist_leads.message_count + 1,
+                last_seen_at = NOW(),
+                notified_at = CASE
+                    WHEN waitlist_leads.notified_at < NOW() - INTERVAL '24 hours' THEN NOW()
+                    ELSE waitlist_leads.notified_at
+                END
+            RETURNING notified_at = last_seen_at
+            "#,
+        )
+        .bind(phone_number)
+        .bind(message)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(notify)
+    }
+
+    pub async fn update_waitlist_lead_name(&self, phone_number: &str, name: &str) -> Result<()> {
+        sqlx::query("UPDATE waitlist_leads SET name = $1 WHERE phone_number = $2")
+            .bind(name)
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Leads not approved yet, most recently active first
+    pub async fn get_waitlist_leads(&self, limit: i64) -> Result<Vec<WaitlistLead>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT l.phone_number, l.name, l.first_message, l.last_message, l.message_count, l.first_seen_at, l.last_seen_at
+            FROM waitlist_leads l
+            WHERE NOT EXISTS (SELECT 1 FROM beta_allowlist a WHERE a.phone_number = l.phone_number)
+            ORDER BY l.last_seen_at DESC
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| WaitlistLead {
+                phone_number: row.get("phone_number"),
+                name: row.get("name"),
+                first_message: row.get("first_message"),
+                last_message: row.get("last_message"),
+                message_count: row.get("message_count"),
+                first_seen_at: row.get("first_seen_at"),
+                last_seen_at: row.get("last_seen_at"),
+            })
+            .collect())
+    }
+
+    /// Approve numbers in one statement; returns the ones that were not approved before
+    pub async fn approve_numbers(&self, phone_numbers: &[String], note: Option<&str>) -> Result<Vec<String>> {
+        let approved: Vec<String> = sqlx::query_scalar(
+            r#"
+            INSERT INTO beta_allowlist (phone_number, note)
+            SELECT DISTINCT unnest($1::TEXT[]), $2
+            ON CONFLICT (phone_number) DO NOTHING
+            RETURNING phone_number
+            "#,
+        )
+        .bind(phone_numbers)
+        .bind(note)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(approved)
+    }
+
+    /// Numbers of all leads still waiting (bulk "approve everyone on the waitlist")
+    pub async fn get_waiting_lead_numbers(&self) -> Result<Vec<String>> {
+        let numbers = sqlx::query_scalar(
+            r#"
+            SELECT l.phone_number FROM waitlist_leads l
+            WHERE NOT EXISTS (SELECT 1 FROM beta_allowlist a WHERE a.phone_number = l.phone_number)
+            ORDER BY l.first_seen_at
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(numbers)
+    }
+
+    /// Subset of `phone_numbers` that wrote to the bot (safe to message: they started the chat)
+    pub async fn filter_waitlist_leads(&self, phone_numbers: &[String]) -> Result<Vec<String>> {
+        let leads = sqlx::query_scalar("SELECT phone_number FROM waitlist_leads WHERE phone_number = ANY($1)")
+            .bind(phone_numbers)
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(leads)
+    }
+
+    // ============================================================
+    // Nightly maintenance
+    // ============================================================
+
+    /// Expired codes, and code requests older than the hourly limit looks at
+    pub async fn purge_expired_email_verifications(&self) -> Result<u64> {
+        let codes = sqlx::query("DELETE FROM email_verifications WHERE expires_at < NOW()")
+            .execute(&self.pool)
+            .await?;
+        let requests = sqlx::query("DELETE FROM email_verification_requests WHERE requested_at < NOW() - INTERVAL '1 day'")
+            .execute(&self.pool)
+            .await?;
+        Ok(codes.rows_affected() + requests.rows_affected())
+    }
+
+    /// Warnings only matter for a few hours (`was_recently_warned`); older rows are dead weight
+    pub async fn purge_window_warnings_before(&self, cutoff: chrono::DateTime<chrono::Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM window_warnings WHERE last_warned_at < $1")
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// One row per reminder send; only the variant report reads them
+    pub async fn purge_reminder_variant_sends_before(&self, cutoff: chrono::DateTime<chrono::Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM reminder_variant_sends WHERE sent_at < $1")
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Age limit on top of the `WEBHOOK_ARCHIVE_SIZE` ring buffer (quiet deployments keep rows for months)
+    pub async fn purge_webhook_payloads_before(&self, cutoff: chrono::DateTime<chrono::Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM webhook_payloads WHERE received_at < $1")
+            .bind(cutoff)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// (table, live tuples, dead tuples) for the tables in the current schema
+    pub async fn get_table_tuple_stats(&self) -> Result<Vec<(String, i64, i64)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT relname::TEXT AS table_name, n_live_tup, n_dead_tup
+            FROM pg_stat_user_tables
+            WHERE schemaname = current_schema()
+            ORDER BY n_dead_tup DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("table_name"), row.get("n_live_tup"), row.get("n_dead_tup")))
+            .collect())
+    }
+
+    /// `VACUUM (ANALYZE)` one table; the name must come from `get_table_tuple_stats`
+    pub async fn vacuum_analyze(&self, table: &str) -> Result<()> {
+        // Tablo adı bind edilemez; katalogdan gelse de tırnaklanır
+        sqlx::query(&format!("VACUUM (ANALYZE) \"{}\"", table.replace('"', "\"\"")))
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Refresh planner statistics for the whole schema
+    pub async fn analyze(&self) -> Result<()> {
+        sqlx::query("ANALYZE").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// Archive a maintenance run and keep the newest `keep` rows
+    pub async fn record_maintenance_run(&self, run: &MaintenanceRun, keep: i64) -> Result<()> {
+        sqlx::query("INSERT INTO maintenance_runs (started_at, duration_ms, steps) VALUES ($1, $2, $3)")
+            .bind(run.started_at)
+            .bind(run.duration_ms)
+            .bind(serde_json::to_value(&run.steps)?)
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query(
+            "DELETE FROM maintenance_runs WHERE id NOT IN (SELECT id FROM maintenance_runs ORDER BY started_at DESC LIMIT $1)",
+        )
+        .bind(keep)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_recent_maintenance_runs(&self, limit: i64) -> Result<Vec<MaintenanceRun>> {
+        let rows = sqlx::query(
+            "SELECT started_at, duration_ms, steps FROM maintenance_runs ORDER BY started_at DESC LIMIT $1",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| MaintenanceRun {
+                started_at: row.get("started_at"),
+                duration_ms: row.get("duration_ms"),
+                steps: serde_json::from_value(row.get("steps")).unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    // ============================================================
+    // Raw Webhook Payloads (ring buffer)
+    // ============================================================
+
+    /// Store a raw webhook body and trim the table to the newest `keep` rows
+    pub async fn store_webhook_payload(
+        &self,
+        message_id: Option<&str>,
+        body: &str,
+        parse_error: Option<&str>,
+        keep: i64,
+    ) -> Result<i64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO webhook_payloads (message_id, body, parse_error, received_at)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            "#,
+        )
+        .bind(message_id)
+        .bind(body)
+        .bind(parse_error)
+        .bind(chrono::Utc::now())
+        .fetch_one(&self.pool)
+        .await?;
+
+        let id: i32 = result.get(0);
+
+        sqlx::query("DELETE FROM webhook_payloads WHERE id <= $1")
+            .bind(id as i64 - keep)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(id as i64)
+    }
+
+    /// Get a stored webhook payload by ID
+    pub async fn get_webhook_payload(&self, id: i64) -> Result<Option<StoredWebhookPayload>> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, message_id, body, parse_error, received_at
+            FROM webhook_payloads
+            WHERE id = $1
+            "#,
+        )
+        .bind(id as i32)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| StoredWebhookPayload {
+            id: row.get::<i32, _>(0) as i64,
+            message_id: row.get(1),
+            body: row.get(2),
+            parse_error: row.get(3),
+            received_at: row.get(4),
+        }))
+    }
+
+    /// List most recent stored webhook payloads (newest first)
+    pub async fn get_recent_webhook_payloads(&self, limit: i32, only_failed: bool) -> Result<Vec<StoredWebhookPayload>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, message_id, body, parse_error, received_at
+            FROM webhook_payloads
+            WHERE ($2 = FALSE OR parse_error IS NOT NULL)
+            ORDER BY id DESC
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .bind(only_failed)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| StoredWebhookPayload {
+                id: row.get::<i32, _>(0) as i64,
+                message_id: row.get(1),
+                body: row.get(2),
+                parse_error: row.get(3),
+                received_at: row.get(4),
+            })
+            .collect())
+    }
+
+    /// Link (or unlink with None) a coach/dietitian to a user.
+    /// Changing the coach revokes sharing consent; the user must opt in again.
+    pub async fn update_coach(&self, phone_number: &str, coach_phone: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE users SET coach_phone = $1, coach_sharing = FALSE WHERE phone_number = $2")
+            .bind(coach_phone)
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    pub async fn update_benchmark_opt_in(&self, phone_number: &str, enabled: bool) -> Result<()> {
+        sqlx::query("UPDATE users SET benchmark_opt_in = $1 WHERE phone_number = $2")
+            .bind(enabled)
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    pub async fn update_summary_narrative(&self, phone_number: &str, enabled: bool) -> Result<()> {
+        sqlx::query("UPDATE users SET summary_narrative = $1 WHERE phone_number = $2")
+            .bind(enabled)
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    /// Cached narrative for the day, only if it was written from the same stats
+    pub async fn get_day_narrative(&self, user_phone: &str, day: NaiveDate, stats_key: &str) -> Result<Option<String>> {
+        let narrative = sqlx::query_scalar(
+            "SELECT narrative FROM day_narratives WHERE user_phone = $1 AND day = $2 AND stats_key = $3",
+        )
+        .bind(user_phone)
+        .bind(day)
+        .bind(stats_key)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(narrative)
+    }
+
+    pub async fn save_day_narrative(&self, user_phone: &str, day: NaiveDate, stats_key: &str, narrative: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO day_narratives (user_phone, day, stats_key, narrative)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_phone, day)
+            DO UPDATE SET stats_key = EXCLUDED.stats_key, narrative = EXCLUDED.narrative, created_at = NOW()
+            "#,
+        )
+        .bind(user_phone)
+        .bind(day)
+        .bind(stats_key)
+        .bind(narrative)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn purge_day_narratives_before(&self, day: NaiveDate) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM day_narratives WHERE day < $1")
+            .bind(day)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn update_units(&self, phone_number: &str, units: UnitSystem) -> Result<()> {
+        sqlx::query("UPDATE users SET units = $1 WHERE phone_number = $2")
+            .bind(units.as_str())
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    pub async fn update_language(&self, phone_number: &str, language: Language) -> Result<()> {
+        sqlx::query("UPDATE users SET language = $1 WHERE phone_number = $2")
+            .bind(language.as_str())
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    /// Öğün bazlı kalori dağılımını ayarla; None varsayılana döndürür
+    pub async fn update_meal_budget(&self, phone_number: &str, budget: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE users SET meal_budget = $1 WHERE phone_number = $2")
+            .bind(budget)
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    /// Meals since `since`, grouped by meal type and hour in the user's timezone
+    pub async fn get_meal_hour_buckets(
+        &self,
+        user_phone: &str,
+        since: chrono::DateTime<chrono::Utc>,
+        timezone: &str,
+    ) -> Result<Vec<MealHourBucket>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                meal_type,
+                EXTRACT(HOUR FROM created_at AT TIME ZONE $3)::INT AS hour,
+                COUNT(*)::BIGINT AS count,
+                COALESCE(SUM(calories), 0.0) AS total_calories
+            FROM meals
+            WHERE user_phone = $1 AND created_at >= $2
+            GROUP BY meal_type, hour
+            "#,
+        )
+        .bind(user_phone)
+        .bind(since)
+        .bind(timezone)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                Some(MealHourBucket {
+                    meal_type: MealType::from_string(row.get::<&str, _>("meal_type"))?,
+                    hour: row.get("hour"),
+                    count: row.get("count"),
+                    total_calories: row.get("total_calories"),
+                })
+            })
+            .collect())
+    }
+
+    /// Full-text search over conversation content and meal descriptions across all users
+    /// (websearch syntax: "exact phrase", -exclude, or). Newest first, each hit with
+    /// `context_size` messages before and after it from the same user.
+    pub async fn search_content(&self, query: &str, limit: i64, context_size: i64) -> Result<Vec<SearchHit>> {
+        self.conversation_log.flush().await;
+        let rows = sqlx::query(
+            r#"
+            WITH q AS (SELECT websearch_to_tsquery('simple', $1) AS query),
+            hits AS (
+                SELECT 'conversation' AS kind, c.id::BIGINT AS id, c.user_phone, c.created_at,
+                       c.content AS text, c.direction || '/' || c.message_type AS detail,
+                       ts_rank(to_tsvector('simple', c.content), q.query) AS rank
+                FROM conversations c, q
+                WHERE to_tsvector('simple', c.content) @@ q.query
+                UNION ALL
+                SELECT 'meal', m.id::BIGINT, m.user_phone, m.created_at,
+                       m.description, m.meal_type,
+                       ts_rank(to_tsvector('simple', m.description), q.query)
+                FROM meals m, q
+                WHERE to_tsvector('simple', m.description) @@ q.query
+                ORDER BY created_at DESC
+                LIMIT $2
+            )
+            SELECT h.kind, h.id, h.user_phone, u.name AS user_name, h.created_at, h.text, h.detail, h.rank,
+                COALESCE((
+                    SELECT jsonb_agg(
+                        jsonb_build_object('direction', x.direction, 'content', x.content, 'created_at', x.created_at)
+                        ORDER BY x.created_at
+                    )
+                    FROM (
+                        (SELECT b.direction, b.content, b.created_at FROM conversations b
+                         WHERE b.user_phone = h.user_phone AND b.created_at < h.created_at
+                         ORDER BY b.created_at DESC LIMIT $3)
+                        UNION ALL
+                        (SELECT a.direction, a.content, a.created_at FROM conversations a
+                         WHERE a.user_phone = h.user_phone AND a.created_at > h.created_at
+                         ORDER BY a.created_at ASC LIMIT $3)
+                    ) x
+                ), '[]'::jsonb) AS context
+            FROM hits h
+            LEFT JOIN users u ON u.phone_number = h.user_phone
+            ORDER BY h.created_at DESC
+            "#,
+        )
+        .bind(query)
+        .bind(limit)
+        .bind(context_size)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| SearchHit {
+                kind: row.get("kind"),
+                id: row.get("id"),
+                user_phone: row.get("user_phone"),
+                user_name: row.get("user_name"),
+                created_at: row.get("created_at"),
+                text: row.get("text"),
+                detail: row.get("detail"),
+                rank: row.get("rank"),
+                context: row.get("context"),
+            })
+            .collect())
+    }
+
+    /// Start (or restart) verification of a new e-mail address; returns the 6 digit code to send,
+    /// or None when the user or the address already got `EMAIL_CODE_MAX_REQUESTS_PER_HOUR` codes
+    pub async fn start_email_verification(&self, phone_number: &str, email: &str) -> Result<Option<String>> {
+        let mut tx = self.pool.begin().await?;
+        // Aynı kullanıcının eşzamanlı istekleri sırayla sayılsın
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('email_verification:' || $1))")
+            .bind(phone_number)
+            .execute(&mut *tx)
+            .await?;
+        let (by_user, by_address): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COUNT(*) FILTER (WHERE user_phone = $1)::BIGINT, COUNT(*) FILTER (WHERE email = $2)::BIGINT
+            FROM email_verification_requests
+            WHERE requested_at > NOW() - INTERVAL '1 hour' AND (user_phone = $1 OR email = $2)
+            "#,
+        )
+        .bind(phone_number)
+        .bind(email)
+        .fetch_one(&mut *tx)
+        .await?;
+        if by_user >= EMAIL_CODE_MAX_REQUESTS_PER_HOUR || by_address >= EMAIL_CODE_MAX_REQUESTS_PER_HOUR {
+            return Ok(None);
+        }
+
+        sqlx::query("INSERT INTO email_verification_requests (user_phone, email) VALUES ($1, $2)")
+            .bind(phone_number)
+            .bind(email)
+            .execute(&mut *tx)
+            .await?;
+        let code: String = sqlx::query_scalar(
+            r#"
+            WITH c AS (SELECT lpad(floor(random() * 1000000)::int::text, 6, '0') AS code)
+            INSERT INTO email_verifications (user_phone, email, code_hash, attempts, expires_at)
+            SELECT $1, $2, encode(sha256(convert_to(c.code, 'UTF8')), 'hex'), 0, NOW() + make_interval(mins => $3)
+            FROM c
+            ON CONFLICT (user_phone) DO UPDATE
+            SET email = EXCLUDED.email, code_hash = EXCLUDED.code_hash, attempts = 0, expires_at = EXCLUDED.expires_at
+            RETURNING (SELECT code FROM c)
+            "#,
+        )
+        .bind(phone_number)
+        .bind(email)
+        .bind(EMAIL_CODE_VALID_MINUTES)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(Some(code))
+    }
+
+    /// Check a code typed by the user; on success the address becomes the user's report address.
+    /// The attempt is counted in the same statement that reads the row, so parallel guesses
+    /// can't each see the old count.
+    pub async fn confirm_email_verification(&self, phone_number: &str, code: &str) -> Result<EmailVerification> {
+        let row = sqlx::query(
+            r#"
+            UPDATE email_verifications
+            SET attempts = attempts + 1
+            WHERE user_phone = $1
+            RETURNING email, attempts, expires_at < NOW() AS expired,
+                      code_hash = encode(sha256(convert_to($2, 'UTF8')), 'hex') AS matches
+            "#,
+        )
+        .bind(phone_number)
+        .bind(code)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(EmailVerification::NoPending);
+        };
+        // Bu deneme dahil
+        let attempts: i32 = row.get("attempts");
+
+        if row.get::<bool, _>("expired") || attempts > EMAIL_CODE_MAX_ATTEMPTS {
+            sqlx::query("DELETE FROM email_verifications WHERE user_phone = $1")
+                .bind(phone_number)
+                .execute(&self.pool)
+                .await?;
+            return Ok(EmailVerification::Expired);
+        }
+
+        if !row.get::<bool, _>("matches") {
+            return Ok(EmailVerification::WrongCode { attempts_left: EMAIL_CODE_MAX_ATTEMPTS - attempts });
+        }
+
+        let email: String = row.get("email");
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("UPDATE users SET email = $1 WHERE phone_number = $2")
+            .bind(&email)
+            .bind(phone_number)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM email_verifications WHERE user_phone = $1")
+            .bind(phone_number)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(EmailVerification::Verified(email))
+    }
+
+    pub async fn update_email_reports(&self, phone_number: &str, frequency: EmailReportFrequency) -> Result<()> {
+        sqlx::query("UPDATE users SET email_reports = $1 WHERE phone_number = $2")
+            .bind(frequency.as_str())
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    /// Remove the report address and any pending verification
+    pub async fn clear_email(&self, phone_number: &str) -> Result<()> {
+        sqlx::query("DELETE FROM email_verifications WHERE user_phone = $1")
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("UPDATE users SET email = NULL WHERE phone_number = $1")
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    /// New quick-log API token for the user (replaces the old one). Only the hash is stored.
+    pub async fn create_quicklog_token(&self, phone_number: &str) -> Result<String> {
+        let token: String = sqlx::query_scalar(
+            r#"
+            WITH t AS (SELECT replace(gen_random_uuid()::text || gen_random_uuid()::text, '-', '') AS token)
+            UPDATE users
+            SET quicklog_token_hash = encode(sha256(convert_to(t.token, 'UTF8')), 'hex')
+            FROM t
+            WHERE phone_number = $1
+            RETURNING t.token
+            "#,
+        )
+        .bind(phone_number)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(token)
+    }
+
+    pub async fn revoke_quicklog_token(&self, phone_number: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE users SET quicklog_token_hash = NULL WHERE phone_number = $1 AND quicklog_token_hash IS NOT NULL",
+        )
+        .bind(phone_number)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Phone number of the user owning a quick-log token
+    pub async fn find_phone_by_quicklog_token(&self, token: &str) -> Result<Option<String>> {
+        let phone = sqlx::query_scalar(
+            "SELECT phone_number FROM users WHERE quicklog_token_hash = encode(sha256(convert_to($1, 'UTF8')), 'hex')",
+        )
+        .bind(token)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(phone)
+    }
+
+    /// Local dates (user's timezone) with at least one meal in the last `days` days, newest first
+    pub async fn get_meal_days(&self, user_phone: &str, timezone: &str, days: i64) -> Result<Vec<NaiveDate>> {
+        let dates = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT (created_at AT TIME ZONE $2)::DATE AS day
+            FROM meals
+            WHERE user_phone = $1 AND created_at >= NOW() - make_interval(days => $3::INT)
+            ORDER BY day DESC
+            "#,
+        )
+        .bind(user_phone)
+        .bind(timezone)
+        .bind(days as i32)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(dates)
+    }
+
+    /// Günlük özet bölümlerini kaydet; varsayılan (hepsi açık) NULL olarak saklanır
+    pub async fn update_summary_sections(&self, phone_number: &str, sections: &SummarySections) -> Result<()> {
+        let value = if *sections == SummarySections::default() { None } else { Some(serde_json::to_value(sections)?) };
+        sqlx::query("UPDATE users SET summary_sections = $1 WHERE phone_number = $2")
+            .bind(value)
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    /// Total calories logged in one meal slot on the given day
+    pub async fn get_slot_calories(&self, user_phone: &str, date: NaiveDate, meal_type: &MealType) -> Result<f64> {
+        let total: f64 = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(calories), 0.0)
+            FROM meals
+            WHERE user_phone = $1
+                AND meal_type = $2
+                AND created_at >= $3::DATE
+                AND created_at < ($3::DATE + INTERVAL '1 day')
+            "#,
+        )
+        .bind(user_phone)
+        .bind(meal_type.to_string())
+        .bind(date)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(total)
+    }
+
+    /// Günlük özet saatini ayarla; None özeti kapatır
+    pub async fn update_daily_summary_time(&self, phone_number: &str, time: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE users SET daily_summary_time = $1 WHERE phone_number = $2")
+            .bind(time)
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    pub async fn update_coach_sharing(&self, phone_number: &str, enabled: bool) -> Result<()> {
+        sqlx::query("UPDATE users SET coach_sharing = $1 WHERE phone_number = $2")
+            .bind(enabled)
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(())
+    }
+
+    /// Daily stats for the 7 days ending at `end_date` (newest first)
+    pub async fn get_weekly_stats(&self, user_phone: &str, end_date: NaiveDate) -> Result<Vec<DailyStats>> {
+        self.get_period_stats(user_phone, end_date, 7).await
+    }
+
+    /// Daily stats for `days` days ending at `end_date`, newest first
+    pub async fn get_period_stats(&self, user_phone: &str, end_date: NaiveDate, days_count: i64) -> Result<Vec<DailyStats>> {
+        let mut days = Vec::with_capacity(days_count.max(0) as usize);
+        for i in 0..days_count {
+            let date = end_date - chrono::Duration::days(i);
+            days.push(self.get_daily_stats(user_phone, date).await?);
+        }
+        Ok(days)
+    }
+
+    /// Usage since `since` for the metrics collector; AI calls are estimated like `get_kpi_counts`
+    pub async fn get_usage_counts(&self, since: chrono::DateTime<chrono::Utc>) -> Result<UsageCounts> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) FILTER (WHERE direction = 'incoming') AS messages_received,
+                COUNT(DISTINCT user_phone) FILTER (WHERE direction = 'incoming') AS active_users,
+                COUNT(*) FILTER (WHERE direction = 'incoming' AND message_type = 'text') AS text_analyses,
+                (SELECT COUNT(*) FROM meals WHERE image_path IS NOT NULL AND created_at >= $1) AS image_analyses
+            FROM conversations
+            WHERE created_at >= $1
+            "#,
+        )
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let messages_sent = sqlx::query(
+            r#"
+            SELECT message_type, COUNT(*) AS sent FROM conversations
+            WHERE direction = 'outgoing' AND created_at >= $1
+            GROUP BY message_type ORDER BY message_type
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(|r| (r.get::<String, _>("message_type"), r.get::<i64, _>("sent")))
+        .collect();
+
+        Ok(UsageCounts {
+            messages_received: row.get("messages_received"),
+            active_users: row.get("active_users"),
+            image_analyses: row.get("image_analyses"),
+            text_analyses: row.get("text_analyses"),
+            messages_sent,
+        })
+    }
+
+    /// Random id of this deployment, created on first use
+    pub async fn get_installation_id(&self) -> Result<String> {
+        sqlx::query("INSERT INTO installation (singleton) VALUES (TRUE) ON CONFLICT DO NOTHING")
+            .execute(&self.pool)
+            .await?;
+        let id: String = sqlx::query_scalar("SELECT id::TEXT FROM installation")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(id)
+    }
+
+    /// True if this instance should send today's telemetry report
+    pub async fn claim_telemetry_report(&self, day: NaiveDate) -> Result<bool> {
+        let result = sqlx::query("INSERT INTO telemetry_reports (day) VALUES ($1) ON CONFLICT DO NOTHING")
+            .bind(day)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Undo the claim when the report could not be delivered, so a later run can retry it
+    pub async fn release_telemetry_report(&self, day: NaiveDate) -> Result<()> {
+        sqlx::query("DELETE FROM telemetry_reports WHERE day = $1")
+            .bind(day)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Totals for the telemetry report: users, activity since `since`, users per enabled feature
+    pub async fn get_telemetry_counts(&self, since: chrono::DateTime<chrono::Utc>) -> Result<TelemetryCounts> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) AS users_total,
+                COUNT(*) FILTER (WHERE onboarding_completed) AS users_onboarded,
+                COUNT(*) FILTER (WHERE units = 'us') AS units_us,
+                COUNT(*) FILTER (WHERE language <> 'tr') AS non_turkish_language,
+                COUNT(*) FILTER (WHERE summary_narrative) AS summary_narrative,
+                COUNT(*) FILTER (WHERE email IS NOT NULL) AS email_reports,
+                COUNT(*) FILTER (WHERE coach_phone IS NOT NULL) AS coach_linked,
+                COUNT(*) FILTER (WHERE benchmark_opt_in) AS benchmark_opt_in,
+                COUNT(*) FILTER (WHERE meal_budget IS NOT NULL) AS custom_meal_budget,
+                COUNT(*) FILTER (WHERE water_reminder) AS water_reminder,
+                COUNT(*) FILTER (WHERE daily_summary_time IS NOT NULL) AS daily_summary,
+                (SELECT COUNT(DISTINCT user_phone) FROM conversations
+                    WHERE direction = 'incoming' AND created_at >= $1) AS active_users,
+                (SELECT COUNT(*) FROM conversations
+                    WHERE direction = 'incoming' AND created_at >= $1) AS messages_received,
+                (SELECT COUNT(*) FROM meals WHERE created_at >= $1) AS meals_logged,
+                (SELECT COUNT(*) FROM meals WHERE image_path IS NOT NULL AND created_at >= $1) AS photo_meals,
+                (SELECT COUNT(*) FROM water_logs WHERE created_at >= $1) AS water_logs
+            FROM users
+            "#,
+        )
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let features = [
+            "units_us", "non_turkish_language", "summary_narrative", "email_reports", "coach_linked",
+            "benchmark_opt_in", "custom_meal_budget", "water_reminder", "daily_summary",
+        ]
+        .into_iter()
+        .map(|feature| (feature, row.get::<i64, _>(feature)))
+        .collect();
+
+        Ok(TelemetryCounts {
+            users_total: row.get("users_total"),
+            users_onboarded: row.get("users_onboarded"),
+            active_users: row.get("active_users"),
+            messages_received: row.get("messages_received"),
+            meals_logged: row.get("meals_logged"),
+            photo_meals: row.get("photo_meals"),
+            water_logs: row.get("water_logs"),
+            features,
+        })
+    }
+
+    /// Raw KPI counts for the week starting at `week_start` (UTC days); AI cost is left at 0
+    pub async fn get_kpi_counts(&self, week_start: NaiveDate) -> Result<KpiSnapshot> {
+        let row = sqlx::query(
+            r#"
+            WITH this_week AS (
+                SELECT DISTINCT user_phone FROM conversations
+                WHERE direction = 'incoming'
+                    AND created_at >= $1::DATE AND created_at < ($1::DATE + INTERVAL '7 days')
+            ),
+            last_week AS (
+                SELECT DISTINCT user_phone FROM conversations
+                WHERE direction = 'incoming'
+                    AND created_at >= ($1::DATE - INTERVAL '7 days') AND created_at < $1::DATE
+            )
+            SELECT
+                (SELECT COUNT(*) FROM users
+                    WHERE created_at >= $1::DATE AND created_at < ($1::DATE + INTERVAL '7 days')) AS new_users,
+                (SELECT COUNT(*) FROM this_week) AS active_users,
+                (SELECT COUNT(*) FROM last_week WHERE user_phone NOT IN (SELECT user_phone FROM this_week)) AS churned_users,
+                (SELECT COUNT(*) FROM conversations
+                    WHERE direction = 'incoming'
+                        AND created_at >= $1::DATE AND created_at < ($1::DATE + INTERVAL '7 days')) AS messages_handled,
+                (SELECT COUNT(*) FROM meals
+                    WHERE image_path IS NOT NULL
+                        AND created_at >= $1::DATE AND created_at < ($1::DATE + INTERVAL '7 days')) AS image_analyses,
+                (SELECT COUNT(*) FROM conversations
+                    WHERE direction = 'incoming' AND message_type = 'text'
+                        AND created_at >= $1::DATE AND created_at < ($1::DATE + INTERVAL '7 days')) AS text_analyses,
+                (SELECT COUNT(*) FROM conversations
+                    WHERE direction = 'outgoing' AND metadata ? 'non_food_image'
+                        AND created_at >= $1::DATE AND created_at < ($1::DATE + INTERVAL '7 days')) AS non_food_images
+            "#,
+        )
+        .bind(week_start)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let top_errors = sqlx::query(
+            r#"
+            SELECT LEFT(content, 120) AS error, COUNT(*) AS occurrences
+            FROM conversations
+            WHERE message_type = 'error'
+                AND created_at >= $1::DATE AND created_at < ($1::DATE + INTERVAL '7 days')
+            GROUP BY LEFT(content, 120)
+            ORDER BY occurrences DESC
+            LIMIT 5
+            "#,
+        )
+        .bind(week_start)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(|r| (r.get::<String, _>("error"), r.get::<i64, _>("occurrences")))
+        .collect();
+
+        Ok(KpiSnapshot {
+            week_start,
+            new_users: row.get("new_users"),
+            active_users: row.get("active_users"),
+            churned_users: row.get("churned_users"),
+            messages_handled: row.get("messages_handled"),
+            image_analyses: row.get("image_analyses"),
+            text_analyses: row.get("text_analyses"),
+            non_food_images: row.get("non_food_images"),
+            ai_cost_estimate_usd: 0.0,
+            top_errors,
+        })
+    }
+
+    /// (sample_size, p10..p90) of opted-in users' daily totals for one metric.
+    /// Only users with any log that day are counted; returns None for unknown metrics.
+    pub async fn compute_benchmark_deciles(&self, date: NaiveDate, metric: &str) -> Result<Option<(i64, Vec<f64>)>> {
+        let value_expr = match metric {
+            "water_ml" => "COALESCE((SELECT SUM(w.amount_ml) FROM water_logs w WHERE w.user_phone = u.phone_number \
+                 AND w.created_at >= $1::DATE AND w.created_at < ($1::DATE + INTERVAL '1 day')), 0)",
+            "meals_count" => "(SELECT COUNT(*) FROM meals m WHERE m.user_phone = u.phone_number \
+                 AND m.created_at >= $1::DATE AND m.created_at < ($1::DATE + INTERVAL '1 day'))",
+            _ => return Ok(None),
+        };
+
+        let row = sqlx::query(&format!(
+            r#"
+            WITH totals AS (
+                SELECT {}::DOUBLE PRECISION AS value
+                FROM users u
+                WHERE u.benchmark_opt_in = TRUE
+                    AND (EXISTS (SELECT 1 FROM meals m WHERE m.user_phone = u.phone_number
+                                 AND m.created_at >= $1::DATE AND m.created_at < ($1::DATE + INTERVAL '1 day'))
+                      OR EXISTS (SELECT 1 FROM water_logs w WHERE w.user_phone = u.phone_number
+                                 AND w.created_at >= $1::DATE AND w.created_at < ($1::DATE + INTERVAL '1 day')))
+            )
+            SELECT COUNT(*) AS sample_size,
+                   percentile_cont(ARRAY[0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9]) WITHIN GROUP (ORDER BY value) AS deciles
+            FROM totals
+            "#,
+            value_expr
+        ))
+        .bind(date)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let sample_size: i64 = row.get("sample_size");
+        let deciles: Option<Vec<f64>> = row.get("deciles");
+        Ok(Some((sample_size, deciles.unwrap_or_default())))
+    }
+
+    pub async fn store_benchmark_aggregate(&self, date: NaiveDate, metric: &str, sample_size: i64, deciles: &[f64]) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO benchmark_aggregates (date, metric, sample_size, deciles)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (date, metric) DO UPDATE SET
+                sample_size = EXCLUDED.sample_size,
+                deciles = EXCLUDED.deciles,
+                created_at = NOW()
+            "#,
+        )
+        .bind(date)
+        .bind(metric)
+        .bind(sample_size)
+        .bind(serde_json::to_value(deciles)?)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Most recent deciles for a metric (from the last 7 days, older aggregates are stale)
+    pub async fn get_latest_benchmark(&self, metric: &str) -> Result<Option<Vec<f64>>> {
+        let row = sqlx::query(
+            r#"
+            SELECT deciles FROM benchmark_aggregates
+            WHERE metric = $1 AND date >= CURRENT_DATE - 7
+            ORDER BY date DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(metric)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.and_then(|r| serde_json::from_value(r.get::<serde_json::Value, _>(0)).ok()))
+    }
+
+    /// Archive a weekly KPI snapshot (re-running the same week overwrites it)
+    pub async fn store_kpi_snapshot(&self, snapshot: &KpiSnapshot) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO kpi_snapshots (
+                week_start, new_users, active_users, churned_users, messages_handled,
+                image_analyses, text_analyses, ai_cost_estimate_usd, top_errors, non_food_images
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (week_start) DO UPDATE SET
+                new_users = EXCLUDED.new_users,
+                active_users = EXCLUDED.active_users,
+                churned_users = EXCLUDED.churned_users,
+                messages_handled = EXCLUDED.messages_handled,
+                image_analyses = EXCLUDED.image_analyses,
+                text_analyses = EXCLUDED.text_analyses,
+                ai_cost_estimate_usd = EXCLUDED.ai_cost_estimate_usd,
+                top_errors = EXCLUDED.top_errors,
+                non_food_images = EXCLUDED.non_food_images,
+                created_at = NOW()
+            "#,
+        )
+        .bind(snapshot.week_start)
+        .bind(snapshot.new_users)
+        .bind(snapshot.active_users)
+        .bind(snapshot.churned_users)
+        .bind(snapshot.messages_handled)
+        .bind(snapshot.image_analyses)
+        .bind(snapshot.text_analyses)
+        .bind(snapshot.ai_cost_estimate_usd)
+        .bind(serde_json::to_value(&snapshot.top_errors)?)
+        .bind(snapshot.non_food_images)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn toggle_user_active(&self, phone_number: &str) -> Result<bool> {
+        // Get current status
+        let current = sqlx::query(
+            "SELECT is_active FROM users WHERE phone_number = $1"
+        )
+        .bind(phone_number)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let current_status: bool = current.get(0);
+        let new_status = !current_status;
+
+        // Update status
+        sqlx::query(
+            "UPDATE users SET is_active = $1 WHERE phone_number = $2"
+        )
+        .bind(new_status)
+        .bind(phone_number)
+        .execute(&self.pool)
+        .await?;
+
+        self.user_cache.invalidate(phone_number);
+        Ok(new_status)
+    }
+
+    /// Reset user completely - delete all meals, water logs, conversations, favorite meals
+    /// and reset onboarding status (keeps user record with phone number)
+    pub async fn reset_user(&self, phone_number: &str) -> Result<()> {
+        log::info!("🔄 Resetting user: {}", phone_number);
+
+        // Delete all meals
+        sqlx::query("DELETE FROM meals WHERE user_phone = $1")
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+        log::debug!("Deleted meals for {}", phone_number);
+
+        // Delete all water logs
+        sqlx::query("DELETE FROM water_logs WHERE user_phone = $1")
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+        log::debug!("Deleted water logs for {}", phone_number);
+
+        // Delete all conversations
+        sqlx::query("DELETE FROM conversations WHERE user_phone = $1")
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM conversations_archive WHERE user_phone = $1")
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+        log::debug!("Deleted conversations for {}", phone_number);
+
+        // Delete all favorite meals
+        sqlx::query("DELETE FROM favorite_meals WHERE user_phone = $1")
+            .bind(phone_number)
+            .execute(&self.pool)
+            .await?;
+        log::debug!("Deleted favorite meals for {}", phone_number);
+
+        // Reset user to initial state (not onboarded)
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET onboarding_completed = false,
+                onboarding_step = NULL,
+                breakfast_time = NULL,
+                lunch_time = NULL,
+                dinner_time = NULL,
+                daily_calorie_goal = NULL,
+                daily_water_goal = NULL,
+                is_active = true
+            WHERE phone_number = $1
+            "#
+        )
+        .bind(phone_number)
+        .execute(&self.pool)
+        .await?;
+
+        self.user_cache.invalidate(phone_number);
+        log::info!("✅ User {} has been completely reset", phone_number);
+        Ok(())
+    }
+
+    /// Get only active users (for reminders)
+    pub async fn get_active_users(&self) -> Result<Vec<User>> {
+        self.query_users("WHERE is_active = TRUE").await
+    }
+}
+
+fn country_defaults_from_row(row: &PgRow) -> CountryDefaults {
+    CountryDefaults {
+        prefix: row.get(0),
+        timezone: row.get(1),
+        breakfast_time: row.get(2),
+        lunch_time: row.get(3),
+        dinner_time: row.get(4),
+        daily_water_goal: row.get(5),
+        silent_hours_start: row.get(6),
+        silent_hours_end: row.get(7),
+        language: Language::from_string(&row.get::<String, _>(8)).unwrap_or_default(),
+    }
+}
+
+fn changelog_from_row(row: &PgRow) -> ChangelogEntry {
+    ChangelogEntry {
+        id: row.get::<i32, _>(0) as i64,
+        version: row.get(1),
+        message: row.get(2),
+        announce: row.get(3),
+        created_at: row.get(4),
+        deployed_at: row.get(5),
+    }
+}
+
+fn conversation_from_row(row: &PgRow) -> Conversation {
+    let id_i32: i32 = row.get(0);
+    let direction_str: String = row.get(2);
+    let message_type_str: String = row.get(3);
+
+    let direction = match direction_str.as_str() {
+        "incoming" => ConversationDirection::Incoming,
+        "outgoing" => ConversationDirection::Outgoing,
+        "system" => ConversationDirection::System,
+        _ => ConversationDirection::Incoming,
+    };
+
+    let message_type: MessageType = serde_json::from_str(&format!("\"{}\"", message_type_str))
+        .unwrap_or(MessageType::Text);
+
+    Conversation {
+        id: Some(id_i32 as i64),
+        user_phone: row.get(1),
+        direction,
+        message_type,
+        content: row.get(4),
+        metadata: row.get(5),
+        created_at: row.get(6),
+    }
+}
+
+/// User-facing settings copied into a snapshot whenever one of them changes (not internal state
+/// such as pending_command or onboarding_step, nor contact data like email / coach_phone)
+const SETTINGS_SNAPSHOT_COLUMNS: &[&str] = &[
+    "timezone", "breakfast_time", "lunch_time", "dinner_time", "breakfast_reminder", "lunch_reminder",
+    "dinner_reminder", "water_reminder", "water_reminder_interval", "silent_hours_start", "silent_hours_end",
+    "daily_water_goal", "daily_calorie_goal", "daily_summary_time", "summary_sections", "summary_narrative",
+    "meal_budget", "units", "language", "email_reports", "benchmark_opt_in", "coach_sharing", "is_active",
+];
+
+/// `jsonb_build_object('timezone', NEW.timezone, ...)` over the snapshot columns of `record`
+fn settings_json(record: &str) -> String {
+    let pairs: Vec<String> = SETTINGS_SNAPSHOT_COLUMNS
+        .iter()
+        .map(|column| format!("'{column}', {record}.{column}"))
+        .collect();
+    format!("jsonb_build_object({})", pairs.join(", "))
+}
+
+fn settings_snapshot_from_row(row: &PgRow) -> SettingsSnapshot {
+    let metadata: serde_json::Value = row.get("metadata");
+    SettingsSnapshot {
+        created_at: row.get("created_at"),
+        settings: metadata.get("settings").cloned().unwrap_or_default(),
+        changed: metadata.get("changed").cloned().unwrap_or_default(),
+    }
+}
+
+/// Columns selected for a full `User` row (keep in sync with `user_from_row`)
+const USER_COLUMNS: &str = "phone_number, name, created_at, onboarding_completed, onboarding_step, \
+     breakfast_reminder, lunch_reminder, dinner_reminder, water_reminder, \
+     breakfast_time, lunch_time, dinner_time, opted_in, timezone, \
+     daily_water_goal, daily_calorie_goal, \
+     silent_hours_start, silent_hours_end, is_active, pending_command, \
+     coach_phone, coach_sharing, daily_summary_time, benchmark_opt_in, units, meal_budget, water_reminder_interval, summary_sections, \
+     email, email_reports, language, summary_narrative, pending_command_at";
+
+type PgQuery<'q> = sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments>;
+
+/// "$1, $2, ..." for every `USER_COLUMNS` entry
+fn user_placeholders() -> String {
+    (1..=USER_COLUMNS.split(',').count()).map(|i| format!("${}", i)).collect::<Vec<_>>().join(", ")
+}
+
+/// Bind a user's fields in `USER_COLUMNS` order
+fn bind_user<'q>(query: PgQuery<'q>, user: &'q User) -> PgQuery<'q> {
+    // Default sections are stored as NULL (see update_summary_sections)
+    let summary_sections = (user.summary_sections != SummarySections::default())
+        .then(|| serde_json::to_value(user.summary_sections).ok())
+        .flatten();
+    query
+        .bind(&user.phone_number)
+        .bind(&user.name)
+        .bind(user.created_at)
+        .bind(user.onboarding_completed)
+        .bind(&user.onboarding_step)
+        .bind(user.breakfast_reminder)
+        .bind(user.lunch_reminder)
+        .bind(user.dinner_reminder)
+        .bind(user.water_reminder)
+        .bind(&user.breakfast_time)
+        .bind(&user.lunch_time)
+        .bind(&user.dinner_time)
+        .bind(user.opted_in)
+        .bind(&user.timezone)
+        .bind(user.daily_water_goal)
+        .bind(user.daily_calorie_goal)
+        .bind(&user.silent_hours_start)
+        .bind(&user.silent_hours_end)
+        .bind(user.is_active)
+        .bind(&user.pending_command)
+        .bind(&user.coach_phone)
+        .bind(user.coach_sharing)
+        .bind(&user.daily_summary_time)
+        .bind(user.benchmark_opt_in)
+        .bind(user.units.as_str())
+        .bind(&user.meal_budget)
+        .bind(user.water_reminder_interval)
+        .bind(summary_sections)
+        .bind(&user.email)
+        .bind(user.email_reports.as_str())
+        .bind(user.language.as_str())
+        .bind(user.summary_narrative)
+        .bind(user.pending_command_at)
+}
+
+/// Columns that exist on databases created before the later migrations
+const LEGACY_USER_COLUMNS: &str = "phone_number, created_at, onboarding_completed, onboarding_step, \
+     breakfast_reminder, lunch_reminder, dinner_reminder, water_reminder, \
+     breakfast_time, lunch_time, dinner_time, opted_in, timezone, \
+     daily_water_goal, daily_calorie_goal, \
+     silent_hours_start, silent_hours_end, is_active";
+
+fn user_from_row(row: &PgRow) -> User {
+    User {
+        name: row.get("name"),
+        pending_command: row.get("pending_command"),
+        coach_phone: row.get("coach_phone"),
+        coach_sharing: row.get("coach_sharing"),
+        daily_summary_time: row.get("daily_summary_time"),
+        benchmark_opt_in: row.get("benchmark_opt_in"),
+        units: UnitSystem::from_string(row.get::<&str, _>("units")).unwrap_or_default(),
+        meal_budget: row.get("meal_budget"),
+        water_reminder_interval: row.get("water_reminder_interval"),
+        summary_sections: row
+            .get::<Option<serde_json::Value>, _>("summary_sections")
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default(),
+        email: row.get("email"),
+        email_reports: EmailReportFrequency::from_string(row.get::<&str, _>("email_reports")).unwrap_or_default(),
+        language: Language::from_string(row.get::<&str, _>("language")).unwrap_or_default(),
+        summary_narrative: row.get("summary_narrative"),
+        pending_command_at: row.get("pending_command_at"),
+        ..legacy_user_from_row(row)
+    }
+}
+
+/// Legacy fallback - columns added by later migrations get their defaults
+fn legacy_user_from_row(row: &PgRow) -> User {
+    User {
+        phone_number: row.get("phone_number"),
+        name: None,
+        created_at: row.get("created_at"),
+        onboarding_completed: row.get("onboarding_completed"),
+        onboarding_step: row.get("onboarding_step"),
+        breakfast_reminder: row.get("breakfast_reminder"),
+        lunch_reminder: row.get("lunch_reminder"),
+        dinner_reminder: row.get("dinner_reminder"),
+        water_reminder: row.get("water_reminder"),
+        breakfast_time: row.get("breakfast_time"),
+        lunch_time: row.get("lunch_time"),
+        dinner_time: row.get("dinner_time"),
+        opted_in: row.get("opted_in"),
+        timezone: row.get("timezone"),
+        daily_water_goal: row.get("daily_water_goal"),
+        daily_calorie_goal: row.get("daily_calorie_goal"),
+        silent_hours_start: row.get("silent_hours_start"),
+        silent_hours_end: row.get("silent_hours_end"),
+        is_active: row.get("is_active"),
+        pending_command: None,
+        coach_phone: None,
+        coach_sharing: false,
+        daily_summary_time: Some("22:00".to_string()),
+        benchmark_opt_in: false,
+        units: UnitSystem::Metric,
+        meal_budget: None,
+        water_reminder_interval: crate::models::DEFAULT_WATER_REMINDER_INTERVAL,
+        summary_sections: SummarySections::default(),
+        email: None,
+        email_reports: EmailReportFrequency::Weekly,
+        language: Language::Tr,
+        summary_narrative: false,
+        pending_command_at: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Against a scratch database: TEST_DATABASE_URL=postgres://... cargo test -- --ignored
+    fn test_database_url() -> String {
+        std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL must point to a scratch database")
+    }
+
+    fn user(phone: &str) -> User {
+        serde_json::from_value(serde_json::json!({
+            "phone_number": phone,
+            "created_at": "2025-01-01T00:00:00Z",
+            "onboarding_completed": true,
+            "breakfast_reminder": true,
+            "lunch_reminder": true,
+            "dinner_reminder": true,
+            "water_reminder": true,
+            "opted_in": true,
+            "timezone": "Europe/Istanbul",
+            "daily_calorie_goal": 1800,
+            "silent_hours_start": "22:30",
+            "is_active": true,
+            "coach_sharing": false,
+            "benchmark_opt_in": false,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn test_shadow_schema_next_to_public_tables() {
+        let url = test_database_url();
+        // public.users already has every migrated column
+        let live = Database::new(&url).await.unwrap();
+        sqlx::query("DROP SCHEMA IF EXISTS replay_shadow_test CASCADE").execute(&live.pool).await.unwrap();
+
+        let shadow = Database::new_with_schema(&url, "replay_shadow_test").await.unwrap();
+        let phone = "+905550000001";
+        shadow.create_user(&user(phone)).await.unwrap();
+        // Settings snapshot trigger reads the migrated columns too
+        shadow.update_water_reminder(phone, true, Some(3)).await.unwrap();
+
+        let copied = shadow.get_user(phone).await.unwrap().unwrap();
+        assert_eq!(copied.daily_calorie_goal, Some(1800));
+        assert_eq!(copied.silent_hours_start.as_deref(), Some("22:30"));
+        assert_eq!(copied.water_reminder_interval, 3);
+        assert!(live.get_user(phone).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn test_created_user_keeps_country_defaults() {
+        let db = Database::new(&test_database_url()).await.unwrap();
+        // Fresh number per run (rows referencing old test users stay behind)
+        let phone = &format!("+1555{:07}", chrono::Utc::now().timestamp_millis() % 10_000_000);
+
+        let mut seeded = user(phone);
+        seeded.language = Language::En;
+        seeded.units = UnitSystem::Us;
+        seeded.water_reminder_interval = 3;
+        let (created, is_new) = db.get_or_create_user(&seeded).await.unwrap();
+        assert!(is_new);
+        assert_eq!(created.language, Language::En);
+
+        let stored = db.get_user(phone).await.unwrap().unwrap();
+        assert_eq!((stored.language, stored.units, stored.water_reminder_interval), (Language::En, UnitSystem::Us, 3));
+        assert!(!db.get_or_create_user(&user(phone)).await.unwrap().1);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn test_proactive_count_includes_tagged_rows() {
+        let db = Database::new(&test_database_url()).await.unwrap();
+        let phone = &format!("+1556{:07}", chrono::Utc::now().timestamp_millis() % 10_000_000);
+        db.create_user(&user(phone)).await.unwrap();
+        let since = chrono::Utc::now() - chrono::Duration::minutes(1);
+
+        let log = |message_type, metadata| db.log_conversation(phone, ConversationDirection::Outgoing, message_type, "x", Some(metadata));
+        log(MessageType::Reminder, serde_json::json!({"reminder_type": "water"})).await.unwrap();
+        log(MessageType::Response, serde_json::json!({"proactive": "marketing"})).await.unwrap();
+        log(MessageType::Response, serde_json::json!({})).await.unwrap();
+        // Koça gider, kullanıcının sayacına girmez
+        log(MessageType::Reminder, serde_json::json!({"reminder_type": "coach_summary", "proactive": "reminder"})).await.unwrap();
+
+        assert_eq!(db.count_proactive_messages_since(phone, since).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn test_email_verification_limits() {
+        let db = Database::new(&test_database_url()).await.unwrap();
+        let phone = &format!("+1557{:07}", chrono::Utc::now().timestamp_millis() % 10_000_000);
+        let email = &format!("test{}@example.com", &phone[1..]);
+        db.create_user(&user(phone)).await.unwrap();
+
+        let code = db.start_email_verification(phone, email).await.unwrap().unwrap();
+        let wrong = if code == "000000" { "111111" } else { "000000" };
+        for attempts_left in (0..EMAIL_CODE_MAX_ATTEMPTS).rev() {
+            assert_eq!(db.confirm_email_verification(phone, wrong).await.unwrap(), EmailVerification::WrongCode { attempts_left });
+        }
+        // Hak bitti: doğru kod da kabul edilmez
+        assert_eq!(db.confirm_email_verification(phone, &code).await.unwrap(), EmailVerification::Expired);
+
+        for _ in 1..EMAIL_CODE_MAX_REQUESTS_PER_HOUR {
+            assert!(db.start_email_verification(phone, email).await.unwrap().is_some());
+        }
+        assert_eq!(db.start_email_verification(phone, email).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs TEST_DATABASE_URL"]
+    async fn test_conversation_pages_split_same_timestamp() {
+        let db = Database::new(&test_database_url()).await.unwrap();
+        let phone = &format!("+1558{:07}", chrono::Utc::now().timestamp_millis() % 10_000_000);
+        db.create_user(&user(phone)).await.unwrap();
+        // Aynı toplu yazımdaki satırlar aynı zaman damgasını taşıyabilir
+        sqlx::query(
+            "INSERT INTO conversations (user_phone, direction, message_type, content, created_at) \
+             SELECT $1, 'incoming', 'text', 'm' || n, '2026-01-01T12:00:00Z' FROM generate_series(1, 3) n",
+        )
+        .bind(phone)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+        let first = db.get_conversation_page(phone, None, 2).await.unwrap();
+        let rest = db.get_conversation_page(phone, first.last().map(ConversationCursor::after), 2).await.unwrap();
+        let mut contents: Vec<String> = first.iter().chain(&rest).map(|c| c.content.clone()).collect();
+        contents.sort();
+        assert_eq!(contents, ["m1", "m2", "m3"]);
+
+        let history = db.get_conversation_history(phone, 2).await.unwrap();
+        assert_eq!(history.iter().map(|c| c.content.as_str()).collect::<Vec<_>>(), ["m2", "m3"]);
+    }
+}
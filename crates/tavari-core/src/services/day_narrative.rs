@@ -0,0 +1,148 @@
+use chrono::NaiveDate;
+use chrono_tz::Tz;
+
+use super::summary_sections::report_streak;
+use super::units::format_water;
+use super::{Database, OpenRouterService};
+use crate::models::{DailyStats, UnitSystem, User};
+
+/// Enough for a busy day; the narrative only mentions a few meals anyway
+const MEALS_LOOKBACK: i32 = 20;
+
+/// Structured stats the AI narrative is written from (nothing else is sent)
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayFacts {
+    pub calories: f64,
+    pub calorie_goal: i32,
+    pub water_ml: i64,
+    pub water_goal: i32,
+    /// "Kahvaltı: menemen (350 kcal)", oldest first
+    pub meals: Vec<String>,
+    /// None when the user turned the streak section off
+    pub streak: Option<i64>,
+    pub units: UnitSystem,
+}
+
+impl DayFacts {
+    /// The cached narrative is reused only while these stay the same
+    pub fn stats_key(&self) -> String {
+        format!(
+            "{:.0}/{}/{}/{}/{}/{}",
+            self.calories,
+            self.calorie_goal,
+            self.water_ml,
+            self.water_goal,
+            self.meals.len(),
+            self.streak.map(|s| s.to_string()).unwrap_or_default()
+        )
+    }
+
+    /// Data block of the narrative prompt
+    pub fn prompt_data(&self) -> String {
+        let meals = if self.meals.is_empty() {
+            "none logged".to_string()
+        } else {
+            self.meals.join("; ")
+        };
+        let mut data = format!(
+            "- Calories: {:.0} kcal (goal {} kcal)\n\
+             - Water: {} (goal {})\n\
+             - Meals: {}\n",
+            self.calories,
+            self.calorie_goal,
+            format_water(self.water_ml, self.units),
+            format_water(self.water_goal as i64, self.units),
+            meals
+        );
+        if let Some(streak) = self.streak {
+            data.push_str(&format!("- Logging streak: {} days\n", streak));
+        }
+        data
+    }
+}
+
+async fn day_facts(db: &Database, user: &User, stats: &DailyStats, today: NaiveDate) -> DayFacts {
+    let user_tz: Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+    let mut meals: Vec<String> = db
+        .get_recent_meals(&user.phone_number, MEALS_LOOKBACK)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|meal| meal.created_at.with_timezone(&user_tz).date_naive() == today)
+        .map(|meal| format!("{}: {} ({:.0} kcal)", meal.meal_type, meal.description, meal.calories))
+        .collect();
+    meals.reverse();
+
+    DayFacts {
+        calories: stats.total_calories,
+        calorie_goal: user.daily_calorie_goal.unwrap_or(2000),
+        water_ml: stats.total_water_ml,
+        water_goal: user.daily_water_goal.unwrap_or(2000),
+        meals,
+        streak: report_streak(db, user, today).await,
+        units: user.units,
+    }
+}
+
+/// Narrative for the nightly summary ("ozet anlati"). Cached per day and stats; None when the AI
+/// is unavailable, so the caller falls back to the numeric report.
+pub async fn narrative_summary(
+    db: &Database,
+    ai: &OpenRouterService,
+    user: &User,
+    stats: &DailyStats,
+    today: NaiveDate,
+) -> Option<String> {
+    let facts = day_facts(db, user, stats, today).await;
+    let key = facts.stats_key();
+
+    match db.get_day_narrative(&user.phone_number, today, &key).await {
+        Ok(Some(cached)) => return Some(cached),
+        Ok(None) => {}
+        Err(e) => log::warn!("⚠️ Failed to read cached day narrative for {}: {}", user.phone_number, e),
+    }
+
+    let narrative = match ai.get_day_narrative(&facts, user.language).await {
+        Ok(narrative) if !narrative.trim().is_empty() => narrative.trim().to_string(),
+        Ok(_) => return None,
+        Err(e) => {
+            log::warn!("⚠️ Day narrative failed for {}, sending the numeric summary: {}", user.phone_number, e);
+            return None;
+        }
+    };
+
+    if let Err(e) = db.save_day_narrative(&user.phone_number, today, &key, &narrative).await {
+        log::warn!("⚠️ Failed to cache day narrative for {}: {}", user.phone_number, e);
+    }
+    Some(narrative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_facts() {
+        let mut facts = DayFacts {
+            calories: 1849.6,
+            calorie_goal: 2000,
+            water_ml: 1500,
+            water_goal: 2500,
+            meals: vec!["Kahvaltı: menemen (350 kcal)".into(), "Öğle Yemeği: tavuk pilav (650 kcal)".into()],
+            streak: Some(4),
+            units: UnitSystem::Metric,
+        };
+        assert_eq!(facts.stats_key(), "1850/2000/1500/2500/2/4");
+        let data = facts.prompt_data();
+        assert!(data.contains("- Calories: 1850 kcal (goal 2000 kcal)"));
+        assert!(data.contains("- Water: 1500 ml (goal 2500 ml)"));
+        assert!(data.contains("menemen (350 kcal); Öğle Yemeği"));
+        assert!(data.contains("- Logging streak: 4 days"));
+
+        // Yeni su kaydı = yeni anlatı
+        facts.water_ml += 250;
+        facts.streak = None;
+        assert_eq!(facts.stats_key(), "1850/2000/1750/2500/2/");
+        assert!(!facts.prompt_data().contains("streak"));
+    }
+}
@@ -0,0 +1,227 @@
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate};
+
+use super::database::EMAIL_CODE_VALID_MINUTES;
+use super::kpi::escape_html;
+use super::notifier::Notifier;
+use super::units::format_water;
+use super::Database;
+use crate::models::{DailyStats, EmailReportFrequency, UnitSystem, User};
+
+/// Reports are emailed at this local hour (Monday for weekly, 1st of the month for monthly)
+pub const EMAIL_REPORT_HOUR: u32 = 9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportPeriod {
+    Weekly,
+    Monthly,
+}
+
+impl ReportPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportPeriod::Weekly => "weekly",
+            ReportPeriod::Monthly => "monthly",
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            ReportPeriod::Weekly => "Haftalık Rapor",
+            ReportPeriod::Monthly => "Aylık Rapor",
+        }
+    }
+}
+
+/// When reports arrive, for the WhatsApp replies ("her Pazartesi 09:00'da")
+pub fn schedule_text(frequency: EmailReportFrequency) -> String {
+    let days = match frequency {
+        EmailReportFrequency::Weekly => "her Pazartesi",
+        EmailReportFrequency::Monthly => "her ayın 1'inde",
+        EmailReportFrequency::Both => "her Pazartesi ve her ayın 1'inde",
+    };
+    format!("{} {:02}:00'da", days, EMAIL_REPORT_HOUR)
+}
+
+/// "Ali@Example.com " → "ali@example.com"; None if it doesn't look like an address
+pub fn normalize_email(text: &str) -> Option<String> {
+    let email = text.trim().trim_matches(|c| c == '<' || c == '>').to_lowercase();
+    let (local, domain) = email.split_once('@')?;
+    let valid = !local.is_empty()
+        && !domain.contains('@')
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && email.len() <= 254
+        && !email.chars().any(|c| c.is_whitespace() || c == ',' || c == ';');
+    valid.then_some(email)
+}
+
+/// Completed period covered by a report sent on `today`: the last 7 days, or the previous calendar month
+pub fn period_range(period: ReportPeriod, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+    match period {
+        ReportPeriod::Weekly => {
+            let end = today - Duration::days(1);
+            (end - Duration::days(6), end)
+        }
+        ReportPeriod::Monthly => {
+            let end = today.with_day(1).unwrap_or(today) - Duration::days(1);
+            (end.with_day(1).unwrap_or(end), end)
+        }
+    }
+}
+
+/// HTML body of a user report; `days` newest first as returned by `get_period_stats`
+pub fn render_html(
+    name: Option<&str>,
+    period: ReportPeriod,
+    (start, end): (NaiveDate, NaiveDate),
+    days: &[DailyStats],
+    calorie_goal: i32,
+    water_goal: i32,
+    units: UnitSystem,
+) -> String {
+    let day_count = days.len().max(1) as f64;
+    let total_calories: f64 = days.iter().map(|d| d.total_calories).sum();
+    let total_water: i64 = days.iter().map(|d| d.total_water_ml).sum();
+    let logged_days = days.iter().filter(|d| d.meals_count > 0).count();
+    let calorie_days = days
+        .iter()
+        .filter(|d| d.meals_count > 0 && d.total_calories <= calorie_goal as f64)
+        .count();
+    let water_days = days.iter().filter(|d| d.total_water_ml >= water_goal as i64).count();
+
+    let rows: String = days
+        .iter()
+        .rev()
+        .map(|d| {
+            let date = NaiveDate::parse_from_str(&d.date, "%Y-%m-%d")
+                .map(|date| date.format("%d.%m.%Y").to_string())
+                .unwrap_or_else(|_| escape_html(&d.date));
+            format!(
+                "<tr><td>{}</td><td>{:.0} kcal</td><td>{}</td><td>{}</td></tr>",
+                date,
+                d.total_calories,
+                format_water(d.total_water_ml, units),
+                d.meals_count
+            )
+        })
+        .collect();
+
+    let greeting = name
+        .map(|n| format!("<p>Merhaba {},</p>", escape_html(n)))
+        .unwrap_or_default();
+
+    format!(
+        "<html><body style=\"font-family: sans-serif\">\
+         <h2>🥗 Tavari {}</h2>\
+         {}<p>{} – {}</p>\
+         <table border=\"1\" cellpadding=\"6\" cellspacing=\"0\">\
+         <tr><td>Ortalama kalori</td><td>{:.0} kcal/gün (hedef {} kcal)</td></tr>\
+         <tr><td>Ortalama su</td><td>{}/gün (hedef {})</td></tr>\
+         <tr><td>Öğün kaydı olan gün</td><td>{} / {}</td></tr>\
+         <tr><td>Kalori hedefinde kalınan gün</td><td>{}</td></tr>\
+         <tr><td>Su hedefine ulaşılan gün</td><td>{}</td></tr>\
+         </table>\
+         <h3>Günlük Döküm</h3>\
+         <table border=\"1\" cellpadding=\"6\" cellspacing=\"0\">\
+         <tr><th>Tarih</th><th>Kalori</th><th>Su</th><th>Öğün</th></tr>{}</table>\
+         <p style=\"color: #888\">E-posta raporlarını kapatmak için WhatsApp'tan <b>eposta kapat</b> yaz.</p>\
+         </body></html>",
+        period.title(),
+        greeting,
+        start.format("%d.%m.%Y"),
+        end.format("%d.%m.%Y"),
+        total_calories / day_count,
+        calorie_goal,
+        format_water((total_water as f64 / day_count).round() as i64, units),
+        format_water(water_goal as i64, units),
+        logged_days,
+        days.len(),
+        calorie_days,
+        water_days,
+        rows
+    )
+}
+
+/// Build and email one report to the user's verified address
+pub async fn send_report(db: &Database, notifier: &Notifier, user: &User, period: ReportPeriod, today: NaiveDate) -> Result<()> {
+    let Some(email) = &user.email else {
+        return Ok(());
+    };
+
+    let (start, end) = period_range(period, today);
+    let days = db.get_period_stats(&user.phone_number, end, (end - start).num_days() + 1).await?;
+    let html = render_html(
+        user.name.as_deref(),
+        period,
+        (start, end),
+        &days,
+        user.daily_calorie_goal.unwrap_or(2000),
+        user.daily_water_goal.unwrap_or(2000),
+        user.units,
+    );
+    let subject = format!("Tavari {} ({} – {})", period.title().to_lowercase(), start.format("%d.%m"), end.format("%d.%m.%Y"));
+
+    notifier.send_email(std::slice::from_ref(email), &subject, &html).await
+}
+
+pub async fn send_verification_code(notifier: &Notifier, email: &str, code: &str) -> Result<()> {
+    let html = format!(
+        "<html><body style=\"font-family: sans-serif\">\
+         <h2>🥗 Tavari e-posta doğrulama</h2>\
+         <p>WhatsApp'ta şunu yazarak adresini onayla:</p>\
+         <p style=\"font-size: 20px\"><b>eposta kod {}</b></p>\
+         <p>Kod {} dakika geçerlidir. Bu isteği sen yapmadıysan bu e-postayı yok sayabilirsin.</p>\
+         </body></html>",
+        code, EMAIL_CODE_VALID_MINUTES
+    );
+    notifier.send_email(&[email.to_string()], "Tavari doğrulama kodu", &html).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(date: &str, calories: f64, water: i64, meals: i64) -> DailyStats {
+        DailyStats {
+            user_phone: "905551112233".into(),
+            date: date.into(),
+            total_calories: calories,
+            total_water_ml: water,
+            meals_count: meals,
+            water_logs_count: 0,
+            extra_totals: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_email_report() {
+        assert_eq!(normalize_email(" Ali.Veli@Example.com "), Some("ali.veli@example.com".into()));
+        assert_eq!(normalize_email("ali@localhost"), None);
+        assert_eq!(normalize_email("ali@@example.com"), None);
+        assert_eq!(normalize_email("ali veli@example.com"), None);
+        assert_eq!(schedule_text(EmailReportFrequency::Monthly), "her ayın 1'inde 09:00'da");
+
+        let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        assert_eq!(period_range(ReportPeriod::Weekly, date("2025-11-10")), (date("2025-11-03"), date("2025-11-09")));
+        assert_eq!(period_range(ReportPeriod::Monthly, date("2025-03-01")), (date("2025-02-01"), date("2025-02-28")));
+
+        let days = vec![day("2025-11-09", 2400.0, 2500, 3), day("2025-11-08", 1800.0, 1000, 2), day("2025-11-07", 0.0, 0, 0)];
+        let html = render_html(
+            Some("Ayşe <3"),
+            ReportPeriod::Weekly,
+            (date("2025-11-07"), date("2025-11-09")),
+            &days,
+            2000,
+            2000,
+            UnitSystem::Metric,
+        );
+        assert!(html.contains("Merhaba Ayşe &lt;3"));
+        assert!(html.contains("1400 kcal/gün"));
+        assert!(html.contains("<tr><td>Öğün kaydı olan gün</td><td>2 / 3</td></tr>"));
+        assert!(html.contains("<tr><td>Kalori hedefinde kalınan gün</td><td>1</td></tr>"));
+        // Eski gün önce
+        assert!(html.find("07.11.2025").unwrap() < html.find("09.11.2025").unwrap());
+    }
+}
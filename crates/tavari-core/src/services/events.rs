@@ -0,0 +1,176 @@
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+
+/// Bot events forwarded to operator-configured webhooks (Zapier, Mixpanel, CRM...)
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum BotEvent {
+    MealLogged {
+        phone: String,
+        meal_type: String,
+        calories: f64,
+        has_image: bool,
+    },
+    UserOnboarded {
+        phone: String,
+    },
+    GoalReached {
+        phone: String,
+        goal: GoalKind,
+        target: i32,
+        value: f64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalKind {
+    Water,
+    Calories,
+}
+
+impl GoalKind {
+    /// Same as the serialized name (also `goal_history.goal`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GoalKind::Water => "water",
+            GoalKind::Calories => "calories",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct EventEnvelope<'a> {
+    #[serde(flatten)]
+    event: &'a BotEvent,
+    timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// Fire-and-forget delivery of `BotEvent`s to outbound webhooks.
+///
+/// Configured with `EVENT_WEBHOOK_URLS` (comma separated). When `EVENT_WEBHOOK_SECRET`
+/// is set, every request carries `X-Tavari-Signature: sha256=<hex hmac of body>`.
+pub struct EventDispatcher {
+    urls: Vec<String>,
+    secret: Option<String>,
+    client: reqwest::Client,
+}
+
+impl EventDispatcher {
+    pub fn new(urls: Vec<String>, secret: Option<String>) -> Self {
+        Self {
+            urls,
+            secret,
+            client: super::http::shared_client(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let urls = std::env::var("EVENT_WEBHOOK_URLS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|u| u.trim().to_string())
+            .filter(|u| !u.is_empty())
+            .collect();
+        let secret = std::env::var("EVENT_WEBHOOK_SECRET").ok().filter(|s| !s.is_empty());
+
+        Self::new(urls, secret)
+    }
+
+    /// Dispatcher that drops every event (dry-run replay, tests)
+    pub fn disabled() -> Self {
+        Self::new(Vec::new(), None)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.urls.is_empty()
+    }
+
+    /// Send the event to all configured URLs in the background; never blocks message handling
+    pub fn emit(&self, event: BotEvent) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let body = match serde_json::to_string(&EventEnvelope {
+            event: &event,
+            timestamp: chrono::Utc::now(),
+        }) {
+            Ok(body) => body,
+            Err(e) => {
+                log::error!("❌ Failed to serialize event: {}", e);
+                return;
+            }
+        };
+        let signature = self.secret.as_deref().map(|secret| sign_payload(&body, secret));
+
+        for url in &self.urls {
+            let client = self.client.clone();
+            let url = url.clone();
+            let body = body.clone();
+            let signature = signature.clone();
+
+            tokio::spawn(async move {
+                let mut request = client
+                    .post(&url)
+                    .header("Content-Type", "application/json")
+                    .timeout(std::time::Duration::from_secs(10))
+                    .body(body);
+                if let Some(signature) = signature {
+                    request = request.header("X-Tavari-Signature", signature);
+                }
+
+                match request.send().await {
+                    Ok(response) if response.status().is_success() => {
+                        log::debug!("📡 Event delivered to {}", url);
+                    }
+                    Ok(response) => {
+                        log::warn!("⚠️ Event webhook {} returned {}", url, response.status());
+                    }
+                    Err(e) => {
+                        log::warn!("⚠️ Event webhook {} failed: {}", url, e);
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// `sha256=<hex>` HMAC signature, same scheme Bird.com uses for inbound webhooks
+pub fn sign_payload(body: &str, secret: &str) -> String {
+    type HmacSha256 = Hmac<Sha256>;
+
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_serialization() {
+        let event = BotEvent::GoalReached {
+            phone: "+905551234567".to_string(),
+            goal: GoalKind::Water,
+            target: 2000,
+            value: 2100.0,
+        };
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "goal_reached");
+        assert_eq!(json["goal"], "water");
+        assert_eq!(json["target"], 2000);
+    }
+
+    #[test]
+    fn test_sign_payload() {
+        // echo -n '{"a":1}' | openssl dgst -sha256 -hmac secret
+        assert_eq!(
+            sign_payload(r#"{"a":1}"#, "secret"),
+            "sha256=aa9e2e3575f5d7098b6caccd790888c36d5fdb63342a73bada2d6a51747a8494"
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+/// `users.pending_command` while we wait for the 0-10 answer
+pub const NPS_PENDING: &str = "nps";
+
+/// Each user is asked at most once per this many days
+pub const NPS_INTERVAL_DAYS: i64 = 30;
+
+/// New users are not asked before they have used the bot for a while
+pub const NPS_MIN_ACCOUNT_AGE_DAYS: i64 = 14;
+
+/// Admin dashboard aggregates cover this window
+pub const NPS_REPORT_DAYS: i64 = 90;
+
+pub const NPS_QUESTION: &str = "📣 *Kısa bir soru (isteğe bağlı)*\n\n\
+Botu bir arkadaşına tavsiye eder misin?\n\
+0 (hiç) ile 10 (kesinlikle) arasında bir puan seç ya da yaz.\n\n\
+Cevap vermek istemezsen *atla* yazman yeterli.";
+
+/// "7", "7/10", "puanım 9" → score; anything outside 0-10 is not an answer
+pub fn parse_nps_score(text: &str) -> Option<i32> {
+    let text = text.trim();
+    let number = text.split('/').next().unwrap_or(text);
+    let digits: String = number.chars().filter(|c| c.is_ascii_digit()).collect();
+    let other = number.chars().filter(|c| c.is_alphabetic()).count();
+
+    // "2 bardak su içtim" is a message, not a score
+    if digits.is_empty() || digits.len() > 2 || other > "puanım".chars().count() {
+        return None;
+    }
+    digits.parse().ok().filter(|score| (0..=10).contains(score))
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NpsSummary {
+    pub responses: i64,
+    pub promoters: i64,
+    pub passives: i64,
+    pub detractors: i64,
+    /// % promoters − % detractors (−100..100); None without responses
+    pub score: Option<f64>,
+}
+
+impl NpsSummary {
+    pub fn from_scores(scores: &[i32]) -> Self {
+        let promoters = scores.iter().filter(|s| **s >= 9).count() as i64;
+        let detractors = scores.iter().filter(|s| **s <= 6).count() as i64;
+        let responses = scores.len() as i64;
+
+        Self {
+            responses,
+            promoters,
+            passives: responses - promoters - detractors,
+            detractors,
+            score: (responses > 0)
+                .then(|| ((promoters - detractors) as f64 * 100.0 / responses as f64).round()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nps_parsing_and_summary() {
+        assert_eq!(parse_nps_score("7"), Some(7));
+        assert_eq!(parse_nps_score("10/10"), Some(10));
+        assert_eq!(parse_nps_score("puanım 9"), Some(9));
+        assert_eq!(parse_nps_score("11"), None);
+        assert_eq!(parse_nps_score("2 bardak su içtim"), None);
+        assert_eq!(parse_nps_score("atla"), None);
+
+        let summary = NpsSummary::from_scores(&[10, 9, 8, 7, 3]);
+        assert_eq!((summary.promoters, summary.passives, summary.detractors), (2, 2, 1));
+        assert_eq!(summary.score, Some(20.0));
+        assert_eq!(NpsSummary::from_scores(&[]).score, None);
+    }
+}
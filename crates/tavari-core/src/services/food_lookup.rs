@@ -0,0 +1,390 @@
+/// Built-in calorie table for common Turkish foods (average kcal per typical portion).
+///
+/// Used when AI is unavailable (TEXT_ONLY_MODE, outage) or returns an unusable answer,
+/// so `ogun mercimek çorbası` still gets a reasonable estimate. Names are matched after
+/// Turkish-character folding, longest name first.
+const FOODS: &[(&str, f64, &str)] = &[
+    // Çorbalar
+    ("mercimek çorbası", 180.0, "1 kase"),
+    ("ezogelin çorbası", 170.0, "1 kase"),
+    ("tarhana çorbası", 150.0, "1 kase"),
+    ("yayla çorbası", 160.0, "1 kase"),
+    ("domates çorbası", 140.0, "1 kase"),
+    ("tavuk suyu çorba", 150.0, "1 kase"),
+    ("şehriye çorbası", 140.0, "1 kase"),
+    ("işkembe çorbası", 230.0, "1 kase"),
+    ("kelle paça", 280.0, "1 kase"),
+    ("mantar çorbası", 160.0, "1 kase"),
+    ("sebze çorbası", 120.0, "1 kase"),
+    ("düğün çorbası", 210.0, "1 kase"),
+    ("toyga çorbası", 170.0, "1 kase"),
+    ("çorba", 150.0, "1 kase"),
+    // Kahvaltılık
+    ("menemen", 250.0, "1 porsiyon"),
+    ("sucuklu yumurta", 350.0, "1 porsiyon"),
+    ("haşlanmış yumurta", 75.0, "1 adet"),
+    ("omlet", 220.0, "2 yumurtalı"),
+    ("sahanda yumurta", 200.0, "2 yumurta"),
+    ("çılbır", 300.0, "1 porsiyon"),
+    ("yumurta", 75.0, "1 adet"),
+    ("beyaz peynir", 95.0, "1 dilim (30 g)"),
+    ("kaşar peyniri", 110.0, "1 dilim (30 g)"),
+    ("tulum peyniri", 110.0, "30 g"),
+    ("lor peyniri", 50.0, "2 yemek kaşığı"),
+    ("peynir", 100.0, "1 dilim"),
+    ("siyah zeytin", 45.0, "5 adet"),
+    ("yeşil zeytin", 40.0, "5 adet"),
+    ("zeytin", 40.0, "5 adet"),
+    ("bal", 65.0, "1 yemek kaşığı"),
+    ("kaymak", 90.0, "1 yemek kaşığı"),
+    ("tereyağı", 75.0, "1 yemek kaşığı"),
+    ("reçel", 50.0, "1 yemek kaşığı"),
+    ("tahin pekmez", 190.0, "2 yemek kaşığı"),
+    ("pekmez", 60.0, "1 yemek kaşığı"),
+    ("nutella", 100.0, "1 yemek kaşığı"),
+    ("fındık ezmesi", 95.0, "1 yemek kaşığı"),
+    ("sucuk", 150.0, "5 dilim"),
+    ("pastırma", 90.0, "4 dilim"),
+    ("salam", 80.0, "3 dilim"),
+    ("sosis", 120.0, "2 adet"),
+    ("simit", 280.0, "1 adet"),
+    ("poğaça", 250.0, "1 adet"),
+    ("açma", 300.0, "1 adet"),
+    ("su böreği", 350.0, "1 dilim"),
+    ("sigara böreği", 120.0, "1 adet"),
+    ("kol böreği", 320.0, "1 dilim"),
+    ("börek", 300.0, "1 dilim"),
+    ("gözleme", 350.0, "1 adet"),
+    ("pişi", 200.0, "1 adet"),
+    ("kumpir", 600.0, "1 adet"),
+    ("tost", 300.0, "1 adet"),
+    ("kaşarlı tost", 320.0, "1 adet"),
+    ("karışık tost", 380.0, "1 adet"),
+    ("yulaf ezmesi", 150.0, "40 g"),
+    ("yulaf", 150.0, "40 g"),
+    ("granola", 200.0, "45 g"),
+    ("mısır gevreği", 110.0, "30 g"),
+    // Ekmek ve hamur işleri
+    ("tam buğday ekmeği", 65.0, "1 dilim"),
+    ("çavdar ekmeği", 65.0, "1 dilim"),
+    ("ekmek", 75.0, "1 dilim"),
+    ("lavaş", 200.0, "1 adet"),
+    ("bazlama", 250.0, "1 adet"),
+    ("pide ekmeği", 280.0, "1/2 adet"),
+    ("yufka", 170.0, "1/4 yaprak"),
+    ("kruvasan", 270.0, "1 adet"),
+    // Et ve tavuk
+    ("tavuk göğsü", 200.0, "150 g"),
+    ("tavuk but", 250.0, "1 adet"),
+    ("tavuk kanat", 300.0, "6 adet"),
+    ("tavuk şiş", 350.0, "1 porsiyon"),
+    ("tavuk döner", 450.0, "1 porsiyon"),
+    ("tavuk sote", 350.0, "1 porsiyon"),
+    ("tavuk", 250.0, "1 porsiyon"),
+    ("et döner", 550.0, "1 porsiyon"),
+    ("döner dürüm", 600.0, "1 adet"),
+    ("dürüm", 550.0, "1 adet"),
+    ("döner", 500.0, "1 porsiyon"),
+    ("iskender", 800.0, "1 porsiyon"),
+    ("adana kebap", 600.0, "1 porsiyon"),
+    ("urfa kebap", 580.0, "1 porsiyon"),
+    ("beyti", 700.0, "1 porsiyon"),
+    ("şiş kebap", 500.0, "1 porsiyon"),
+    ("patlıcan kebabı", 550.0, "1 porsiyon"),
+    ("tas kebabı", 450.0, "1 porsiyon"),
+    ("kebap", 550.0, "1 porsiyon"),
+    ("köfte", 350.0, "5 adet"),
+    ("izmir köfte", 450.0, "1 porsiyon"),
+    ("içli köfte", 220.0, "1 adet"),
+    ("çiğ köfte", 250.0, "1 dürüm"),
+    ("kuzu pirzola", 450.0, "3 adet"),
+    ("pirzola", 450.0, "3 adet"),
+    ("biftek", 400.0, "200 g"),
+    ("antrikot", 450.0, "200 g"),
+    ("kavurma", 450.0, "1 porsiyon"),
+    ("et sote", 400.0, "1 porsiyon"),
+    ("kuşbaşı", 380.0, "1 porsiyon"),
+    ("hünkar beğendi", 650.0, "1 porsiyon"),
+    ("kokoreç", 500.0, "yarım ekmek"),
+    ("hamburger", 550.0, "1 adet"),
+    ("cheeseburger", 620.0, "1 adet"),
+    ("hot dog", 400.0, "1 adet"),
+    // Balık
+    ("levrek", 300.0, "1 adet"),
+    ("çipura", 300.0, "1 adet"),
+    ("somon", 350.0, "150 g"),
+    ("hamsi tava", 400.0, "1 porsiyon"),
+    ("hamsi", 300.0, "1 porsiyon"),
+    ("balık ekmek", 500.0, "1 adet"),
+    ("ton balığı", 180.0, "1 kutu"),
+    ("midye dolma", 45.0, "1 adet"),
+    ("karides", 200.0, "1 porsiyon"),
+    ("balık", 300.0, "1 porsiyon"),
+    // Sebze ve zeytinyağlılar
+    ("kuru fasulye", 350.0, "1 porsiyon"),
+    ("nohut yemeği", 330.0, "1 porsiyon"),
+    ("etli nohut", 380.0, "1 porsiyon"),
+    ("nohut", 270.0, "1 porsiyon"),
+    ("taze fasulye", 180.0, "1 porsiyon"),
+    ("zeytinyağlı fasulye", 180.0, "1 porsiyon"),
+    ("barbunya", 220.0, "1 porsiyon"),
+    ("bezelye", 200.0, "1 porsiyon"),
+    ("ıspanak", 150.0, "1 porsiyon"),
+    ("pırasa", 170.0, "1 porsiyon"),
+    ("kabak yemeği", 140.0, "1 porsiyon"),
+    ("mücver", 250.0, "3 adet"),
+    ("karnıyarık", 450.0, "1 adet"),
+    ("imam bayıldı", 350.0, "1 adet"),
+    ("musakka", 420.0, "1 porsiyon"),
+    ("türlü", 250.0, "1 porsiyon"),
+    ("biber dolması", 250.0, "2 adet"),
+    ("yaprak sarma", 50.0, "1 adet"),
+    ("sarma", 50.0, "1 adet"),
+    ("dolma", 250.0, "1 porsiyon"),
+    ("enginar", 200.0, "1 adet"),
+    ("bamya", 180.0, "1 porsiyon"),
+    ("kısır", 250.0, "1 porsiyon"),
+    ("humus", 170.0, "4 yemek kaşığı"),
+    ("haydari", 120.0, "3 yemek kaşığı"),
+    ("cacık", 80.0, "1 kase"),
+    ("ezme", 70.0, "3 yemek kaşığı"),
+    ("patlıcan salatası", 150.0, "1 porsiyon"),
+    ("çoban salata", 120.0, "1 porsiyon"),
+    ("mevsim salata", 100.0, "1 porsiyon"),
+    ("sezar salata", 350.0, "1 porsiyon"),
+    ("ton balıklı salata", 300.0, "1 porsiyon"),
+    ("salata", 100.0, "1 porsiyon"),
+    ("haşlanmış patates", 130.0, "1 orta boy"),
+    ("patates kızartması", 400.0, "1 porsiyon"),
+    ("patates", 150.0, "1 porsiyon"),
+    ("fırın sebze", 200.0, "1 porsiyon"),
+    // Pilav, makarna, hamur
+    ("pirinç pilavı", 250.0, "1 porsiyon"),
+    ("bulgur pilavı", 220.0, "1 porsiyon"),
+    ("iç pilav", 320.0, "1 porsiyon"),
+    ("pilav", 250.0, "1 porsiyon"),
+    ("makarna", 350.0, "1 porsiyon"),
+    ("spagetti", 350.0, "1 porsiyon"),
+    ("fırın makarna", 450.0, "1 porsiyon"),
+    ("erişte", 330.0, "1 porsiyon"),
+    ("mantı", 550.0, "1 porsiyon"),
+    ("lahmacun", 300.0, "1 adet"),
+    ("kıymalı pide", 650.0, "1 adet"),
+    ("kaşarlı pide", 600.0, "1 adet"),
+    ("karışık pide", 700.0, "1 adet"),
+    ("pide", 650.0, "1 adet"),
+    ("pizza", 300.0, "1 dilim"),
+    ("hamsili pilav", 450.0, "1 porsiyon"),
+    ("tantuni", 450.0, "1 dürüm"),
+    ("kumru", 550.0, "1 adet"),
+    ("sandviç", 350.0, "1 adet"),
+    ("wrap", 400.0, "1 adet"),
+    // Tatlılar
+    ("baklava", 170.0, "1 dilim"),
+    ("künefe", 600.0, "1 porsiyon"),
+    ("sütlaç", 250.0, "1 kase"),
+    ("kazandibi", 280.0, "1 porsiyon"),
+    ("tavuk göğsü tatlısı", 280.0, "1 porsiyon"),
+    ("muhallebi", 230.0, "1 kase"),
+    ("keşkül", 260.0, "1 kase"),
+    ("aşure", 300.0, "1 kase"),
+    ("revani", 300.0, "1 dilim"),
+    ("şekerpare", 150.0, "1 adet"),
+    ("lokma", 60.0, "1 adet"),
+    ("tulumba", 80.0, "1 adet"),
+    ("irmik helvası", 280.0, "1 porsiyon"),
+    ("tahin helvası", 160.0, "30 g"),
+    ("helva", 280.0, "1 porsiyon"),
+    ("lokum", 60.0, "1 adet"),
+    ("dondurma", 200.0, "2 top"),
+    ("kek", 250.0, "1 dilim"),
+    ("pasta", 350.0, "1 dilim"),
+    ("cheesecake", 400.0, "1 dilim"),
+    ("profiterol", 350.0, "1 porsiyon"),
+    ("çikolata", 150.0, "30 g"),
+    ("bisküvi", 45.0, "1 adet"),
+    ("kurabiye", 80.0, "1 adet"),
+    ("gofret", 110.0, "1 adet"),
+    // Meyve ve kuruyemiş
+    ("elma", 80.0, "1 orta boy"),
+    ("armut", 100.0, "1 orta boy"),
+    ("muz", 105.0, "1 orta boy"),
+    ("portakal", 65.0, "1 orta boy"),
+    ("mandalina", 45.0, "1 adet"),
+    ("greyfurt", 80.0, "1/2 adet"),
+    ("çilek", 50.0, "1 kase"),
+    ("kiraz", 90.0, "1 kase"),
+    ("üzüm", 100.0, "1 kase"),
+    ("karpuz", 90.0, "1 dilim"),
+    ("kavun", 60.0, "1 dilim"),
+    ("şeftali", 60.0, "1 adet"),
+    ("kayısı", 50.0, "3 adet"),
+    ("incir", 50.0, "1 adet"),
+    ("nar", 130.0, "1 adet"),
+    ("kivi", 45.0, "1 adet"),
+    ("hurma", 65.0, "2 adet"),
+    ("kuru kayısı", 80.0, "5 adet"),
+    ("kuru üzüm", 85.0, "1 avuç"),
+    ("ceviz", 130.0, "5 adet"),
+    ("fındık", 180.0, "1 avuç"),
+    ("badem", 170.0, "1 avuç"),
+    ("fıstık", 170.0, "1 avuç"),
+    ("leblebi", 120.0, "1 avuç"),
+    ("ay çekirdeği", 170.0, "1 avuç"),
+    ("çekirdek", 170.0, "1 avuç"),
+    ("meyve", 80.0, "1 porsiyon"),
+    // Süt ürünleri ve içecekler
+    ("yoğurt", 100.0, "1 kase"),
+    ("süzme yoğurt", 120.0, "1 kase"),
+    ("ayran", 75.0, "1 bardak"),
+    ("kefir", 110.0, "1 bardak"),
+    ("süt", 120.0, "1 bardak"),
+    ("türk kahvesi", 10.0, "1 fincan"),
+    ("sütlü kahve", 120.0, "1 kupa"),
+    ("latte", 190.0, "1 orta boy"),
+    ("cappuccino", 130.0, "1 orta boy"),
+    ("filtre kahve", 5.0, "1 kupa"),
+    ("kahve", 10.0, "1 fincan"),
+    ("çay", 2.0, "1 bardak"),
+    ("salep", 220.0, "1 kupa"),
+    ("sahlep", 220.0, "1 kupa"),
+    ("sıcak çikolata", 250.0, "1 kupa"),
+    ("portakal suyu", 110.0, "1 bardak"),
+    ("meyve suyu", 120.0, "1 bardak"),
+    ("kola", 140.0, "1 kutu"),
+    ("gazoz", 130.0, "1 şişe"),
+    ("şalgam", 20.0, "1 bardak"),
+    ("boza", 200.0, "1 bardak"),
+    ("limonata", 120.0, "1 bardak"),
+    ("smoothie", 200.0, "1 bardak"),
+    ("protein shake", 150.0, "1 ölçek"),
+    ("bira", 150.0, "33 cl"),
+    ("şarap", 125.0, "1 kadeh"),
+    ("rakı", 150.0, "1 tek"),
+];
+
+/// A built-in estimate for a free-text meal description
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoodEstimate {
+    pub calories: f64,
+    /// (food name, portion, kcal) for each recognized item
+    pub items: Vec<(String, String, f64)>,
+}
+
+impl FoodEstimate {
+    /// "Mercimek çorbası (1 kase) ~180 kcal\n..." for the confirmation message
+    pub fn description(&self) -> String {
+        self.items
+            .iter()
+            .map(|(name, portion, kcal)| format!("{} ({}) ~{:.0} kcal", capitalize(name), portion, kcal))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Lowercase + fold Turkish letters so "Mercimek Çorbası" and "mercimek corbasi" match
+pub(crate) fn normalize(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| match c {
+            'I' => vec!['i'],
+            'İ' => vec!['i'],
+            other => other.to_lowercase().collect(),
+        })
+        .map(|c| match c {
+            'ç' => 'c',
+            'ğ' => 'g',
+            'ı' => 'i',
+            'ö' => 'o',
+            'ş' => 's',
+            'ü' => 'u',
+            'â' => 'a',
+            c if c.is_alphanumeric() => c,
+            _ => ' ',
+        })
+        .collect()
+}
+
+/// Find the most specific (longest) food name contained in one item of the description
+fn match_item(item: &str) -> Option<&'static (&'static str, f64, &'static str)> {
+    let item = format!(" {} ", normalize(item));
+    FOODS
+        .iter()
+        .filter(|(name, _, _)| item.contains(&format!(" {} ", normalize(name))))
+        .max_by_key(|(name, _, _)| name.chars().count())
+}
+
+/// Leading count like "2 simit" / "3 adet yumurta" (1-10)
+fn leading_count(item: &str) -> f64 {
+    item.split_whitespace()
+        .next()
+        .and_then(|w| w.parse::<u32>().ok())
+        .filter(|n| (1..=10).contains(n))
+        .map(|n| n as f64)
+        .unwrap_or(1.0)
+}
+
+/// Estimate calories from the built-in table; `None` if nothing is recognized
+pub fn estimate(description: &str) -> Option<FoodEstimate> {
+    let normalized = description.replace(['+', ';'], ",");
+    let items = normalized
+        .split(',')
+        .flat_map(|part| part.split(" ve "))
+        .flat_map(|part| part.split(" ile "))
+        .map(str::trim)
+        .filter(|part| !part.is_empty());
+
+    let mut estimate = FoodEstimate {
+        calories: 0.0,
+        items: Vec::new(),
+    };
+
+    for item in items {
+        if let Some((name, kcal, portion)) = match_item(item) {
+            let count = leading_count(item);
+            let portion = if count > 1.0 {
+                format!("{} x {}", count, portion)
+            } else {
+                portion.to_string()
+            };
+            estimate.calories += kcal * count;
+            estimate.items.push((name.to_string(), portion, kcal * count));
+        }
+    }
+
+    if estimate.items.is_empty() {
+        None
+    } else {
+        Some(estimate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_turkish_foods() {
+        let soup = estimate("Mercimek Çorbası").unwrap();
+        assert_eq!(soup.calories, 180.0);
+        assert!(soup.description().starts_with("Mercimek çorbası (1 kase)"));
+
+        // ASCII yazım, birden fazla yemek ve adet
+        let breakfast = estimate("2 simit ve beyaz peynir, cay").unwrap();
+        assert_eq!(breakfast.items.len(), 3);
+        assert_eq!(breakfast.calories, 2.0 * 280.0 + 95.0 + 2.0);
+
+        // En spesifik isim kazanır ("tavuk göğsü" > "tavuk")
+        assert_eq!(estimate("ızgara tavuk göğsü").unwrap().calories, 200.0);
+
+        assert_eq!(estimate("uzaylı yemeği"), None);
+        assert!(FOODS.len() >= 200);
+    }
+}
@@ -0,0 +1,236 @@
+use super::events::GoalKind;
+use super::units::format_water;
+use crate::models::{DailyStats, UnitSystem};
+
+/// `users.pending_command` while we wait for the user's choice
+pub const GOAL_REVIEW_PENDING: &str = "goal_review";
+
+/// List row ids ("goalrev_calorie_up"); the webhook passes them on by id, not by title
+pub const OPTION_ID_PREFIX: &str = "goalrev_";
+
+/// Sent on Sunday at this local hour (before the coach summary at 20:00)
+pub const GOAL_REVIEW_HOUR: u32 = 19;
+
+const CALORIE_STEP: i32 = 100;
+const WATER_STEP_ML: i32 = 250;
+
+/// Same limits as `kalorihedefi` / `suhedefi`
+const CALORIE_RANGE: (i32, i32) = (500, 5000);
+const WATER_RANGE: (i32, i32) = (500, 10000);
+
+/// How the week went against the current goals (same ±10% rule as the coach summary)
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeekAdherence {
+    pub days: usize,
+    pub logged_days: usize,
+    pub calorie_days: usize,
+    pub over_days: usize,
+    pub water_days: usize,
+    pub avg_calories: f64,
+    pub avg_water_ml: i64,
+}
+
+impl WeekAdherence {
+    pub fn from_days(days: &[DailyStats], calorie_goal: i32, water_goal: i32) -> Self {
+        let logged: Vec<&DailyStats> = days.iter().filter(|d| d.meals_count > 0).collect();
+        let logged_count = logged.len().max(1);
+
+        Self {
+            days: days.len(),
+            logged_days: logged.len(),
+            calorie_days: logged
+                .iter()
+                .filter(|d| (d.total_calories - calorie_goal as f64).abs() <= calorie_goal as f64 * 0.1)
+                .count(),
+            over_days: logged.iter().filter(|d| d.total_calories > calorie_goal as f64 * 1.1).count(),
+            water_days: days.iter().filter(|d| d.total_water_ml >= water_goal as i64).count(),
+            // Kayıtsız günler ortalamayı düşürmesin
+            avg_calories: logged.iter().map(|d| d.total_calories).sum::<f64>() / logged_count as f64,
+            avg_water_ml: days.iter().map(|d| d.total_water_ml).sum::<i64>() / days.len().max(1) as i64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoalReviewOption {
+    Keep,
+    CalorieUp,
+    CalorieDown,
+    WaterUp,
+    WaterDown,
+}
+
+impl GoalReviewOption {
+    /// Listed (and numbered) in this order
+    pub const ALL: [GoalReviewOption; 5] = [
+        GoalReviewOption::Keep,
+        GoalReviewOption::CalorieUp,
+        GoalReviewOption::CalorieDown,
+        GoalReviewOption::WaterUp,
+        GoalReviewOption::WaterDown,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GoalReviewOption::Keep => "keep",
+            GoalReviewOption::CalorieUp => "calorie_up",
+            GoalReviewOption::CalorieDown => "calorie_down",
+            GoalReviewOption::WaterUp => "water_up",
+            GoalReviewOption::WaterDown => "water_down",
+        }
+    }
+
+    pub fn id(&self) -> String {
+        format!("{}{}", OPTION_ID_PREFIX, self.as_str())
+    }
+
+    pub fn title(&self, units: UnitSystem) -> String {
+        match self {
+            GoalReviewOption::Keep => "Hedefler aynı kalsın".to_string(),
+            GoalReviewOption::CalorieUp => format!("Kalori +{}", CALORIE_STEP),
+            GoalReviewOption::CalorieDown => format!("Kalori -{}", CALORIE_STEP),
+            GoalReviewOption::WaterUp => format!("Su +{}", format_water(WATER_STEP_ML as i64, units)),
+            GoalReviewOption::WaterDown => format!("Su -{}", format_water(WATER_STEP_ML as i64, units)),
+        }
+    }
+
+    /// List row id, its number ("2") or a typed choice ("koru", "kalori artir")
+    pub fn parse(reply: &str) -> Option<Self> {
+        let reply = reply.trim().to_lowercase();
+        if let Some(option) = Self::ALL.iter().find(|option| option.id() == reply) {
+            return Some(*option);
+        }
+        if let Ok(number) = reply.parse::<usize>() {
+            return number.checked_sub(1).and_then(|i| Self::ALL.get(i)).copied();
+        }
+
+        let normalized = super::food_lookup::normalize(&reply);
+        let words: Vec<&str> = normalized.split_whitespace().collect();
+        let has = |stems: &[&str]| words.iter().any(|w| stems.iter().any(|stem| w.starts_with(stem)));
+        let up = has(&["artir", "yukselt", "raise"]) || reply.contains('+');
+        let down = has(&["azalt", "dusur", "lower"]) || reply.contains('-');
+
+        match (has(&["kalori", "calorie"]), has(&["su", "water"]), up, down) {
+            (true, false, true, false) => Some(GoalReviewOption::CalorieUp),
+            (true, false, false, true) => Some(GoalReviewOption::CalorieDown),
+            (false, true, true, false) => Some(GoalReviewOption::WaterUp),
+            (false, true, false, true) => Some(GoalReviewOption::WaterDown),
+            _ if matches!(words.as_slice(), ["koru"] | ["ayni"] | ["kalsin"] | ["keep"]) => Some(GoalReviewOption::Keep),
+            _ => None,
+        }
+    }
+
+    /// Goal to change and its new value; None for "keep" or when the limit is already reached
+    pub fn apply(&self, calorie_goal: i32, water_goal: i32) -> Option<(GoalKind, i32)> {
+        let (kind, current, step, (min, max)) = match self {
+            GoalReviewOption::Keep => return None,
+            GoalReviewOption::CalorieUp => (GoalKind::Calories, calorie_goal, CALORIE_STEP, CALORIE_RANGE),
+            GoalReviewOption::CalorieDown => (GoalKind::Calories, calorie_goal, -CALORIE_STEP, CALORIE_RANGE),
+            GoalReviewOption::WaterUp => (GoalKind::Water, water_goal, WATER_STEP_ML, WATER_RANGE),
+            GoalReviewOption::WaterDown => (GoalKind::Water, water_goal, -WATER_STEP_ML, WATER_RANGE),
+        };
+        let new_value = (current + step).clamp(min, max);
+        (new_value != current).then_some((kind, new_value))
+    }
+}
+
+/// Sunday review text; also works as a typed prompt for clients that can't show the list
+pub fn format_review(week: &WeekAdherence, calorie_goal: i32, water_goal: i32, units: UnitSystem) -> String {
+    let mut message = format!(
+        "🗓️ *Haftalık Hedef Değerlendirmesi*\n\n\
+         📝 Kayıt yapılan gün: {}/{}\n\
+         🎯 Kalori hedefinde ({} kcal ±%10): {} gün\n\
+         ⚠️ Hedef aşımı: {} gün\n\
+         💧 Su hedefi ({}) tutturulan: {} gün\n\
+         📊 Ortalama: {:.0} kcal • {}/gün\n\n\
+         Gelecek hafta için hedeflerini ne yapalım?\n",
+        week.logged_days,
+        week.days,
+        calorie_goal,
+        week.calorie_days,
+        week.over_days,
+        format_water(water_goal as i64, units),
+        week.water_days,
+        week.avg_calories,
+        format_water(week.avg_water_ml, units),
+    );
+    for (i, option) in GoalReviewOption::ALL.iter().enumerate() {
+        message.push_str(&format!("{}. {}\n", i + 1, option.title(units)));
+    }
+    message.push_str("\nSeç ya da numarasını yaz. Cevap vermezsen hedeflerin aynı kalır.");
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(calories: f64, water_ml: i64, meals: i64) -> DailyStats {
+        DailyStats {
+            user_phone: "905551112233".into(),
+            date: "2026-10-11".into(),
+            total_calories: calories,
+            total_water_ml: water_ml,
+            meals_count: meals,
+            water_logs_count: 0,
+            extra_totals: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_goal_review() {
+        let days = [day(1950.0, 2000, 3), day(2400.0, 1500, 4), day(0.0, 0, 0), day(1850.0, 2500, 2)];
+        let week = WeekAdherence::from_days(&days, 2000, 2000);
+        assert_eq!((week.logged_days, week.calorie_days, week.over_days, week.water_days), (3, 2, 1, 2));
+        assert_eq!(week.avg_calories.round(), 2067.0);
+        assert_eq!(week.avg_water_ml, 1500);
+
+        assert_eq!(GoalReviewOption::parse("goalrev_water_up"), Some(GoalReviewOption::WaterUp));
+        assert_eq!(GoalReviewOption::parse("1"), Some(GoalReviewOption::Keep));
+        assert_eq!(GoalReviewOption::parse("3"), Some(GoalReviewOption::CalorieDown));
+        assert_eq!(GoalReviewOption::parse("6"), None);
+        assert_eq!(GoalReviewOption::parse("kaloriyi artır"), Some(GoalReviewOption::CalorieUp));
+        assert_eq!(GoalReviewOption::parse("Su -250 ml"), Some(GoalReviewOption::WaterDown));
+        assert_eq!(GoalReviewOption::parse("koru"), Some(GoalReviewOption::Keep));
+        assert_eq!(GoalReviewOption::parse("tavuk pilav yedim"), None);
+
+        assert_eq!(GoalReviewOption::CalorieUp.apply(2000, 2000), Some((GoalKind::Calories, 2100)));
+        assert_eq!(GoalReviewOption::WaterDown.apply(2000, 600), Some((GoalKind::Water, 500)));
+        // Sınırda: değişiklik yok
+        assert_eq!(GoalReviewOption::WaterDown.apply(2000, 500), None);
+        assert_eq!(GoalReviewOption::Keep.apply(2000, 2000), None);
+
+        let text = format_review(&week, 2000, 2000, UnitSystem::Metric);
+        assert!(text.contains("Kayıt yapılan gün: 3/4"));
+        assert!(text.contains("4. Su +250 ml"));
+    }
+
+    #[test]
+    fn test_unanswered_review_expires() {
+        let now = chrono::Utc::now();
+        let mut user: crate::models::User = serde_json::from_value(serde_json::json!({
+            "phone_number": "905551112233",
+            "created_at": "2025-01-01T00:00:00Z",
+            "onboarding_completed": true,
+            "breakfast_reminder": true,
+            "lunch_reminder": true,
+            "dinner_reminder": true,
+            "water_reminder": true,
+            "opted_in": true,
+            "timezone": "Europe/Istanbul",
+            "is_active": true,
+            "coach_sharing": false,
+            "benchmark_opt_in": false,
+            "pending_command": GOAL_REVIEW_PENDING,
+        }))
+        .unwrap();
+        // Zamanı bilinmeyen (eski) bekleyen soru cevap beklemez
+        assert_eq!(user.awaiting_reply(now), None);
+
+        user.pending_command_at = Some(now - chrono::Duration::hours(1));
+        assert_eq!(user.awaiting_reply(now), Some(GOAL_REVIEW_PENDING));
+        // Pazar akşamı sorulan değerlendirmeye çarşamba yazılan "2" su kaydıdır
+        user.pending_command_at = Some(now - chrono::Duration::days(3));
+        assert_eq!(user.awaiting_reply(now), None);
+    }
+}
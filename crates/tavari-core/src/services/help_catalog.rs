@@ -0,0 +1,216 @@
+use super::food_lookup::normalize;
+
+/// One "how do I ..." answer. Keywords are word stems (Turkish-folded, matched as word
+/// prefixes so "hedefimi" matches "hedef"); a multi-word keyword needs all its stems.
+pub struct HelpTopic {
+    pub id: &'static str,
+    pub keywords: &'static [&'static str],
+    pub answer: &'static str,
+}
+
+pub const HELP_TOPICS: &[HelpTopic] = &[
+    HelpTopic {
+        id: "goals",
+        keywords: &["hedef", "kalori hedef", "su hedef", "kac kalori", "limit"],
+        answer: "🎯 *Hedef değiştirme*\n\n\
+                 kalorihedefi 2500 - Günlük kalori hedefi\n\
+                 suhedefi 3000 - Günlük su hedefi (ml)\n\n\
+                 Doğal dille de olur: \"kalori hedefim 1800\", \"su hedefim 2.5 litre\"",
+    },
+    HelpTopic {
+        id: "log_meal",
+        keywords: &["yemek kaydet", "ogun kaydet", "ogun ekle", "yemek ekle", "kalori hesap", "fotograf gonder", "yedigim"],
+        answer: "🍽️ *Öğün kaydetme*\n\n\
+                 • Yemeğin fotoğrafını gönder, kalorisini hesaplayayım\n\
+                 • Ya da yaz: \"tavuk göğsü ve salata yedim\"\n\
+                 • Kalorisini biliyorsan: ogun menemen 350",
+    },
+    HelpTopic {
+        id: "log_water",
+        keywords: &["su kaydet", "su ekle", "su gir", "su yaz", "ictigim su"],
+        answer: "💧 *Su kaydetme*\n\n\
+                 • su 250 veya \"250 ml içtim\"\n\
+                 • Kısayollar: 1 (200ml) / 2 (250ml) / 3 (500ml)\n\
+                 • \"1 bardak su\", \"yarım litre su\" da anlaşılır",
+    },
+    HelpTopic {
+        id: "water_reminder",
+        keywords: &["su hatirlat", "suaraligi", "hatirlatma sikl", "su bildirim", "cok sik"],
+        answer: "⏰ *Su hatırlatması*\n\n\
+                 suaraligi 3 - Son su kaydından 3 saat sonra hatırlat (1-6)\n\
+                 suaraligi kapat - Su hatırlatmalarını kapat",
+    },
+    HelpTopic {
+        id: "meal_times",
+        keywords: &["ogun saat", "kahvalti saat", "ogle saat", "aksam saat", "yemek saat", "hatirlatma saat"],
+        answer: "🕐 *Öğün saatleri*\n\n\
+                 saat kahvalti 09:00\n\
+                 saat ogle 13:00\n\
+                 saat aksam 19:30\n\n\
+                 Hatırlatmalar bu saatlere göre gelir. Hepsini baştan ayarlamak için: kurulum",
+    },
+    HelpTopic {
+        id: "silent_hours",
+        keywords: &["sessiz", "rahatsiz etme", "gece mesaj", "gece bildirim", "mesaj atma"],
+        answer: "🌙 *Sessiz saatler*\n\n\
+                 sessiz 23:00 07:00 - Bu saatler arasında mesaj atmam\n\
+                 Doğal dille: \"sessiz saat 22-8\"",
+    },
+    HelpTopic {
+        id: "reports",
+        keywords: &["rapor", "ozet", "toplam", "bugun ne kadar", "gecmis", "haftalik"],
+        answer: "📊 *Raporlar*\n\n\
+                 rapor - Bugünün özeti\n\
+                 haftalık - 7 günlük trend\n\
+                 geçmiş - Son öğünler\n\
+                 ozet saat 21:00 - Günlük özet saati (ozet kapat ile kapanır)\n\
+                 ozet icerik kalori su - Özette hangi bölümler olsun\n\
+                 ozet anlati - Özet, günün kısa bir anlatısı olarak gelsin",
+    },
+    HelpTopic {
+        id: "fix_meal",
+        keywords: &["duzelt", "yanlis ogun", "yanlis kayit", "ogun turu", "ara ogun olarak"],
+        answer: "✏️ *Son öğünü düzeltme*\n\n\
+                 duzelt ogle - Son öğünü öğle yemeği yap (kahvalti / ogle / aksam / ara)\n\
+                 detay - Son öğünün tam analizini gör",
+    },
+    HelpTopic {
+        id: "units",
+        keywords: &["birim", "pound", "litre yerine", "ml yerine"],
+        answer: "📏 *Birimler*\n\n\
+                 birim us - Su ons (oz), kilo pound (lb)\n\
+                 birim metrik - ml / kg",
+    },
+    HelpTopic {
+        id: "language",
+        keywords: &["ingilizce", "english", "language", "cevap dil"],
+        answer: "🌐 *AI dili*\n\n\
+                 dil en - Öğün analizleri ve tavsiyeler İngilizce\n\
+                 dil tr - Türkçe'ye dön",
+    },
+    HelpTopic {
+        id: "timezone",
+        keywords: &["saat dilim", "timezone", "yurt disi", "saat fark", "saatler yanlis"],
+        answer: "🌍 *Zaman dilimi*\n\n\
+                 timezone Europe/Istanbul\n\
+                 Örnek: timezone Europe/Berlin, timezone America/New_York",
+    },
+    HelpTopic {
+        id: "budget",
+        keywords: &["butce", "ogunlere dagit", "ogun pay", "dagilim"],
+        answer: "🎯 *Öğün bütçesi*\n\n\
+                 butce 25 35 30 10 - Kalori hedefini kahvaltı/öğle/akşam/ara öğüne böl (toplam 100)",
+    },
+    HelpTopic {
+        id: "email",
+        keywords: &["eposta", "e posta", "mail", "email"],
+        answer: "📧 *E-posta raporu*\n\n\
+                 eposta ornek@mail.com - Adres ekle (kod gönderilir)\n\
+                 eposta kod 123456 - Onayla\n\
+                 eposta haftalik / aylik / ikisi - Sıklık",
+    },
+    HelpTopic {
+        id: "shortcut",
+        keywords: &["kisayol", "kestirme", "widget", "iphone", "shortcut"],
+        answer: "📲 *Hızlı kayıt kısayolu*\n\n\
+                 kisayol - iOS Kestirmeler/widget için kişisel anahtar al\n\
+                 kisayol sil - Anahtarı iptal et",
+    },
+    HelpTopic {
+        id: "photos",
+        keywords: &["fotograflari sil", "fotolari sil", "resimleri sil", "fotograf sil"],
+        answer: "🗑️ *Fotoğrafları silme*\n\n\
+                 fotolari sil - Kayıtlı tüm yemek fotoğraflarını siler (onay ister)\n\
+                 Kalori kayıtların korunur.",
+    },
+    HelpTopic {
+        id: "coach",
+        keywords: &["diyetisyen", "koc", "paylas"],
+        answer: "🧑‍⚕️ *Diyetisyen paylaşımı*\n\n\
+                 koc - Bağlı koçun ve paylaşım durumun\n\
+                 Koç ataması ekip tarafından yapılır; haftalık özet sadece onay verirsen paylaşılır.",
+    },
+    HelpTopic {
+        id: "bug",
+        keywords: &["hata", "sorun", "calismiyor", "bozuk"],
+        answer: "🐞 *Sorun bildirme*\n\n\
+                 hata bildir [açıklama] - Son mesajlarınla birlikte ekibe iletilir",
+    },
+];
+
+/// Words that mark a how-to question rather than a meal or a command
+const QUESTION_MARKERS: &[&str] = &[
+    "nasil", "nereden", "nerede", "ne yazmam", "ne yazmaliyim", "mumkun mu", "yapabilir miyim",
+    "edebilir miyim", "how do", "how to", "how can",
+];
+
+/// Longer messages are meal descriptions or chat, not help questions
+const MAX_QUESTION_CHARS: usize = 120;
+
+/// "nasıl hedef değiştiririm", "su hatırlatması çok sık, azaltabilir miyim?"
+pub fn looks_like_question(message: &str) -> bool {
+    if message.chars().count() > MAX_QUESTION_CHARS {
+        return false;
+    }
+    let text = format!(" {} ", normalize(message));
+    message.trim_end().ends_with('?') || QUESTION_MARKERS.iter().any(|m| text.contains(&format!(" {}", m)))
+}
+
+/// Best matching topic by keyword stems; ties go to the topic listed first
+pub fn search(message: &str) -> Option<&'static HelpTopic> {
+    let text = normalize(message);
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let matches = |keyword: &str| keyword.split_whitespace().all(|stem| words.iter().any(|w| w.starts_with(stem)));
+
+    HELP_TOPICS
+        .iter()
+        .map(|topic| {
+            let score: usize = topic
+                .keywords
+                .iter()
+                .filter(|k| matches(k))
+                .map(|k| k.split_whitespace().count())
+                .sum();
+            (topic, score)
+        })
+        .filter(|(_, score)| *score > 0)
+        .fold(None, |best: Option<(&HelpTopic, usize)>, (topic, score)| match best {
+            Some((_, best_score)) if best_score >= score => best,
+            _ => Some((topic, score)),
+        })
+        .map(|(topic, _)| topic)
+}
+
+pub fn find(id: &str) -> Option<&'static HelpTopic> {
+    HELP_TOPICS.iter().find(|topic| topic.id == id.trim())
+}
+
+/// "goals, log_meal, ..." for the AI intent prompt
+pub fn topic_ids() -> String {
+    HELP_TOPICS.iter().map(|topic| topic.id).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_help_search() {
+        assert!(looks_like_question("nasil hedef degistiririm"));
+        assert!(looks_like_question("Su hatırlatması çok sık, azaltabilir miyim?"));
+        assert!(!looks_like_question("tavuk göğsü ve salata yedim"));
+
+        let id = |message| search(message).map(|topic| topic.id);
+        assert_eq!(id("nasıl hedef değiştiririm"), Some("goals"));
+        assert_eq!(id("su hedefimi nasıl artırırım"), Some("goals"));
+        assert_eq!(id("su hatırlatması çok sık, azaltabilir miyim?"), Some("water_reminder"));
+        assert_eq!(id("kahvaltı saatimi nasıl değiştiririm"), Some("meal_times"));
+        assert_eq!(id("fotoğrafları nasıl silerim"), Some("photos"));
+        assert_eq!(id("analizler ingilizce gelebilir mi?"), Some("language"));
+        assert_eq!(id("saat dilimimi nasıl değiştiririm"), Some("timezone"));
+        assert_eq!(id("bugün hava nasıl"), None);
+
+        assert_eq!(find(" units").map(|topic| topic.id), Some("units"));
+        assert!(topic_ids().starts_with("goals, log_meal"));
+    }
+}
@@ -0,0 +1,166 @@
+use std::path::Path;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use tokio::io::AsyncWriteExt;
+
+/// Timeouts and pooling shared by every outbound HTTP client (Bird, OpenRouter, media, webhooks).
+/// Without them a hung upstream could stall a handler forever.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpSettings {
+    pub connect_timeout: Duration,
+    pub request_timeout: Duration,
+    /// Vision calls on free models regularly take 30s+, so AI gets its own limit
+    pub ai_request_timeout: Duration,
+    pub pool_idle_timeout: Duration,
+    pub pool_max_idle_per_host: usize,
+    /// Extra attempts for idempotent requests (media downloads)
+    pub max_retries: u32,
+    /// Inbound media larger than this is rejected instead of downloaded
+    pub media_max_bytes: u64,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(5),
+            request_timeout: Duration::from_secs(30),
+            ai_request_timeout: Duration::from_secs(90),
+            pool_idle_timeout: Duration::from_secs(90),
+            pool_max_idle_per_host: 16,
+            max_retries: 2,
+            media_max_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+impl HttpSettings {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let secs = |key: &str, default: Duration| {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|v| *v > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(default)
+        };
+
+        Self {
+            connect_timeout: secs("HTTP_CONNECT_TIMEOUT_SECS", defaults.connect_timeout),
+            request_timeout: secs("HTTP_REQUEST_TIMEOUT_SECS", defaults.request_timeout),
+            ai_request_timeout: secs("AI_REQUEST_TIMEOUT_SECS", defaults.ai_request_timeout),
+            pool_idle_timeout: secs("HTTP_POOL_IDLE_TIMEOUT_SECS", defaults.pool_idle_timeout),
+            pool_max_idle_per_host: std::env::var("HTTP_POOL_MAX_IDLE_PER_HOST")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.pool_max_idle_per_host),
+            max_retries: std::env::var("HTTP_MAX_RETRIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.max_retries),
+            media_max_bytes: std::env::var("MEDIA_MAX_MB")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|v| *v > 0)
+                .map(|mb| mb * 1024 * 1024)
+                .unwrap_or(defaults.media_max_bytes),
+        }
+    }
+
+    /// Settings are read once per process
+    pub fn global() -> &'static HttpSettings {
+        static SETTINGS: OnceLock<HttpSettings> = OnceLock::new();
+        SETTINGS.get_or_init(HttpSettings::from_env)
+    }
+
+    pub fn build_client(&self, request_timeout: Duration) -> reqwest::Client {
+        reqwest::Client::builder()
+            .connect_timeout(self.connect_timeout)
+            .timeout(request_timeout)
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .build()
+            .unwrap_or_else(|e| {
+                // Only fails if the TLS backend can't initialize; keep running without limits
+                log::error!("❌ Failed to build HTTP client ({}), using defaults", e);
+                reqwest::Client::new()
+            })
+    }
+}
+
+/// Process-wide client for Bird, media downloads, webhooks and email.
+/// `reqwest::Client` is reference counted, so clones share one connection pool.
+pub fn shared_client() -> reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT
+        .get_or_init(|| {
+            let settings = HttpSettings::global();
+            settings.build_client(settings.request_timeout)
+        })
+        .clone()
+}
+
+/// Client for AI calls (longer request timeout, separate pool)
+pub fn ai_client() -> reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT
+        .get_or_init(|| {
+            let settings = HttpSettings::global();
+            settings.build_client(settings.ai_request_timeout)
+        })
+        .clone()
+}
+
+/// Download refused because the body is over the configured limit (not worth retrying)
+#[derive(Debug)]
+pub struct MediaTooLarge {
+    pub limit_bytes: u64,
+}
+
+impl std::fmt::Display for MediaTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "media larger than {} bytes", self.limit_bytes)
+    }
+}
+
+impl std::error::Error for MediaTooLarge {}
+
+/// Stream a response body to `path` chunk by chunk, never holding more than one chunk in memory.
+/// Writes to `<path>.part`, syncs and renames on success, so a failed/oversized download (or a crash
+/// mid-download) never leaves a truncated file under the final name.
+pub async fn stream_to_file(mut response: reqwest::Response, path: &Path, max_bytes: u64) -> anyhow::Result<u64> {
+    if response.content_length().is_some_and(|len| len > max_bytes) {
+        return Err(MediaTooLarge { limit_bytes: max_bytes }.into());
+    }
+
+    let part_path = path.with_extension("part");
+    let result = async {
+        let mut file = tokio::fs::File::create(&part_path).await?;
+        let mut written: u64 = 0;
+        // Content-Length can be missing or wrong, so the limit is enforced on the actual bytes
+        while let Some(chunk) = response.chunk().await? {
+            written += chunk.len() as u64;
+            if written > max_bytes {
+                return Err(MediaTooLarge { limit_bytes: max_bytes }.into());
+            }
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+        // Rename'den önce diske yazılmış olsun; aksi halde çökmede boş/yarım dosya kalabilir
+        file.sync_all().await?;
+        Ok::<u64, anyhow::Error>(written)
+    }
+    .await;
+
+    match result {
+        Ok(written) => {
+            tokio::fs::rename(&part_path, path).await?;
+            Ok(written)
+        }
+        Err(e) => {
+            let _ = tokio::fs::remove_file(&part_path).await;
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use anyhow::Result;
+
+/// Image formats we can recognize from file contents
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Gif,
+    Webp,
+    Heic,
+    Unknown,
+}
+
+impl ImageFormat {
+    /// Detect format from magic bytes. File extensions are unreliable here:
+    /// everything we download is saved as `.jpg`, but iPhones often send HEIC.
+    pub fn sniff(bytes: &[u8]) -> Self {
+        if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            ImageFormat::Jpeg
+        } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            ImageFormat::Png
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            ImageFormat::Gif
+        } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+            ImageFormat::Webp
+        } else if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" && is_heif_brand(&bytes[8..12]) {
+            ImageFormat::Heic
+        } else {
+            ImageFormat::Unknown
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Png => "image/png",
+            ImageFormat::Gif => "image/gif",
+            ImageFormat::Webp => "image/webp",
+            ImageFormat::Heic => "image/heic",
+            // Eski davranış: bilinmeyen formatlar JPEG olarak gönderilir
+            ImageFormat::Unknown => "image/jpeg",
+        }
+    }
+
+    /// Formats vision models reliably accept as-is
+    fn is_widely_supported(&self) -> bool {
+        matches!(self, ImageFormat::Jpeg | ImageFormat::Png)
+    }
+}
+
+fn is_heif_brand(brand: &[u8]) -> bool {
+    matches!(
+        brand,
+        b"heic" | b"heix" | b"hevc" | b"hevx" | b"heim" | b"heis" | b"mif1" | b"msf1"
+    )
+}
+
+/// Prepare image bytes for a vision model: returns (mime_type, bytes).
+/// WEBP/HEIC are converted to JPEG when the matching feature is enabled.
+pub fn prepare_for_vision(bytes: Vec<u8>) -> Result<(&'static str, Vec<u8>)> {
+    let format = ImageFormat::sniff(&bytes);
+    log::debug!("🔎 Detected image format: {:?}", format);
+
+    if format.is_widely_supported() || format == ImageFormat::Unknown {
+        return Ok((format.mime_type(), bytes));
+    }
+
+    match convert_to_jpeg(format, &bytes) {
+        Ok(jpeg) => {
+            log::info!("🔄 Converted {:?} image to JPEG ({} -> {} bytes)", format, bytes.len(), jpeg.len());
+            Ok((ImageFormat::Jpeg.mime_type(), jpeg))
+        }
+        Err(e) if format == ImageFormat::Heic => {
+            // HEIC is not accepted by the vision models, sending it would only fail later
+            Err(e.context("HEIC image could not be converted"))
+        }
+        Err(e) => {
+            log::warn!("⚠️ Could not convert {:?} to JPEG, sending as-is: {}", format, e);
+            Ok((format.mime_type(), bytes))
+        }
+    }
+}
+
+/// Downscaled JPEG (longest side `max_px`) for list views; HEIC/WEBP go through the vision conversion first
+pub fn make_thumbnail(bytes: Vec<u8>, max_px: u32) -> Result<Vec<u8>> {
+    let (_, bytes) = prepare_for_vision(bytes)?;
+    resize_to_jpeg(&bytes, max_px)
+}
+
+#[cfg(feature = "image-convert")]
+fn resize_to_jpeg(bytes: &[u8], max_px: u32) -> Result<Vec<u8>> {
+    let image = image::load_from_memory(bytes)?;
+    encode_jpeg(image.thumbnail(max_px, max_px))
+}
+
+#[cfg(not(feature = "image-convert"))]
+fn resize_to_jpeg(_bytes: &[u8], _max_px: u32) -> Result<Vec<u8>> {
+    anyhow::bail!("thumbnails are not enabled in this build (image-convert feature)")
+}
+
+#[cfg(feature = "image-convert")]
+fn encode_jpeg(image: image::DynamicImage) -> Result<Vec<u8>> {
+    let mut out = std::io::Cursor::new(Vec::new());
+    image
+        .to_rgb8()
+        .write_to(&mut out, image::ImageFormat::Jpeg)?;
+    Ok(out.into_inner())
+}
+
+#[cfg_attr(not(feature = "image-convert"), allow(unused_variables))]
+fn convert_to_jpeg(format: ImageFormat, bytes: &[u8]) -> Result<Vec<u8>> {
+    match format {
+        #[cfg(feature = "image-convert")]
+        ImageFormat::Webp | ImageFormat::Gif => {
+            let image = image::load_from_memory(bytes)?;
+            encode_jpeg(image)
+        }
+        #[cfg(feature = "heic")]
+        ImageFormat::Heic => heic_to_jpeg(bytes),
+        _ => anyhow::bail!("conversion of {:?} is not enabled in this build", format),
+    }
+}
+
+#[cfg(feature = "heic")]
+fn heic_to_jpeg(bytes: &[u8]) -> Result<Vec<u8>> {
+    use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+    let lib_heif = LibHeif::new();
+    let ctx = HeifContext::read_from_bytes(bytes)?;
+    let handle = ctx.primary_image_handle()?;
+    let decoded = lib_heif.decode(&handle, ColorSpace::Rgb(RgbChroma::Rgb), None)?;
+
+    let planes = decoded.planes();
+    let plane = planes
+        .interleaved
+        .ok_or_else(|| anyhow::anyhow!("HEIC image has no interleaved RGB plane"))?;
+
+    // Rows may be padded (stride > width * 3)
+    let (width, height) = (plane.width, plane.height);
+    let row_len = width as usize * 3;
+    let mut rgb = Vec::with_capacity(row_len * height as usize);
+    for row in plane.data.chunks(plane.stride).take(height as usize) {
+        rgb.extend_from_slice(&row[..row_len]);
+    }
+
+    let buffer = image::RgbImage::from_raw(width, height, rgb)
+        .ok_or_else(|| anyhow::anyhow!("Invalid HEIC pixel buffer"))?;
+    encode_jpeg(image::DynamicImage::ImageRgb8(buffer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_formats() {
+        assert_eq!(ImageFormat::sniff(&[0xFF, 0xD8, 0xFF, 0xE0]), ImageFormat::Jpeg);
+        assert_eq!(ImageFormat::sniff(b"\x89PNG\r\n\x1a\n...."), ImageFormat::Png);
+        assert_eq!(ImageFormat::sniff(b"RIFF\x10\x00\x00\x00WEBPVP8 "), ImageFormat::Webp);
+        assert_eq!(ImageFormat::sniff(b"\x00\x00\x00\x18ftypheic\x00\x00"), ImageFormat::Heic);
+        assert_eq!(ImageFormat::sniff(b"\x00\x00\x00\x18ftypmif1\x00\x00"), ImageFormat::Heic);
+        assert_eq!(ImageFormat::sniff(b"\x00\x00\x00\x18ftypisom\x00\x00"), ImageFormat::Unknown);
+        assert_eq!(ImageFormat::sniff(b""), ImageFormat::Unknown);
+    }
+
+    #[test]
+    fn test_jpeg_passes_through() {
+        let bytes = vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00];
+        let (mime, out) = prepare_for_vision(bytes.clone()).unwrap();
+        assert_eq!(mime, "image/jpeg");
+        assert_eq!(out, bytes);
+    }
+}
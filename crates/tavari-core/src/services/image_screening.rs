@@ -0,0 +1,131 @@
+use super::image_format::ImageFormat;
+
+/// Why an image was rejected before the vision call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonFoodKind {
+    /// Phone screenshot / chat forward: tall screen ratio with large flat UI areas
+    Screenshot,
+    /// Text-on-background memes, posters, promo cards
+    Graphic,
+    /// GIFs are forwarded stickers/memes, never meal photos
+    Animation,
+}
+
+impl NonFoodKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NonFoodKind::Screenshot => "screenshot",
+            NonFoodKind::Graphic => "graphic",
+            NonFoodKind::Animation => "animation",
+        }
+    }
+
+    pub fn reply(&self) -> &'static str {
+        match self {
+            NonFoodKind::Screenshot => {
+                "📱 Bu bir ekran görüntüsüne benziyor, tabağını göremedim 🙂\n\
+                 Yemeğin fotoğrafını çekip gönderirsen hemen hesaplarım."
+            }
+            NonFoodKind::Graphic => {
+                "🖼️ Güzel paylaşım ama bunun kalorisi yok sanırım 😄\n\
+                 Öğününün fotoğrafını gönder ya da yazarak kaydet (örn: ogun menemen 350)."
+            }
+            NonFoodKind::Animation => {
+                "🎞️ Hareketli görsellerin kalorisini sayamıyorum 😄\n\
+                 Yemeğinin fotoğrafını gönderirsen analiz ederim."
+            }
+        }
+    }
+}
+
+/// Phones are ~9:19.5; regular camera photos are at most 4:3 / 3:4 (and 9:16 for some)
+#[cfg(feature = "image-convert")]
+const SCREENSHOT_MIN_ASPECT: f64 = 1.9;
+
+/// Share of pixels in the few most common flat colors (UI backgrounds, poster fills)
+#[cfg(feature = "image-convert")]
+const SCREENSHOT_MIN_FLAT_SHARE: f64 = 0.5;
+#[cfg(feature = "image-convert")]
+const GRAPHIC_MIN_FLAT_SHARE: f64 = 0.8;
+
+/// Cheap local check before paying for a vision call. Only *obvious* non-food images are
+/// rejected; anything uncertain returns None and goes to the model as before.
+pub fn screen(bytes: &[u8]) -> Option<NonFoodKind> {
+    if ImageFormat::sniff(bytes) == ImageFormat::Gif {
+        return Some(NonFoodKind::Animation);
+    }
+    screen_pixels(bytes)
+}
+
+#[cfg(feature = "image-convert")]
+fn screen_pixels(bytes: &[u8]) -> Option<NonFoodKind> {
+    let img = image::load_from_memory(bytes).ok()?;
+    let (width, height) = (img.width() as f64, img.height() as f64);
+    if width == 0.0 || height == 0.0 {
+        return None;
+    }
+    let aspect = width.max(height) / width.min(height);
+    let flat_share = flat_color_share(&img.thumbnail(64, 64).to_rgb8());
+
+    if aspect >= SCREENSHOT_MIN_ASPECT && flat_share >= SCREENSHOT_MIN_FLAT_SHARE {
+        Some(NonFoodKind::Screenshot)
+    } else if flat_share >= GRAPHIC_MIN_FLAT_SHARE {
+        Some(NonFoodKind::Graphic)
+    } else {
+        None
+    }
+}
+
+/// Without the image decoder only the format-based checks run
+#[cfg(not(feature = "image-convert"))]
+fn screen_pixels(_bytes: &[u8]) -> Option<NonFoodKind> {
+    None
+}
+
+/// Share of pixels covered by the 4 most common colors (quantized to 4 bits per channel).
+/// Camera photos spread over many shades; rendered UI and posters don't.
+#[cfg(feature = "image-convert")]
+fn flat_color_share(thumbnail: &image::RgbImage) -> f64 {
+    let mut counts = std::collections::HashMap::new();
+    for pixel in thumbnail.pixels() {
+        let [r, g, b] = pixel.0;
+        *counts.entry((r >> 4, g >> 4, b >> 4)).or_insert(0usize) += 1;
+    }
+
+    let mut sorted: Vec<usize> = counts.into_values().collect();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+    let total = (thumbnail.width() * thumbnail.height()).max(1) as f64;
+    sorted.iter().take(4).sum::<usize>() as f64 / total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screening() {
+        assert_eq!(screen(b"GIF89a\x01\x00\x01\x00"), Some(NonFoodKind::Animation));
+        assert_eq!(screen(b"not an image"), None);
+
+        #[cfg(feature = "image-convert")]
+        {
+            fn png(img: image::RgbImage) -> Vec<u8> {
+                let mut out = std::io::Cursor::new(Vec::new());
+                img.write_to(&mut out, image::ImageFormat::Png).unwrap();
+                out.into_inner()
+            }
+
+            // Beyaz arka plan + birkaç koyu "mesaj balonu" satırı, telefon oranında
+            let screenshot = image::RgbImage::from_fn(390, 844, |_, y| {
+                if y % 60 < 20 { image::Rgb([30, 30, 30]) } else { image::Rgb([255, 255, 255]) }
+            });
+            assert_eq!(screen(&png(screenshot)), Some(NonFoodKind::Screenshot));
+
+            // Fotoğraf benzeri: her piksel farklı ton
+            let photo = image::RgbImage::from_fn(400, 300, |x, y| {
+                image::Rgb([(x * 7 % 256) as u8, (y * 5 % 256) as u8, ((x + y) * 3 % 256) as u8])
+            });
+            assert_eq!(screen(&png(photo)), None);
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use anyhow::Result;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::image_format::make_thumbnail;
+
+/// Longest side of the admin list thumbnails (px)
+pub const THUMBNAIL_MAX_PX: u32 = 320;
+
+/// Thumbnails live next to the originals: `<image dir>/thumbs/<file name>`
+const THUMBNAIL_DIR: &str = "thumbs";
+
+/// Extension of files still being written (`http::stream_to_file`, `write_atomic`)
+pub const PARTIAL_EXTENSION: &str = "part";
+
+/// Partial files younger than this may belong to a download still running on another instance
+const PARTIAL_FILE_MIN_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// Probe file written by `ensure_writable`
+const WRITE_PROBE: &str = ".write_test";
+
+/// Root for files the bot writes (`DATA_DIR`). Defaults to `data` under the working directory,
+/// which is /app/data in the Docker image.
+pub fn data_dir() -> PathBuf {
+    std::env::var("DATA_DIR")
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("data"))
+}
+
+/// Meal photos on local disk (`DATA_DIR/images`, or `IMAGE_DIR` if set) plus their cached thumbnails
+#[derive(Debug, Clone)]
+pub struct ImageStore {
+    dir: PathBuf,
+}
+
+impl ImageStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn from_env() -> Self {
+        match std::env::var("IMAGE_DIR").ok().filter(|v| !v.trim().is_empty()) {
+            Some(dir) => Self::new(dir.trim()),
+            None => Self::new(data_dir().join("images")),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Create the directories and prove a file can be written there (startup check, so a
+    /// read-only or missing volume fails the boot instead of the first photo)
+    pub fn ensure_writable(&self) -> Result<()> {
+        use anyhow::Context;
+
+        fs::create_dir_all(self.dir.join(THUMBNAIL_DIR))
+            .with_context(|| format!("cannot create image directory {}", self.dir.display()))?;
+        let probe = self.dir.join(WRITE_PROBE);
+        write_atomic(&probe, b"ok").with_context(|| format!("image directory {} is not writable", self.dir.display()))?;
+        let _ = fs::remove_file(&probe);
+        Ok(())
+    }
+
+    /// Where a new inbound photo is saved
+    pub fn new_image_path(&self, message_id: &str, now: chrono::DateTime<chrono::Utc>) -> PathBuf {
+        self.dir.join(new_file_name(message_id, now))
+    }
+
+    /// Stored image by file name ("img_1700000000.jpg"); None for anything that could leave the directory
+    pub fn original_path(&self, file_name: &str) -> Option<PathBuf> {
+        let valid = !file_name.is_empty()
+            && !file_name.starts_with('.')
+            && file_name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+        valid.then(|| self.dir.join(file_name))
+    }
+
+    /// Cached thumbnail; images saved before thumbnails existed get one on first request
+    pub fn thumbnail(&self, file_name: &str) -> Result<Option<Vec<u8>>> {
+        let Some(original) = self.original_path(file_name) else {
+            return Ok(None);
+        };
+        if let Ok(bytes) = fs::read(thumbnail_path(&original)) {
+            return Ok(Some(bytes));
+        }
+        if !original.exists() {
+            return Ok(None);
+        }
+        create_thumbnail(&original).map(Some)
+    }
+}
+
+/// "img_<unix ms>_<message id suffix>.jpg": unique per inbound message, safe for `original_path`
+pub fn new_file_name(message_id: &str, now: chrono::DateTime<chrono::Utc>) -> String {
+    let id: Vec<char> = message_id.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    let suffix: String = id[id.len().saturating_sub(8)..].iter().collect();
+    if suffix.is_empty() {
+        format!("img_{}.jpg", now.timestamp_millis())
+    } else {
+        format!("img_{}_{}.jpg", now.timestamp_millis(), suffix)
+    }
+}
+
+/// Key of a stored image in `stored_images` (its file name inside the image directory)
+pub fn storage_key(image_path: &Path) -> Option<String> {
+    image_path.file_name().and_then(|name| name.to_str()).map(str::to_string)
+}
+
+/// Write to `<path>.part`, fsync, then rename: readers see the old file or the whole new one
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let part_path = path.with_extension(PARTIAL_EXTENSION);
+    let result = (|| {
+        let mut file = fs::File::create(&part_path)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        fs::rename(&part_path, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&part_path);
+    }
+    result
+}
+
+/// Delete `.part` files left behind by a crash mid-download (image dir and thumbnails).
+/// Returns how many were removed.
+pub fn remove_partial_files(dir: &Path) -> usize {
+    let now = SystemTime::now();
+    [dir.to_path_buf(), dir.join(THUMBNAIL_DIR)]
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == PARTIAL_EXTENSION))
+        .filter(|entry| {
+            let modified = entry.metadata().and_then(|m| m.modified()).unwrap_or(now);
+            now.duration_since(modified).unwrap_or_default() >= PARTIAL_FILE_MIN_AGE
+        })
+        .filter(|entry| match fs::remove_file(entry.path()) {
+            Ok(()) => {
+                log::info!("🧹 Removed partial file {}", entry.path().display());
+                true
+            }
+            Err(e) => {
+                log::warn!("⚠️ Could not remove partial file {}: {}", entry.path().display(), e);
+                false
+            }
+        })
+        .count()
+}
+
+pub fn thumbnail_path(image_path: &Path) -> PathBuf {
+    let dir = image_path.parent().unwrap_or_else(|| Path::new("."));
+    dir.join(THUMBNAIL_DIR).join(image_path.file_name().unwrap_or_default())
+}
+
+/// Write the thumbnail of a saved image and return its bytes
+pub fn create_thumbnail(image_path: &Path) -> Result<Vec<u8>> {
+    let thumbnail = make_thumbnail(fs::read(image_path)?, THUMBNAIL_MAX_PX)?;
+    let path = thumbnail_path(image_path);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    write_atomic(&path, &thumbnail)?;
+    log::debug!("🖼️ Thumbnail written: {} ({} bytes)", path.display(), thumbnail.len());
+    Ok(thumbnail)
+}
+
+/// Delete a stored image and its thumbnail; a missing file counts as deleted
+pub fn remove_image(image_path: &Path) -> std::io::Result<()> {
+    let ignore_missing = |result: std::io::Result<()>| match result {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        other => other,
+    };
+    ignore_missing(fs::remove_file(thumbnail_path(image_path)))?;
+    ignore_missing(fs::remove_file(image_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_store_paths() {
+        let store = ImageStore::new("/app/data/images");
+        assert_eq!(
+            store.new_image_path("msg-1", chrono::DateTime::from_timestamp_millis(5).unwrap()),
+            PathBuf::from("/app/data/images/img_5_msg1.jpg")
+        );
+        assert_eq!(store.original_path("img_1700000000.jpg"), Some(PathBuf::from("/app/data/images/img_1700000000.jpg")));
+        assert_eq!(store.original_path("../secrets.env"), None);
+        assert_eq!(store.original_path("a/b.jpg"), None);
+        assert_eq!(store.original_path(""), None);
+
+        assert_eq!(
+            thumbnail_path(Path::new("/app/data/images/img_1.jpg")),
+            PathBuf::from("/app/data/images/thumbs/img_1.jpg")
+        );
+
+        let now = chrono::DateTime::from_timestamp_millis(1_700_000_000_123).unwrap();
+        let name = new_file_name("0f3c1e2a-9b7d-4e51-a1c3-5d7e9f2b4c6a", now);
+        assert_eq!(name, "img_1700000000123_9f2b4c6a.jpg");
+        assert!(store.original_path(&name).is_some());
+        assert_eq!(new_file_name("../", now), "img_1700000000123.jpg");
+        assert_eq!(storage_key(Path::new("/app/data/images/img_1.jpg")).as_deref(), Some("img_1.jpg"));
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = std::env::temp_dir().join(format!("tavari_atomic_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("img_1.jpg");
+
+        ImageStore::new(&dir).ensure_writable().unwrap();
+        assert!(dir.join(THUMBNAIL_DIR).is_dir() && !dir.join(WRITE_PROBE).exists());
+
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        assert!(!path.with_extension(PARTIAL_EXTENSION).exists());
+
+        // Yeni .part dosyası başka bir instance'ın süren indirmesi olabilir, dokunulmaz
+        fs::write(dir.join("img_2.part"), b"half").unwrap();
+        assert_eq!(remove_partial_files(&dir), 0);
+        assert!(dir.join("img_2.part").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(feature = "image-convert")]
+    #[test]
+    fn test_create_and_remove_thumbnail() {
+        let dir = std::env::temp_dir().join(format!("tavari_thumbs_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let original = dir.join("img_1.png");
+        image::RgbImage::new(1200, 800).save(&original).unwrap();
+
+        let store = ImageStore::new(&dir);
+        let bytes = store.thumbnail("img_1.png").unwrap().unwrap();
+        let thumbnail = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (320, 213));
+        assert!(thumbnail_path(&original).exists());
+
+        remove_image(&original).unwrap();
+        assert!(!original.exists() && !thumbnail_path(&original).exists());
+        assert_eq!(store.thumbnail("img_1.png").unwrap(), None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,135 @@
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate};
+
+use super::notifier::Notifier;
+use super::Database;
+use crate::models::KpiSnapshot;
+
+/// Rough per-call prices used for the AI cost estimate (USD).
+/// Override with `AI_COST_PER_IMAGE_USD` / `AI_COST_PER_TEXT_USD` to match the configured model.
+const DEFAULT_COST_PER_IMAGE_USD: f64 = 0.002;
+const DEFAULT_COST_PER_TEXT_USD: f64 = 0.0005;
+
+fn cost_from_env(key: &str, default: f64) -> f64 {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+/// Every incoming text goes through at least one AI call (intent or meal analysis)
+pub fn estimate_ai_cost(image_analyses: i64, text_analyses: i64, per_image: f64, per_text: f64) -> f64 {
+    image_analyses as f64 * per_image + text_analyses as f64 * per_text
+}
+
+/// Monday of the last fully completed week before `today`
+pub fn previous_week_start(today: NaiveDate) -> NaiveDate {
+    let this_monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    this_monday - Duration::days(7)
+}
+
+pub async fn build_weekly_snapshot(db: &Database, week_start: NaiveDate) -> Result<KpiSnapshot> {
+    let mut snapshot = db.get_kpi_counts(week_start).await?;
+    snapshot.ai_cost_estimate_usd = estimate_ai_cost(
+        snapshot.image_analyses,
+        snapshot.text_analyses,
+        cost_from_env("AI_COST_PER_IMAGE_USD", DEFAULT_COST_PER_IMAGE_USD),
+        cost_from_env("AI_COST_PER_TEXT_USD", DEFAULT_COST_PER_TEXT_USD),
+    );
+    Ok(snapshot)
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn render_html(snapshot: &KpiSnapshot) -> String {
+    let week_end = snapshot.week_start + Duration::days(6);
+
+    let errors = if snapshot.top_errors.is_empty() {
+        "<p>Bu hafta kayıtlı hata yok. 🎉</p>".to_string()
+    } else {
+        let rows: String = snapshot
+            .top_errors
+            .iter()
+            .map(|(error, count)| format!("<tr><td>{}</td><td>{}</td></tr>", escape_html(error), count))
+            .collect();
+        format!("<table border=\"1\" cellpadding=\"6\" cellspacing=\"0\"><tr><th>Hata</th><th>Adet</th></tr>{}</table>", rows)
+    };
+
+    format!(
+        "<html><body style=\"font-family: sans-serif\">\
+         <h2>📊 Haftalık KPI Raporu</h2>\
+         <p>{} – {}</p>\
+         <table border=\"1\" cellpadding=\"6\" cellspacing=\"0\">\
+         <tr><td>Yeni kullanıcı</td><td>{}</td></tr>\
+         <tr><td>Aktif kullanıcı</td><td>{}</td></tr>\
+         <tr><td>Kaybedilen kullanıcı (churn)</td><td>{}</td></tr>\
+         <tr><td>İşlenen mesaj</td><td>{}</td></tr>\
+         <tr><td>Fotoğraf analizi</td><td>{}</td></tr>\
+         <tr><td>Metin analizi</td><td>{}</td></tr>\
+         <tr><td>Elenen yemek dışı görsel</td><td>{}</td></tr>\
+         <tr><td>Tahmini AI maliyeti</td><td>${:.2}</td></tr>\
+         </table>\
+         <h3>En sık hatalar</h3>{}\
+         </body></html>",
+        snapshot.week_start.format("%d.%m.%Y"),
+        week_end.format("%d.%m.%Y"),
+        snapshot.new_users,
+        snapshot.active_users,
+        snapshot.churned_users,
+        snapshot.messages_handled,
+        snapshot.image_analyses,
+        snapshot.text_analyses,
+        snapshot.non_food_images,
+        snapshot.ai_cost_estimate_usd,
+        errors
+    )
+}
+
+/// Build, archive and email last week's KPI report
+pub async fn send_weekly_report(db: &Database, notifier: &Notifier, today: NaiveDate) -> Result<()> {
+    let week_start = previous_week_start(today);
+    let snapshot = build_weekly_snapshot(db, week_start).await?;
+    db.store_kpi_snapshot(&snapshot).await?;
+    log::info!("📈 KPI snapshot archived for week of {}", week_start);
+
+    let subject = format!("Tavari haftalık KPI raporu ({})", week_start.format("%d.%m.%Y"));
+    notifier.notify_operators(&subject, &render_html(&snapshot)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weekly_report_rendering() {
+        // 2025-11-10 Pazartesi -> önceki hafta 2025-11-03'te başlar
+        let monday = NaiveDate::from_ymd_opt(2025, 11, 10).unwrap();
+        let sunday = NaiveDate::from_ymd_opt(2025, 11, 16).unwrap();
+        assert_eq!(previous_week_start(monday), NaiveDate::from_ymd_opt(2025, 11, 3).unwrap());
+        assert_eq!(previous_week_start(sunday), NaiveDate::from_ymd_opt(2025, 11, 3).unwrap());
+
+        assert!((estimate_ai_cost(100, 1000, 0.002, 0.0005) - 0.7).abs() < 1e-9);
+
+        let snapshot = KpiSnapshot {
+            week_start: NaiveDate::from_ymd_opt(2025, 11, 3).unwrap(),
+            new_users: 12,
+            active_users: 40,
+            churned_users: 3,
+            messages_handled: 950,
+            image_analyses: 100,
+            text_analyses: 1000,
+            non_food_images: 7,
+            ai_cost_estimate_usd: 0.7,
+            top_errors: vec![("Resim <analiz> edilemedi".to_string(), 4)],
+        };
+        let html = render_html(&snapshot);
+        assert!(html.contains("03.11.2025 – 09.11.2025"));
+        assert!(html.contains("$0.70"));
+        assert!(html.contains("Resim &lt;analiz&gt; edilemedi"));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::models::Language;
+
+/// Turkish-only letters; a few of them settle a short analysis on their own
+const TURKISH_LETTERS: &[char] = &['ç', 'ğ', 'ı', 'ö', 'ş', 'ü', 'Ç', 'Ğ', 'İ', 'Ö', 'Ş', 'Ü'];
+
+const TURKISH_WORDS: &[&str] = &[
+    "ve", "bir", "için", "icin", "ile", "çok", "cok", "bu", "daha", "olarak", "gibi", "yüksek", "düşük", "orta",
+    "sağlıklı", "saglikli", "öğün", "ogun", "yaklaşık", "bugün", "bugun", "hedef",
+];
+
+const ENGLISH_WORDS: &[&str] = &[
+    "the", "and", "with", "of", "is", "a", "an", "for", "your", "you", "high", "low", "medium", "healthy", "meal",
+    "about", "today", "goal", "portion", "balanced",
+];
+
+/// Letters/words needed before a guess is trusted; short answers ("Kalori: 350") stay undecided
+const MIN_SIGNAL: usize = 3;
+
+/// Field labels are always Turkish (they are parsed), so only the values after them are looked at
+fn strip_label(line: &str) -> &str {
+    match line.split_once(':') {
+        Some((label, value)) if label.chars().count() <= 20 && !label.chars().any(|c| c.is_ascii_digit()) => value,
+        _ => line,
+    }
+}
+
+/// Best guess of the language an AI answer is written in; None when there is too little text to tell
+pub fn detect(text: &str) -> Option<Language> {
+    let mut turkish = 0;
+    let mut english = 0;
+
+    for line in text.lines() {
+        let value = strip_label(line);
+        turkish += value.chars().filter(|c| TURKISH_LETTERS.contains(c)).count();
+        for word in value.split(|c: char| !c.is_alphabetic()).filter(|w| !w.is_empty()) {
+            let word = word.to_lowercase();
+            if TURKISH_WORDS.contains(&word.as_str()) {
+                turkish += 1;
+            } else if ENGLISH_WORDS.contains(&word.as_str()) {
+                english += 1;
+            }
+        }
+    }
+
+    if turkish >= MIN_SIGNAL && turkish > english * 2 {
+        Some(Language::Tr)
+    } else if english >= MIN_SIGNAL && english > turkish * 2 {
+        Some(Language::En)
+    } else {
+        None
+    }
+}
+
+/// The answer is clearly in another language than the user asked for
+pub fn is_mismatch(text: &str, expected: Language) -> bool {
+    matches!(detect(text), Some(found) if found != expected)
+}
+
+/// Line added to every AI prompt; the field labels stay Turkish because `parse_response` reads them
+pub fn prompt_instruction(language: Language) -> String {
+    match language {
+        Language::Tr => "CEVAP DİLİ: Türkçe yaz.\n".to_string(),
+        Language::En => "ANSWER LANGUAGE: Write every value in English. Keep the field labels \
+                         (Yemek:, Kalori:, Porsiyon:, Besin Değeri:, Sağlık Notu:) exactly as given.\n"
+            .to_string(),
+    }
+}
+
+/// Appended when the first answer came back in the wrong language
+pub fn retry_instruction(language: Language) -> String {
+    format!(
+        "IMPORTANT: Your previous answer was not in {}. Answer again, ONLY in {}, in the same format.",
+        language.prompt_name(),
+        language.prompt_name()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        let turkish = "Yemek: Izgara tavuk göğsü, pilav, salata\nKalori: 520\nSağlık Notu: Dengeli ve sağlıklı bir öğün.";
+        let english = "Yemek: Grilled chicken breast with rice and a salad\nKalori: 520\nSağlık Notu: A balanced and healthy meal.";
+
+        assert_eq!(detect(turkish), Some(Language::Tr));
+        // Türkçe etiketler İngilizce cevabı Türkçe saydırmamalı
+        assert_eq!(detect(english), Some(Language::En));
+        assert_eq!(detect("Kalori: 350"), None);
+
+        assert!(is_mismatch(turkish, Language::En));
+        assert!(!is_mismatch(english, Language::En));
+        assert!(!is_mismatch("Kalori: 350", Language::En));
+    }
+}
@@ -0,0 +1,208 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use std::future::Future;
+use std::time::Instant;
+
+use super::image_store::remove_image;
+use super::Database;
+use crate::models::{MaintenanceRun, MaintenanceStep};
+
+/// Window warnings older than this are never read again (`was_recently_warned` looks back 4h)
+const WINDOW_WARNING_RETENTION_DAYS: i64 = 2;
+
+/// Cached day narratives are only reused on the same day; a week is kept for support questions
+const DAY_NARRATIVE_RETENTION_DAYS: i64 = 7;
+
+/// Longest range the admin reminder variant report can ask for (`days` is clamped to 365)
+const REMINDER_VARIANT_RETENTION_DAYS: i64 = 365;
+
+/// Photos are attached to their meal seconds after the download; older unattached ones are orphans
+const UNATTACHED_IMAGE_GRACE_MINUTES: i64 = 60;
+
+/// Meals per `description_normalized` backfill query
+const NORMALIZE_BATCH: i64 = 1_000;
+
+/// Runs kept in `maintenance_runs`
+const RUNS_KEPT: i64 = 90;
+
+/// Autovacuum thresholds are tuned for big tables; small hot tables (users, reminder_log)
+/// can pile up dead rows between runs
+const VACUUM_MIN_DEAD_TUPLES: i64 = 1_000;
+const VACUUM_MIN_DEAD_RATIO: f64 = 0.2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaintenanceSettings {
+    /// WEBHOOK_PAYLOAD_RETENTION_DAYS (0 keeps payloads until the ring buffer drops them)
+    pub webhook_payload_retention_days: i64,
+}
+
+impl MaintenanceSettings {
+    pub fn from_env() -> Self {
+        Self {
+            webhook_payload_retention_days: std::env::var("WEBHOOK_PAYLOAD_RETENTION_DAYS")
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(30),
+        }
+    }
+}
+
+/// Dead tuples worth an explicit `VACUUM (ANALYZE)` tonight
+pub fn needs_vacuum(live_tuples: i64, dead_tuples: i64) -> bool {
+    dead_tuples >= VACUUM_MIN_DEAD_TUPLES
+        && dead_tuples as f64 >= (live_tuples + dead_tuples) as f64 * VACUUM_MIN_DEAD_RATIO
+}
+
+async fn timed<F>(steps: &mut Vec<MaintenanceStep>, name: &str, step: F)
+where
+    F: Future<Output = Result<Option<i64>>>,
+{
+    let started = Instant::now();
+    let result = step.await;
+    let duration_ms = started.elapsed().as_millis() as i64;
+
+    match &result {
+        Ok(affected) => log::info!("🧹 Maintenance '{}' done in {} ms ({:?})", name, duration_ms, affected),
+        Err(e) => log::error!("❌ Maintenance '{}' failed after {} ms: {}", name, duration_ms, e),
+    }
+    steps.push(MaintenanceStep {
+        name: name.to_string(),
+        duration_ms,
+        affected: result.as_ref().ok().copied().flatten(),
+        error: result.err().map(|e| e.to_string()),
+    });
+}
+
+/// Delete photos that never became a meal (crash mid-processing, analysis failed, meal deleted)
+/// together with their `stored_images` rows. Returns how many were removed.
+pub async fn purge_unattached_images(db: &Database) -> Result<i64> {
+    let cutoff = Utc::now() - Duration::minutes(UNATTACHED_IMAGE_GRACE_MINUTES);
+    let mut removed = 0;
+    for path in db.get_unattached_images(cutoff).await? {
+        match remove_image(std::path::Path::new(&path)) {
+            Ok(()) => {
+                db.release_image(&path).await?;
+                removed += 1;
+            }
+            Err(e) => log::warn!("⚠️ Could not remove unattached image {}: {}", path, e),
+        }
+    }
+    if removed > 0 {
+        log::info!("🧹 Removed {} unattached images", removed);
+    }
+    Ok(removed)
+}
+
+/// Nightly job: purge expired rows, vacuum bloated tables, refresh statistics.
+/// Steps are independent; a failing step is recorded and the rest still run.
+pub async fn run_nightly(db: &Database, settings: &MaintenanceSettings) -> MaintenanceRun {
+    let started_at = Utc::now();
+    let started = Instant::now();
+    let mut steps = Vec::new();
+
+    timed(&mut steps, "purge_email_verifications", async {
+        Ok(Some(db.purge_expired_email_verifications().await? as i64))
+    })
+    .await;
+
+    timed(&mut steps, "purge_window_warnings", async {
+        let cutoff = Utc::now() - Duration::days(WINDOW_WARNING_RETENTION_DAYS);
+        Ok(Some(db.purge_window_warnings_before(cutoff).await? as i64))
+    })
+    .await;
+
+    timed(&mut steps, "purge_reminder_variant_sends", async {
+        let cutoff = Utc::now() - Duration::days(REMINDER_VARIANT_RETENTION_DAYS);
+        Ok(Some(db.purge_reminder_variant_sends_before(cutoff).await? as i64))
+    })
+    .await;
+
+    timed(&mut steps, "purge_unattached_images", async { Ok(Some(purge_unattached_images(db).await?)) }).await;
+
+    timed(&mut steps, "normalize_meal_descriptions", async {
+        let mut updated = 0;
+        loop {
+            let batch = db.backfill_normalized_descriptions(NORMALIZE_BATCH).await?;
+            updated += batch as i64;
+            if batch < NORMALIZE_BATCH as u64 {
+                break;
+            }
+        }
+        Ok(Some(updated))
+    })
+    .await;
+
+    timed(&mut steps, "purge_day_narratives", async {
+        let cutoff = (Utc::now() - Duration::days(DAY_NARRATIVE_RETENTION_DAYS)).date_naive();
+        Ok(Some(db.purge_day_narratives_before(cutoff).await? as i64))
+    })
+    .await;
+
+    if settings.webhook_payload_retention_days > 0 {
+        timed(&mut steps, "purge_webhook_payloads", async {
+            let cutoff = Utc::now() - Duration::days(settings.webhook_payload_retention_days);
+            Ok(Some(db.purge_webhook_payloads_before(cutoff).await? as i64))
+        })
+        .await;
+    }
+
+    timed(&mut steps, "vacuum", async {
+        let mut vacuumed = 0;
+        for (table, live, dead) in db.get_table_tuple_stats().await? {
+            if needs_vacuum(live, dead) {
+                log::info!("🧹 VACUUM hint: {} has {} dead / {} live tuples", table, dead, live);
+                db.vacuum_analyze(&table).await?;
+                vacuumed += 1;
+            }
+        }
+        Ok(Some(vacuumed))
+    })
+    .await;
+
+    timed(&mut steps, "analyze", async {
+        db.analyze().await?;
+        Ok(None)
+    })
+    .await;
+
+    let run = MaintenanceRun {
+        started_at,
+        duration_ms: started.elapsed().as_millis() as i64,
+        steps,
+    };
+
+    if let Err(e) = db.record_maintenance_run(&run, RUNS_KEPT).await {
+        log::error!("❌ Failed to record maintenance run: {}", e);
+    }
+    log::info!("🧹 Nightly maintenance finished in {} ms", run.duration_ms);
+    run
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_needs_vacuum() {
+        assert!(needs_vacuum(2_000, 1_000));
+        // Çok ölü satır ama tablo büyük: autovacuum'a bırak
+        assert!(!needs_vacuum(1_000_000, 5_000));
+        // Oran yüksek ama mutlak sayı küçük
+        assert!(!needs_vacuum(10, 500));
+    }
+
+    #[test]
+    fn test_timed_records_failures() {
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let mut steps = Vec::new();
+        runtime.block_on(async {
+            timed(&mut steps, "ok", async { Ok(Some(3)) }).await;
+            timed(&mut steps, "broken", async { Err(anyhow::anyhow!("boom")) }).await;
+        });
+
+        assert_eq!(steps[0].affected, Some(3));
+        assert_eq!(steps[0].error, None);
+        assert_eq!(steps[1].affected, None);
+        assert_eq!(steps[1].error.as_deref(), Some("boom"));
+    }
+}
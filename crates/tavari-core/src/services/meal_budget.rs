@@ -0,0 +1,106 @@
+use crate::models::MealType;
+
+/// Share of the daily calorie goal per meal slot, in percent (sums to 100)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MealBudget {
+    pub breakfast: u8,
+    pub lunch: u8,
+    pub dinner: u8,
+    pub snack: u8,
+}
+
+impl Default for MealBudget {
+    /// Kullanıcı ayarlamadıysa: kahvaltı 25, öğle 35, akşam 30, ara öğün 10
+    fn default() -> Self {
+        Self { breakfast: 25, lunch: 35, dinner: 30, snack: 10 }
+    }
+}
+
+impl MealBudget {
+    /// Stored form `users.meal_budget`: "25,35,30,10"; None / invalid → default split
+    pub fn from_stored(stored: Option<&str>) -> Self {
+        stored.and_then(Self::parse).unwrap_or_default()
+    }
+
+    /// "25 35 30 10", "25,35,30,10" or "%25 %35 %30 %10" - four shares that add up to 100
+    pub fn parse(text: &str) -> Option<Self> {
+        let shares: Vec<u8> = text
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .map(|p| p.trim_matches('%'))
+            .filter(|p| !p.is_empty())
+            .map(|p| p.parse().ok())
+            .collect::<Option<_>>()?;
+
+        match shares.as_slice() {
+            [breakfast, lunch, dinner, snack]
+                if shares.iter().map(|s| *s as u32).sum::<u32>() == 100 =>
+            {
+                Some(Self { breakfast: *breakfast, lunch: *lunch, dinner: *dinner, snack: *snack })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn to_stored(&self) -> String {
+        format!("{},{},{},{}", self.breakfast, self.lunch, self.dinner, self.snack)
+    }
+
+    pub fn share(&self, meal_type: &MealType) -> u8 {
+        match meal_type {
+            MealType::Breakfast => self.breakfast,
+            MealType::Lunch => self.lunch,
+            MealType::Dinner => self.dinner,
+            MealType::Snack => self.snack,
+        }
+    }
+
+    pub fn slot_calories(&self, meal_type: &MealType, daily_goal: i32) -> f64 {
+        daily_goal as f64 * self.share(meal_type) as f64 / 100.0
+    }
+}
+
+/// Line appended to the meal confirmation: remaining budget of the slot, or a warning once it is blown
+pub fn format_slot_status(budget: &MealBudget, meal_type: &MealType, slot_total: f64, daily_goal: i32) -> String {
+    let slot_budget = budget.slot_calories(meal_type, daily_goal);
+    if slot_budget <= 0.0 {
+        return String::new();
+    }
+
+    if slot_total > slot_budget {
+        format!(
+            "\n⚠️ {} payını aştın: {:.0}/{:.0} kcal (+{:.0})\nGünün kalanında biraz daha hafif seçebilirsin.",
+            meal_type,
+            slot_total,
+            slot_budget,
+            slot_total - slot_budget
+        )
+    } else {
+        format!(
+            "\n🎯 {} bütçesi: {:.0}/{:.0} kcal ({:.0} kcal kaldı)",
+            meal_type,
+            slot_total,
+            slot_budget,
+            slot_budget - slot_total
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_parsing_and_status() {
+        assert_eq!(MealBudget::parse("30 30 30 10"), Some(MealBudget { breakfast: 30, lunch: 30, dinner: 30, snack: 10 }));
+        assert_eq!(MealBudget::parse("%20,%40,%30,%10").map(|b| b.lunch), Some(40));
+        assert_eq!(MealBudget::parse("30 30 30 30"), None);
+        assert_eq!(MealBudget::parse("50 50"), None);
+        assert_eq!(MealBudget::from_stored(Some("bozuk")), MealBudget::default());
+
+        let budget = MealBudget::default();
+        assert_eq!(budget.slot_calories(&MealType::Lunch, 2000), 700.0);
+        assert!(format_slot_status(&budget, &MealType::Lunch, 540.0, 2000).contains("160 kcal kaldı"));
+        assert!(format_slot_status(&budget, &MealType::Snack, 350.0, 2000).contains("+150"));
+        assert_eq!(format_slot_status(&MealBudget::parse("30 40 30 0").unwrap(), &MealType::Snack, 100.0, 2000), "");
+    }
+}
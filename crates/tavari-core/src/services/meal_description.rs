@@ -0,0 +1,109 @@
+use super::food_lookup::normalize;
+
+/// Filler words that don't change what was eaten ("2 adet yumurta" = "2 yumurta")
+const FILLER_WORDS: &[&str] = &["adet", "tane"];
+
+/// Unit spellings -> (canonical unit, multiplier to it)
+const UNITS: &[(&str, &str, f64)] = &[
+    ("g", "g", 1.0),
+    ("gr", "g", 1.0),
+    ("grm", "g", 1.0),
+    ("gram", "g", 1.0),
+    ("grams", "g", 1.0),
+    ("kg", "g", 1000.0),
+    ("kilo", "g", 1000.0),
+    ("kilogram", "g", 1000.0),
+    ("ml", "ml", 1.0),
+    ("cc", "ml", 1.0),
+    ("mililitre", "ml", 1.0),
+    ("l", "ml", 1000.0),
+    ("lt", "ml", 1000.0),
+    ("litre", "ml", 1000.0),
+    ("liter", "ml", 1000.0),
+];
+
+fn unit(word: &str) -> Option<(&'static str, f64)> {
+    UNITS.iter().find(|(spelling, _, _)| *spelling == word).map(|(_, unit, factor)| (*unit, *factor))
+}
+
+fn format_number(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Lowercase + Turkish folding, keeping decimal separators between digits ("1,5" -> "1.5")
+fn fold(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut folded = String::with_capacity(text.len());
+    for (i, c) in chars.iter().enumerate() {
+        let decimal = matches!(c, '.' | ',')
+            && i > 0
+            && chars[i - 1].is_ascii_digit()
+            && chars.get(i + 1).is_some_and(|next| next.is_ascii_digit());
+        if decimal {
+            folded.push('.');
+        } else {
+            folded.push_str(&normalize(&c.to_string()));
+        }
+    }
+    folded
+}
+
+/// "200gr" -> ("200", "gr"); words without a leading number stay whole
+fn split_glued_unit(word: &str) -> (Option<&str>, &str) {
+    let digits = word.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(word.len());
+    if digits == 0 || digits == word.len() {
+        return (None, word);
+    }
+    (Some(&word[..digits]), &word[digits..])
+}
+
+/// Canonical form of a meal description, stored in `meals.description_normalized` and used to
+/// match the same meal written differently: "Mercimek Çorbası, 0,3 LT" and "mercimek corbasi 300ml"
+/// both become "mercimek corbasi 300 ml".
+pub fn canonicalize(description: &str) -> String {
+    let mut words: Vec<String> = Vec::new();
+    for word in fold(description).split_whitespace() {
+        let (number, rest) = split_glued_unit(word);
+        if let Some(number) = number {
+            words.push(number.to_string());
+        }
+        if rest == "yarim" {
+            words.push("0.5".to_string());
+        } else if !FILLER_WORDS.contains(&rest) {
+            words.push(rest.to_string());
+        }
+    }
+
+    let mut canonical: Vec<String> = Vec::with_capacity(words.len());
+    for word in words {
+        // Only a unit after a number is a unit ("l" alone is just a letter)
+        let amount = canonical.last().and_then(|w| w.parse::<f64>().ok());
+        if let (Some((unit, factor)), Some(amount)) = (unit(&word), amount) {
+            canonical.pop();
+            canonical.push(format_number(amount * factor));
+            canonical.push(unit.to_string());
+        } else {
+            canonical.push(word);
+        }
+    }
+    canonical.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonicalize() {
+        assert_eq!(canonicalize("Mercimek Çorbası, 0,3 LT"), "mercimek corbasi 300 ml");
+        assert_eq!(canonicalize("mercimek corbasi 300ml"), "mercimek corbasi 300 ml");
+        assert_eq!(canonicalize("2 adet Yumurta + 1 dilim EKMEK"), "2 yumurta 1 dilim ekmek");
+        assert_eq!(canonicalize("İskender 250 gr"), "iskender 250 g");
+        assert_eq!(canonicalize("Tavuk 0.5kg"), "tavuk 500 g");
+        assert_eq!(canonicalize("yarım simit"), "0.5 simit");
+        // Sayısız birim harfi olduğu gibi kalır
+        assert_eq!(canonicalize("L beden pizza"), "l beden pizza");
+        assert_eq!(canonicalize("  "), "");
+    }
+}
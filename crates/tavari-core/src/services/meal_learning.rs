@@ -0,0 +1,97 @@
+use chrono::{NaiveTime, Timelike};
+
+use crate::models::{MealType, MealTypeCorrection};
+
+/// Corrections needed before a learned value overrides the configured behaviour
+pub const MIN_CORRECTIONS: usize = 2;
+
+/// How many recent corrections are considered (older habits fade out)
+pub const CORRECTION_WINDOW: i64 = 20;
+
+/// Per-user meal slots used by meal type detection
+#[derive(Debug, Clone, PartialEq)]
+pub struct MealSchedule {
+    pub breakfast: NaiveTime,
+    pub lunch: NaiveTime,
+    pub dinner: NaiveTime,
+    /// Kahvaltı -> öğle -> akşam sırası zorunlu mu? Kullanıcı öğün atlıyorsa (ara öğün olarak
+    /// algılanan öğünü sürekli ana öğüne düzeltiyorsa) kapatılır.
+    pub sequential: bool,
+}
+
+fn minutes(time: NaiveTime) -> u32 {
+    time.num_seconds_from_midnight() / 60
+}
+
+/// Configured time blended with the times the user corrected meals to this type.
+/// The configured time counts as one sample, so a single odd correction can't move it far.
+fn learned_time(configured: NaiveTime, corrected_minutes: &[u32]) -> NaiveTime {
+    if corrected_minutes.len() < MIN_CORRECTIONS {
+        return configured;
+    }
+
+    let total: u32 = minutes(configured) + corrected_minutes.iter().sum::<u32>();
+    let mean = total / (corrected_minutes.len() as u32 + 1);
+    NaiveTime::from_hms_opt(mean / 60, mean % 60, 0).unwrap_or(configured)
+}
+
+impl MealSchedule {
+    pub fn learn(configured: [NaiveTime; 3], corrections: &[MealTypeCorrection]) -> Self {
+        let times_for = |meal_type: MealType| -> Vec<u32> {
+            corrections
+                .iter()
+                .filter(|c| c.corrected == meal_type)
+                .map(|c| c.local_minutes)
+                .collect()
+        };
+
+        let skipped_sequence = corrections
+            .iter()
+            .filter(|c| c.detected == MealType::Snack && c.corrected != MealType::Snack)
+            .count();
+
+        Self {
+            breakfast: learned_time(configured[0], &times_for(MealType::Breakfast)),
+            lunch: learned_time(configured[1], &times_for(MealType::Lunch)),
+            dinner: learned_time(configured[2], &times_for(MealType::Dinner)),
+            sequential: skipped_sequence < MIN_CORRECTIONS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn correction(detected: MealType, corrected: MealType, time: &str) -> MealTypeCorrection {
+        MealTypeCorrection {
+            detected,
+            corrected,
+            local_minutes: minutes(NaiveTime::parse_from_str(time, "%H:%M").unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_learn_from_corrections() {
+        let t = |s| NaiveTime::parse_from_str(s, "%H:%M").unwrap();
+        let configured = [t("09:00"), t("13:00"), t("19:00")];
+
+        // Tek düzeltme: yapılandırılmış davranış korunur
+        let one = [correction(MealType::Snack, MealType::Lunch, "15:00")];
+        assert_eq!(
+            MealSchedule::learn(configured, &one),
+            MealSchedule { breakfast: t("09:00"), lunch: t("13:00"), dinner: t("19:00"), sequential: true }
+        );
+
+        // Geç öğle yemeği yiyen ve kahvaltıyı atlayan kullanıcı
+        let late_lunches = [
+            correction(MealType::Snack, MealType::Lunch, "15:00"),
+            correction(MealType::Snack, MealType::Lunch, "15:30"),
+            correction(MealType::Dinner, MealType::Lunch, "15:30"),
+        ];
+        let schedule = MealSchedule::learn(configured, &late_lunches);
+        assert_eq!(schedule.lunch, t("14:45"));
+        assert_eq!(schedule.breakfast, t("09:00"));
+        assert!(!schedule.sequential);
+    }
+}
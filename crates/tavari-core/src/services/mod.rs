@@ -0,0 +1,51 @@
+pub mod database;
+pub mod http; // Shared reqwest clients with timeouts and pooling
+pub mod conversation_log; // Batched, off-hot-path conversation logging
+pub mod user_cache; // get_user cache invalidated via Postgres LISTEN/NOTIFY
+pub mod openrouter; // OpenRouter AI service
+pub mod circuit_breaker; // Fail-fast guard for the AI provider
+pub mod ai_limiter; // Global cap on concurrent AI requests
+pub mod whatsapp;
+pub mod bird; // Bird.com WhatsApp Business API
+pub mod bird_error; // Typed Bird.com API errors (retry / drop / template)
+pub mod webhook_subscription; // Checks that Bird actually calls our webhook URL with our secret
+pub mod allowlist; // Closed beta: approved numbers only, everyone else joins the waitlist
+pub mod admin; // Admin dashboard service
+pub mod image_format; // Magic-byte sniffing + HEIC/WEBP conversion
+pub mod image_store; // Saved meal photos and their cached admin thumbnails
+pub mod image_screening; // Rejects obvious screenshots/memes before the vision call
+pub mod events; // Outbound event webhooks (HMAC-signed)
+pub mod nutrition_fields; // Deployment-specific tracked metrics (CUSTOM_NUTRITION_FIELDS)
+pub mod notifier; // Operator email notifications
+pub mod kpi; // Weekly operator KPI report
+pub mod telemetry; // Opt-in anonymous daily usage totals for the maintainers (TELEMETRY=true)
+pub mod usage_metrics; // Prometheus text for /admin/metrics, refreshed by a background collector
+pub mod email_report; // Weekly/monthly HTML report for users with a verified address
+pub mod benchmark; // Opt-in anonymous "insan ortalaması" comparison
+pub mod food_lookup; // Offline calorie table for common Turkish foods
+pub mod meal_description; // Canonical meal text (folded, units standardized) for matching the same meal
+pub mod language; // User's AI answer language: prompt line + wrong-language check
+pub mod help_catalog; // "nasıl ..." questions answered with the matching command instructions
+pub mod command_hints; // "Bunu mu demek istedin: rapor?" for mistyped commands
+pub mod meal_learning; // Per-user meal slots learned from meal type corrections
+pub mod archive; // Moves old conversations to cold storage
+pub mod maintenance; // Nightly purge / VACUUM / ANALYZE with step durations
+pub mod feedback; // Monthly in-chat NPS poll
+pub mod changelog; // "Yenilikler" announcements after a deploy
+pub mod calling_code; // Longest-prefix calling code lookup shared by country defaults and compliance profiles
+pub mod country_defaults; // Locale defaults (timezone, meal times, silent hours, language) for new users
+pub mod compliance; // Per-country quiet hours / marketing hours / daily caps for proactive messages
+pub mod units; // ml/kg <-> oz/lb for "birim us" users
+pub mod meal_budget; // Daily calorie goal split across meal slots
+pub mod snacks; // Snack frequency insights (`atistirma`, weekly report)
+pub mod summary_sections; // Per-user daily summary sections (`ozet icerik`)
+pub mod day_narrative; // Opt-in AI-written nightly summary (`ozet anlati`), cached per day
+pub mod goal_review; // Sunday evening "keep / raise / lower" review of calorie and water goals
+pub mod reminder_copy; // A/B wordings of meal and water reminders, picked per send
+pub mod settings_history; // Settings snapshots written by the users trigger; "what were they on date X"
+
+pub use database::Database;
+pub use openrouter::{OpenRouterService, UserIntent};
+pub use whatsapp::WhatsAppService;
+pub use bird::BirdComClient;
+pub use admin::AdminService;
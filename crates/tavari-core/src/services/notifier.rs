@@ -0,0 +1,88 @@
+use anyhow::Result;
+
+/// Operator notifications (reports, alerts) delivered by email.
+///
+/// Email goes through an HTTP email API (Resend-compatible JSON: `from`, `to`, `subject`, `html`),
+/// so no SMTP setup is needed:
+/// - `NOTIFIER_EMAIL_API_KEY` - API key (Bearer); notifier is disabled without it
+/// - `NOTIFIER_EMAIL_API_URL` - default `https://api.resend.com/emails`
+/// - `NOTIFIER_EMAIL_FROM` - sender address
+/// - `OPERATOR_EMAILS` - comma separated operator recipients
+pub struct Notifier {
+    api_url: String,
+    api_key: Option<String>,
+    from: String,
+    operator_emails: Vec<String>,
+    client: reqwest::Client,
+}
+
+impl Notifier {
+    pub fn new(api_url: String, api_key: Option<String>, from: String, operator_emails: Vec<String>) -> Self {
+        Self {
+            api_url,
+            api_key,
+            from,
+            operator_emails,
+            client: super::http::shared_client(),
+        }
+    }
+
+    pub fn from_env() -> Self {
+        let api_url = std::env::var("NOTIFIER_EMAIL_API_URL")
+            .unwrap_or_else(|_| "https://api.resend.com/emails".to_string());
+        let api_key = std::env::var("NOTIFIER_EMAIL_API_KEY").ok().filter(|k| !k.is_empty());
+        let from = std::env::var("NOTIFIER_EMAIL_FROM")
+            .unwrap_or_else(|_| "Tavari Bot <bot@localhost>".to_string());
+        let operator_emails = std::env::var("OPERATOR_EMAILS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|e| e.trim().to_string())
+            .filter(|e| !e.is_empty())
+            .collect();
+
+        Self::new(api_url, api_key, from, operator_emails)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.api_key.is_some()
+    }
+
+    pub async fn send_email(&self, to: &[String], subject: &str, html: &str) -> Result<()> {
+        let Some(api_key) = &self.api_key else {
+            log::debug!("📭 Notifier disabled, skipping email '{}'", subject);
+            return Ok(());
+        };
+        if to.is_empty() {
+            log::warn!("⚠️ No recipients for email '{}'", subject);
+            return Ok(());
+        }
+
+        let response = self
+            .client
+            .post(&self.api_url)
+            .bearer_auth(api_key)
+            .timeout(std::time::Duration::from_secs(15))
+            .json(&serde_json::json!({
+                "from": self.from,
+                "to": to,
+                "subject": subject,
+                "html": html,
+            }))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Email API error ({}): {}", status, error_text);
+        }
+
+        log::info!("📧 Email '{}' sent to {} recipient(s)", subject, to.len());
+        Ok(())
+    }
+
+    /// Email all operators (OPERATOR_EMAILS)
+    pub async fn notify_operators(&self, subject: &str, html: &str) -> Result<()> {
+        self.send_email(&self.operator_emails, subject, html).await
+    }
+}
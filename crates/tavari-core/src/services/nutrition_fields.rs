@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// Deployment-specific metric tracked next to calories (e.g. caffeine, sugar, sodium).
+///
+/// Configured with `CUSTOM_NUTRITION_FIELDS` as comma separated `key:Label:unit` entries:
+/// `CUSTOM_NUTRITION_FIELDS=caffeine:Kafein:mg,sugar:Şeker:g,sodium:Sodyum:mg`
+#[derive(Debug, Clone, PartialEq)]
+pub struct NutritionField {
+    pub key: String,
+    pub label: String,
+    pub unit: String,
+}
+
+impl NutritionField {
+    /// Line prefix the AI is asked to use, e.g. "Kafein (mg):"
+    fn prompt_prefix(&self) -> String {
+        format!("{} ({}):", self.label, self.unit)
+    }
+}
+
+pub fn parse_config(spec: &str) -> Vec<NutritionField> {
+    spec.split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.trim().splitn(3, ':');
+            let key = parts.next()?.trim();
+            let label = parts.next()?.trim();
+            let unit = parts.next()?.trim();
+
+            let valid_key = !key.is_empty()
+                && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            if !valid_key || label.is_empty() {
+                log::warn!("⚠️ Ignoring invalid custom nutrition field: '{}'", entry);
+                return None;
+            }
+
+            Some(NutritionField {
+                key: key.to_string(),
+                label: label.to_string(),
+                unit: unit.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Fields configured for this deployment (read once from the environment)
+pub fn configured() -> &'static [NutritionField] {
+    static FIELDS: OnceLock<Vec<NutritionField>> = OnceLock::new();
+    FIELDS.get_or_init(|| parse_config(&std::env::var("CUSTOM_NUTRITION_FIELDS").unwrap_or_default()))
+}
+
+/// Extra lines for the "CEVAP FORMATI" section of the AI prompts
+pub fn prompt_format_lines(fields: &[NutritionField]) -> String {
+    fields
+        .iter()
+        .map(|f| format!("{} [sadece sayı - birim YAZMA]\n", f.prompt_prefix()))
+        .collect()
+}
+
+/// If the AI response line is one of the custom fields, return (key, value)
+pub fn parse_line(fields: &[NutritionField], line: &str) -> Option<(String, f64)> {
+    let field = fields.iter().find(|f| {
+        line.starts_with(&f.prompt_prefix()) || line.starts_with(&format!("{}:", f.label))
+    })?;
+
+    let value_part = line.split_once(':').map(|(_, v)| v).unwrap_or("");
+    let number: String = value_part
+        .trim()
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+        .collect();
+    let value = number.replace(',', ".").parse::<f64>().unwrap_or(0.0);
+
+    Some((field.key.clone(), value))
+}
+
+/// Human readable lines for totals/values keyed by field key (unknown keys are skipped)
+pub fn format_values(fields: &[NutritionField], values: &BTreeMap<String, f64>) -> String {
+    fields
+        .iter()
+        .filter_map(|f| {
+            values
+                .get(&f.key)
+                .map(|v| format!("• {}: {:.0} {}\n", f.label, v, f.unit))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config_and_lines() {
+        let fields = parse_config("caffeine:Kafein:mg, sugar:Şeker:g,Bad Key:X:y,broken");
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[1].label, "Şeker");
+
+        assert_eq!(
+            parse_line(&fields, "Kafein (mg): 95"),
+            Some(("caffeine".to_string(), 95.0))
+        );
+        assert_eq!(
+            parse_line(&fields, "Şeker: 12,5 g"),
+            Some(("sugar".to_string(), 12.5))
+        );
+        assert_eq!(parse_line(&fields, "Kalori: 650"), None);
+
+        let mut totals = BTreeMap::new();
+        totals.insert("caffeine".to_string(), 190.0);
+        assert_eq!(format_values(&fields, &totals), "• Kafein: 190 mg\n");
+    }
+}
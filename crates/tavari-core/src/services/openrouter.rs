@@ -3,6 +3,16 @@ use base64::{engine::general_purpose, Engine};
 use serde::{Deserialize, Serialize};
 use std::fs;
 
+use super::image_format::prepare_for_vision;
+use super::circuit_breaker::{BreakerState, CircuitBreaker};
+use super::ai_limiter::AiLimiter;
+use super::nutrition_fields;
+use super::help_catalog;
+use super::language;
+use super::day_narrative::DayFacts;
+use crate::models::Language;
+use std::collections::BTreeMap;
+
 #[derive(Debug, Clone)]
 pub enum UserIntent {
     LogMeal(String),           // Yemek açıklaması
@@ -12,6 +22,7 @@ pub enum UserIntent {
     SetWaterGoal(i32),         // Su hedefi (ml)
     SetMealTime(String, String), // (meal_type, time) - "kahvalti", "09:00"
     SetSilentHours(String, String), // (start, end) - "23:00", "07:00"
+    HelpQuestion(String),      // Yardım konusu id - "goals"
     Unknown,                   // Belirsiz/normal konuşma
 }
 
@@ -67,12 +78,118 @@ struct MessageContent {
 pub struct CalorieInfo {
     pub calories: f64,
     pub description: String,
+    pub extras: BTreeMap<String, f64>,  // CUSTOM_NUTRITION_FIELDS değerleri
+    pub full_description: Option<String>,  // Sadece açıklama kısaltıldıysa: AI'ın tam analizi ("detay" komutu)
+    pub low_confidence: bool,  // AI kaloriyi veremedi, varsayılan değer kullanıldı
+}
+
+/// WhatsApp onay mesajında gösterilecek maksimum açıklama uzunluğu (karakter)
+pub const SHORT_DESCRIPTION_LIMIT: usize = 600;
+
+/// Uzun AI açıklamalarını kısalt: satır satır limite kadar al, sığmayan satırı kelime sınırından kes
+pub fn summarize_description(full: &str, limit: usize) -> String {
+    if full.chars().count() <= limit {
+        return full.to_string();
+    }
+
+    // "…" için bir karakter ayır
+    let budget = limit.saturating_sub(1);
+    let mut short = String::new();
+    for line in full.lines() {
+        // Satır sonundaki '\n' de yer kaplar
+        let used = short.chars().count();
+        if used + line.chars().count() < budget {
+            short.push_str(line);
+            short.push('\n');
+            continue;
+        }
+
+        // Kalan yer anlamlı bir parça için yeterliyse satırı kelime sınırından kes
+        let remaining = budget.saturating_sub(used);
+        if remaining >= 40 || short.is_empty() {
+            let cut: String = line.chars().take(remaining).collect();
+            match cut.rfind(' ') {
+                Some(pos) if pos > cut.len() / 2 => short.push_str(&cut[..pos]),
+                _ => short.push_str(&cut),
+            }
+        }
+        break;
+    }
+
+    format!("{}…", short.trim_end())
+}
+
+/// Default OpenAI-compatible endpoint
+pub const DEFAULT_AI_BASE_URL: &str = "https://openrouter.ai/api/v1";
+
+/// Where chat completions are sent: OpenRouter by default, or a self-hosted
+/// OpenAI-compatible gateway (LiteLLM, vLLM, ...) via `AI_BASE_URL`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AiGateway {
+    pub base_url: String,
+    /// Extra headers sent with every request (gateway auth, team/tenant routing...)
+    pub headers: Vec<(String, String)>,
+}
+
+impl Default for AiGateway {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_AI_BASE_URL.to_string(),
+            headers: Vec::new(),
+        }
+    }
+}
+
+impl AiGateway {
+    /// `AI_BASE_URL=http://litellm:4000/v1`, `AI_EXTRA_HEADERS=X-Team:tavari,X-Env:prod`
+    pub fn from_env() -> Self {
+        Self::parse(
+            std::env::var("AI_BASE_URL").ok().as_deref(),
+            std::env::var("AI_EXTRA_HEADERS").ok().as_deref(),
+        )
+    }
+
+    fn parse(base_url: Option<&str>, headers: Option<&str>) -> Self {
+        let base_url = base_url
+            .map(|url| url.trim().trim_end_matches('/'))
+            .filter(|url| !url.is_empty())
+            .unwrap_or(DEFAULT_AI_BASE_URL)
+            .to_string();
+
+        let headers = headers
+            .unwrap_or("")
+            .split(',')
+            .filter_map(|pair| {
+                let (name, value) = pair.split_once(':')?;
+                let name = name.trim();
+                if name.is_empty() {
+                    log::warn!("⚠️ Ignoring AI_EXTRA_HEADERS entry without a name: '{}'", pair);
+                    return None;
+                }
+                Some((name.to_string(), value.trim().to_string()))
+            })
+            .collect();
+
+        Self { base_url, headers }
+    }
+
+    fn chat_completions_url(&self) -> String {
+        format!("{}/chat/completions", self.base_url)
+    }
+
+    pub fn is_openrouter(&self) -> bool {
+        self.base_url == DEFAULT_AI_BASE_URL
+    }
 }
 
 pub struct OpenRouterService {
     api_key: String,
     model: String,
+    gateway: AiGateway,
     client: reqwest::Client,
+    breaker: CircuitBreaker,
+    limiter: &'static AiLimiter,
+    text_only: bool,
 }
 
 impl OpenRouterService {
@@ -80,28 +197,87 @@ impl OpenRouterService {
         Self {
             api_key,
             model,
-            client: reqwest::Client::new(),
+            gateway: AiGateway::default(),
+            client: super::http::ai_client(),
+            breaker: CircuitBreaker::new(),
+            limiter: AiLimiter::global(),
+            text_only: false,
+        }
+    }
+
+    /// Send requests to another OpenAI-compatible endpoint instead of OpenRouter
+    pub fn with_gateway(mut self, gateway: AiGateway) -> Self {
+        self.gateway = gateway;
+        self
+    }
+
+    /// Sadece metin modu: tüm AI çağrıları devre dışı (AI bütçesi bittiğinde operatör açar)
+    pub fn with_text_only(mut self, text_only: bool) -> Self {
+        self.text_only = text_only;
+        self
+    }
+
+    pub fn is_text_only(&self) -> bool {
+        self.text_only
+    }
+
+    /// AI sağlayıcısının durumu ("durum" komutu için)
+    pub fn breaker_state(&self) -> BreakerState {
+        self.breaker.state()
+    }
+
+    /// Chat completion isteği gönder; devre kesici açıksa hiç istek atmadan hata döner.
+    /// Bağlantı hataları, 429 ve 5xx yanıtlar devre kesicide hata olarak sayılır.
+    async fn post_chat(&self, request: &ChatRequest) -> Result<reqwest::Response> {
+        if self.text_only {
+            anyhow::bail!("AI features are disabled (TEXT_ONLY_MODE)");
         }
+        if !self.breaker.allow_request() {
+            anyhow::bail!("AI service temporarily unavailable (circuit breaker open)");
+        }
+
+        // Held until the response headers arrive (the model's work); the body is read after
+        let _permit = self.limiter.acquire().await?;
+
+        let mut builder = self
+            .client
+            .post(self.gateway.chat_completions_url())
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json");
+        if self.gateway.is_openrouter() {
+            builder = builder
+                .header("HTTP-Referer", "https://github.com/tavari-bot") // OpenRouter için gerekli
+                .header("X-Title", "Tavari Nutrition Bot"); // OpenRouter için opsiyonel
+        }
+        for (name, value) in &self.gateway.headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+
+        let result = builder.json(request).send().await;
+
+        match &result {
+            Ok(response) if response.status() == 429 || response.status().is_server_error() => {
+                self.breaker.record_failure();
+            }
+            Ok(_) => self.breaker.record_success(),
+            Err(_) => self.breaker.record_failure(),
+        }
+
+        Ok(result?)
     }
 
-    pub async fn analyze_food_image(&self, image_path: &str) -> Result<CalorieInfo> {
+    pub async fn analyze_food_image(&self, image_path: &str, language: Language) -> Result<CalorieInfo> {
         log::debug!("📸 Starting image analysis for: {}", image_path);
 
+        // Formatı dosya içeriğinden tespit et (uzantı güvenilir değil), gerekirse JPEG'e çevir
+        let (mime_type, image_data) = prepare_for_vision(fs::read(image_path)?)?;
+
         // Resmi base64'e çevir
-        let image_data = fs::read(image_path)?;
         let base64_image = general_purpose::STANDARD.encode(&image_data);
 
         log::debug!("📊 Image file size: {} bytes", image_data.len());
         log::debug!("🔄 Base64 encoded size: {} bytes", base64_image.len());
 
-        // Dosya uzantısından MIME type belirle
-        let mime_type = if image_path.ends_with(".png") {
-            "image/png"
-        } else {
-            // jpg, jpeg ve diğer formatlar için varsayılan
-            "image/jpeg"
-        };
-
         let data_url = format!("data:{};base64,{}", mime_type, base64_image);
         log::debug!("🖼️ Image data URL created: {}... (first 100 chars)", &data_url[..100.min(data_url.len())]);
 
@@ -110,7 +286,7 @@ impl OpenRouterService {
             content: vec![
                 ContentPart::Text {
                     content_type: "text".to_string(),
-                    text: "Sen bir gıda analizi uzmanısın. Bu yemek resmini analiz et ve kullanıcıya detaylı bilgi ver.\n\
+                    text: format!("Sen bir gıda analizi uzmanısın. Bu yemek resmini analiz et ve kullanıcıya detaylı bilgi ver.\n\
                            \n\
                            ANALİZ ADIMLARI:\n\
                            1. Yemekleri tanı (ana yemek, yan yemekler, içecekler)\n\
@@ -125,7 +301,9 @@ impl OpenRouterService {
                            Porsiyon: [büyüklük açıklaması]\n\
                            Besin Değeri: [protein/karbonhidrat/yağ dengesi]\n\
                            Sağlık Notu: [sağlıklı mı, iyileştirme önerileri]\n\
+                           {}\
                            \n\
+                           {}\
                            ÖNEMLİ:\n\
                            - Markdown kullanma (**, ###, __, vb. YASAK)\n\
                            - Sadece düz metin kullan\n\
@@ -138,7 +316,10 @@ impl OpenRouterService {
                            Kalori: 520\n\
                            Porsiyon: Orta büyüklük, yaklaşık 350g\n\
                            Besin Değeri: Yüksek protein, orta karbonhidrat, düşük yağ\n\
-                           Sağlık Notu: Dengeli ve sağlıklı bir öğün. Salata miktarını arttırabilirsiniz.".to_string(),
+                           Sağlık Notu: Dengeli ve sağlıklı bir öğün. Salata miktarını arttırabilirsiniz.",
+                        nutrition_fields::prompt_format_lines(nutrition_fields::configured()),
+                        language::prompt_instruction(language)
+                    ),
                 },
                 ContentPart::ImageUrl {
                     content_type: "image_url".to_string(),
@@ -158,16 +339,7 @@ impl OpenRouterService {
         log::info!("🤖 Sending request to OpenRouter with model: {}", self.model);
         log::debug!("📤 Request payload size: {} bytes", serde_json::to_string(&request)?.len());
 
-        let response = self
-            .client
-            .post("https://openrouter.ai/api/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .header("HTTP-Referer", "https://github.com/tavari-bot") // OpenRouter için gerekli
-            .header("X-Title", "Tavari Nutrition Bot") // OpenRouter için opsiyonel
-            .json(&request)
-            .send()
-            .await?;
+        let response = self.post_chat(&request).await?;
 
         let status = response.status();
         log::info!("📥 OpenRouter response status: {}", status);
@@ -210,13 +382,60 @@ impl OpenRouterService {
 
         let content = &chat_response.choices[0].message.content;
         log::info!("💬 OpenRouter response content: {}", content);
+        let content = self.ensure_language(request, content.clone(), language).await;
 
         // Parse the response
-        let calorie_info = self.parse_response(content)?;
+        let calorie_info = self.parse_response(&content)?;
 
         Ok(calorie_info)
     }
 
+    /// Tek seferlik ham istek: ilk seçeneğin metni
+    async fn chat_text(&self, request: &ChatRequest) -> Result<String> {
+        let response = self.post_chat(request).await?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("OpenRouter API error ({}): {}", status, response.text().await?);
+        }
+
+        let chat_response: ChatResponse = response.json().await?;
+        chat_response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .ok_or_else(|| anyhow::anyhow!("OpenRouter returned empty response"))
+    }
+
+    /// Cevap kullanıcının dilinde değilse bir kez daha, daha sıkı bir talimatla sor.
+    /// İkinci cevap olduğu gibi kullanılır; yeniden sorma başarısız olursa ilk cevap döner.
+    async fn ensure_language(&self, mut request: ChatRequest, content: String, language: Language) -> String {
+        if !language::is_mismatch(&content, language) {
+            return content;
+        }
+        log::warn!("🌐 AI answered in the wrong language (expected {}), re-prompting", language.as_str());
+
+        if let Some(message) = request.messages.first_mut() {
+            message.content.push(ContentPart::Text {
+                content_type: "text".to_string(),
+                text: language::retry_instruction(language),
+            });
+        }
+
+        match self.chat_text(&request).await {
+            Ok(retried) => {
+                if language::is_mismatch(&retried, language) {
+                    log::warn!("⚠️ AI answered in the wrong language again (expected {})", language.as_str());
+                }
+                retried
+            }
+            Err(e) => {
+                log::warn!("⚠️ Language re-prompt failed, keeping the first answer: {}", e);
+                content
+            }
+        }
+    }
+
     /// Markdown ve özel karakterleri temizle
     fn clean_markdown(&self, text: &str) -> String {
         text
@@ -269,6 +488,7 @@ impl OpenRouterService {
     fn parse_response(&self, response: &str) -> Result<CalorieInfo> {
         let mut calories = 0.0;
         let mut description = String::new();
+        let mut extras = BTreeMap::new();
 
         for line in response.lines() {
             let trimmed = line.trim();
@@ -277,6 +497,12 @@ impl OpenRouterService {
                 continue;
             }
 
+            // Özel besin alanları (örn: "Kafein (mg): 95") açıklamaya eklenmez, ayrı saklanır
+            if let Some((key, value)) = nutrition_fields::parse_line(nutrition_fields::configured(), trimmed) {
+                extras.insert(key, value);
+                continue;
+            }
+
             if trimmed.starts_with("Kalori:") {
                 let calorie_str = trimmed
                     .replace("Kalori:", "")
@@ -350,7 +576,8 @@ impl OpenRouterService {
             }
         }
 
-        if calories == 0.0 {
+        let low_confidence = calories == 0.0;
+        if low_confidence {
             // Eğer parse edilemezse, tüm metni açıklama olarak al ve ortalama bir değer ver
             description = response.to_string();
             log::warn!("⚠️ Could not parse calories from response, using default 400 kcal");
@@ -361,14 +588,19 @@ impl OpenRouterService {
 
         // Markdown ve özel karakterleri temizle
         let clean_description = self.clean_markdown(&description);
+        let short_description = summarize_description(&clean_description, SHORT_DESCRIPTION_LIMIT);
+        let full_description = (short_description != clean_description).then_some(clean_description);
 
         Ok(CalorieInfo {
             calories,
-            description: clean_description,
+            description: short_description,
+            extras,
+            full_description,
+            low_confidence,
         })
     }
 
-    pub async fn analyze_text_meal(&self, meal_description: &str) -> Result<CalorieInfo> {
+    pub async fn analyze_text_meal(&self, meal_description: &str, language: Language) -> Result<CalorieInfo> {
         log::info!("📝 Analyzing text meal description: {}", meal_description);
 
         let messages = vec![ChatMessage {
@@ -392,7 +624,9 @@ impl OpenRouterService {
                      Porsiyon: [büyüklük tahmini]\n\
                      Besin Değeri: [protein/karbonhidrat/yağ dengesi]\n\
                      Sağlık Notu: [kısa değerlendirme]\n\
+                     {}\
                      \n\
+                     {}\
                      ÖNEMLİ:\n\
                      - Markdown kullanma (**, ###, __, vb. YASAK)\n\
                      - Sadece düz metin kullan\n\
@@ -405,7 +639,9 @@ impl OpenRouterService {
                      Porsiyon: Orta büyüklük (tahmini 250g)\n\
                      Besin Değeri: Yüksek protein, düşük karbonhidrat\n\
                      Sağlık Notu: Hafif ve sağlıklı bir öğün",
-                    meal_description
+                    meal_description,
+                    nutrition_fields::prompt_format_lines(nutrition_fields::configured()),
+                    language::prompt_instruction(language)
                 ),
             }],
         }];
@@ -418,16 +654,7 @@ impl OpenRouterService {
 
         log::info!("🤖 Sending text meal analysis request to OpenRouter with model: {}", self.model);
 
-        let response = self
-            .client
-            .post("https://openrouter.ai/api/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .header("HTTP-Referer", "https://github.com/tavari-bot")
-            .header("X-Title", "Tavari Nutrition Bot")
-            .json(&request)
-            .send()
-            .await?;
+        let response = self.post_chat(&request).await?;
 
         let status = response.status();
         log::info!("📥 OpenRouter response status: {}", status);
@@ -470,14 +697,22 @@ impl OpenRouterService {
 
         let content = &chat_response.choices[0].message.content;
         log::info!("💬 OpenRouter text meal analysis: {}", content);
+        let content = self.ensure_language(request, content.clone(), language).await;
 
         // Parse the response
-        let calorie_info = self.parse_response(content)?;
+        let calorie_info = self.parse_response(&content)?;
 
         Ok(calorie_info)
     }
 
-    pub async fn get_nutrition_advice(&self, daily_calories: f64, daily_water: i64, water_goal: i32, meals_count: i64) -> Result<String> {
+    pub async fn get_nutrition_advice(
+        &self,
+        daily_calories: f64,
+        daily_water: i64,
+        water_goal: i32,
+        meals_count: i64,
+        language: Language,
+    ) -> Result<String> {
         log::info!("🤖 Requesting nutrition advice for {} kcal, {} ml water, {} meals", daily_calories, daily_water, meals_count);
 
         let messages = vec![ChatMessage {
@@ -485,20 +720,22 @@ impl OpenRouterService {
             content: vec![ContentPart::Text {
                 content_type: "text".to_string(),
                 text: format!(
-                    "You are a wellness coach. Provide brief encouraging feedback in Turkish about daily progress.\n\
+                    "You are a wellness coach. Provide brief encouraging feedback in {} about daily progress.\n\
                      \n\
                      Data: {} kcal, {} meals, {} ml water (goal: {} ml)\n\
                      \n\
-                     Write 3-4 short sentences in Turkish. Use actual numbers. Be positive. No markdown. Start sentences with emoji.\n\
+                     Write 3-4 short sentences in {}. Use actual numbers. Be positive. No markdown. Start sentences with emoji.\n\
                      \n\
                      Example:\n\
                      🎯 Bugun 1500 kcal aldiniz, gayet iyi.\n\
                      💧 Su hedefinize 700 ml kaldi.\n\
                      ✨ Devam edin!",
+                    language.prompt_name(),
                     daily_calories,
                     meals_count,
                     daily_water,
-                    water_goal
+                    water_goal,
+                    language.prompt_name()
                 ),
             }],
         }];
@@ -511,16 +748,7 @@ impl OpenRouterService {
 
         log::info!("📤 Sending request to OpenRouter with model: {}", self.model);
 
-        let response = self
-            .client
-            .post("https://openrouter.ai/api/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .header("HTTP-Referer", "https://github.com/tavari-bot")
-            .header("X-Title", "Tavari Nutrition Bot")
-            .json(&request)
-            .send()
-            .await?;
+        let response = self.post_chat(&request).await?;
 
         let status = response.status();
         log::info!("📥 OpenRouter response status: {}", status);
@@ -562,11 +790,45 @@ impl OpenRouterService {
         // Markdown ve özel karakterleri temizle
         let advice = &chat_response.choices[0].message.content;
         log::info!("✅ Nutrition advice content length: {} chars", advice.len());
-        let clean_advice = self.clean_markdown(advice);
+        let advice = self.ensure_language(request, advice.clone(), language).await;
+        let clean_advice = self.clean_markdown(&advice);
 
         Ok(clean_advice)
     }
 
+    /// Gece özetinin anlatı hali: günün yapılandırılmış verisinden kısa bir hikaye + tek bir öneri
+    pub async fn get_day_narrative(&self, facts: &DayFacts, language: Language) -> Result<String> {
+        log::info!("🤖 Requesting day narrative ({:.0} kcal, {} meals)", facts.calories, facts.meals.len());
+
+        let request = ChatRequest {
+            model: self.model.clone(),
+            messages: vec![ChatMessage {
+                role: "user".to_string(),
+                content: vec![ContentPart::Text {
+                    content_type: "text".to_string(),
+                    text: format!(
+                        "You are a warm nutrition coach writing a user's end-of-day recap in {}.\n\
+                         \n\
+                         Today's data:\n\
+                         {}\n\
+                         Write 3-5 short sentences in {} (max 600 characters) as a small story of the day: \
+                         what they ate, how hydration went compared to the goal, the logging streak if given. \
+                         End with exactly ONE concrete, actionable tip for tomorrow.\n\
+                         Use only the numbers and meals above, never invent food. No markdown, no lists, at most 3 emoji.",
+                        language.prompt_name(),
+                        facts.prompt_data(),
+                        language.prompt_name()
+                    ),
+                }],
+            }],
+            max_tokens: 300,
+        };
+
+        let narrative = self.chat_text(&request).await?;
+        let narrative = self.ensure_language(request, narrative, language).await;
+        Ok(self.clean_markdown(&narrative))
+    }
+
     /// Kullanıcının mesajını analiz edip ne yapmak istediğini belirle (doğal dil işleme)
     pub async fn detect_user_intent(&self, user_input: &str) -> Result<UserIntent> {
         log::info!("🧠 Detecting user intent for: {}", user_input);
@@ -589,6 +851,7 @@ impl OpenRouterService {
                         - 1 litre = 1000 ml\n\
                         - 2.5 litre = 2500 ml\n\
                         - 1 bardak = 200 ml\n\
+                        - 1 oz = 29.57 ml\n\
                      4. WATER: ve WATER_GOAL: sonrasına SADECE SAYI yaz (ml cinsinden, birim YAZMA)\n\
                      5. Yemek için: tüm açıklamayı MEAL: sonrasına ekle\n\
                      \n\
@@ -600,6 +863,7 @@ impl OpenRouterService {
                      MEAL_TIME:[kahvalti/ogle/aksam]:[HH:MM]\n\
                      SILENT:[HH:MM]:[HH:MM]\n\
                      COMMAND:[komut adı]\n\
+                     HELP:[konu] - kullanıcı bir şeyi NASIL yapacağını soruyorsa. Konular: {}\n\
                      UNKNOWN\n\
                      \n\
                      ÖRNEKLER (SADECE ok sonrası kısmı döndür):\n\
@@ -620,17 +884,23 @@ impl OpenRouterService {
                      \"2.5 litre su içtim\" -> WATER:2500\n\
                      \"3 litre su içtim\" -> WATER:3000\n\
                      \"4 lt su içtim\" -> WATER:4000\n\
+                     \"8 oz su içtim\" -> WATER:237\n\
+                     \"12 oz\" -> WATER:355\n\
                      \"kalori hedefim 2500\" -> CALORIE_GOAL:2500\n\
                      \"su hedefim 3 litre\" -> WATER_GOAL:3000\n\
                      \"su hedefim 2.5 litre\" -> WATER_GOAL:2500\n\
+                     \"su hedefim 64 oz\" -> WATER_GOAL:1893\n\
                      \"kahvaltı saatim 9\" -> MEAL_TIME:kahvalti:09:00\n\
                      \"öğle yemeği saatim 13\" -> MEAL_TIME:ogle:13:00\n\
                      \"sessiz saat 23-7\" -> SILENT:23:00:07:00\n\
                      \"rapor\" -> COMMAND:rapor\n\
+                     \"nasıl hedef değiştiririm\" -> HELP:goals\n\
+                     \"su hatırlatmalarını nasıl azaltırım\" -> HELP:water_reminder\n\
                      \"merhaba\" -> UNKNOWN\n\
                      \n\
                      DİKKAT: 1 lt = 1000 ml, 2 lt = 2000 ml. Litre değerini 1000 ile çarp!",
-                    user_input
+                    user_input,
+                    help_catalog::topic_ids()
                 ),
             }],
         }];
@@ -643,16 +913,7 @@ impl OpenRouterService {
 
         log::info!("📤 Sending intent detection request to OpenRouter");
 
-        let response = self
-            .client
-            .post("https://openrouter.ai/api/v1/chat/completions")
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .header("HTTP-Referer", "https://github.com/tavari-bot")
-            .header("X-Title", "Tavari Nutrition Bot")
-            .json(&request)
-            .send()
-            .await?;
+        let response = self.post_chat(&request).await?;
 
         let status = response.status();
         log::info!("📥 OpenRouter response status: {}", status);
@@ -680,7 +941,7 @@ impl OpenRouterService {
         // Remove Turkish prefixes like "Su kaydı: ", "Yemek kaydı: " etc.
         let prefixes = [
             "Yemek kaydı: ", "Su kaydı: ", "Kalori hedefi: ", "Su hedefi: ",
-            "Öğün saati: ", "Sessiz saat: ", "Komut: ", "Belirsiz: "
+            "Öğün saati: ", "Sessiz saat: ", "Komut: ", "Yardım: ", "Belirsiz: "
         ];
         for prefix in &prefixes {
             if response_text.starts_with(prefix) {
@@ -740,6 +1001,8 @@ impl OpenRouterService {
             }
         } else if let Some(cmd) = response_text.strip_prefix("COMMAND:") {
             Ok(UserIntent::RunCommand(cmd.trim().to_string()))
+        } else if let Some(topic) = response_text.strip_prefix("HELP:") {
+            Ok(UserIntent::HelpQuestion(topic.trim().to_string()))
         } else {
             log::warn!("⚠️ Could not parse AI intent, treating as Unknown: '{}'", original_response);
             Ok(UserIntent::Unknown)
@@ -768,6 +1031,51 @@ mod tests {
         assert!(info.description.contains("Açıklama"));
     }
 
+    #[test]
+    fn test_ai_gateway_parsing() {
+        assert_eq!(AiGateway::parse(None, None), AiGateway::default());
+        assert!(AiGateway::parse(Some(" "), None).is_openrouter());
+
+        let gateway = AiGateway::parse(Some("http://litellm:4000/v1/"), Some("X-Team: tavari, X-Env:prod,bozuk"));
+        assert_eq!(gateway.chat_completions_url(), "http://litellm:4000/v1/chat/completions");
+        assert!(!gateway.is_openrouter());
+        assert_eq!(
+            gateway.headers,
+            vec![("X-Team".to_string(), "tavari".to_string()), ("X-Env".to_string(), "prod".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_long_description_is_summarized() {
+        let service = OpenRouterService::new(
+            "test_key".to_string(),
+            "test_model".to_string(),
+        );
+
+        let long_note = "Sağlık Notu: ".to_string() + &"çok uzun açıklama ".repeat(60);
+        let response = format!("Yemek: Mantı\nKalori: 700\n{}", long_note);
+        let info = service.parse_response(&response).unwrap();
+
+        assert!(info.description.chars().count() <= SHORT_DESCRIPTION_LIMIT);
+        assert!(info.description.starts_with("Yemek: Mantı\nSağlık Notu: çok uzun"));
+        assert!(info.description.ends_with('…'));
+        assert!(info.full_description.unwrap().contains(long_note.trim()));
+
+        // Tek satır bile sığmıyorsa kelime sınırından kesilir
+        let short = summarize_description(&"kelime ".repeat(20), 30);
+        assert!(short.chars().count() <= 30);
+        assert!(short.ends_with("kelime…"));
+
+        // Satır bütçeyi tam dolduruyorsa taşma olmaz
+        let exact = summarize_description(&format!("{}\n{}", "a".repeat(29), "b".repeat(40)), 30);
+        assert_eq!(exact, format!("{}…", "a".repeat(29)));
+        let exact = summarize_description(&format!("{}\n{}", "a".repeat(28), "b".repeat(40)), 30);
+        assert_eq!(exact, format!("{}…", "a".repeat(28)));
+
+        let info = service.parse_response("Kalori: 300\nYemek: Elma").unwrap();
+        assert!(info.full_description.is_none());
+    }
+
     #[test]
     fn test_parse_response_with_comma() {
         let service = OpenRouterService::new(
@@ -0,0 +1,142 @@
+use rand::seq::SliceRandom;
+
+/// A reminder counts as converted when the user logs a meal (meal reminders) or water (water
+/// reminder) within this many hours of the send
+pub const CONVERSION_WINDOW_HOURS: i64 = 2;
+
+/// One wording of a reminder; `variant` is stored with every send (`reminder_variant_sends`)
+#[derive(Debug)]
+pub struct ReminderCopy {
+    pub variant: &'static str,
+    pub text: &'static str,
+}
+
+// "a" is the original wording in every list; add a variant to start a test, remove the loser to end it
+
+const BREAKFAST: &[ReminderCopy] = &[
+    ReminderCopy {
+        variant: "a",
+        text: "☀️ *Günaydın! Kahvaltı zamanı*\n\n\
+Ne yediğini kaydetmek ister misin?\n\
+Fotoğraf gönder veya yaz:\n\
+• \"yumurta ve peynir\"\n\
+• \"kahvaltı yaptım\"",
+    },
+    ReminderCopy {
+        variant: "b",
+        text: "☀️ *Günaydın!*\n\n\
+Kahvaltında ne var? Tabağının fotoğrafını çek, kalorisini ben hesaplayayım 📸\n\
+Ya da kısaca yaz: \"simit ve çay\"",
+    },
+];
+
+const LUNCH: &[ReminderCopy] = &[
+    ReminderCopy {
+        variant: "a",
+        text: "🌞 *Öğle yemeği vakti!*\n\n\
+Ne yediğini kaydetmek ister misin?\n\
+Fotoğraf gönder veya yaz:\n\
+• \"tavuk pilav ve salata\"\n\
+• \"öğle yemeği yaptım\"",
+    },
+    ReminderCopy {
+        variant: "b",
+        text: "🌞 *Öğle arası!*\n\n\
+Yemeğe başlamadan bir fotoğraf çekersen günü takip etmek 10 saniye sürer 📸\n\
+Ya da yaz: \"mercimek çorbası ve pilav\"",
+    },
+];
+
+const DINNER: &[ReminderCopy] = &[
+    ReminderCopy {
+        variant: "a",
+        text: "🌙 *Akşam yemeği zamanı!*\n\n\
+Ne yediğini kaydetmek ister misin?\n\
+Fotoğraf gönder veya yaz:\n\
+• \"balık ve zeytinyağlılar\"\n\
+• \"akşam yemeği yaptım\"",
+    },
+    ReminderCopy {
+        variant: "b",
+        text: "🌙 *Günün son öğünü*\n\n\
+Akşam yemeğini de ekleyelim, günlük özetin eksiksiz olsun 📊\n\
+Fotoğraf gönder ya da yaz: \"köfte ve salata\"",
+    },
+];
+
+const WATER: &[ReminderCopy] = &[
+    ReminderCopy {
+        variant: "a",
+        text: "💧 *Su içmeyi unutma!*\n\n\
+Hidrasyonun önemli! En az 1 bardak su iç.\n\
+Kaydetmek için yaz:\n\
+• \"su içtim\"\n\
+• \"250 ml\"  \n\
+• 1 (200ml) / 2 (250ml) / 3 (500ml)",
+    },
+    ReminderCopy {
+        variant: "b",
+        text: "💧 *Bir bardak su molası?*\n\n\
+İçtiysen tek tuşla kaydet:\n\
+• 1 (200ml) / 2 (250ml) / 3 (500ml)\n\
+• ya da \"su içtim\"",
+    },
+];
+
+/// Reminders whose wording is tested
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReminderKind {
+    Breakfast,
+    Lunch,
+    Dinner,
+    Water,
+}
+
+impl ReminderKind {
+    pub const ALL: [ReminderKind; 4] = [ReminderKind::Breakfast, ReminderKind::Lunch, ReminderKind::Dinner, ReminderKind::Water];
+
+    /// Same as `reminder_type` in reminder_log / conversation metadata
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReminderKind::Breakfast => "breakfast",
+            ReminderKind::Lunch => "lunch",
+            ReminderKind::Dinner => "dinner",
+            ReminderKind::Water => "water",
+        }
+    }
+
+    pub fn variants(&self) -> &'static [ReminderCopy] {
+        match self {
+            ReminderKind::Breakfast => BREAKFAST,
+            ReminderKind::Lunch => LUNCH,
+            ReminderKind::Dinner => DINNER,
+            ReminderKind::Water => WATER,
+        }
+    }
+
+    /// Random wording for this send (each send is assigned independently, not per user)
+    pub fn pick(&self) -> &'static ReminderCopy {
+        self.variants().choose(&mut rand::thread_rng()).unwrap_or(&self.variants()[0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reminder_copy_variants() {
+        for kind in ReminderKind::ALL {
+            let copies = kind.variants();
+            assert_eq!(copies.first().map(|c| c.variant), Some("a"), "{:?} must keep its original wording", kind);
+
+            let mut ids: Vec<&str> = copies.iter().map(|c| c.variant).collect();
+            ids.sort();
+            ids.dedup();
+            assert_eq!(ids.len(), copies.len(), "duplicate variant id in {:?}", kind);
+
+            let picked = kind.pick();
+            assert!(copies.iter().any(|c| std::ptr::eq(c, picked)));
+        }
+    }
+}
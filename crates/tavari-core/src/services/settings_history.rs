@@ -0,0 +1,51 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use super::Database;
+use crate::models::SettingsSnapshot;
+
+/// Settings right before this change: the snapshot with the changed values put back
+pub fn before_change(snapshot: &SettingsSnapshot) -> serde_json::Value {
+    let mut settings = snapshot.settings.clone();
+    if let (Some(settings), Some(changed)) = (settings.as_object_mut(), snapshot.changed.as_object()) {
+        for (key, change) in changed {
+            settings.insert(key.clone(), change.get("from").cloned().unwrap_or_default());
+        }
+    }
+    settings
+}
+
+/// "What were this user's settings last Tuesday?" The last snapshot before `at`; without one, the
+/// state before the first change after it; without any change since, today's settings.
+/// None for an unknown user.
+pub async fn settings_at(db: &Database, user_phone: &str, at: DateTime<Utc>) -> Result<Option<serde_json::Value>> {
+    if let Some(snapshot) = db.get_settings_snapshots(user_phone, Some(at), 1).await?.into_iter().next() {
+        return Ok(Some(snapshot.settings));
+    }
+    if let Some(snapshot) = db.get_first_settings_snapshot_after(user_phone, at).await? {
+        return Ok(Some(before_change(&snapshot)));
+    }
+    db.get_current_settings(user_phone).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_before_change() {
+        let snapshot = SettingsSnapshot {
+            created_at: Utc::now(),
+            settings: json!({ "lunch_time": "13:00", "timezone": "Europe/Berlin", "meal_budget": "25,35,30,10" }),
+            changed: json!({
+                "lunch_time": { "from": "12:30", "to": "13:00" },
+                "meal_budget": { "from": null, "to": "25,35,30,10" }
+            }),
+        };
+        assert_eq!(
+            before_change(&snapshot),
+            json!({ "lunch_time": "12:30", "timezone": "Europe/Berlin", "meal_budget": null })
+        );
+    }
+}
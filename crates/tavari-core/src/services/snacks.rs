@@ -0,0 +1,99 @@
+use crate::models::{MealHourBucket, MealType};
+
+/// `atistirma` komutu bu kadar günü kapsar; haftalık rapor 7 gün kullanır
+pub const SNACK_INSIGHT_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnackInsights {
+    pub snack_count: i64,
+    pub avg_calories: f64,
+    /// Snack share of all logged meals (0-100)
+    pub share_of_meals: f64,
+    /// Snack share of all logged calories (0-100)
+    pub share_of_calories: f64,
+    /// Local hours with the most snacks, most frequent first (max 3)
+    pub top_hours: Vec<(i32, i64)>,
+}
+
+impl SnackInsights {
+    pub fn from_buckets(buckets: &[MealHourBucket]) -> Self {
+        let snacks: Vec<&MealHourBucket> = buckets.iter().filter(|b| b.meal_type == MealType::Snack).collect();
+
+        let total_meals: i64 = buckets.iter().map(|b| b.count).sum();
+        let total_calories: f64 = buckets.iter().map(|b| b.total_calories).sum();
+        let snack_count: i64 = snacks.iter().map(|b| b.count).sum();
+        let snack_calories: f64 = snacks.iter().map(|b| b.total_calories).sum();
+
+        let mut top_hours: Vec<(i32, i64)> = snacks.iter().map(|b| (b.hour, b.count)).collect();
+        // En sık saat önce; eşitlikte günün erken saati
+        top_hours.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        top_hours.truncate(3);
+
+        let percent = |part: f64, whole: f64| if whole > 0.0 { part * 100.0 / whole } else { 0.0 };
+
+        Self {
+            snack_count,
+            avg_calories: if snack_count > 0 { snack_calories / snack_count as f64 } else { 0.0 },
+            share_of_meals: percent(snack_count as f64, total_meals as f64),
+            share_of_calories: percent(snack_calories, total_calories),
+            top_hours,
+        }
+    }
+}
+
+pub fn format_insights(insights: &SnackInsights, days: i64) -> String {
+    if insights.snack_count == 0 {
+        return format!("🍪 Son {} günde ara öğün kaydı yok.", days);
+    }
+
+    let hours = insights
+        .top_hours
+        .iter()
+        .map(|(hour, count)| format!("{:02}:00 ({}x)", hour, count))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "🍪 *Atıştırma Analizi* (son {} gün)\n\n\
+         Sayı: {} ara öğün (günde ~{:.1})\n\
+         Ortalama: {:.0} kcal\n\
+         Öğünlerin %{:.0}'i, kalorinin %{:.0}'i\n\
+         En sık saatler: {}",
+        days,
+        insights.snack_count,
+        insights.snack_count as f64 / days.max(1) as f64,
+        insights.avg_calories,
+        insights.share_of_meals,
+        insights.share_of_calories,
+        hours
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bucket(meal_type: MealType, hour: i32, count: i64, total_calories: f64) -> MealHourBucket {
+        MealHourBucket { meal_type, hour, count, total_calories }
+    }
+
+    #[test]
+    fn test_snack_insights() {
+        let buckets = vec![
+            bucket(MealType::Lunch, 13, 6, 3600.0),
+            bucket(MealType::Snack, 16, 3, 600.0),
+            bucket(MealType::Snack, 22, 3, 900.0),
+            bucket(MealType::Snack, 11, 1, 100.0),
+            bucket(MealType::Snack, 10, 1, 100.0),
+        ];
+        let insights = SnackInsights::from_buckets(&buckets);
+
+        assert_eq!(insights.snack_count, 8);
+        assert_eq!(insights.avg_calories, 212.5);
+        assert_eq!(insights.share_of_meals, 8.0 * 100.0 / 14.0);
+        assert_eq!(insights.top_hours, vec![(16, 3), (22, 3), (10, 1)]);
+        assert!(format_insights(&insights, 7).contains("16:00 (3x), 22:00 (3x), 10:00 (1x)"));
+
+        assert_eq!(format_insights(&SnackInsights::from_buckets(&[]), 7), "🍪 Son 7 günde ara öğün kaydı yok.");
+    }
+}
@@ -0,0 +1,124 @@
+use chrono::NaiveDate;
+
+use crate::models::{SummarySections, User};
+use crate::services::Database;
+
+/// How far back the streak is counted (longer streaks show as this many days)
+const STREAK_LOOKBACK_DAYS: i64 = 60;
+
+/// Section keywords for `ozet icerik`, in report order
+const SECTIONS: [(&str, &str); 5] = [
+    ("kalori", "🔥 Kalori"),
+    ("su", "💧 Su"),
+    ("besin", "🧪 Besin değerleri"),
+    ("seri", "📅 Kayıt serisi"),
+    ("ipucu", "💡 İpucu"),
+];
+
+fn section_key(word: &str) -> Option<&'static str> {
+    match word {
+        "kalori" | "calories" | "kcal" => Some("kalori"),
+        "su" | "water" => Some("su"),
+        "besin" | "makro" | "makrolar" | "macros" | "degerler" | "değerler" => Some("besin"),
+        "seri" | "streak" => Some("seri"),
+        "ipucu" | "ipuçları" | "ipuclari" | "tavsiye" | "motivasyon" | "tip" => Some("ipucu"),
+        _ => None,
+    }
+}
+
+fn is_enabled(sections: &SummarySections, key: &str) -> bool {
+    match key {
+        "kalori" => sections.calories,
+        "su" => sections.water,
+        "besin" => sections.macros,
+        "seri" => sections.streak,
+        _ => sections.tip,
+    }
+}
+
+/// "kalori su seri" -> only those sections; "hepsi" -> all. Unknown words -> None.
+pub fn parse_sections(text: &str) -> Option<SummarySections> {
+    let words: Vec<String> = text
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+
+    if words.is_empty() {
+        return None;
+    }
+    if words.len() == 1 && matches!(words[0].as_str(), "hepsi" | "tumu" | "tümü" | "all" | "sifirla" | "sıfırla") {
+        return Some(SummarySections::default());
+    }
+
+    let keys: Vec<&str> = words.iter().map(|w| section_key(w)).collect::<Option<_>>()?;
+    Some(SummarySections {
+        calories: keys.contains(&"kalori"),
+        water: keys.contains(&"su"),
+        macros: keys.contains(&"besin"),
+        streak: keys.contains(&"seri"),
+        tip: keys.contains(&"ipucu"),
+    })
+}
+
+/// Checklist shown by `ozet icerik`
+pub fn format_sections(sections: &SummarySections) -> String {
+    SECTIONS
+        .iter()
+        .map(|(key, label)| format!("{} {} ({})", if is_enabled(sections, key) { "✅" } else { "❌" }, label, key))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Consecutive days with at least one meal, counting back from today (or yesterday if
+/// nothing is logged yet today). `days` are local dates, newest first.
+pub fn logging_streak(today: NaiveDate, days: &[NaiveDate]) -> i64 {
+    let mut expected = match days.first() {
+        Some(first) if *first == today => today,
+        Some(first) if Some(*first) == today.pred_opt() => *first,
+        _ => return 0,
+    };
+
+    let mut streak = 0;
+    for day in days {
+        if *day != expected {
+            break;
+        }
+        streak += 1;
+        expected = match expected.pred_opt() {
+            Some(prev) => prev,
+            None => break,
+        };
+    }
+    streak
+}
+
+/// Streak for the daily report; only queried when the section is on, None on DB errors
+pub async fn report_streak(db: &Database, user: &User, today: NaiveDate) -> Option<i64> {
+    if !user.summary_sections.streak {
+        return None;
+    }
+    let days = db.get_meal_days(&user.phone_number, &user.timezone, STREAK_LOOKBACK_DAYS).await.ok()?;
+    Some(logging_streak(today, &days))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections_and_streak() {
+        let only = parse_sections("kalori, su seri").unwrap();
+        assert!(only.calories && only.water && only.streak);
+        assert!(!only.macros && !only.tip);
+        assert_eq!(parse_sections("hepsi"), Some(SummarySections::default()));
+        assert_eq!(parse_sections("kalori kilo"), None);
+        assert!(format_sections(&only).contains("❌ 💡 İpucu (ipucu)"));
+
+        let d = |day| NaiveDate::from_ymd_opt(2025, 3, day).unwrap();
+        assert_eq!(logging_streak(d(10), &[d(10), d(9), d(8), d(6)]), 3);
+        assert_eq!(logging_streak(d(10), &[d(9), d(8)]), 2);
+        assert_eq!(logging_streak(d(10), &[d(7)]), 0);
+        assert_eq!(logging_streak(d(10), &[]), 0);
+    }
+}
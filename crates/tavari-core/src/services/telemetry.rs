@@ -0,0 +1,181 @@
+//! Opt-in anonymous usage telemetry for the maintainers (`TELEMETRY=true`, off by default).
+//!
+//! Once a day one instance POSTs a JSON report of aggregate counts to `TELEMETRY_ENDPOINT`.
+//! It never contains phone numbers, names, message or meal text, e-mail addresses or anything
+//! else about a single user - only totals. Payload schema (version 1):
+//!
+//! ```json
+//! {
+//!   "schema_version": 1,
+//!   "installation_id": "5b0c1f0e-...",   // random UUID created once per database, not derived from anything
+//!   "version": "0.1.0",                   // bot version
+//!   "period_hours": 24,                   // activity counts cover this window
+//!   "users":    { "total": 120, "onboarded": 95, "active": 40 },
+//!   "activity": { "messages_received": 850, "meals_logged": 210, "photo_meals": 130, "water_logs": 300 },
+//!   "features": {                         // users with the feature turned on
+//!     "units_us": 3, "non_turkish_language": 7, "summary_narrative": 12, "email_reports": 9,
+//!     "coach_linked": 2, "benchmark_opt_in": 15, "custom_meal_budget": 4, "water_reminder": 60,
+//!     "daily_summary": 80
+//!   }
+//! }
+//! ```
+//!
+//! Fields are only ever added; a breaking change bumps `schema_version`.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use super::Database;
+use crate::models::TelemetryCounts;
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Activity counts cover the day before the report
+const PERIOD_HOURS: i64 = 24;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetrySettings {
+    pub enabled: bool,
+    pub endpoint: Option<String>,
+}
+
+impl TelemetrySettings {
+    /// TELEMETRY (default false) and TELEMETRY_ENDPOINT; enabled without an endpoint stays off
+    pub fn from_env() -> Self {
+        let enabled = std::env::var("TELEMETRY")
+            .map(|v| matches!(v.trim().to_lowercase().as_str(), "true" | "1" | "yes"))
+            .unwrap_or(false);
+        let endpoint = std::env::var("TELEMETRY_ENDPOINT").ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        Self { enabled, endpoint }
+    }
+
+    /// Where to send, or None when telemetry is off
+    pub fn active_endpoint(&self) -> Option<&str> {
+        if self.enabled {
+            self.endpoint.as_deref()
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TelemetryReport {
+    pub schema_version: u32,
+    pub installation_id: String,
+    pub version: &'static str,
+    pub period_hours: i64,
+    pub users: BTreeMap<&'static str, i64>,
+    pub activity: BTreeMap<&'static str, i64>,
+    pub features: BTreeMap<&'static str, i64>,
+}
+
+pub fn build_report(installation_id: String, counts: &TelemetryCounts) -> TelemetryReport {
+    TelemetryReport {
+        schema_version: SCHEMA_VERSION,
+        installation_id,
+        version: crate::VERSION,
+        period_hours: PERIOD_HOURS,
+        users: BTreeMap::from([
+            ("total", counts.users_total),
+            ("onboarded", counts.users_onboarded),
+            ("active", counts.active_users),
+        ]),
+        activity: BTreeMap::from([
+            ("messages_received", counts.messages_received),
+            ("meals_logged", counts.meals_logged),
+            ("photo_meals", counts.photo_meals),
+            ("water_logs", counts.water_logs),
+        ]),
+        features: counts.features.iter().map(|(name, users)| (*name, *users)).collect(),
+    }
+}
+
+/// Daily job: build and send the report; the day is claimed in the DB first so only one
+/// instance sends it, and released again if the report doesn't get through
+pub async fn send_daily_report(db: &Database, settings: &TelemetrySettings) -> Result<()> {
+    let Some(endpoint) = settings.active_endpoint() else {
+        return Ok(());
+    };
+    let now = chrono::Utc::now();
+    let today = now.date_naive();
+    if !db.claim_telemetry_report(today).await? {
+        log::debug!("📡 Telemetry report for today already sent by another instance");
+        return Ok(());
+    }
+
+    let sent = match post_report(db, endpoint, now).await {
+        Ok(sent) => sent,
+        Err(e) => {
+            let _ = db.release_telemetry_report(today).await;
+            return Err(e);
+        }
+    };
+    if !sent {
+        db.release_telemetry_report(today).await?;
+    }
+    Ok(())
+}
+
+/// True only when the endpoint answered with a 2xx
+async fn post_report(db: &Database, endpoint: &str, now: chrono::DateTime<chrono::Utc>) -> Result<bool> {
+    let counts = db.get_telemetry_counts(now - chrono::Duration::hours(PERIOD_HOURS)).await?;
+    let report = build_report(db.get_installation_id().await?, &counts);
+
+    let response = super::http::shared_client()
+        .post(endpoint)
+        .timeout(std::time::Duration::from_secs(10))
+        .json(&report)
+        .send()
+        .await;
+    match response {
+        Ok(response) if response.status().is_success() => {
+            log::info!("📡 Sent anonymous telemetry report to {}", endpoint);
+            Ok(true)
+        }
+        Ok(response) => {
+            log::warn!("⚠️ Telemetry endpoint {} returned {}", endpoint, response.status());
+            Ok(false)
+        }
+        Err(e) => {
+            log::warn!("⚠️ Telemetry report to {} failed: {}", endpoint, e);
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_telemetry_report() {
+        let counts = TelemetryCounts {
+            users_total: 120,
+            users_onboarded: 95,
+            active_users: 40,
+            messages_received: 850,
+            meals_logged: 210,
+            photo_meals: 130,
+            water_logs: 300,
+            features: vec![("units_us", 3), ("summary_narrative", 12)],
+        };
+        let json = serde_json::to_value(build_report("5b0c1f0e".into(), &counts)).unwrap();
+
+        assert_eq!(json["schema_version"], 1);
+        assert_eq!(json["users"]["active"], 40);
+        assert_eq!(json["activity"]["photo_meals"], 130);
+        assert_eq!(json["features"]["units_us"], 3);
+        assert_eq!(
+            json.as_object().unwrap().keys().collect::<Vec<_>>(),
+            ["activity", "features", "installation_id", "period_hours", "schema_version", "users", "version"]
+        );
+
+        // Kapalı varsayılan: endpoint olsa bile gönderilmez
+        let settings = TelemetrySettings { enabled: false, endpoint: Some("https://example.org/t".into()) };
+        assert_eq!(settings.active_endpoint(), None);
+        let settings = TelemetrySettings { enabled: true, endpoint: None };
+        assert_eq!(settings.active_endpoint(), None);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::models::UnitSystem;
+
+pub const ML_PER_FL_OZ: f64 = 29.5735;
+pub const LB_PER_KG: f64 = 2.20462;
+
+/// Stored ml → "250 ml" / "8.5 oz"
+pub fn format_water(ml: i64, units: UnitSystem) -> String {
+    match units {
+        UnitSystem::Metric => format!("{} ml", ml),
+        UnitSystem::Us => {
+            let oz = ml as f64 / ML_PER_FL_OZ;
+            // Küçük miktarlarda yarım ons farkı anlamlı, büyüklerde tam sayı yeterli
+            if oz.abs() < 20.0 {
+                format!("{:.1} oz", oz)
+            } else {
+                format!("{:.0} oz", oz)
+            }
+        }
+    }
+}
+
+/// "250", "250ml", "8oz", "1.5l" → ml. A bare number is read in the user's unit.
+pub fn parse_water_ml(text: &str, units: UnitSystem) -> Option<i32> {
+    let text = text.trim().to_lowercase().replace(',', ".");
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let value: f64 = number.parse().ok()?;
+
+    let ml = match unit.trim() {
+        "" if units == UnitSystem::Us => value * ML_PER_FL_OZ,
+        "" | "ml" => value,
+        "oz" | "floz" | "fl oz" => value * ML_PER_FL_OZ,
+        "l" | "lt" | "litre" => value * 1000.0,
+        _ => return None,
+    };
+    Some(ml.round() as i32)
+}
+
+/// Stored kg → "72.5 kg" / "159.8 lb".
+/// Kilo henüz takip edilmiyor; kilo alanı eklendiğinde gösterim buradan geçmeli.
+pub fn format_weight(kg: f64, units: UnitSystem) -> String {
+    match units {
+        UnitSystem::Metric => format!("{:.1} kg", kg),
+        UnitSystem::Us => format!("{:.1} lb", kg * LB_PER_KG),
+    }
+}
+
+/// User input in their unit ("160", "160lb", "72kg") → kg for storage
+pub fn parse_weight_kg(text: &str, units: UnitSystem) -> Option<f64> {
+    let text = text.trim().to_lowercase().replace(',', ".");
+    let (value, unit_is_lb) = if let Some(v) = text.strip_suffix("lbs").or_else(|| text.strip_suffix("lb")) {
+        (v, true)
+    } else if let Some(v) = text.strip_suffix("kg") {
+        (v, false)
+    } else {
+        (text.as_str(), units == UnitSystem::Us)
+    };
+    let value: f64 = value.trim().parse().ok()?;
+    Some(if unit_is_lb { value / LB_PER_KG } else { value })
+}
+
+/// Input range hint in the user's unit, e.g. for "50-3000 ml"
+pub fn water_range(min_ml: i64, max_ml: i64, units: UnitSystem) -> String {
+    match units {
+        UnitSystem::Metric => format!("{}-{} ml", min_ml, max_ml),
+        UnitSystem::Us => format!(
+            "{:.0}-{:.0} oz",
+            (min_ml as f64 / ML_PER_FL_OZ).ceil(),
+            (max_ml as f64 / ML_PER_FL_OZ).floor()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_conversion() {
+        assert_eq!(parse_water_ml("250", UnitSystem::Metric), Some(250));
+        assert_eq!(parse_water_ml("8", UnitSystem::Us), Some(237));
+        assert_eq!(parse_water_ml("250ml", UnitSystem::Us), Some(250));
+        assert_eq!(parse_water_ml("12oz", UnitSystem::Metric), Some(355));
+        assert_eq!(parse_water_ml("1,5l", UnitSystem::Us), Some(1500));
+        assert_eq!(parse_water_ml("bardak", UnitSystem::Metric), None);
+
+        assert_eq!(format_water(250, UnitSystem::Metric), "250 ml");
+        assert_eq!(format_water(237, UnitSystem::Us), "8.0 oz");
+        assert_eq!(format_water(2000, UnitSystem::Us), "68 oz");
+        assert_eq!(water_range(50, 3000, UnitSystem::Us), "2-101 oz");
+
+        assert_eq!(format_weight(72.5, UnitSystem::Us), "159.8 lb");
+        let kg = parse_weight_kg("160 lb", UnitSystem::Metric).unwrap();
+        assert!((kg - 72.57).abs() < 0.01);
+        assert_eq!(parse_weight_kg("72", UnitSystem::Metric), Some(72.0));
+    }
+}
@@ -0,0 +1,132 @@
+use chrono::{DateTime, Duration, Utc};
+use std::fmt::Write;
+use std::sync::{Arc, RwLock};
+
+use super::Database;
+use crate::models::UsageCounts;
+
+/// Counts cover this rolling window; gauges, since archiving removes old conversations
+const WINDOW_HOURS: i64 = 24;
+
+/// USAGE_METRICS_INTERVAL_SECS: how often the collector queries the usage tables
+const DEFAULT_INTERVAL_SECS: u64 = 60;
+
+/// Usage numbers for operators, refreshed in the background and scraped in Prometheus text
+/// format from `/admin/metrics`. There are no tenants or plans yet, so series are deployment-wide.
+#[derive(Default)]
+pub struct UsageMetrics {
+    latest: RwLock<Option<(DateTime<Utc>, UsageCounts)>>,
+}
+
+impl UsageMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Query the usage tables every `USAGE_METRICS_INTERVAL_SECS` (a failed run keeps the last numbers)
+    pub fn spawn_collector(self: &Arc<Self>, db: Arc<Database>) {
+        let interval_secs = std::env::var("USAGE_METRICS_INTERVAL_SECS")
+            .ok()
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_INTERVAL_SECS);
+        let metrics = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                let now = Utc::now();
+                match db.get_usage_counts(now - Duration::hours(WINDOW_HOURS)).await {
+                    Ok(counts) => {
+                        *metrics.latest.write().unwrap_or_else(|e| e.into_inner()) = Some((now, counts));
+                    }
+                    Err(e) => log::warn!("⚠️ Usage metrics collection failed: {}", e),
+                }
+            }
+        });
+        log::info!("📈 Usage metrics collector started (every {}s)", interval_secs);
+    }
+
+    pub fn render(&self) -> String {
+        let latest = self.latest.read().unwrap_or_else(|e| e.into_inner());
+        match latest.as_ref() {
+            Some((collected_at, counts)) => render_prometheus(counts, *collected_at),
+            // Henüz ilk toplama yapılmadı: boş gövde, scrape hata vermesin
+            None => String::new(),
+        }
+    }
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, series: &[(String, i64)]) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    for (labels, value) in series {
+        let _ = writeln!(out, "{}{} {}", name, labels, value);
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+pub fn render_prometheus(counts: &UsageCounts, collected_at: DateTime<Utc>) -> String {
+    let mut out = String::new();
+    gauge(
+        &mut out,
+        "tavari_messages_received_24h",
+        "Incoming WhatsApp messages in the last 24 hours",
+        &[(String::new(), counts.messages_received)],
+    );
+    gauge(
+        &mut out,
+        "tavari_ai_calls_24h",
+        "AI calls in the last 24 hours (image: analysed photos, text: incoming text messages, as in the KPI report)",
+        &[
+            ("{kind=\"image\"}".to_string(), counts.image_analyses),
+            ("{kind=\"text\"}".to_string(), counts.text_analyses),
+        ],
+    );
+    let sends: Vec<(String, i64)> = counts
+        .messages_sent
+        .iter()
+        .map(|(message_type, count)| (format!("{{type=\"{}\"}}", escape_label(message_type)), *count))
+        .collect();
+    gauge(&mut out, "tavari_messages_sent_24h", "Outgoing messages in the last 24 hours by message type", &sends);
+    gauge(
+        &mut out,
+        "tavari_active_users_24h",
+        "Users who sent at least one message in the last 24 hours",
+        &[(String::new(), counts.active_users)],
+    );
+    gauge(
+        &mut out,
+        "tavari_usage_collected_timestamp_seconds",
+        "When these usage numbers were collected",
+        &[(String::new(), collected_at.timestamp())],
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus() {
+        let counts = UsageCounts {
+            messages_received: 120,
+            active_users: 14,
+            image_analyses: 30,
+            text_analyses: 70,
+            messages_sent: vec![("reminder".into(), 40), ("text".into(), 95)],
+        };
+        let text = render_prometheus(&counts, DateTime::from_timestamp(1_700_000_000, 0).unwrap());
+
+        assert!(text.contains("# TYPE tavari_messages_received_24h gauge\ntavari_messages_received_24h 120\n"));
+        assert!(text.contains("tavari_ai_calls_24h{kind=\"image\"} 30\n"));
+        assert!(text.contains("tavari_messages_sent_24h{type=\"reminder\"} 40\n"));
+        assert!(text.ends_with("tavari_usage_collected_timestamp_seconds 1700000000\n"));
+        assert_eq!(UsageMetrics::new().render(), "");
+    }
+}
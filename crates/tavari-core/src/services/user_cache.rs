@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+
+use crate::models::User;
+
+/// Channel the `users_notify_change` trigger publishes changed phone numbers on
+pub const USER_CHANGED_CHANNEL: &str = "tavari_user_changed";
+
+/// Safety net: entries expire even if a notification is missed
+const TTL: Duration = Duration::from_secs(300);
+
+/// In-process cache for `Database::get_user` (looked up several times per incoming message).
+///
+/// Every write to `users` fires a Postgres NOTIFY (trigger in `init_tables`); each instance
+/// LISTENs and drops the entry, so settings changed on one replica are seen by the others.
+/// Reminder jobs don't need invalidation: they read users fresh on every tick.
+#[derive(Default)]
+pub struct UserCache {
+    entries: RwLock<HashMap<String, (Instant, User)>>,
+}
+
+impl UserCache {
+    pub fn get(&self, phone_number: &str) -> Option<User> {
+        self.get_at(phone_number, Instant::now())
+    }
+
+    fn get_at(&self, phone_number: &str, now: Instant) -> Option<User> {
+        let entries = self.entries.read().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(phone_number)
+            .filter(|(cached_at, _)| now.saturating_duration_since(*cached_at) < TTL)
+            .map(|(_, user)| user.clone())
+    }
+
+    pub fn insert(&self, user: &User) {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        entries.insert(user.phone_number.clone(), (Instant::now(), user.clone()));
+    }
+
+    pub fn invalidate(&self, phone_number: &str) {
+        let mut entries = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        entries.remove(phone_number);
+    }
+
+    pub fn clear(&self) {
+        self.entries.write().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+}
+
+/// Background task: drop cache entries on `USER_CHANGED_CHANNEL` notifications.
+/// If the listener connection drops, notifications may have been missed, so the whole cache is cleared.
+pub async fn listen_for_changes(pool: PgPool, cache: std::sync::Arc<UserCache>) {
+    loop {
+        let mut listener = match PgListener::connect_with(&pool).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::error!("❌ User cache listener could not connect: {}", e);
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                continue;
+            }
+        };
+        if let Err(e) = listener.listen(USER_CHANGED_CHANNEL).await {
+            log::error!("❌ LISTEN {} failed: {}", USER_CHANGED_CHANNEL, e);
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            continue;
+        }
+        log::info!("👂 Listening for user changes ({})", USER_CHANGED_CHANNEL);
+
+        loop {
+            match listener.try_recv().await {
+                Ok(Some(notification)) => {
+                    log::debug!("🔄 User {} changed, invalidating cache", notification.payload());
+                    cache.invalidate(notification.payload());
+                }
+                Ok(None) => {
+                    // Bağlantı koptu; sqlx yeniden bağlanır ama aradaki bildirimler kaçmış olabilir
+                    log::warn!("⚠️ User cache listener reconnecting, clearing cache");
+                    cache.clear();
+                }
+                Err(e) => {
+                    log::error!("❌ User cache listener error: {}", e);
+                    cache.clear();
+                    break;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(phone: &str) -> User {
+        serde_json::from_value(serde_json::json!({
+            "phone_number": phone,
+            "created_at": "2025-01-01T00:00:00Z",
+            "onboarding_completed": true,
+            "breakfast_reminder": true,
+            "lunch_reminder": true,
+            "dinner_reminder": true,
+            "water_reminder": true,
+            "opted_in": true,
+            "timezone": "Europe/Istanbul",
+            "is_active": true,
+            "coach_sharing": false,
+            "benchmark_opt_in": false,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cache_invalidation_and_ttl() {
+        let cache = UserCache::default();
+        cache.insert(&user("905551112233"));
+        assert!(cache.get("905551112233").is_some());
+
+        cache.invalidate("905551112233");
+        assert!(cache.get("905551112233").is_none());
+
+        cache.insert(&user("905551112233"));
+        assert!(cache.get_at("905551112233", Instant::now() + TTL).is_none());
+
+        cache.clear();
+        assert!(cache.get("905551112233").is_none());
+    }
+}
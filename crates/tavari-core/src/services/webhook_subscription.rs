@@ -0,0 +1,251 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use super::bird::{BirdComClient, EventFilter, WebhookSubscription};
+
+/// Inbound WhatsApp messages: the only event this bot needs
+pub const INBOUND_SERVICE: &str = "channels";
+pub const INBOUND_EVENT: &str = "whatsapp.inbound";
+
+/// Path of the webhook route (see `webhook::server::create_webhook_router`)
+pub const WEBHOOK_PATH: &str = "/webhook/whatsapp";
+
+/// Settings for checking the Bird webhook subscription. The check is off without
+/// `BIRD_ORGANIZATION_ID` and `PUBLIC_BASE_URL` (the URL Bird should call).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubscriptionSettings {
+    pub organization_id: String,
+    /// `PUBLIC_BASE_URL` + `/webhook/whatsapp`
+    pub webhook_url: String,
+    /// BIRD_WEBHOOK_SECRET
+    pub signing_key: Option<String>,
+    /// BIRD_WEBHOOK_AUTO_REGISTER=true: create the subscription at startup if it is missing
+    pub auto_register: bool,
+}
+
+impl SubscriptionSettings {
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let organization_id = var("BIRD_ORGANIZATION_ID")?;
+        let base_url = var("PUBLIC_BASE_URL")?;
+
+        Some(Self {
+            organization_id,
+            webhook_url: format!("{}{}", base_url.trim_end_matches('/'), WEBHOOK_PATH),
+            signing_key: var("BIRD_WEBHOOK_SECRET"),
+            auto_register: var("BIRD_WEBHOOK_AUTO_REGISTER").is_some_and(|v| v == "true" || v == "1"),
+        })
+    }
+}
+
+/// Why Bird may not be delivering messages to this deployment
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SubscriptionIssue {
+    /// No inbound subscription for this channel at all
+    Missing,
+    /// Subscriptions exist for the channel but call another URL (old domain, staging...)
+    OtherUrl { id: String, url: String },
+    Inactive { id: String, status: String },
+    /// Bird signs with a different key than BIRD_WEBHOOK_SECRET: every webhook gets 401
+    SigningKeyMismatch { id: String },
+    /// BIRD_WEBHOOK_SECRET is empty: signatures are not verified
+    NoSigningKey,
+}
+
+impl SubscriptionIssue {
+    pub fn describe(&self) -> String {
+        match self {
+            SubscriptionIssue::Missing => format!("no {} subscription for this channel", INBOUND_EVENT),
+            SubscriptionIssue::OtherUrl { id, url } => format!("subscription {} points to {}", id, url),
+            SubscriptionIssue::Inactive { id, status } => format!("subscription {} is {}", id, status),
+            SubscriptionIssue::SigningKeyMismatch { id } => {
+                format!("subscription {} signs with another key than BIRD_WEBHOOK_SECRET", id)
+            }
+            SubscriptionIssue::NoSigningKey => "BIRD_WEBHOOK_SECRET is not set, signatures are not verified".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SubscriptionReport {
+    pub webhook_url: String,
+    pub channel_id: String,
+    /// Id of the subscription calling this deployment, if any
+    pub subscription_id: Option<String>,
+    /// Created by this check (auto-register or admin request)
+    pub registered: bool,
+    pub issues: Vec<SubscriptionIssue>,
+}
+
+impl SubscriptionReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.iter().all(|issue| matches!(issue, SubscriptionIssue::NoSigningKey))
+    }
+}
+
+/// Subscription applies to our channel: no channel filter (whole workspace) or a matching one
+fn covers_channel(subscription: &WebhookSubscription, channel_id: &str) -> bool {
+    let channel_filters: Vec<&EventFilter> = subscription.event_filters.iter().filter(|f| f.key == "channelId").collect();
+    channel_filters.is_empty() || channel_filters.iter().any(|f| f.value == channel_id)
+}
+
+fn same_url(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/').eq_ignore_ascii_case(b.trim_end_matches('/'))
+}
+
+/// Compare Bird's subscriptions with what this deployment expects; returns the matching id and the issues
+pub fn check(
+    subscriptions: &[WebhookSubscription],
+    channel_id: &str,
+    webhook_url: &str,
+    signing_key: Option<&str>,
+) -> (Option<String>, Vec<SubscriptionIssue>) {
+    let mut issues = Vec::new();
+    if signing_key.is_none() {
+        issues.push(SubscriptionIssue::NoSigningKey);
+    }
+
+    let inbound: Vec<&WebhookSubscription> = subscriptions
+        .iter()
+        .filter(|s| s.event == INBOUND_EVENT && covers_channel(s, channel_id))
+        .collect();
+
+    let Some(ours) = inbound.iter().find(|s| same_url(&s.url, webhook_url)) else {
+        if inbound.is_empty() {
+            issues.push(SubscriptionIssue::Missing);
+        }
+        issues.extend(inbound.iter().map(|s| SubscriptionIssue::OtherUrl { id: s.id.clone(), url: s.url.clone() }));
+        return (None, issues);
+    };
+
+    if !ours.status.is_empty() && !ours.status.eq_ignore_ascii_case("active") {
+        issues.push(SubscriptionIssue::Inactive { id: ours.id.clone(), status: ours.status.clone() });
+    }
+    if let (Some(expected), Some(actual)) = (signing_key, ours.signing_key.as_deref()) {
+        if expected != actual {
+            issues.push(SubscriptionIssue::SigningKeyMismatch { id: ours.id.clone() });
+        }
+    }
+
+    (Some(ours.id.clone()), issues)
+}
+
+/// Fetch and check the subscription; with `register` a missing one is created for this channel
+pub async fn verify(client: &BirdComClient, settings: &SubscriptionSettings, register: bool) -> Result<SubscriptionReport> {
+    let subscriptions = client.list_webhook_subscriptions(&settings.organization_id).await?;
+    let (mut subscription_id, mut issues) =
+        check(&subscriptions, client.channel_id(), &settings.webhook_url, settings.signing_key.as_deref());
+
+    let mut registered = false;
+    if register && subscription_id.is_none() {
+        let created = client
+            .create_webhook_subscription(
+                &settings.organization_id,
+                &WebhookSubscription {
+                    service: INBOUND_SERVICE.to_string(),
+                    event: INBOUND_EVENT.to_string(),
+                    url: settings.webhook_url.clone(),
+                    signing_key: settings.signing_key.clone(),
+                    event_filters: vec![EventFilter { key: "channelId".to_string(), value: client.channel_id().to_string() }],
+                    ..Default::default()
+                },
+            )
+            .await?;
+        log::info!("🔗 Registered Bird webhook subscription {} -> {}", created.id, settings.webhook_url);
+
+        // Eski URL'ler bilgi olarak kalır; "Missing" artık geçerli değil
+        issues.retain(|issue| !matches!(issue, SubscriptionIssue::Missing));
+        subscription_id = Some(created.id);
+        registered = true;
+    }
+
+    Ok(SubscriptionReport {
+        webhook_url: settings.webhook_url.clone(),
+        channel_id: client.channel_id().to_string(),
+        subscription_id,
+        registered,
+        issues,
+    })
+}
+
+/// Startup check: log the result loudly, never fail the boot
+pub async fn check_at_startup(client: &BirdComClient) {
+    let Some(settings) = SubscriptionSettings::from_env() else {
+        log::info!("ℹ️ Bird webhook subscription check skipped (BIRD_ORGANIZATION_ID / PUBLIC_BASE_URL not set)");
+        return;
+    };
+
+    match verify(client, &settings, settings.auto_register).await {
+        Ok(report) if report.is_ok() => {
+            log::info!(
+                "✅ Bird webhook subscription {} -> {}",
+                report.subscription_id.as_deref().unwrap_or("-"),
+                report.webhook_url
+            );
+            for issue in &report.issues {
+                log::warn!("⚠️ Bird webhook: {}", issue.describe());
+            }
+        }
+        Ok(report) => {
+            for issue in &report.issues {
+                log::error!("❌ Bird webhook: {}", issue.describe());
+            }
+            log::error!("❌ Inbound messages may not reach {} - check the Bird webhook settings", report.webhook_url);
+        }
+        Err(e) => log::warn!("⚠️ Could not check the Bird webhook subscription: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscription(id: &str, url: &str, channel: Option<&str>, status: &str, key: Option<&str>) -> WebhookSubscription {
+        WebhookSubscription {
+            id: id.into(),
+            service: INBOUND_SERVICE.into(),
+            event: INBOUND_EVENT.into(),
+            url: url.into(),
+            status: status.into(),
+            signing_key: key.map(Into::into),
+            event_filters: channel
+                .map(|c| vec![EventFilter { key: "channelId".into(), value: c.into() }])
+                .unwrap_or_default(),
+        }
+    }
+
+    #[test]
+    fn test_subscription_check() {
+        let url = "https://tavari.example.com/webhook/whatsapp";
+
+        let ok = [subscription("s1", "https://tavari.example.com/webhook/whatsapp/", Some("ch1"), "active", None)];
+        assert_eq!(check(&ok, "ch1", url, Some("secret")), (Some("s1".into()), vec![]));
+
+        // Başka kanalın aboneliği sayılmaz
+        let other_channel = [subscription("s1", url, Some("ch2"), "active", None)];
+        assert_eq!(check(&other_channel, "ch1", url, Some("secret")).1, vec![SubscriptionIssue::Missing]);
+
+        let old_domain = [subscription("s2", "https://old.example.com/webhook/whatsapp", None, "active", None)];
+        assert_eq!(
+            check(&old_domain, "ch1", url, Some("secret")),
+            (None, vec![SubscriptionIssue::OtherUrl { id: "s2".into(), url: "https://old.example.com/webhook/whatsapp".into() }])
+        );
+
+        let broken = [subscription("s3", url, Some("ch1"), "inactive", Some("other"))];
+        assert_eq!(
+            check(&broken, "ch1", url, None).1,
+            vec![
+                SubscriptionIssue::NoSigningKey,
+                SubscriptionIssue::Inactive { id: "s3".into(), status: "inactive".into() },
+            ]
+        );
+        assert_eq!(
+            check(&broken, "ch1", url, Some("secret")).1,
+            vec![
+                SubscriptionIssue::Inactive { id: "s3".into(), status: "inactive".into() },
+                SubscriptionIssue::SigningKeyMismatch { id: "s3".into() },
+            ]
+        );
+    }
+}
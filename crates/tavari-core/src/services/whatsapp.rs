@@ -0,0 +1,658 @@
+#[allow(dead_code)]
+use anyhow::Result;
+#[allow(dead_code)]
+use serde::{Deserialize, Serialize};
+use chrono::Datelike;
+use std::sync::Arc;
+
+use crate::models::{SummarySections, UnitSystem};
+use super::units::format_water;
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhatsAppMessage {
+    pub from: String,
+    pub body: String,
+    pub has_media: bool,
+    pub media_type: Option<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
+pub struct WhatsAppClient {
+    // WhatsApp Web için birkaç seçenek var:
+    // 1. whatsmeow (Go library) - en olgun çözüm ama Rust değil
+    // 2. baileys (Node.js) wrapper
+    // 3. REST API yaklaşımı (WhatsApp Business API)
+    // 4. Python whatsapp-web.js bridge
+
+    // Bu örnekte basit bir trait tanımlayacağız
+    // Gerçek implementasyon için external service kullanılabilir
+}
+
+// WhatsApp istemcisi için trait
+#[allow(dead_code)]
+#[async_trait::async_trait]
+pub trait WhatsAppService: Send + Sync {
+    async fn send_message(&self, to: &str, message: &str) -> Result<()>;
+    #[allow(dead_code)]
+    async fn send_image(&self, to: &str, image_path: &str, caption: &str) -> Result<()>;
+    #[allow(dead_code)]
+    async fn download_media(&self, message_id: &str, output_path: &str) -> Result<String>;
+
+    /// Send a message with quick reply buttons (WhatsApp interactive messages)
+    /// buttons: Vec of (id, title) pairs (max 3 buttons)
+    /// Default implementation sends plain text (for clients that don't support buttons)
+    async fn send_message_with_buttons(
+        &self,
+        to: &str,
+        message: &str,
+        _buttons: Vec<(String, String)>,
+    ) -> Result<()> {
+        // Default implementation: just send the message without buttons
+        self.send_message(to, message).await
+    }
+
+    /// Send an interactive list (up to 10 rows of (id, title)); `button` opens the list.
+    /// Default implementation sends only the text, so `message` must also work as a typed prompt.
+    async fn send_list_message(
+        &self,
+        to: &str,
+        message: &str,
+        _button: &str,
+        _rows: Vec<(String, String)>,
+    ) -> Result<()> {
+        self.send_message(to, message).await
+    }
+}
+
+// Mock implementasyon - gerçek WhatsApp entegrasyonu için değiştirilmeli
+#[allow(dead_code)]
+#[derive(Default)]
+pub struct MockWhatsAppClient;
+
+#[async_trait::async_trait]
+impl WhatsAppService for MockWhatsAppClient {
+    async fn send_message(&self, to: &str, message: &str) -> Result<()> {
+        log::info!("📱 Sending message to {}: {}", to, message);
+        // Gerçek implementasyon: WhatsApp API çağrısı
+        Ok(())
+    }
+
+    async fn send_image(&self, to: &str, image_path: &str, caption: &str) -> Result<()> {
+        log::info!("📱 Sending image to {}: {} ({})", to, image_path, caption);
+        // Gerçek implementasyon: WhatsApp Media API çağrısı
+        Ok(())
+    }
+
+    async fn download_media(&self, message_id: &str, output_path: &str) -> Result<String> {
+        log::info!("📥 Downloading media {} to {}", message_id, output_path);
+        // Gerçek implementasyon: WhatsApp Media download
+        Ok(output_path.to_string())
+    }
+}
+
+#[allow(dead_code)]
+impl MockWhatsAppClient {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Gönderim yapmayan, mesajları sadece kaydeden istemci (dry-run replay için)
+#[derive(Default)]
+pub struct RecordingWhatsAppClient {
+    sent: std::sync::Mutex<Vec<RecordedMessage>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedMessage {
+    pub to: String,
+    pub message: String,
+}
+
+impl RecordingWhatsAppClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Messages that would have been sent, in order
+    pub fn sent_messages(&self) -> Vec<RecordedMessage> {
+        self.sent.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl WhatsAppService for RecordingWhatsAppClient {
+    async fn send_message(&self, to: &str, message: &str) -> Result<()> {
+        log::debug!("🧪 [dry-run] Would send to {}: {}", to, message);
+        self.sent.lock().unwrap_or_else(|e| e.into_inner()).push(RecordedMessage {
+            to: to.to_string(),
+            message: message.to_string(),
+        });
+        Ok(())
+    }
+
+    async fn send_image(&self, to: &str, image_path: &str, caption: &str) -> Result<()> {
+        self.send_message(to, &format!("📷 [Image: {}]\n{}", image_path, caption)).await
+    }
+
+    async fn download_media(&self, _message_id: &str, output_path: &str) -> Result<String> {
+        Ok(output_path.to_string())
+    }
+
+    async fn send_message_with_buttons(
+        &self,
+        to: &str,
+        message: &str,
+        buttons: Vec<(String, String)>,
+    ) -> Result<()> {
+        let titles: Vec<&str> = buttons.iter().map(|(_, title)| title.as_str()).collect();
+        self.send_message(to, &format!("{}\n[{}]", message, titles.join(" | "))).await
+    }
+
+    async fn send_list_message(
+        &self,
+        to: &str,
+        message: &str,
+        button: &str,
+        rows: Vec<(String, String)>,
+    ) -> Result<()> {
+        let titles: Vec<&str> = rows.iter().map(|(_, title)| title.as_str()).collect();
+        self.send_message(to, &format!("{}\n[{}: {}]", message, button, titles.join(" | "))).await
+    }
+}
+
+/// Gönderim hatasını loglayıp yutan sarmalayıcı: kaydı zaten yapılmış isteklerde onay mesajı
+/// ulaşmadı diye işlemi başarısız saymamak için (quick-log API)
+pub struct BestEffortWhatsAppClient {
+    inner: Arc<dyn WhatsAppService>,
+}
+
+impl BestEffortWhatsAppClient {
+    pub fn new(inner: Arc<dyn WhatsAppService>) -> Self {
+        Self { inner }
+    }
+
+    fn ignore_failure(to: &str, result: Result<()>) -> Result<()> {
+        if let Err(e) = result {
+            log::warn!("⚠️ Confirmation to {} not delivered: {}", to, e);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl WhatsAppService for BestEffortWhatsAppClient {
+    async fn send_message(&self, to: &str, message: &str) -> Result<()> {
+        Self::ignore_failure(to, self.inner.send_message(to, message).await)
+    }
+
+    async fn send_image(&self, to: &str, image_path: &str, caption: &str) -> Result<()> {
+        Self::ignore_failure(to, self.inner.send_image(to, image_path, caption).await)
+    }
+
+    async fn download_media(&self, message_id: &str, output_path: &str) -> Result<String> {
+        self.inner.download_media(message_id, output_path).await
+    }
+
+    async fn send_message_with_buttons(
+        &self,
+        to: &str,
+        message: &str,
+        buttons: Vec<(String, String)>,
+    ) -> Result<()> {
+        Self::ignore_failure(to, self.inner.send_message_with_buttons(to, message, buttons).await)
+    }
+
+    async fn send_list_message(
+        &self,
+        to: &str,
+        message: &str,
+        button: &str,
+        rows: Vec<(String, String)>,
+    ) -> Result<()> {
+        Self::ignore_failure(to, self.inner.send_list_message(to, message, button, rows).await)
+    }
+}
+
+// WhatsApp Business API Client (gerçek kullanım için)
+#[allow(dead_code)]
+pub struct WhatsAppBusinessClient {
+    api_key: String,
+    phone_number_id: String,
+    client: reqwest::Client,
+}
+
+#[allow(dead_code)]
+impl WhatsAppBusinessClient {
+    pub fn new(api_key: String, phone_number_id: String) -> Self {
+        Self {
+            api_key,
+            phone_number_id,
+            client: super::http::shared_client(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl WhatsAppService for WhatsAppBusinessClient {
+    async fn send_message(&self, to: &str, message: &str) -> Result<()> {
+        let url = format!(
+            "https://graph.facebook.com/v18.0/{}/messages",
+            self.phone_number_id
+        );
+
+        #[derive(Serialize)]
+        struct MessageRequest {
+            messaging_product: String,
+            to: String,
+            #[serde(rename = "type")]
+            msg_type: String,
+            text: TextContent,
+        }
+
+        #[derive(Serialize)]
+        struct TextContent {
+            body: String,
+        }
+
+        let request = MessageRequest {
+            messaging_product: "whatsapp".to_string(),
+            to: to.to_string(),
+            msg_type: "text".to_string(),
+            text: TextContent {
+                body: message.to_string(),
+            },
+        };
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("WhatsApp API error: {}", error_text);
+        }
+
+        Ok(())
+    }
+
+    async fn send_list_message(
+        &self,
+        to: &str,
+        message: &str,
+        button: &str,
+        rows: Vec<(String, String)>,
+    ) -> Result<()> {
+        let url = format!(
+            "https://graph.facebook.com/v18.0/{}/messages",
+            self.phone_number_id
+        );
+
+        // WhatsApp allows at most 10 rows per list, titles up to 24 chars
+        let rows: Vec<serde_json::Value> = rows
+            .into_iter()
+            .take(10)
+            .map(|(id, title)| serde_json::json!({ "id": id, "title": title.chars().take(24).collect::<String>() }))
+            .collect();
+
+        let request = serde_json::json!({
+            "messaging_product": "whatsapp",
+            "to": to,
+            "type": "interactive",
+            "interactive": {
+                "type": "list",
+                "body": { "text": message },
+                "action": {
+                    "button": button,
+                    "sections": [{ "title": button, "rows": rows }]
+                }
+            }
+        });
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(&request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await?;
+            anyhow::bail!("WhatsApp API error: {}", error_text);
+        }
+
+        Ok(())
+    }
+
+    async fn send_image(&self, to: &str, image_path: &str, _caption: &str) -> Result<()> {
+        // WhatsApp Business API ile resim gönderme
+        log::info!("Sending image via WhatsApp Business API: {} to {}", image_path, to);
+        // Implementasyon: önce media upload, sonra message send
+        Ok(())
+    }
+
+    async fn download_media(&self, message_id: &str, output_path: &str) -> Result<String> {
+        // WhatsApp Business API ile media indirme
+        log::info!("Downloading media from WhatsApp Business API: {}", message_id);
+        Ok(output_path.to_string())
+    }
+}
+
+/// WhatsApp text message limit (characters)
+pub const WHATSAPP_MAX_MESSAGE_CHARS: usize = 4096;
+
+/// Split a long message into parts that fit `max_chars`.
+/// Prefers paragraph boundaries, then line boundaries; only a single oversized line is cut mid-text.
+pub fn split_message(message: &str, max_chars: usize) -> Vec<String> {
+    if message.chars().count() <= max_chars {
+        return vec![message.to_string()];
+    }
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    let mut push_piece = |piece: &str, separator: &str, chunks: &mut Vec<String>| {
+        let joined_len = current.chars().count() + separator.chars().count() + piece.chars().count();
+        if !current.is_empty() && joined_len > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str(separator);
+        }
+        current.push_str(piece);
+    };
+
+    for paragraph in message.split("\n\n") {
+        if paragraph.chars().count() <= max_chars {
+            push_piece(paragraph, "\n\n", &mut chunks);
+            continue;
+        }
+
+        // Paragraf tek başına sığmıyor: satır satır böl
+        for (i, line) in paragraph.lines().enumerate() {
+            let separator = if i == 0 { "\n\n" } else { "\n" };
+            if line.chars().count() <= max_chars {
+                push_piece(line, separator, &mut chunks);
+                continue;
+            }
+
+            let chars: Vec<char> = line.chars().collect();
+            for (j, part) in chars.chunks(max_chars).enumerate() {
+                let part: String = part.iter().collect();
+                push_piece(&part, if j == 0 { separator } else { "" }, &mut chunks);
+            }
+        }
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+/// Daily report with only the sections the user kept (`ozet icerik`).
+/// `streak_days` is None when the caller didn't look it up.
+pub fn format_daily_report(
+    stats: &crate::models::DailyStats,
+    calorie_goal: i32,
+    water_goal: i32,
+    units: UnitSystem,
+    sections: &SummarySections,
+    streak_days: Option<i64>,
+) -> String {
+    let mut blocks = Vec::new();
+
+    if sections.calories {
+        let calorie_bar = create_progress_bar(stats.total_calories, calorie_goal as f64);
+        blocks.push(format!(
+            "🔥 Kalori\n{}\n{:.0}/{:.0} kcal ({}%)\n🍽️ Öğün Sayısı: {}",
+            calorie_bar.bar, stats.total_calories, calorie_goal, calorie_bar.percentage, stats.meals_count
+        ));
+    }
+    if sections.water {
+        let water_bar = create_progress_bar(stats.total_water_ml as f64, water_goal as f64);
+        blocks.push(format!(
+            "💧 Su\n{}\n{} / {} ({}%)\n📝 Su Kayıt: {}",
+            water_bar.bar,
+            format_water(stats.total_water_ml, units),
+            format_water(water_goal as i64, units),
+            water_bar.percentage,
+            stats.water_logs_count
+        ));
+    }
+    if let Some(days) = streak_days.filter(|d| sections.streak && *d > 0) {
+        blocks.push(format!("📅 Kayıt serisi: {} gün 🔥", days));
+    }
+    if sections.tip {
+        blocks.push(get_motivational_message(stats.total_calories, stats.total_water_ml));
+    }
+
+    let mut report = format!("📊 *Günlük Rapor*\n\n{}", blocks.join("\n\n"));
+    if sections.macros {
+        report = with_extra_totals(report, stats);
+    }
+    report.trim_end().to_string()
+}
+
+/// Append custom nutrition field totals (CUSTOM_NUTRITION_FIELDS) to a daily report
+pub fn with_extra_totals(report: String, stats: &crate::models::DailyStats) -> String {
+    let extras = super::nutrition_fields::format_values(
+        super::nutrition_fields::configured(),
+        &stats.extra_totals,
+    );
+    if extras.is_empty() {
+        report
+    } else {
+        format!("{}\n\n🧪 *Diğer Değerler*\n{}", report, extras.trim_end())
+    }
+}
+
+/// 7-day breakdown used by the `haftalik` command (days newest first)
+pub fn format_weekly_report(days: &[crate::models::DailyStats], units: UnitSystem) -> String {
+    let mut response = "📅 *Haftalık Özet*\n\n".to_string();
+    let mut total_calories = 0.0;
+    let mut total_water = 0;
+
+    for stats in days {
+        total_calories += stats.total_calories;
+        total_water += stats.total_water_ml as i32;
+
+        let (day_name, date) = match chrono::NaiveDate::parse_from_str(&stats.date, "%Y-%m-%d") {
+            Ok(date) => (short_day_name(date.weekday()), date.format("%d.%m").to_string()),
+            Err(_) => ("", stats.date.clone()),
+        };
+
+        response.push_str(&format!(
+            "{} {}: {:.0} kcal • {}\n",
+            day_name,
+            date,
+            stats.total_calories,
+            format_water(stats.total_water_ml, units)
+        ));
+    }
+
+    let day_count = days.len().max(1);
+    let avg_calories = total_calories / day_count as f64;
+    let avg_water = total_water / day_count as i32;
+
+    response.push_str("\n📊 *Ortalamalar*\n");
+    response.push_str(&format!("🍽️ Kalori: {:.0} kcal/gün\n", avg_calories));
+    response.push_str(&format!("💧 Su: {}/gün", format_water(avg_water as i64, units)));
+    response
+}
+
+/// Weekly adherence summary sent to a linked coach (with the client's consent)
+pub fn format_coach_summary(
+    client_name: &str,
+    days: &[crate::models::DailyStats],
+    calorie_goal: i32,
+    water_goal: i32,
+) -> String {
+    let logged_days = days.iter().filter(|d| d.meals_count > 0).count();
+    // Hedefte: kayıt var ve hedefin ±%10 aralığında
+    let calorie_days = days
+        .iter()
+        .filter(|d| d.meals_count > 0)
+        .filter(|d| (d.total_calories - calorie_goal as f64).abs() <= calorie_goal as f64 * 0.1)
+        .count();
+    let over_days = days
+        .iter()
+        .filter(|d| d.total_calories > calorie_goal as f64 * 1.1)
+        .count();
+    let water_days = days.iter().filter(|d| d.total_water_ml >= water_goal as i64).count();
+
+    format!(
+        "👩‍⚕️ *Danışan Haftalık Özeti: {}*\n\n\
+         📝 Kayıt yapılan gün: {}/{}\n\
+         🎯 Kalori hedefinde ({} kcal ±%10): {} gün\n\
+         ⚠️ Hedef aşımı: {} gün\n\
+         💧 Su hedefi ({} ml) tutturulan: {} gün\n\n\
+         {}",
+        client_name,
+        logged_days,
+        days.len(),
+        calorie_goal,
+        calorie_days,
+        over_days,
+        water_goal,
+        water_days,
+        // Koç tarafı her zaman metrik görür
+        format_weekly_report(days, UnitSystem::Metric)
+    )
+}
+
+fn short_day_name(weekday: chrono::Weekday) -> &'static str {
+    match weekday {
+        chrono::Weekday::Mon => "Pzt",
+        chrono::Weekday::Tue => "Sal",
+        chrono::Weekday::Wed => "Çar",
+        chrono::Weekday::Thu => "Per",
+        chrono::Weekday::Fri => "Cum",
+        chrono::Weekday::Sat => "Cmt",
+        chrono::Weekday::Sun => "Paz",
+    }
+}
+
+struct ProgressBar {
+    bar: String,
+    percentage: i32,
+}
+
+fn create_progress_bar(current: f64, goal: f64) -> ProgressBar {
+    let percentage = ((current / goal) * 100.0).min(100.0) as i32;
+    let filled = (percentage / 10) as usize; // 10 basamak
+    let empty = 10 - filled;
+
+    let bar = format!(
+        "{}{}",
+        "█".repeat(filled),
+        "░".repeat(empty)
+    );
+
+    ProgressBar { bar, percentage }
+}
+
+fn get_motivational_message(calories: f64, water_ml: i64) -> String {
+    let recommended_water = 2000; // 2 litre
+    let water_percentage = (water_ml as f64 / recommended_water as f64 * 100.0) as i32;
+
+    if water_percentage >= 100 && (1500.0..=2500.0).contains(&calories) {
+        "🎉 Harika! Hem kalori hedefinde hem de su tüketiminde başarılı!"
+    } else if water_percentage < 50 {
+        "💧 Su tüketimine dikkat et! Daha fazla su içmeyi unutma."
+    } else if calories < 1200.0 {
+        "🍽️ Kalori alımın düşük. Yeterli beslenmeye dikkat et."
+    } else if calories > 3000.0 {
+        "⚠️ Kalori alımın yüksek. Porsiyonlara dikkat edebilirsin."
+    } else {
+        "👍 İyi gidiyorsun! Böyle devam et."
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DailyStats;
+
+    fn day(date: &str, calories: f64, water: i64, meals: i64) -> DailyStats {
+        DailyStats {
+            user_phone: "+905551234567".to_string(),
+            date: date.to_string(),
+            total_calories: calories,
+            total_water_ml: water,
+            meals_count: meals,
+            water_logs_count: 0,
+            extra_totals: Default::default(),
+        }
+    }
+
+    struct FailingClient;
+
+    #[async_trait::async_trait]
+    impl WhatsAppService for FailingClient {
+        async fn send_message(&self, _to: &str, _message: &str) -> Result<()> {
+            anyhow::bail!("provider down")
+        }
+
+        async fn send_image(&self, _to: &str, _image_path: &str, _caption: &str) -> Result<()> {
+            anyhow::bail!("provider down")
+        }
+
+        async fn download_media(&self, _message_id: &str, _output_path: &str) -> Result<String> {
+            anyhow::bail!("provider down")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_best_effort_client_swallows_send_errors() {
+        let client = BestEffortWhatsAppClient::new(Arc::new(FailingClient));
+        assert!(client.send_message("+905551234567", "💧 250 ml kaydedildi!").await.is_ok());
+        assert!(client
+            .send_message_with_buttons("+905551234567", "?", vec![("a".into(), "A".into())])
+            .await
+            .is_ok());
+        // İndirme bir onay mesajı değil; hata aynen döner
+        assert!(client.download_media("wamid", "/tmp/x.jpg").await.is_err());
+    }
+
+    #[test]
+    fn test_split_message() {
+        assert_eq!(split_message("kısa mesaj", 20), vec!["kısa mesaj"]);
+
+        // Paragraf sınırından bölünür, sıra korunur
+        let parts = split_message("birinci paragraf\n\nikinci paragraf\n\nüçüncü", 35);
+        assert_eq!(parts, vec!["birinci paragraf\n\nikinci paragraf", "üçüncü"]);
+
+        // Sığmayan paragraf satırlardan, sığmayan satır karakterden bölünür
+        let parts = split_message("aaaa\nbbbb\n\nçççççççççç", 9);
+        assert_eq!(parts, vec!["aaaa\nbbbb", "ççççççççç", "ç"]);
+        assert!(parts.iter().all(|p| p.chars().count() <= 9));
+    }
+
+    #[test]
+    fn test_coach_summary_adherence_counts() {
+        let days = vec![
+            day("2025-11-09", 2050.0, 2500, 3), // hedefte, su tamam
+            day("2025-11-08", 2600.0, 1000, 4), // hedef aşımı
+            day("2025-11-07", 0.0, 0, 0),       // kayıt yok
+        ];
+
+        let summary = format_coach_summary("Ayşe", &days, 2000, 2000);
+        assert!(summary.contains("Kayıt yapılan gün: 2/3"));
+        assert!(summary.contains("±%10): 1 gün"));
+        assert!(summary.contains("Hedef aşımı: 1 gün"));
+        assert!(summary.contains("tutturulan: 1 gün"));
+        assert!(summary.contains("Paz 09.11: 2050 kcal"));
+    }
+}
@@ -0,0 +1,210 @@
+#[cfg(feature = "webhook-server")]
+mod webhook; // Bird.com webhook handler
+
+use tavari_core::services;
+// Webhook modules reach these as `crate::handlers` / `crate::models`
+#[cfg(feature = "webhook-server")]
+use tavari_core::{handlers, models};
+
+#[cfg(feature = "webhook-server")]
+use webhook::server::create_webhook_router;
+
+use anyhow::Result;
+use dotenv::dotenv;
+use std::env;
+use std::sync::Arc;
+
+use services::BirdComClient;
+#[cfg(feature = "webhook-server")]
+use services::AdminService;
+use services::image_store::ImageStore;
+use tavari_core::BotBuilder;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize logger
+    env_logger::init();
+
+    // Load environment variables
+    dotenv().ok();
+
+    log::info!("🚀 Starting WhatsApp Nutrition Bot v{}...", tavari_core::VERSION);
+
+    // Load configuration
+    let openrouter_api_key = env::var("OPENROUTER_API_KEY")
+        .expect("OPENROUTER_API_KEY must be set in .env file");
+
+    let openrouter_model = env::var("OPENROUTER_MODEL")
+        .unwrap_or_else(|_| tavari_core::bot::DEFAULT_AI_MODEL.to_string());
+
+    let database_url = env::var("DATABASE_URL")
+        .expect("DATABASE_URL must be set");
+
+    // DATA_DIR (varsayılan ./data, Docker'da /app/data): fotoğraflar burada; yazılamıyorsa hemen dur
+    let image_store = Arc::new(ImageStore::from_env());
+    image_store.ensure_writable()?;
+    log::info!("📁 Data directory: {} (images: {})", services::image_store::data_dir().display(), image_store.dir().display());
+
+    // TEXT_ONLY_MODE=true: AI bütçesi bittiğinde tüm AI özelliklerini kapat (manuel kayıt/rapor çalışır)
+    let text_only = env::var("TEXT_ONLY_MODE").map(|v| v == "true" || v == "1").unwrap_or(false);
+
+    // Bird.com WhatsApp service (Production)
+    let bird_api_key = env::var("BIRD_API_KEY")
+        .expect("BIRD_API_KEY must be set in .env file");
+    let bird_workspace_id = env::var("BIRD_WORKSPACE_ID")
+        .expect("BIRD_WORKSPACE_ID must be set in .env file");
+    let bird_channel_id = env::var("BIRD_CHANNEL_ID")
+        .expect("BIRD_CHANNEL_ID must be set in .env file");
+
+    // BIRD_REENGAGEMENT_TEMPLATE_ID: sent automatically when a user is outside the 24h window
+    let bird_client = Arc::new(
+        BirdComClient::new(bird_api_key, bird_workspace_id, bird_channel_id)
+            .with_reengagement_template(services::bird::ReengagementTemplate::from_env()),
+    );
+
+    // AI_BASE_URL: LiteLLM / self-hosted OpenAI-compatible gateway instead of OpenRouter
+    let ai_gateway = services::openrouter::AiGateway::from_env();
+
+    let mut bot = BotBuilder::new()
+        .database_url(&database_url)
+        .openrouter(openrouter_api_key, Some(openrouter_model.clone()))
+        .ai_gateway(ai_gateway.clone())
+        .text_only(text_only)
+        .whatsapp(bird_client.clone() as Arc<dyn services::WhatsAppService>)
+        // Outbound event webhooks (EVENT_WEBHOOK_URLS) - Zapier, Mixpanel, CRM...
+        .events(Arc::new(services::events::EventDispatcher::from_env()))
+        // Operator notifications (weekly KPI report email)
+        .notifier(Arc::new(services::notifier::Notifier::from_env()))
+        .build()
+        .await?;
+    log::info!("✅ PostgreSQL database initialized");
+    log::info!("✅ OpenRouter service initialized with model: {} ({})", openrouter_model, ai_gateway.base_url);
+    log::info!("✅ WhatsApp service initialized (Bird.com Production)");
+
+    // Yanlış webhook ayarı sessizce "hiç mesaj gelmiyor" demek; açılışta Bird'e sor
+    {
+        let bird_client = bird_client.clone();
+        tokio::spawn(async move {
+            services::webhook_subscription::check_at_startup(&bird_client).await;
+        });
+    }
+    if text_only {
+        log::warn!("⏸️ TEXT_ONLY_MODE enabled - AI features are disabled");
+    }
+    if bot.events.is_enabled() {
+        log::info!("✅ Event webhooks enabled");
+    }
+
+    // Start reminder service
+    bot.start().await?;
+    log::info!("✅ Reminder service started");
+
+    // Start webhook server with admin dashboard
+    #[cfg(feature = "webhook-server")]
+    {
+        use webhook::admin::{create_admin_router, AdminState};
+
+        let db = bot.db.clone();
+        let message_handler = bot.message_handler.clone();
+
+        let webhook_addr = "0.0.0.0:8080";
+        let mut webhook_app = create_webhook_router(message_handler.clone(), bird_client.clone(), db.clone(), image_store.clone());
+
+        // Add admin dashboard routes with token authentication
+        let admin_token = env::var("ADMIN_TOKEN")
+            .unwrap_or_else(|_| {
+                log::warn!("⚠️ ADMIN_TOKEN not set, using default 'admin123' (INSECURE!)");
+                "admin123".to_string()
+            });
+
+        let route_metrics = Arc::new(webhook::request_log::RouteMetrics::new());
+        let admin_service = Arc::new(AdminService::new(db.clone()));
+        let usage_metrics = Arc::new(services::usage_metrics::UsageMetrics::new());
+        usage_metrics.spawn_collector(db.clone());
+        let replayer = Arc::new(webhook::replay::WebhookReplayer::new(database_url.clone(), db.clone()));
+
+        // Optional HTTPS (TLS_CERT_PATH + TLS_KEY_PATH) for deployments without a reverse proxy
+        let tls_settings = webhook::tls::TlsSettings::from_env();
+        let cookie_settings = webhook::tls::CookieSettings::from_env(tls_settings.as_ref());
+        log::info!("🍪 Admin cookies secure flag: {}", cookie_settings.secure);
+
+        let admin_router = create_admin_router(AdminState {
+            admin_service,
+            admin_token: admin_token.clone(),
+            whatsapp: bird_client.clone(),
+            route_metrics: route_metrics.clone(),
+            replayer,
+            images: image_store.clone(),
+            usage_metrics,
+            cookies: cookie_settings,
+        });
+
+        webhook_app = webhook_app.nest("/admin", admin_router);
+        webhook_app = webhook_app.merge(webhook::quicklog::create_quicklog_router(message_handler.clone(), db.clone()));
+
+        // Serve static images (use absolute path for Docker)
+        use tower_http::services::ServeDir;
+        log::info!("📁 Serving images from: {}", image_store.dir().display());
+
+        // Çökmeden kalan yarım indirmeler ve hiçbir öğüne bağlanmamış fotoğraflar
+        let partial = services::image_store::remove_partial_files(image_store.dir());
+        if partial > 0 {
+            log::warn!("🧹 Removed {} partial image files left by an earlier crash", partial);
+        }
+        {
+            let db = db.clone();
+            tokio::spawn(async move {
+                if let Err(e) = services::maintenance::purge_unattached_images(&db).await {
+                    log::warn!("⚠️ Startup sweep of unattached images failed: {}", e);
+                }
+            });
+        }
+        webhook_app = webhook_app.nest_service("/images", ServeDir::new(image_store.dir()));
+
+        // Log method/path/status/latency for every route (after all routes are mounted)
+        webhook_app = webhook_app.layer(axum::middleware::from_fn_with_state(
+            route_metrics,
+            webhook::request_log::log_requests,
+        ));
+
+        let scheme = if tls_settings.is_some() { "https" } else { "http" };
+
+        log::info!("🌐 Webhook server starting on {} ({})", webhook_addr, scheme);
+        log::info!("🔐 Admin dashboard: {}://localhost:8080/admin?token={}", scheme, admin_token);
+
+        tokio::spawn(async move {
+            webhook::tls::serve(webhook_addr, webhook_app, tls_settings)
+                .await
+                .expect("Failed to start webhook server");
+        });
+
+        log::info!("✅ Webhook server started");
+    }
+
+    log::info!("🎉 Bot is ready!");
+
+    println!("\n📱 Bot çalışıyor!");
+    println!("📞 WhatsApp Numarası: +1 302-726-0990");
+    println!("🌐 Webhook Server: http://localhost:8080");
+    #[cfg(feature = "webhook-server")]
+    {
+        let admin_url = format!("http://localhost:8080/admin?token={}",
+            env::var("ADMIN_TOKEN").unwrap_or_else(|_| "admin123".to_string()));
+        println!("🔐 Admin Dashboard: {}", admin_url);
+    }
+    println!("⏰ Hatırlatma servisi aktif");
+    println!("\n💬 WhatsApp'tan mesaj gönderin:");
+    println!("   'Merhaba' - Hoşgeldin mesajı");
+    println!("   *Yemek fotoğrafı* - Kalori analizi");
+    println!("   '250 ml su içtim' - Su kaydı");
+    println!("   '/rapor' - Günlük rapor");
+    println!("\n🛑 Durdurmak için Ctrl+C basın\n");
+
+    // Keep running
+    tokio::signal::ctrl_c().await?;
+
+    log::info!("🛑 Shutting down...");
+    bot.shutdown().await?;
+
+    Ok(())
+}
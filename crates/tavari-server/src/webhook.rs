@@ -0,0 +1,912 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::handlers::MessageHandler;
+use crate::handlers::onboarding::BUTTON_ID_PREFIX as ONBOARDING_BUTTON_ID_PREFIX;
+use crate::services::goal_review::OPTION_ID_PREFIX as GOAL_REVIEW_ID_PREFIX;
+use crate::services::bird::BirdComClient;
+use crate::services::http::{stream_to_file, MediaTooLarge};
+use crate::services::image_store::{self, ImageStore};
+
+/// Fields we don't model yet are kept here instead of failing deserialization
+type ExtraFields = serde_json::Map<String, Value>;
+
+/// Bird.com webhook payload structures (whatsapp.inbound format)
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BirdWebhook {
+    #[serde(default)]
+    pub service: String,
+    #[serde(default)]
+    pub event: String,
+    pub payload: WebhookPayload,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WebhookPayload {
+    pub id: String,
+    #[serde(rename = "channelId", default)]
+    pub channel_id: String,
+    pub sender: Sender,
+    pub body: MessageBody,
+    /// Set when the message was sent in a WhatsApp group the bot was added to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<GroupInfo>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct GroupInfo {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Sender {
+    pub contact: Contact,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Contact {
+    #[serde(rename = "identifierValue")]
+    pub identifier_value: String,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MessageBody {
+    #[serde(rename = "type")]
+    pub msg_type: String,
+    #[serde(default)]
+    pub text: Option<TextContent>,
+    #[serde(default)]
+    pub image: Option<MediaContent>,
+    #[serde(default)]
+    pub interactive: Option<InteractiveResponse>,
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// Text body; accepts both `{"text": "..."}` and a plain string
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(from = "TextContentRepr")]
+pub struct TextContent {
+    pub text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TextContentRepr {
+    Object { text: String },
+    Plain(String),
+}
+
+impl From<TextContentRepr> for TextContent {
+    fn from(repr: TextContentRepr) -> Self {
+        match repr {
+            TextContentRepr::Object { text } | TextContentRepr::Plain(text) => Self { text },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MediaContent {
+    #[serde(default)]
+    pub images: Vec<ImageData>,
+    #[serde(default)]
+    pub caption: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ImageData {
+    #[serde(rename = "mediaUrl")]
+    pub media_url: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct InteractiveResponse {
+    #[serde(rename = "type", default)]
+    pub interactive_type: String,
+    #[serde(rename = "buttonReply", default)]
+    pub button_reply: Option<ButtonReplyData>,
+    #[serde(rename = "listReply", default)]
+    pub list_reply: Option<ListReplyData>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ButtonReplyData {
+    pub id: String,
+    pub title: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ListReplyData {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Payload shapes we know how to read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadVersion {
+    /// `{"service", "event", "payload": {...}}` - channels whatsapp.inbound envelope
+    Envelope,
+    /// Message object without the envelope (e.g. forwarded by Bird Flows)
+    Bare,
+    Unknown,
+}
+
+impl PayloadVersion {
+    pub fn detect(value: &Value) -> Self {
+        let has = |v: &Value, key: &str| v.get(key).is_some();
+
+        if value.get("payload").map(|p| has(p, "sender") && has(p, "body")).unwrap_or(false) {
+            PayloadVersion::Envelope
+        } else if has(value, "sender") && has(value, "body") {
+            PayloadVersion::Bare
+        } else {
+            PayloadVersion::Unknown
+        }
+    }
+}
+
+/// Result of parsing a raw webhook body
+#[derive(Debug)]
+pub enum ParsedWebhook {
+    Message(Box<BirdWebhook>),
+    /// Valid JSON but not a structure we can handle; logged and acknowledged
+    Unrecognized { reason: String },
+}
+
+/// Parse a raw webhook body, tolerating unknown fields and envelope changes.
+/// Only invalid JSON is an error; unknown structures come back as `Unrecognized`.
+pub fn parse_bird_webhook(body: &str) -> Result<ParsedWebhook, serde_json::Error> {
+    let value: Value = serde_json::from_str(body)?;
+    let version = PayloadVersion::detect(&value);
+
+    let result = match version {
+        PayloadVersion::Envelope => serde_json::from_value::<BirdWebhook>(value),
+        PayloadVersion::Bare => serde_json::from_value::<WebhookPayload>(value).map(|payload| BirdWebhook {
+            service: String::new(),
+            event: "whatsapp.inbound".to_string(),
+            payload,
+            extra: ExtraFields::new(),
+        }),
+        PayloadVersion::Unknown => {
+            let keys: Vec<&str> = value
+                .as_object()
+                .map(|o| o.keys().map(String::as_str).collect())
+                .unwrap_or_default();
+            return Ok(ParsedWebhook::Unrecognized {
+                reason: format!("unknown payload structure (top-level keys: {:?})", keys),
+            });
+        }
+    };
+
+    match result {
+        Ok(webhook) => {
+            let unknown = webhook.unknown_fields();
+            if !unknown.is_empty() {
+                log::debug!("🧩 Webhook {} has unmodelled fields: {:?}", webhook.payload.id, unknown);
+            }
+            Ok(ParsedWebhook::Message(Box::new(webhook)))
+        }
+        Err(e) => Ok(ParsedWebhook::Unrecognized {
+            reason: format!("{:?} payload did not match: {}", version, e),
+        }),
+    }
+}
+
+impl BirdWebhook {
+    /// Names of fields present in the payload that we don't model (for logging)
+    pub fn unknown_fields(&self) -> Vec<String> {
+        let top = self.extra.keys().cloned();
+        let payload = self.payload.extra.keys().map(|k| format!("payload.{}", k));
+        let body = self.payload.body.extra.keys().map(|k| format!("payload.body.{}", k));
+        top.chain(payload).chain(body).collect()
+    }
+}
+
+/// Words that address the bot in a group chat (family/team groups)
+pub struct GroupTriggers {
+    /// Text messages need one of these (e.g. `@tavari`)
+    pub mentions: Vec<String>,
+    /// Photos are also picked up when the caption has one of these (e.g. `#öğün`)
+    pub photo_captions: Vec<String>,
+}
+
+impl GroupTriggers {
+    /// GROUP_MENTION_NAMES / GROUP_PHOTO_TRIGGERS, comma separated
+    pub fn from_env() -> Self {
+        let list = |key: &str, default: &str| -> Vec<String> {
+            std::env::var(key)
+                .unwrap_or_else(|_| default.to_string())
+                .split(',')
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect()
+        };
+        Self {
+            mentions: list("GROUP_MENTION_NAMES", "@tavari"),
+            photo_captions: list("GROUP_PHOTO_TRIGGERS", "#öğün,#ogun,#kalori"),
+        }
+    }
+
+    /// Triggers from the environment, read once
+    pub fn get() -> &'static Self {
+        static TRIGGERS: OnceLock<GroupTriggers> = OnceLock::new();
+        TRIGGERS.get_or_init(Self::from_env)
+    }
+}
+
+/// Remove trigger words from `text`; None if none of them is present
+fn strip_triggers<'a>(text: &str, triggers: impl Iterator<Item = &'a String> + Clone) -> Option<String> {
+    let mut found = false;
+    let rest: Vec<&str> = text
+        .split_whitespace()
+        .filter(|word| {
+            let word = word.trim_end_matches([',', '.', ':', '!', '?']).to_lowercase();
+            let is_trigger = triggers.clone().any(|t| *t == word);
+            found |= is_trigger;
+            !is_trigger
+        })
+        .collect();
+    found.then(|| rest.join(" "))
+}
+
+/// Decide whether a group message is meant for the bot; if so, strip the mention/trigger
+/// so the rest is handled like a private message. Returns false for ordinary group chatter.
+pub fn apply_group_filter(body: &mut MessageBody, triggers: &GroupTriggers) -> bool {
+    match body.msg_type.as_str() {
+        "text" => match body.text.as_mut() {
+            Some(text) => match strip_triggers(&text.text, triggers.mentions.iter()) {
+                Some(rest) => {
+                    text.text = rest;
+                    true
+                }
+                None => false,
+            },
+            None => false,
+        },
+        "image" => match body.image.as_mut() {
+            Some(image) => {
+                let caption = image.caption.as_deref().unwrap_or("");
+                match strip_triggers(caption, triggers.mentions.iter().chain(triggers.photo_captions.iter())) {
+                    Some(rest) => {
+                        image.caption = Some(rest);
+                        true
+                    }
+                    None => false,
+                }
+            }
+            None => false,
+        },
+        // Button/list replies always answer one of the bot's own messages
+        "interactive" => true,
+        _ => false,
+    }
+}
+
+/// Where inbound photos are downloaded from
+#[derive(Clone)]
+pub enum MediaSource {
+    /// Signed mediaUrl, then the Bird media API
+    Bird(Arc<BirdComClient>),
+    /// Dry-run replays: nothing is fetched, the photo fails like an expired download
+    Disabled,
+}
+
+impl MediaSource {
+    async fn download(&self, media_url: &str, message_id: &str, path: &Path, max_bytes: u64) -> anyhow::Result<u64> {
+        match self {
+            MediaSource::Bird(bird_client) => download_image_to(bird_client, media_url, message_id, path, max_bytes).await,
+            MediaSource::Disabled => anyhow::bail!("media downloads are disabled (dry-run)"),
+        }
+    }
+}
+
+/// Handle incoming webhook from Bird.com
+pub async fn handle_bird_webhook(
+    handler: Arc<MessageHandler>,
+    media: MediaSource,
+    images: &ImageStore,
+    mut webhook: BirdWebhook,
+) -> anyhow::Result<()> {
+    log::info!("📨 Received webhook: event={}, id={}", webhook.event, webhook.payload.id);
+
+    let message_id = webhook.payload.id.clone();
+
+    // In groups the sender is still an individual contact: logs, meals and replies go to
+    // their own account. Only mentions and trigger-captioned photos reach the handler.
+    if let Some(group) = &webhook.payload.group {
+        if !apply_group_filter(&mut webhook.payload.body, GroupTriggers::get()) {
+            log::debug!("👥 Ignoring group chatter in {}", group.id);
+            return Ok(());
+        }
+        log::info!(
+            "👥 Group message for the bot in {} from {}",
+            group.name.as_deref().unwrap_or(&group.id),
+            webhook.payload.sender.contact.identifier_value
+        );
+    }
+
+    let from = &webhook.payload.sender.contact.identifier_value;
+    let sender_name = webhook.payload.sender.contact.name.as_deref();
+
+    // Update user's name if provided by WhatsApp
+    if let Some(name) = sender_name {
+        log::debug!("📝 Updating name for {}: {}", from, name);
+        let _ = handler.update_user_name(from, Some(name)).await;
+    }
+
+    // Clear 24h window warning status since user just sent a message
+    let _ = handler.clear_window_warning(from).await;
+
+    match webhook.payload.body.msg_type.as_str() {
+        "text" => {
+            if let Some(text) = webhook.payload.body.text {
+                log::info!("💬 Text message from {}: {}", from, text.text);
+                handler.handle_message(from, &text.text, false, None).await?;
+            }
+        }
+        "image" => {
+            if let Some(image) = webhook.payload.body.image {
+                if let Some(first_image) = image.images.first() {
+                    log::info!("📸 Image message from {}: mediaUrl={}", from, first_image.media_url);
+
+                    // Directory is checked at startup (DATA_DIR); recreate it if it was removed since
+                    let filename = images.new_image_path(&message_id, chrono::Utc::now()).to_string_lossy().to_string();
+                    if let Err(e) = std::fs::create_dir_all(images.dir()) {
+                        log::error!("❌ Failed to create image directory {}: {}", images.dir().display(), e);
+                        let _ = handler.notify_media_download_failed(from).await;
+                        return Err(e.into());
+                    }
+
+                    // Reserved before the download: if we crash before a meal is saved with it,
+                    // the sweep deletes the file instead of leaving an orphan
+                    handler.reserve_image(from, &filename).await?;
+
+                    // Streamed to a .part file and renamed when complete; oversized media is refused (MEDIA_MAX_MB)
+                    let max_bytes = crate::services::http::HttpSettings::global().media_max_bytes;
+                    match media.download(&first_image.media_url, &message_id, Path::new(&filename), max_bytes).await {
+                        Ok(written) => log::info!("💾 Wrote {} bytes to: {}", written, filename),
+                        Err(e) if e.downcast_ref::<MediaTooLarge>().is_some() => {
+                            log::warn!("📦 Image from {} rejected: {}", from, e);
+                            handler.discard_image(&filename).await;
+                            handler.notify_media_too_large(from, max_bytes).await?;
+                            return Ok(());
+                        }
+                        Err(e) => {
+                            handler.discard_image(&filename).await;
+                            let _ = handler.notify_media_download_failed(from).await;
+                            return Err(e);
+                        }
+                    }
+
+                    // Verify file was written
+                    match std::fs::metadata(&filename) {
+                        Ok(metadata) => {
+                            log::info!("✅ Image saved successfully: {} ({} bytes)", filename, metadata.len());
+                        }
+                        Err(e) => {
+                            log::error!("❌ File written but cannot verify: {}", e);
+                        }
+                    }
+
+                    // Admin listesi için küçük önizleme; başarısız olursa ilk istekte yeniden denenir
+                    let image_path = PathBuf::from(&filename);
+                    match tokio::task::spawn_blocking(move || image_store::create_thumbnail(&image_path)).await {
+                        Ok(Ok(thumbnail)) => log::debug!("🖼️ Thumbnail created for {} ({} bytes)", filename, thumbnail.len()),
+                        Ok(Err(e)) => log::warn!("⚠️ Could not create thumbnail for {}: {}", filename, e),
+                        Err(e) => log::warn!("⚠️ Thumbnail task failed for {}: {}", filename, e),
+                    }
+
+                    // Handle with caption if present
+                    let caption = image.caption.as_deref().unwrap_or("");
+                    handler
+                        .handle_message(from, caption, true, Some(filename))
+                        .await?;
+                } else {
+                    log::warn!("⚠️ Image message received but no images in array");
+                }
+            }
+        }
+        "interactive" => {
+            if let Some(interactive) = webhook.payload.body.interactive {
+                // Check for list reply first (WhatsApp list messages)
+                if let Some(list_reply) = interactive.list_reply {
+                    log::info!("📋 List selection from {}: id={}, title={}", from, list_reply.id, list_reply.title);
+
+                    // Handle water list selections
+                    if list_reply.id.starts_with("water_") {
+                        // Extract amount from list ID (e.g., "water_200" -> "200")
+                        let amount = list_reply.id.strip_prefix("water_").unwrap_or("0");
+                        let water_message = format!("{} ml içtim", amount);
+                        log::info!("💧 Processing water list selection: {}", water_message);
+                        handler.handle_message(from, &water_message, false, None).await?;
+                    } else if list_reply.id.starts_with(ONBOARDING_BUTTON_ID_PREFIX)
+                        || list_reply.id.starts_with(GOAL_REVIEW_ID_PREFIX)
+                    {
+                        // Onboarding and goal review choices are parsed by id, not by (localized) title
+                        handler.handle_message(from, &list_reply.id, false, None).await?;
+                    } else {
+                        // Unknown selection, just handle as text
+                        handler.handle_message(from, &list_reply.title, false, None).await?;
+                    }
+                } else if let Some(button_reply) = interactive.button_reply {
+                    log::info!("🔘 Button click from {}: id={}, title={}", from, button_reply.id, button_reply.title);
+
+                    // Handle water button clicks
+                    if button_reply.id.starts_with("water_") {
+                        // Extract amount from button ID (e.g., "water_200" -> "200")
+                        let amount = button_reply.id.strip_prefix("water_").unwrap_or("0");
+                        let water_message = format!("{} ml içtim", amount);
+                        log::info!("💧 Processing water button: {}", water_message);
+                        handler.handle_message(from, &water_message, false, None).await?;
+                    } else if button_reply.id.starts_with(ONBOARDING_BUTTON_ID_PREFIX) {
+                        handler.handle_message(from, &button_reply.id, false, None).await?;
+                    } else {
+                        // Unknown button, just handle as text
+                        handler.handle_message(from, &button_reply.title, false, None).await?;
+                    }
+                } else {
+                    log::warn!("⚠️ Interactive message received but no button/list reply");
+                }
+            }
+        }
+        _ => {
+            log::warn!("⚠️ Unknown message type: {}", webhook.payload.body.msg_type);
+        }
+    }
+
+    Ok(())
+}
+
+
+/// Download an inbound image to `path`. The signed `mediaUrl` may already be expired by the time
+/// we process the webhook (403), so fall back to the media API by message ID.
+/// Oversized media (`MediaTooLarge`) fails immediately without retries or fallback.
+async fn download_image_to(
+    bird_client: &BirdComClient,
+    media_url: &str,
+    message_id: &str,
+    path: &Path,
+    max_bytes: u64,
+) -> anyhow::Result<u64> {
+    // Attempts per download source (signed mediaUrl, then media API): 1 + HTTP_MAX_RETRIES
+    let attempts = 1 + crate::services::http::HttpSettings::global().max_retries;
+
+    // Download directly from mediaUrl with AccessKey authentication
+    // (shared client: timeouts from HTTP_*_TIMEOUT_SECS, default redirect policy follows up to 10)
+    let client = crate::services::http::shared_client();
+
+    for attempt in 1..=attempts {
+        let result = client
+            .get(media_url)
+            .header("Authorization", format!("AccessKey {}", std::env::var("BIRD_API_KEY").unwrap_or_default()))
+            .send()
+            .await;
+
+        match result {
+            Ok(response) if response.status().is_success() => {
+                match stream_to_file(response, path, max_bytes).await {
+                    Ok(written) => return Ok(written),
+                    Err(e) if e.downcast_ref::<MediaTooLarge>().is_some() => return Err(e),
+                    Err(e) => {
+                        log::warn!("⚠️ mediaUrl download interrupted (attempt {}/{}): {}",
+                            attempt, attempts, e);
+                    }
+                }
+            }
+            Ok(response) => {
+                let status = response.status();
+                log::warn!("⚠️ mediaUrl download failed (attempt {}/{}): HTTP {}",
+                    attempt, attempts, status);
+                // Expired/invalid signed URL - retrying the same URL won't help
+                if matches!(status.as_u16(), 401 | 403 | 404 | 410) {
+                    break;
+                }
+            }
+            Err(e) => {
+                log::warn!("⚠️ mediaUrl download failed (attempt {}/{}): {}",
+                    attempt, attempts, e);
+            }
+        }
+
+        if attempt < attempts {
+            tokio::time::sleep(std::time::Duration::from_millis(500 * attempt as u64)).await;
+        }
+    }
+
+    log::info!("🔁 Falling back to media API for message {}", message_id);
+
+    let mut last_error = None;
+    for attempt in 1..=attempts {
+        let result = match bird_client.fetch_media_response(message_id).await {
+            Ok(response) => stream_to_file(response, path, max_bytes).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(written) => return Ok(written),
+            Err(e) if e.downcast_ref::<MediaTooLarge>().is_some() => return Err(e),
+            Err(e) => {
+                log::warn!("⚠️ Media API download failed (attempt {}/{}): {}",
+                    attempt, attempts, e);
+                last_error = Some(e);
+            }
+        }
+
+        if attempt < attempts {
+            tokio::time::sleep(std::time::Duration::from_millis(500 * attempt as u64)).await;
+        }
+    }
+
+    Err(last_error
+        .unwrap_or_else(|| anyhow::anyhow!("Media download failed"))
+        .context(format!("Failed to download image for message {}", message_id)))
+}
+
+/// Verify webhook signature using HMAC-SHA256
+fn verify_webhook_signature(payload: &str, signature: &str, secret: &str) -> bool {
+    type HmacSha256 = Hmac<Sha256>;
+
+    let mut mac = match HmacSha256::new_from_slice(secret.as_bytes()) {
+        Ok(mac) => mac,
+        Err(_) => return false,
+    };
+
+    mac.update(payload.as_bytes());
+    let result = mac.finalize();
+    let expected_signature = hex::encode(result.into_bytes());
+
+    // Bird.com typically sends signature as "sha256=<signature>"
+    let provided_signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+
+    expected_signature == provided_signature
+}
+
+// Admin dashboard module
+#[cfg(feature = "webhook-server")]
+pub mod admin;
+
+// Server-side rendered admin pages (askama templates)
+#[cfg(feature = "webhook-server")]
+pub mod admin_pages;
+
+// Optional HTTPS serving (rustls)
+#[cfg(feature = "webhook-server")]
+pub mod tls;
+
+// Request logging middleware and per-route latency metrics
+#[cfg(feature = "webhook-server")]
+pub mod request_log;
+
+// Dry-run replay of stored webhook payloads
+#[cfg(feature = "webhook-server")]
+pub mod replay;
+
+// Token-authenticated quick log API (iOS Shortcuts, widgets)
+#[cfg(feature = "webhook-server")]
+pub mod quicklog;
+
+// Buffers inbound webhooks while Postgres is unreachable and replays them afterwards
+#[cfg(feature = "webhook-server")]
+pub mod spillover;
+
+// Axum integration (optional - requires axum dependency)
+#[cfg(feature = "webhook-server")]
+pub mod server {
+    use super::*;
+    use axum::{
+        extract::State,
+        http::StatusCode,
+        response::{IntoResponse, Response},
+        routing::{get, post},
+        Extension, Router,
+    };
+    use super::request_log::WebhookMessageId;
+    use super::spillover::WebhookSpillover;
+    use crate::services::database::is_connection_error;
+    use crate::services::Database;
+
+    pub struct AppState {
+        pub message_handler: Arc<MessageHandler>,
+        pub bird_client: Arc<BirdComClient>,
+        pub db: Arc<Database>,
+        pub images: Arc<ImageStore>,
+        pub payload_archive_size: i64,
+        pub spillover: Arc<WebhookSpillover>,
+    }
+
+    pub fn create_webhook_router(
+        message_handler: Arc<MessageHandler>,
+        bird_client: Arc<BirdComClient>,
+        db: Arc<Database>,
+        images: Arc<ImageStore>,
+    ) -> Router {
+        // Number of raw webhook bodies kept for debugging/replay (ring buffer)
+        let payload_archive_size = std::env::var("WEBHOOK_ARCHIVE_SIZE")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(500);
+
+        let spillover = Arc::new(WebhookSpillover::from_env());
+        let state = Arc::new(AppState {
+            message_handler,
+            bird_client,
+            db,
+            images,
+            payload_archive_size,
+            spillover: spillover.clone(),
+        });
+        spillover.spawn_replayer(state.clone());
+
+        Router::new()
+            .route("/", get(root_handler))
+            .route("/webhook/whatsapp", post(webhook_handler))
+            .route("/health", get(health_check))
+            .with_state(state)
+    }
+
+    async fn webhook_handler(
+        headers: axum::http::HeaderMap,
+        State(state): State<Arc<AppState>>,
+        body: String,
+    ) -> Response {
+        log::debug!("🔔 Webhook received: {}", &body[..body.len().min(500)]);
+
+        // Try to parse the payload
+        let payload: BirdWebhook = match parse_bird_webhook(&body) {
+            Ok(ParsedWebhook::Message(p)) => *p,
+            Ok(ParsedWebhook::Unrecognized { reason }) => {
+                // Acknowledge so Bird.com doesn't retry forever; the body is archived
+                // and can be replayed once the new structure is supported
+                log::warn!("⚠️ Unrecognized webhook payload: {}", reason);
+                log::warn!("📦 Raw payload: {}", &body[..body.len().min(2000)]);
+                let _ = state
+                    .db
+                    .store_webhook_payload(None, &body, Some(&reason), state.payload_archive_size)
+                    .await;
+                return StatusCode::OK.into_response();
+            }
+            Err(e) => {
+                log::error!("❌ Failed to parse webhook payload: {}", e);
+                log::error!("📦 Raw payload: {}", body);
+                let _ = state
+                    .db
+                    .store_webhook_payload(None, &body, Some(&e.to_string()), state.payload_archive_size)
+                    .await;
+                return StatusCode::UNPROCESSABLE_ENTITY.into_response();
+            }
+        };
+
+        // Keep the raw body so production issues can be replayed later (dry-run).
+        // This is also the first DB write, so it tells us whether Postgres is reachable.
+        let mut database_down = false;
+        if let Err(e) = state
+            .db
+            .store_webhook_payload(Some(&payload.payload.id), &body, None, state.payload_archive_size)
+            .await
+        {
+            database_down = is_connection_error(&e);
+            log::warn!("⚠️ Failed to archive webhook payload: {}", e);
+        }
+
+        let webhook_id = payload.payload.id.clone();
+        let message_id = Extension(WebhookMessageId(webhook_id.clone()));
+        log::debug!("✅ Parsed webhook: {} (event: {})", payload.payload.id, payload.event);
+
+        // Log all headers for debugging
+        for (name, value) in headers.iter() {
+            if name.as_str().to_lowercase().contains("signature") ||
+               name.as_str().to_lowercase().contains("hub") {
+                log::debug!("Header: {} = {:?}", name, value);
+            }
+        }
+
+        // Verify webhook signature for security
+        let webhook_secret = match std::env::var("BIRD_WEBHOOK_SECRET") {
+            Ok(secret) => {
+                log::debug!("Using webhook secret: {}...", &secret[..8]);
+                secret
+            }
+            Err(_) => {
+                log::warn!("⚠️ BIRD_WEBHOOK_SECRET not configured, skipping signature verification");
+                String::new()
+            }
+        };
+
+        // Get signature from headers (Bird.com typically sends X-Hub-Signature-256 or similar)
+        let signature = if let Some(sig_header) = headers.get("x-hub-signature-256") {
+            sig_header.to_str().unwrap_or("")
+        } else if let Some(sig_header) = headers.get("x-signature") {
+            sig_header.to_str().unwrap_or("")
+        } else if let Some(sig_header) = headers.get("signature") {
+            sig_header.to_str().unwrap_or("")
+        } else {
+            log::info!("ℹ️ No signature header found in webhook request (expected for testing)");
+            ""
+        };
+
+        // Verify signature if both secret and signature are provided
+        if !webhook_secret.is_empty() && !signature.is_empty() {
+            // Use raw body for signature verification (not re-serialized JSON)
+            // Re-serialization can change whitespace/key ordering and break HMAC verification
+            if !verify_webhook_signature(&body, signature, &webhook_secret) {
+                log::error!("❌ Webhook signature verification failed");
+                return (StatusCode::UNAUTHORIZED, message_id).into_response();
+            }
+
+            log::debug!("✅ Webhook signature verified");
+        } else if !signature.is_empty() {
+            log::warn!("⚠️ Signature provided but no webhook secret configured");
+        }
+
+        // Database outage: keep the message for later. While older messages are still buffered,
+        // newer ones queue behind them so a user's messages are handled in order.
+        if database_down || !state.spillover.is_empty() {
+            log::warn!("📥 Buffering webhook {} until the database is reachable", webhook_id);
+            state.spillover.push(&body, !database_down);
+            return (StatusCode::OK, message_id).into_response();
+        }
+
+        // Process the webhook
+        match handle_bird_webhook(state.message_handler.clone(), MediaSource::Bird(state.bird_client.clone()), &state.images, payload).await {
+            Ok(_) => (StatusCode::OK, message_id).into_response(),
+            Err(e) if is_connection_error(&e) => {
+                // Lost the database mid-message: buffer it instead of dropping it (parts that already
+                // ran, e.g. a sent reply, may run again on replay)
+                log::error!("❌ Database unavailable while processing {}, buffering: {}", webhook_id, e);
+                state.spillover.push(&body, true);
+                (StatusCode::OK, message_id).into_response()
+            }
+            Err(e) => {
+                // Log the error but don't fail the webhook - Bird.com expects 200
+                log::error!("❌ Webhook processing error (message_id={}): {}", webhook_id, e);
+                // Return OK to prevent Bird.com from retrying
+                (StatusCode::OK, message_id).into_response()
+            }
+        }
+    }
+
+    async fn root_handler() -> &'static str {
+        "WhatsApp Nutrition Bot Webhook Server - Use /webhook/whatsapp for Bird.com webhooks"
+    }
+
+    /// 503 while Postgres is unreachable (webhooks are buffered meanwhile), so load balancers
+    /// and orchestrators see the outage instead of a green check
+    async fn health_check(State(state): State<Arc<AppState>>) -> Response {
+        let buffered = state.spillover.len();
+        if !state.db.ping().await {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                format!("DATABASE UNAVAILABLE ({} webhooks buffered)", buffered),
+            )
+                .into_response();
+        }
+        if buffered > 0 {
+            return (StatusCode::OK, format!("OK ({} buffered webhooks replaying)", buffered)).into_response();
+        }
+        (StatusCode::OK, "OK").into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_deserialization() {
+        let json = r#"{
+            "service": "channels",
+            "event": "whatsapp.inbound",
+            "payload": {
+                "id": "msg_123",
+                "channelId": "channel_456",
+                "sender": {
+                    "contact": {
+                        "identifierValue": "+905551234567"
+                    }
+                },
+                "body": {
+                    "type": "text",
+                    "text": {
+                        "text": "Merhaba"
+                    }
+                }
+            }
+        }"#;
+
+        let webhook: BirdWebhook = serde_json::from_str(json).unwrap();
+
+        assert_eq!(webhook.service, "channels");
+        assert_eq!(webhook.event, "whatsapp.inbound");
+        assert_eq!(webhook.payload.id, "msg_123");
+        assert_eq!(webhook.payload.sender.contact.identifier_value, "+905551234567");
+        assert_eq!(webhook.payload.body.msg_type, "text");
+        assert_eq!(
+            webhook.payload.body.text.unwrap().text,
+            "Merhaba"
+        );
+    }
+
+    #[test]
+    fn test_tolerant_deserialization_keeps_unknown_fields() {
+        let json = r#"{
+            "service": "channels",
+            "event": "whatsapp.inbound",
+            "apiVersion": "2",
+            "payload": {
+                "id": "msg_124",
+                "sender": { "contact": { "identifierValue": "+905551234567" } },
+                "reference": "abc",
+                "body": { "type": "text", "text": "Merhaba", "locale": "tr" }
+            }
+        }"#;
+
+        let webhook = match parse_bird_webhook(json).unwrap() {
+            ParsedWebhook::Message(w) => w,
+            other => panic!("expected message, got {:?}", other),
+        };
+
+        assert_eq!(webhook.payload.channel_id, "");
+        assert_eq!(webhook.payload.body.text.as_ref().unwrap().text, "Merhaba");
+        assert_eq!(
+            webhook.unknown_fields(),
+            vec!["apiVersion", "payload.reference", "payload.body.locale"]
+        );
+    }
+
+    #[test]
+    fn test_payload_version_detection() {
+        let bare = r#"{
+            "id": "msg_125",
+            "sender": { "contact": { "identifierValue": "+905551234567" } },
+            "body": { "type": "text", "text": { "text": "Selam" } }
+        }"#;
+        match parse_bird_webhook(bare).unwrap() {
+            ParsedWebhook::Message(w) => assert_eq!(w.payload.id, "msg_125"),
+            other => panic!("expected message, got {:?}", other),
+        }
+
+        let unknown = r#"{"event": "whatsapp.status", "payload": {"status": "delivered"}}"#;
+        assert!(matches!(
+            parse_bird_webhook(unknown).unwrap(),
+            ParsedWebhook::Unrecognized { .. }
+        ));
+
+        assert!(parse_bird_webhook("not json").is_err());
+    }
+
+    #[test]
+    fn test_group_filter() {
+        let triggers = GroupTriggers {
+            mentions: vec!["@tavari".to_string()],
+            photo_captions: vec!["#öğün".to_string()],
+        };
+        let parse = |body: &str| -> MessageBody { serde_json::from_str(body).unwrap() };
+
+        let mut chatter = parse(r#"{"type": "text", "text": "akşam ne yiyoruz?"}"#);
+        assert!(!apply_group_filter(&mut chatter, &triggers));
+
+        let mut mention = parse(r#"{"type": "text", "text": "@Tavari, rapor"}"#);
+        assert!(apply_group_filter(&mut mention, &triggers));
+        assert_eq!(mention.text.unwrap().text, "rapor");
+
+        let mut photo = parse(r##"{"type": "image", "image": {"images": [], "caption": "#öğün menemen"}}"##);
+        assert!(apply_group_filter(&mut photo, &triggers));
+        assert_eq!(photo.image.unwrap().caption.as_deref(), Some("menemen"));
+
+        let mut holiday_photo = parse(r#"{"type": "image", "image": {"images": [], "caption": "tatilden"}}"#);
+        assert!(!apply_group_filter(&mut holiday_photo, &triggers));
+    }
+}
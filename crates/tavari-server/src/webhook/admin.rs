@@ -0,0 +1,1011 @@
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode, Uri},
+    middleware::{self, Next},
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::{get, post},
+    Router, Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::models::{ConversationCursor, CountryDefaults};
+use crate::services::bird::SendPath;
+use crate::services::bird_error::BirdError;
+use crate::services::{allowlist, country_defaults, settings_history};
+use crate::services::image_store::ImageStore;
+use crate::services::reminder_copy::{self, ReminderKind};
+use crate::services::usage_metrics::UsageMetrics;
+use crate::services::webhook_subscription::{self, SubscriptionSettings};
+use crate::services::{AdminService, BirdComClient};
+use crate::webhook::admin_pages;
+use crate::webhook::replay::{SimulatedMessage, WebhookReplayer};
+use crate::webhook::request_log::RouteMetrics;
+use crate::webhook::tls::CookieSettings;
+
+/// Set when the dashboard is opened with `?token=`; later admin requests may leave the token out
+const SESSION_COOKIE: &str = "admin_session";
+const SESSION_MAX_AGE_SECS: i64 = 12 * 60 * 60;
+
+#[derive(Clone)]
+pub struct AdminState {
+    pub admin_service: Arc<AdminService>,
+    pub admin_token: String,
+    pub whatsapp: Arc<BirdComClient>,
+    pub route_metrics: Arc<RouteMetrics>,
+    pub replayer: Arc<WebhookReplayer>,
+    pub images: Arc<ImageStore>,
+    pub usage_metrics: Arc<UsageMetrics>,
+    pub cookies: CookieSettings,
+}
+
+#[derive(Deserialize)]
+pub struct AuthQuery {
+    token: String,
+}
+
+/// Create admin router with all routes
+pub fn create_admin_router(state: AdminState) -> Router {
+    Router::new()
+        .route("/", get(admin_dashboard_page))
+        .route("/users/:phone", get(admin_pages::user_detail_page))
+        .route("/users/:phone/actions/toggle-active", post(admin_pages::toggle_active_action))
+        .route("/users/:phone/actions/reset", post(admin_pages::reset_action))
+        .route("/users/:phone/actions/send-message", post(admin_pages::send_message_action))
+        .route("/api/dashboard", get(get_dashboard_data))
+        .route("/api/users/:phone/meals", get(get_user_meals))
+        .route("/api/users/:phone/conversations", get(get_user_conversations))
+        .route("/api/users/:phone/settings-history", get(get_user_settings_history))
+        .route("/api/users/:phone/toggle-active", post(toggle_user_active))
+        .route("/api/users/:phone/reset", post(reset_user))
+        .route("/api/users/:phone/send-message", post(send_user_message))
+        .route("/api/users/:phone/coach", post(set_user_coach))
+        .route("/api/broadcast", post(broadcast_message))
+        .route("/api/changelog", get(list_changelog).post(upsert_changelog))
+        .route("/api/changelog/:id/delete", post(delete_changelog))
+        .route("/api/country-defaults", get(list_country_defaults).post(upsert_country_defaults))
+        .route("/api/country-defaults/:prefix/delete", post(delete_country_defaults))
+        .route("/api/metrics/routes", get(get_route_metrics))
+        .route("/metrics", get(get_usage_metrics))
+        .route("/api/maintenance", get(list_maintenance_runs))
+        .route("/api/analytics/reminder-variants", get(reminder_variant_report))
+        .route("/api/search", get(search_content))
+        .route("/api/webhooks", get(list_webhook_payloads))
+        .route("/api/webhooks/:id/replay", post(replay_webhook_payload))
+        .route("/api/webhooks/simulate", post(simulate_webhook_message))
+        .route("/api/images/:name/thumbnail", get(get_image_thumbnail))
+        .route("/api/waitlist", get(list_waitlist))
+        .route("/api/allowlist/approve", post(approve_allowlist))
+        .route("/api/bird/webhook-subscription", get(check_webhook_subscription).post(register_webhook_subscription))
+        .layer(middleware::from_fn_with_state(state.clone(), session_cookie_auth))
+        .with_state(state)
+}
+
+fn session_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|pair| pair.trim().strip_prefix(SESSION_COOKIE)?.strip_prefix('='))
+}
+
+/// A request without `?token=` but with a valid session cookie is handled as if the token had
+/// been in the query, so the handlers keep a single token check
+async fn session_cookie_auth(State(state): State<AdminState>, mut request: Request, next: Next) -> Response {
+    let has_token = request
+        .uri()
+        .query()
+        .is_some_and(|query| query.split('&').any(|pair| pair.starts_with("token=")));
+    let encoded_token = admin_pages::encode(&state.admin_token);
+    if !has_token && session_token(request.headers()) == Some(encoded_token.as_str()) {
+        let path_and_query = match request.uri().query() {
+            Some(query) => format!("{}?{}&token={}", request.uri().path(), query, encoded_token),
+            None => format!("{}?token={}", request.uri().path(), encoded_token),
+        };
+        let mut parts = request.uri().clone().into_parts();
+        match path_and_query.parse() {
+            Ok(path_and_query) => parts.path_and_query = Some(path_and_query),
+            Err(_) => return next.run(request).await,
+        }
+        if let Ok(uri) = Uri::from_parts(parts) {
+            *request.uri_mut() = uri;
+        }
+    }
+    next.run(request).await
+}
+
+#[derive(Deserialize)]
+struct WaitlistQuery {
+    token: String,
+    limit: Option<i64>,
+}
+
+/// Numbers that wrote to the bot while ALLOWLIST_MODE kept them out, not approved yet
+async fn list_waitlist(
+    Query(query): Query<WaitlistQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    if query.token != state.admin_token {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let leads = state
+        .admin_service
+        .db
+        .get_waitlist_leads(query.limit.unwrap_or(200).clamp(1, 1000))
+        .await
+        .map_err(|e| {
+            log::error!("Failed to list waitlist: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(serde_json::json!({
+        "allowlist_mode": allowlist::enabled(),
+        "count": leads.len(),
+        "leads": leads,
+    })))
+}
+
+#[derive(Deserialize)]
+struct ApproveRequest {
+    /// Numbers in WhatsApp international format ("+905551112233"); spaces/dashes are ignored
+    #[serde(default)]
+    phones: Vec<String>,
+    /// Approve every lead currently on the waitlist
+    #[serde(default)]
+    all_waiting: bool,
+    note: Option<String>,
+    /// Tell newly approved leads that their access is open
+    #[serde(default)]
+    notify: bool,
+}
+
+/// Bulk approve numbers for the closed beta (pre-approval before first contact works too)
+async fn approve_allowlist(
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+    Json(payload): Json<ApproveRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+    let db = &state.admin_service.db;
+    let internal_error = |e: anyhow::Error| {
+        log::error!("Allowlist approval failed: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+
+    let mut phones = Vec::new();
+    let mut invalid = Vec::new();
+    for raw in &payload.phones {
+        match allowlist::normalize_phone(raw) {
+            Some(phone) => phones.push(phone),
+            None => invalid.push(raw.clone()),
+        }
+    }
+    if payload.all_waiting {
+        phones.extend(db.get_waiting_lead_numbers().await.map_err(internal_error)?);
+    }
+    phones.sort();
+    phones.dedup();
+    if phones.is_empty() {
+        return Ok((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "no valid phone numbers", "invalid": invalid })),
+        ));
+    }
+
+    let approved = db.approve_numbers(&phones, payload.note.as_deref()).await.map_err(internal_error)?;
+    log::info!("✅ Approved {} numbers for the beta ({} requested)", approved.len(), phones.len());
+
+    // Sadece bize daha önce yazmış olanlara haber verilir (ön onaylı numaralara soğuk mesaj atılmaz)
+    let mut notified = 0;
+    if payload.notify && !approved.is_empty() {
+        for phone in db.filter_waitlist_leads(&approved).await.map_err(internal_error)? {
+            match state.whatsapp.deliver(&phone, allowlist::APPROVED_MESSAGE).await {
+                Ok(_) => notified += 1,
+                Err(e) => log::warn!("⚠️ Could not notify approved lead {}: {}", phone, e),
+            }
+        }
+    }
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "approved": approved.len(),
+            "already_approved": phones.len() - approved.len(),
+            "notified": notified,
+            "invalid": invalid,
+        })),
+    ))
+}
+
+/// Does Bird deliver inbound messages to this deployment? (subscription URL, status, signing key)
+async fn check_webhook_subscription(
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+    webhook_subscription_report(&state, false).await
+}
+
+/// Same check, creating the subscription for this channel if none calls our URL
+async fn register_webhook_subscription(
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+    webhook_subscription_report(&state, true).await
+}
+
+async fn webhook_subscription_report(state: &AdminState, register: bool) -> Result<Json<serde_json::Value>, StatusCode> {
+    let Some(settings) = SubscriptionSettings::from_env() else {
+        return Ok(Json(serde_json::json!({
+            "ok": false,
+            "error": "BIRD_ORGANIZATION_ID and PUBLIC_BASE_URL must be set"
+        })));
+    };
+
+    match webhook_subscription::verify(&state.whatsapp, &settings, register).await {
+        Ok(report) => Ok(Json(serde_json::json!({
+            "ok": report.is_ok(),
+            "report": report,
+            "messages": report.issues.iter().map(|issue| issue.describe()).collect::<Vec<_>>(),
+        }))),
+        Err(e) => {
+            log::error!("❌ Bird webhook subscription check failed: {:#}", e);
+            Ok(Json(serde_json::json!({ "ok": false, "error": format!("{:#}", e) })))
+        }
+    }
+}
+
+/// Small JPEG preview of a stored meal photo for the dashboard lists (originals can be several MB).
+/// Falls back to the original when a thumbnail can't be made (e.g. build without image-convert).
+async fn get_image_thumbnail(
+    Path(name): Path<String>,
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+) -> Result<Response, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let images = state.images.clone();
+    let lookup_name = name.clone();
+    let thumbnail = tokio::task::spawn_blocking(move || images.thumbnail(&lookup_name))
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    match thumbnail {
+        Ok(Some(bytes)) => Ok((
+            [
+                (header::CONTENT_TYPE, "image/jpeg"),
+                (header::CACHE_CONTROL, "private, max-age=86400"),
+            ],
+            bytes,
+        )
+            .into_response()),
+        Ok(None) => Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            log::warn!("⚠️ Thumbnail unavailable for {}: {}", name, e);
+            Ok(Redirect::temporary(&format!("/images/{}", name)).into_response())
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    token: String,
+    q: String,
+    limit: Option<i64>,
+}
+
+/// Queries shorter than this match too much to be useful
+const SEARCH_MIN_QUERY_CHARS: usize = 3;
+
+/// Full-text search over conversations and meal descriptions across users
+/// (e.g. "who received the broken reminder text"), each hit linked to the user's admin page
+async fn search_content(
+    Query(query): Query<SearchQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    if query.token != state.admin_token {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let q = query.q.trim();
+    if q.chars().count() < SEARCH_MIN_QUERY_CHARS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let hits = state
+        .admin_service
+        .search(q, query.limit.unwrap_or(50).clamp(1, 200))
+        .await
+        .map_err(|e| {
+            log::error!("Admin search failed for '{}': {}", q, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let results: Vec<serde_json::Value> = hits
+        .into_iter()
+        .map(|hit| {
+            let link = format!("/admin/users/{}", hit.user_phone);
+            let mut value = serde_json::to_value(hit).unwrap_or_default();
+            value["link"] = serde_json::json!(link);
+            value
+        })
+        .collect();
+
+    log::info!("🔎 Admin search '{}': {} hits", q, results.len());
+
+    Ok((StatusCode::OK, axum::Json(serde_json::json!({
+        "query": q,
+        "count": results.len(),
+        "results": results
+    }))))
+}
+
+/// Verify admin token
+fn verify_token(query: &AuthQuery, admin_token: &str) -> Result<(), StatusCode> {
+    if query.token == admin_token {
+        Ok(())
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+/// Main admin dashboard HTML page
+async fn admin_dashboard_page(
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let html = include_str!("../../static/admin_dashboard.html");
+    let cookie = state
+        .cookies
+        .session_cookie(SESSION_COOKIE, &admin_pages::encode(&state.admin_token), SESSION_MAX_AGE_SECS);
+    Ok(([(header::SET_COOKIE, cookie)], Html(html.to_string())))
+}
+
+/// Get dashboard data (users, stats, etc.)
+async fn get_dashboard_data(
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let data = state
+        .admin_service
+        .get_dashboard_data()
+        .await
+        .map_err(|e| {
+            log::error!("Failed to get dashboard data: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok((StatusCode::OK, axum::Json(data)))
+}
+
+/// Get per-route request counts and latency
+async fn get_route_metrics(
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let routes: Vec<serde_json::Value> = state
+        .route_metrics
+        .snapshot()
+        .into_iter()
+        .map(|(route, stats)| serde_json::json!({ "route": route, "stats": stats }))
+        .collect();
+
+    Ok((StatusCode::OK, axum::Json(routes)))
+}
+
+/// Usage gauges in Prometheus text format (scrape with `params: { token: [...] }`)
+async fn get_usage_metrics(
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    Ok((
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.usage_metrics.render(),
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct WebhookListQuery {
+    token: String,
+    limit: Option<i32>,
+    #[serde(default)]
+    failed: bool,
+}
+
+/// List stored raw webhook payloads (newest first, optionally only parse failures)
+async fn list_webhook_payloads(
+    Query(query): Query<WebhookListQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    if query.token != state.admin_token {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let payloads = state
+        .admin_service
+        .db
+        .get_recent_webhook_payloads(query.limit.unwrap_or(50).min(500), query.failed)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to list webhook payloads: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok((StatusCode::OK, axum::Json(payloads)))
+}
+
+/// Recent nightly maintenance runs with per-step durations
+async fn list_maintenance_runs(
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let runs = state
+        .admin_service
+        .db
+        .get_recent_maintenance_runs(30)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to list maintenance runs: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok((StatusCode::OK, axum::Json(runs)))
+}
+
+#[derive(Deserialize)]
+struct ReminderVariantQuery {
+    token: String,
+    days: Option<i64>,
+}
+
+/// Conversion per reminder wording: sends, how many were followed by a meal / water log within
+/// the window, and which variants are currently being sent
+async fn reminder_variant_report(
+    Query(query): Query<ReminderVariantQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    if query.token != state.admin_token {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    let days = query.days.unwrap_or(30).clamp(1, 365);
+    let since = chrono::Utc::now() - chrono::Duration::days(days);
+
+    let stats = state
+        .admin_service
+        .db
+        .get_reminder_variant_stats(since, reminder_copy::CONVERSION_WINDOW_HOURS)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to build reminder variant report: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let variants: Vec<serde_json::Value> = stats
+        .iter()
+        .map(|s| {
+            serde_json::json!({
+                "reminder_type": s.reminder_type,
+                "variant": s.variant,
+                "sends": s.sends,
+                "conversions": s.conversions,
+                "conversion_rate": (s.conversion_rate() * 1000.0).round() / 1000.0,
+                "pending": s.pending,
+            })
+        })
+        .collect();
+    let active: serde_json::Map<String, serde_json::Value> = ReminderKind::ALL
+        .iter()
+        .map(|kind| (kind.as_str().to_string(), kind.variants().iter().map(|c| c.variant).collect()))
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "days": days,
+        "window_hours": reminder_copy::CONVERSION_WINDOW_HOURS,
+        "variants": variants,
+        "active": active,
+    })))
+}
+
+/// Replay a stored payload through the handler in dry-run mode (shadow schema, no sends)
+async fn replay_webhook_payload(
+    Path(id): Path<i64>,
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let report = state.replayer.replay(id).await.map_err(|e| {
+        log::error!("Failed to replay webhook payload {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    log::info!("🧪 Admin replayed webhook payload {} (would send {} messages)", id, report.would_send.len());
+
+    Ok((StatusCode::OK, axum::Json(report)))
+}
+
+/// Test console: run a made-up inbound message for a number through the handler in dry-run mode
+async fn simulate_webhook_message(
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+    Json(message): Json<SimulatedMessage>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let report = state.replayer.simulate(&message).await.map_err(|e| {
+        log::error!("Failed to simulate message for {}: {}", message.phone, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    match report {
+        Ok(report) => {
+            log::info!(
+                "🧪 Admin simulated a message for {} (would send {} messages)",
+                report.phone,
+                report.would_send.len()
+            );
+            Ok((StatusCode::OK, Json(serde_json::to_value(report).unwrap_or_default())))
+        }
+        Err(error) => Ok((StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": error })))),
+    }
+}
+
+/// Get meals for a specific user
+async fn get_user_meals(
+    Path(phone): Path<String>,
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let meals = state
+        .admin_service
+        .get_user_meals(&phone, 50)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to get user meals: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok((StatusCode::OK, axum::Json(meals)))
+}
+
+#[derive(Deserialize)]
+pub struct ConversationPageQuery {
+    token: String,
+    limit: Option<i32>,
+    /// RFC 3339 timestamp; pass `created_at` of the last (oldest) row of the previous page
+    before: Option<chrono::DateTime<chrono::Utc>>,
+    /// `id` of that same row; without it paging falls back to strictly older timestamps
+    before_id: Option<i64>,
+}
+
+/// Get conversations for a specific user (newest first, paginated with `before` + `before_id`)
+async fn get_user_conversations(
+    Path(phone): Path<String>,
+    Query(query): Query<ConversationPageQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    if query.token != state.admin_token {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let cursor = query.before.map(|created_at| match query.before_id {
+        Some(id) => ConversationCursor { created_at, id },
+        None => ConversationCursor::before_time(created_at),
+    });
+    let conversations = state
+        .admin_service
+        .get_user_conversation_page(&phone, cursor, query.limit.unwrap_or(100).clamp(1, 500))
+        .await
+        .map_err(|e| {
+            log::error!("Failed to get user conversations: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok((StatusCode::OK, axum::Json(conversations)))
+}
+
+#[derive(Deserialize)]
+pub struct SettingsHistoryQuery {
+    token: String,
+    limit: Option<i64>,
+    /// RFC 3339 timestamp: also return the settings in effect at that moment
+    at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Settings snapshots of a user (newest first), optionally with the settings at a given moment
+async fn get_user_settings_history(
+    Path(phone): Path<String>,
+    Query(query): Query<SettingsHistoryQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    if query.token != state.admin_token {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    let db = &state.admin_service.db;
+    let internal_error = |e: anyhow::Error| {
+        log::error!("Failed to load settings history for {}: {}", phone, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+
+    let history = db
+        .get_settings_snapshots(&phone, None, query.limit.unwrap_or(50).clamp(1, 500))
+        .await
+        .map_err(internal_error)?;
+    let settings_at = match query.at {
+        Some(at) => Some(
+            settings_history::settings_at(db, &phone, at)
+                .await
+                .map_err(internal_error)?
+                .ok_or(StatusCode::NOT_FOUND)?,
+        ),
+        None => None,
+    };
+
+    Ok((
+        StatusCode::OK,
+        Json(serde_json::json!({ "at": query.at, "settings_at": settings_at, "history": history })),
+    ))
+}
+
+/// Toggle user active status
+async fn toggle_user_active(
+    Path(phone): Path<String>,
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let new_status = state
+        .admin_service
+        .toggle_user_active(&phone)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to toggle user active status: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    log::info!("User {} active status toggled to: {}", phone, new_status);
+
+    Ok((StatusCode::OK, axum::Json(serde_json::json!({
+        "is_active": new_status
+    }))))
+}
+
+/// Reset user completely - deletes all data and resets to fresh state
+async fn reset_user(
+    Path(phone): Path<String>,
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    state
+        .admin_service
+        .reset_user(&phone)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to reset user {}: {}", phone, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    log::warn!("🔄 Admin reset user: {}", phone);
+
+    Ok((StatusCode::OK, axum::Json(serde_json::json!({
+        "success": true,
+        "message": format!("User {} has been reset successfully", phone)
+    }))))
+}
+
+#[derive(Deserialize)]
+struct SetCoachRequest {
+    /// Coach/dietitian WhatsApp number, or null to unlink
+    coach_phone: Option<String>,
+}
+
+/// Link a coach to a user. Summaries are only forwarded after the user opts in with "koc ac".
+async fn set_user_coach(
+    Path(phone): Path<String>,
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+    axum::Json(payload): axum::Json<SetCoachRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let coach_phone = payload.coach_phone.as_deref().map(str::trim).filter(|p| !p.is_empty());
+
+    state
+        .admin_service
+        .db
+        .update_coach(&phone, coach_phone)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to update coach for {}: {}", phone, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    log::info!("👩‍⚕️ Admin set coach for {}: {:?}", phone, coach_phone);
+
+    Ok((StatusCode::OK, axum::Json(serde_json::json!({
+        "coach_phone": coach_phone,
+        "coach_sharing": false
+    }))))
+}
+
+/// Changelog entries, newest first, plus the running version
+async fn list_changelog(
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let entries = state.admin_service.db.get_changelog_entries().await.map_err(|e| {
+        log::error!("Failed to list changelog: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((StatusCode::OK, axum::Json(serde_json::json!({
+        "running_version": tavari_core::VERSION,
+        "entries": entries
+    }))))
+}
+
+#[derive(Deserialize)]
+struct ChangelogRequest {
+    version: String,
+    message: String,
+    /// false: keep the entry but don't message users
+    #[serde(default = "default_announce")]
+    announce: bool,
+}
+
+fn default_announce() -> bool {
+    true
+}
+
+/// Create or edit the entry for a version. It is announced once that version is running.
+async fn upsert_changelog(
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+    axum::Json(payload): axum::Json<ChangelogRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let version = payload.version.trim().trim_start_matches('v');
+    if version.is_empty() || payload.message.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let entry = state
+        .admin_service
+        .db
+        .upsert_changelog_entry(version, payload.message.trim(), payload.announce)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to save changelog {}: {}", version, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    log::info!("📝 Admin saved changelog v{} (announce={})", entry.version, entry.announce);
+    Ok((StatusCode::OK, axum::Json(entry)))
+}
+
+async fn delete_changelog(
+    Path(id): Path<i64>,
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let deleted = state.admin_service.db.delete_changelog_entry(id).await.map_err(|e| {
+        log::error!("Failed to delete changelog {}: {}", id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    if deleted {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+async fn list_country_defaults(
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let defaults = state.admin_service.db.get_country_defaults().await.map_err(|e| {
+        log::error!("Failed to list country defaults: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((StatusCode::OK, Json(defaults)))
+}
+
+/// Create or replace the defaults for a calling code; only users created afterwards get them
+async fn upsert_country_defaults(
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+    Json(mut payload): Json<CountryDefaults>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    payload.prefix = payload.prefix.trim().trim_start_matches('+').to_string();
+    if let Err(error) = country_defaults::validate(&payload) {
+        return Ok((StatusCode::BAD_REQUEST, Json(serde_json::json!({ "error": error }))));
+    }
+
+    state.admin_service.db.upsert_country_defaults(&payload).await.map_err(|e| {
+        log::error!("Failed to save country defaults {}: {}", payload.prefix, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    log::info!("🌍 Admin saved country defaults for +{} ({}, {})", payload.prefix, payload.timezone, payload.language.as_str());
+    Ok((StatusCode::OK, Json(serde_json::json!(payload))))
+}
+
+async fn delete_country_defaults(
+    Path(prefix): Path<String>,
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let deleted = state.admin_service.db.delete_country_defaults(prefix.trim_start_matches('+')).await.map_err(|e| {
+        log::error!("Failed to delete country defaults {}: {}", prefix, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    if deleted {
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(StatusCode::NOT_FOUND)
+    }
+}
+
+#[derive(Deserialize)]
+struct SendMessageRequest {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct BroadcastRequest {
+    target: String,  // "all" or "active"
+    message: String,
+}
+
+const BROADCAST_RATE_LIMIT_WAIT: std::time::Duration = std::time::Duration::from_secs(5);
+const BROADCAST_MAX_RATE_LIMIT_WAIT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// HTTP status for a failed admin send, so the caller can tell "retry later" from "will never work"
+fn send_error_status(error: &anyhow::Error) -> StatusCode {
+    match BirdError::find(error) {
+        Some(BirdError::TemplateRequired(_)) => StatusCode::CONFLICT,
+        Some(BirdError::InvalidRecipient(_)) => StatusCode::UNPROCESSABLE_ENTITY,
+        Some(BirdError::RateLimited { .. }) => StatusCode::TOO_MANY_REQUESTS,
+        Some(BirdError::ChannelDown(_)) => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Send message to specific user
+async fn send_user_message(
+    Path(phone): Path<String>,
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+    Json(payload): Json<SendMessageRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let path = state
+        .whatsapp
+        .deliver(&phone, &payload.message)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to send message to {}: {}", phone, e);
+            send_error_status(&e)
+        })?;
+
+    log::info!("Admin sent message to {} ({})", phone, path.as_str());
+
+    Ok((StatusCode::OK, axum::Json(serde_json::json!({
+        "success": true,
+        "path": path.as_str()
+    }))))
+}
+
+/// Broadcast message to all or active users
+async fn broadcast_message(
+    Query(query): Query<AuthQuery>,
+    State(state): State<AdminState>,
+    Json(payload): Json<BroadcastRequest>,
+) -> Result<impl IntoResponse, StatusCode> {
+    verify_token(&query, &state.admin_token)?;
+
+    let users = if payload.target == "active" {
+        state.admin_service.db.get_active_users().await
+    } else {
+        state.admin_service.db.get_all_users().await
+    }
+    .map_err(|e| {
+        log::error!("Failed to get users for broadcast: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    log::info!("Broadcasting message to {} users (target: {})", users.len(), payload.target);
+
+    let mut sent_count = 0;
+    let mut template_fallback_count = 0;
+    let mut failed_count = 0;
+    let mut failed_by_reason: std::collections::BTreeMap<&'static str, u32> = std::collections::BTreeMap::new();
+
+    for user in users {
+        let mut result = state.whatsapp.deliver(&user.phone_number, &payload.message).await;
+
+        // Rate limit: wait as long as Bird asks and try this recipient once more
+        if let Err(e) = &result {
+            if let Some(BirdError::RateLimited { retry_after, .. }) = BirdError::find(e) {
+                let wait = retry_after.unwrap_or(BROADCAST_RATE_LIMIT_WAIT).min(BROADCAST_MAX_RATE_LIMIT_WAIT);
+                log::warn!("⏳ Broadcast rate limited, waiting {:?}", wait);
+                tokio::time::sleep(wait).await;
+                result = state.whatsapp.deliver(&user.phone_number, &payload.message).await;
+            }
+        }
+
+        match result {
+            Ok(path) => {
+                sent_count += 1;
+                if path == SendPath::TemplateFallback {
+                    template_fallback_count += 1;
+                }
+                log::debug!("Broadcast sent to {} ({})", user.phone_number, path.as_str());
+            }
+            Err(e) => {
+                failed_count += 1;
+                let reason = BirdError::find(&e).map(|b| b.kind()).unwrap_or("other");
+                *failed_by_reason.entry(reason).or_insert(0) += 1;
+                log::error!("Failed to send broadcast to {}: {}", user.phone_number, e);
+            }
+        }
+    }
+
+    log::info!("Broadcast complete: {} sent, {} failed", sent_count, failed_count);
+
+    Ok((StatusCode::OK, axum::Json(serde_json::json!({
+        "sent": sent_count,
+        "template_fallback": template_fallback_count,
+        "failed": failed_count,
+        "failed_by_reason": failed_by_reason
+    }))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_token_from_cookie_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, "theme=dark; admin_session=abc123".parse().unwrap());
+        assert_eq!(session_token(&headers), Some("abc123"));
+
+        headers.insert(header::COOKIE, "admin_session_old=abc123".parse().unwrap());
+        assert_eq!(session_token(&headers), None);
+    }
+}
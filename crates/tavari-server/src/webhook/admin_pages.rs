@@ -0,0 +1,305 @@
+use askama::Template;
+use axum::{
+    extract::{Form, Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Redirect},
+};
+use serde::Deserialize;
+
+use super::admin::AdminState;
+use crate::services::bird::SendPath;
+use crate::models::{Conversation, Meal, SettingsSnapshot, User};
+
+/// Server-side rendered user detail page (works without client-side JS)
+#[derive(Template)]
+#[template(path = "admin_user_detail.html")]
+pub struct UserDetailTemplate {
+    token: String,
+    user: User,
+    display_name: String,
+    created_at: String,
+    settings: Vec<(&'static str, String)>,
+    meals: Vec<MealRow>,
+    conversations: Vec<ConversationRow>,
+    settings_changes: Vec<SettingsChangeRow>,
+    notice: Option<String>,
+}
+
+pub struct MealRow {
+    meal_type: String,
+    calories: String,
+    description: String,
+    image: Option<MealImage>,
+    created_at: String,
+}
+
+pub struct MealImage {
+    url: String,
+    thumbnail_url: String,
+}
+
+pub struct ConversationRow {
+    direction: String,
+    message_type: String,
+    content: String,
+    created_at: String,
+}
+
+/// One settings snapshot in the "Geçmiş Ayarlar" card
+pub struct SettingsChangeRow {
+    created_at: String,
+    changes: Vec<String>,  // "lunch_time: 12:30 → 13:00"
+}
+
+#[derive(Deserialize)]
+pub struct PageQuery {
+    token: String,
+    notice: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SendMessageForm {
+    message: String,
+}
+
+impl MealRow {
+    fn from_meal(meal: Meal, tz: chrono_tz::Tz, token: &str) -> Self {
+        // Images are served from /images (see main.rs), only the file name is needed;
+        // the list shows the thumbnail and links to the original
+        let image = meal.image_path.as_deref().and_then(|path| {
+            let name = std::path::Path::new(path).file_name()?.to_string_lossy().into_owned();
+            Some(MealImage {
+                url: format!("/images/{}", name),
+                thumbnail_url: format!("/admin/api/images/{}/thumbnail?token={}", name, encode(token)),
+            })
+        });
+
+        Self {
+            meal_type: meal.meal_type.to_string(),
+            calories: format!("{:.0}", meal.calories),
+            description: meal.description,
+            image,
+            created_at: meal.created_at.with_timezone(&tz).format("%d.%m.%Y %H:%M").to_string(),
+        }
+    }
+}
+
+impl ConversationRow {
+    fn from_conversation(conversation: Conversation, tz: chrono_tz::Tz) -> Self {
+        let message_type = serde_json::to_string(&conversation.message_type)
+            .unwrap_or_default()
+            .trim_matches('"')
+            .to_string();
+
+        Self {
+            direction: conversation.direction.to_string(),
+            message_type,
+            content: conversation.content,
+            created_at: conversation.created_at.with_timezone(&tz).format("%d.%m.%Y %H:%M").to_string(),
+        }
+    }
+}
+
+impl SettingsChangeRow {
+    fn from_snapshot(snapshot: SettingsSnapshot, tz: chrono_tz::Tz) -> Self {
+        let value = |change: &serde_json::Value, side: &str| match change.get(side) {
+            Some(serde_json::Value::String(text)) => text.clone(),
+            Some(serde_json::Value::Null) | None => "-".to_string(),
+            Some(other) => other.to_string(),
+        };
+        let changes = snapshot
+            .changed
+            .as_object()
+            .map(|changed| {
+                changed
+                    .iter()
+                    .map(|(key, change)| format!("{}: {} → {}", key, value(change, "from"), value(change, "to")))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            created_at: snapshot.created_at.with_timezone(&tz).format("%d.%m.%Y %H:%M").to_string(),
+            changes,
+        }
+    }
+}
+
+fn user_settings(user: &User) -> Vec<(&'static str, String)> {
+    let on_off = |enabled: bool| if enabled { "✅" } else { "❌" };
+    let or_unset = |value: &Option<String>| value.clone().unwrap_or_else(|| "Ayarlanmamış".to_string());
+
+    vec![
+        ("Kahvaltı", format!("{} {}", or_unset(&user.breakfast_time), on_off(user.breakfast_reminder))),
+        ("Öğle", format!("{} {}", or_unset(&user.lunch_time), on_off(user.lunch_reminder))),
+        ("Akşam", format!("{} {}", or_unset(&user.dinner_time), on_off(user.dinner_reminder))),
+        ("Su hatırlatma", on_off(user.water_reminder).to_string()),
+        ("Kalori hedefi", format!("{} kcal", user.daily_calorie_goal.unwrap_or(2000))),
+        ("Su hedefi", format!("{} ml", user.daily_water_goal.unwrap_or(2000))),
+        (
+            "Sessiz saatler",
+            format!(
+                "{} - {}",
+                user.silent_hours_start.as_deref().unwrap_or("23:00"),
+                user.silent_hours_end.as_deref().unwrap_or("07:00")
+            ),
+        ),
+        ("Zaman dilimi", user.timezone.clone()),
+    ]
+}
+
+fn notice_text(code: &str) -> Option<String> {
+    let text = match code {
+        "toggled" => "✅ Kullanıcı durumu güncellendi.",
+        "reset" => "🔄 Kullanıcı sıfırlandı.",
+        "sent" => "📤 Mesaj gönderildi.",
+        "sent_template" => "📨 Kullanıcı 24 saat penceresi dışında; mesaj yerine yeniden etkileşim şablonu gönderildi.",
+        "send_failed" => "❌ Mesaj gönderilemedi.",
+        _ => return None,
+    };
+    Some(text.to_string())
+}
+
+/// Percent-encode a path segment or query value ("&", "#", "+" or "%" in the token would
+/// otherwise change the URL)
+pub(crate) fn encode(value: &str) -> String {
+    askama::filters::urlencode_strict(value).unwrap_or_default()
+}
+
+fn back_to_page(phone: &str, token: &str, notice: &str) -> Redirect {
+    Redirect::to(&format!("/admin/users/{}?token={}&notice={}", encode(phone), encode(token), notice))
+}
+
+/// GET /admin/users/:phone - user detail page
+pub async fn user_detail_page(
+    Path(phone): Path<String>,
+    Query(query): Query<PageQuery>,
+    State(state): State<AdminState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    if query.token != state.admin_token {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let user = state
+        .admin_service
+        .db
+        .get_user(&phone)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to load user {}: {}", phone, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+
+    let meals = state.admin_service.get_user_meals(&phone, 20).await.map_err(|e| {
+        log::error!("Failed to get user meals: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let conversations = state.admin_service.get_user_conversations(&phone, 100).await.map_err(|e| {
+        log::error!("Failed to get user conversations: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let settings_changes = state.admin_service.db.get_settings_snapshots(&phone, None, 20).await.map_err(|e| {
+        log::error!("Failed to get settings history: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let meals = meals.into_iter().map(|m| MealRow::from_meal(m, tz, &query.token)).collect();
+
+    Ok(UserDetailTemplate {
+        token: query.token,
+        display_name: user.name.clone().unwrap_or_else(|| user.phone_number.clone()),
+        created_at: user.created_at.with_timezone(&tz).format("%d.%m.%Y").to_string(),
+        settings: user_settings(&user),
+        meals,
+        conversations: conversations
+            .into_iter()
+            .map(|c| ConversationRow::from_conversation(c, tz))
+            .collect(),
+        settings_changes: settings_changes
+            .into_iter()
+            .map(|s| SettingsChangeRow::from_snapshot(s, tz))
+            .collect(),
+        notice: query.notice.as_deref().and_then(notice_text),
+        user,
+    })
+}
+
+/// POST /admin/users/:phone/actions/toggle-active
+pub async fn toggle_active_action(
+    Path(phone): Path<String>,
+    Query(query): Query<PageQuery>,
+    State(state): State<AdminState>,
+) -> Result<Redirect, StatusCode> {
+    if query.token != state.admin_token {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    state.admin_service.toggle_user_active(&phone).await.map_err(|e| {
+        log::error!("Failed to toggle user active status: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(back_to_page(&phone, &query.token, "toggled"))
+}
+
+/// POST /admin/users/:phone/actions/reset
+pub async fn reset_action(
+    Path(phone): Path<String>,
+    Query(query): Query<PageQuery>,
+    State(state): State<AdminState>,
+) -> Result<Redirect, StatusCode> {
+    if query.token != state.admin_token {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    state.admin_service.reset_user(&phone).await.map_err(|e| {
+        log::error!("Failed to reset user {}: {}", phone, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    log::warn!("🔄 Admin reset user: {}", phone);
+    Ok(back_to_page(&phone, &query.token, "reset"))
+}
+
+/// POST /admin/users/:phone/actions/send-message
+pub async fn send_message_action(
+    Path(phone): Path<String>,
+    Query(query): Query<PageQuery>,
+    State(state): State<AdminState>,
+    Form(form): Form<SendMessageForm>,
+) -> Result<Redirect, StatusCode> {
+    if query.token != state.admin_token {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let notice = match state.whatsapp.deliver(&phone, &form.message).await {
+        Ok(SendPath::Text) => "sent",
+        Ok(SendPath::TemplateFallback) => "sent_template",
+        Err(e) => {
+            log::error!("Failed to send message to {}: {}", phone, e);
+            "send_failed"
+        }
+    };
+
+    Ok(back_to_page(&phone, &query.token, notice))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::IntoResponse;
+
+    #[test]
+    fn test_back_to_page_encodes_token() {
+        let response = back_to_page("+905551234567", "a&b#c+d%e", "reset").into_response();
+        assert_eq!(
+            response.headers()[axum::http::header::LOCATION],
+            "/admin/users/%2B905551234567?token=a%26b%23c%2Bd%25e&notice=reset"
+        );
+    }
+}
@@ -0,0 +1,190 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::handlers::{MessageHandler, QuickLog};
+use crate::models::UnitSystem;
+use crate::services::units::{format_water, parse_water_ml};
+use crate::services::Database;
+
+/// Quick-log water amounts outside this range are almost always typos ("2500" meant as 250)
+const WATER_ML_RANGE: std::ops::RangeInclusive<i32> = 50..=3000;
+const MAX_MEAL_CALORIES: f64 = 5000.0;
+const MAX_DESCRIPTION_CHARS: usize = 300;
+
+#[derive(Clone)]
+pub struct QuickLogState {
+    pub message_handler: Arc<MessageHandler>,
+    pub db: Arc<Database>,
+}
+
+/// `amount` comes as a number from JSON widgets and as text ("250ml", "8oz") from Shortcuts
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum Amount {
+    Number(f64),
+    Text(String),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QuickLogRequest {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub amount: Option<Amount>,
+    pub description: Option<String>,
+    pub calories: Option<f64>,
+}
+
+/// `POST /api/v1/quicklog` with `Authorization: Bearer <token>` (token from the `kisayol` command)
+pub fn create_quicklog_router(message_handler: Arc<MessageHandler>, db: Arc<Database>) -> Router {
+    Router::new()
+        .route("/api/v1/quicklog", post(quick_log))
+        .with_state(QuickLogState { message_handler, db })
+}
+
+/// Validate the request body into a handler entry; the error is the user-facing reason
+pub fn parse_request(request: &QuickLogRequest, units: UnitSystem) -> Result<QuickLog, String> {
+    match request.kind.trim().to_lowercase().as_str() {
+        "water" | "su" => {
+            let ml = match &request.amount {
+                Some(Amount::Number(value)) => parse_water_ml(&value.to_string(), units),
+                Some(Amount::Text(text)) => parse_water_ml(text, units),
+                None => return Err("amount is required for water".to_string()),
+            }
+            .ok_or_else(|| "amount must be like 250, \"250ml\", \"8oz\" or \"0.5l\"".to_string())?;
+
+            if !WATER_ML_RANGE.contains(&ml) {
+                return Err(format!(
+                    "amount must be between {} and {} ml",
+                    WATER_ML_RANGE.start(),
+                    WATER_ML_RANGE.end()
+                ));
+            }
+            Ok(QuickLog::Water { ml })
+        }
+        "meal" | "ogun" | "öğün" => {
+            let description = request.description.as_deref().map(str::trim).unwrap_or_default();
+            if description.is_empty() {
+                return Err("description is required for meal".to_string());
+            }
+            if description.chars().count() > MAX_DESCRIPTION_CHARS {
+                return Err(format!("description must be at most {} characters", MAX_DESCRIPTION_CHARS));
+            }
+            if let Some(calories) = request.calories {
+                if !(0.0..=MAX_MEAL_CALORIES).contains(&calories) {
+                    return Err(format!("calories must be between 0 and {}", MAX_MEAL_CALORIES));
+                }
+            }
+            Ok(QuickLog::Meal { description: description.to_string(), calories: request.calories })
+        }
+        _ => Err("type must be \"water\" or \"meal\"".to_string()),
+    }
+}
+
+fn error(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(serde_json::json!({ "ok": false, "error": message.into() }))).into_response()
+}
+
+async fn quick_log(
+    State(state): State<QuickLogState>,
+    headers: HeaderMap,
+    Json(request): Json<QuickLogRequest>,
+) -> Response {
+    let Some(token) = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+    else {
+        return error(StatusCode::UNAUTHORIZED, "missing bearer token");
+    };
+
+    let user = match state.db.find_phone_by_quicklog_token(token).await {
+        Ok(Some(phone)) => match state.db.get_user(&phone).await {
+            Ok(Some(user)) => user,
+            Ok(None) => return error(StatusCode::UNAUTHORIZED, "invalid token"),
+            Err(e) => {
+                log::error!("❌ Quick-log user lookup failed: {}", e);
+                return error(StatusCode::INTERNAL_SERVER_ERROR, "internal error");
+            }
+        },
+        Ok(None) => return error(StatusCode::UNAUTHORIZED, "invalid token"),
+        Err(e) => {
+            log::error!("❌ Quick-log token lookup failed: {}", e);
+            return error(StatusCode::INTERNAL_SERVER_ERROR, "internal error");
+        }
+    };
+
+    if !user.is_active {
+        return error(StatusCode::FORBIDDEN, "account is inactive");
+    }
+
+    let entry = match parse_request(&request, user.units) {
+        Ok(entry) => entry,
+        Err(reason) => return error(StatusCode::BAD_REQUEST, reason),
+    };
+
+    if let Err(e) = state.message_handler.quick_log(&user.phone_number, entry).await {
+        log::error!("❌ Quick-log failed for {}: {}", user.phone_number, e);
+        return error(StatusCode::INTERNAL_SERVER_ERROR, "could not save the entry");
+    }
+    log::info!("📲 Quick-log saved for {}", user.phone_number);
+
+    let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
+    let today = chrono::Utc::now().with_timezone(&user_tz).date_naive();
+    match state.db.get_daily_stats(&user.phone_number, today).await {
+        Ok(stats) => Json(serde_json::json!({
+            "ok": true,
+            "today": {
+                "date": stats.date,
+                "calories": stats.total_calories.round(),
+                "calorie_goal": user.daily_calorie_goal.unwrap_or(2000),
+                "water_ml": stats.total_water_ml,
+                "water": format_water(stats.total_water_ml, user.units),
+                "water_goal_ml": user.daily_water_goal.unwrap_or(2000),
+                "meals": stats.meals_count,
+            }
+        }))
+        .into_response(),
+        // Kayıt yapıldı; özet alınamasa da başarılı say
+        Err(_) => Json(serde_json::json!({ "ok": true })).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(json: &str) -> QuickLogRequest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_parse_request() {
+        assert_eq!(
+            parse_request(&request(r#"{"type":"water","amount":250}"#), UnitSystem::Metric),
+            Ok(QuickLog::Water { ml: 250 })
+        );
+        assert_eq!(
+            parse_request(&request(r#"{"type":"water","amount":"8oz"}"#), UnitSystem::Metric),
+            Ok(QuickLog::Water { ml: 237 })
+        );
+        assert!(parse_request(&request(r#"{"type":"water","amount":25000}"#), UnitSystem::Metric).is_err());
+        assert!(parse_request(&request(r#"{"type":"water"}"#), UnitSystem::Metric).is_err());
+
+        assert_eq!(
+            parse_request(&request(r#"{"type":"meal","description":" menemen ","calories":350}"#), UnitSystem::Metric),
+            Ok(QuickLog::Meal { description: "menemen".into(), calories: Some(350.0) })
+        );
+        assert!(parse_request(&request(r#"{"type":"meal","description":""}"#), UnitSystem::Metric).is_err());
+        assert!(parse_request(&request(r#"{"type":"meal","description":"x","calories":-5}"#), UnitSystem::Metric).is_err());
+        assert!(parse_request(&request(r#"{"type":"weight","amount":70}"#), UnitSystem::Metric).is_err());
+    }
+}
@@ -0,0 +1,298 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+use super::{handle_bird_webhook, parse_bird_webhook, MediaSource, ParsedWebhook};
+use crate::handlers::MessageHandler;
+use crate::services::allowlist::normalize_phone;
+use crate::services::events::EventDispatcher;
+use crate::services::http::{shared_client, stream_to_file, HttpSettings};
+use crate::services::image_store::ImageStore;
+use crate::services::whatsapp::{RecordedMessage, RecordingWhatsAppClient};
+use crate::services::{Database, OpenRouterService};
+
+/// Schema used for dry-run replays; all replay DB writes land here
+const SHADOW_SCHEMA: &str = "replay_shadow";
+
+/// Result of a dry-run replay of a stored webhook payload
+#[derive(Debug, Serialize)]
+pub struct ReplayReport {
+    pub payload_id: i64,
+    pub message_id: Option<String>,
+    pub parsed: bool,
+    pub error: Option<String>,
+    pub would_send: Vec<RecordedMessage>,
+}
+
+/// Inbound message typed into the admin test console; exactly one of text / image_url / button_id
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimulatedMessage {
+    pub phone: String,
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Public image URL; fetched without Bird credentials
+    #[serde(default)]
+    pub image_url: Option<String>,
+    /// Photo caption, or the title of a button / list reply
+    #[serde(default)]
+    pub caption: Option<String>,
+    /// Button or list reply id ("onb_...", "goalrev_keep", "water_250")
+    #[serde(default)]
+    pub button_id: Option<String>,
+}
+
+impl SimulatedMessage {
+    /// Bird "whatsapp.inbound" body for a text or button message, so it goes through the real parser
+    pub fn webhook_body(&self, phone: &str, message_id: &str) -> Result<String, String> {
+        let body = match (&self.text, &self.image_url, &self.button_id) {
+            (Some(text), None, None) => serde_json::json!({ "type": "text", "text": { "text": text } }),
+            (None, None, Some(id)) => serde_json::json!({
+                "type": "interactive",
+                "interactive": {
+                    "type": "button_reply",
+                    "buttonReply": { "id": id, "title": self.caption.as_deref().unwrap_or(id) }
+                }
+            }),
+            (None, Some(_), None) => return Err("image messages are not sent as a webhook body".to_string()),
+            _ => return Err("exactly one of text, image_url or button_id is required".to_string()),
+        };
+
+        Ok(serde_json::json!({
+            "service": "channels",
+            "event": "whatsapp.inbound",
+            "payload": {
+                "id": message_id,
+                "sender": { "contact": { "identifierValue": phone } },
+                "body": body
+            }
+        })
+        .to_string())
+    }
+}
+
+/// What the bot would have answered to a simulated message
+#[derive(Debug, Serialize)]
+pub struct SimulationReport {
+    pub phone: String,
+    pub message_id: String,
+    pub error: Option<String>,
+    pub would_send: Vec<RecordedMessage>,
+}
+
+/// Replays stored webhook bodies through the real handler without sending anything
+/// to WhatsApp and without touching production tables
+pub struct WebhookReplayer {
+    database_url: String,
+    live_db: Arc<Database>,
+    shadow_db: OnceCell<Arc<Database>>,
+}
+
+/// AI for dry-runs: text-only mode never sends a request, so replays don't spend AI quota
+/// (answers follow the same path as TEXT_ONLY_MODE)
+fn offline_ai() -> OpenRouterService {
+    OpenRouterService::new(String::new(), String::new()).with_text_only(true)
+}
+
+impl WebhookReplayer {
+    pub fn new(database_url: String, live_db: Arc<Database>) -> Self {
+        Self {
+            database_url,
+            live_db,
+            shadow_db: OnceCell::new(),
+        }
+    }
+
+    /// Shadow database is created lazily on first replay
+    async fn shadow_db(&self) -> Result<Arc<Database>> {
+        let db = self
+            .shadow_db
+            .get_or_try_init(|| async {
+                log::info!("🧪 Initializing replay shadow schema '{}'", SHADOW_SCHEMA);
+                Database::new_with_schema(&self.database_url, SHADOW_SCHEMA)
+                    .await
+                    .map(Arc::new)
+            })
+            .await?;
+        Ok(db.clone())
+    }
+
+    pub async fn replay(&self, payload_id: i64) -> Result<ReplayReport> {
+        let stored = self
+            .live_db
+            .get_webhook_payload(payload_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Webhook payload {} not found", payload_id))?;
+
+        let parsed = match parse_bird_webhook(&stored.body) {
+            Ok(ParsedWebhook::Message(w)) => Ok(*w),
+            Ok(ParsedWebhook::Unrecognized { reason }) => Err(reason),
+            Err(e) => Err(format!("Parse error: {}", e)),
+        };
+        let webhook = match parsed {
+            Ok(w) => w,
+            Err(error) => {
+                return Ok(ReplayReport {
+                    payload_id,
+                    message_id: stored.message_id,
+                    parsed: false,
+                    error: Some(error),
+                    would_send: Vec::new(),
+                });
+            }
+        };
+
+        let phone = webhook.payload.sender.contact.identifier_value.clone();
+        let (handler, recorder) = self.dry_run_handler(&phone).await?;
+
+        log::info!("🧪 Replaying webhook payload {} (dry-run)", payload_id);
+        let result = handle_bird_webhook(handler, MediaSource::Disabled, &scratch_images(), webhook).await;
+
+        Ok(ReplayReport {
+            payload_id,
+            message_id: stored.message_id,
+            parsed: true,
+            error: result.err().map(|e| e.to_string()),
+            would_send: recorder.sent_messages(),
+        })
+    }
+
+    /// Handler that writes to the shadow schema and records sends instead of delivering them;
+    /// AI, events and operator e-mails stay offline too
+    async fn dry_run_handler(&self, phone: &str) -> Result<(Arc<MessageHandler>, Arc<RecordingWhatsAppClient>)> {
+        let shadow_db = self.shadow_db().await?;
+
+        // Copy the live user's settings into the shadow schema so the dry-run follows
+        // the same path (onboarding state, goals, timezone) as production does
+        if let Some(user) = self.live_db.get_user(phone).await? {
+            shadow_db.create_user(&user).await?;
+        }
+
+        let recorder = Arc::new(RecordingWhatsAppClient::new());
+        let handler = Arc::new(MessageHandler::new(
+            shadow_db,
+            Arc::new(offline_ai()),
+            recorder.clone(),
+            // Dry-run: don't notify external integrations
+            Arc::new(EventDispatcher::disabled()),
+        ));
+        Ok((handler, recorder))
+    }
+
+    /// Admin test console: run a made-up inbound message through the real pipeline (dry-run).
+    /// Err is a bad request (invalid phone, no content); pipeline failures land in the report.
+    pub async fn simulate(&self, message: &SimulatedMessage) -> Result<Result<SimulationReport, String>> {
+        let Some(phone) = normalize_phone(&message.phone) else {
+            return Ok(Err(format!("invalid phone number: '{}'", message.phone)));
+        };
+        let message_id = format!("console_{}", chrono::Utc::now().timestamp_millis());
+
+        let result = if let (Some(url), None, None) = (&message.image_url, &message.text, &message.button_id) {
+            let (handler, recorder) = self.dry_run_handler(&phone).await?;
+            log::info!("🧪 Console image message for {} (dry-run): {}", phone, url);
+            let result = simulate_image(&handler, &phone, &message_id, url, message.caption.as_deref()).await;
+            (result, recorder)
+        } else {
+            let body = match message.webhook_body(&phone, &message_id) {
+                Ok(body) => body,
+                Err(error) => return Ok(Err(error)),
+            };
+            let webhook = match parse_bird_webhook(&body)? {
+                ParsedWebhook::Message(webhook) => *webhook,
+                ParsedWebhook::Unrecognized { reason } => return Ok(Err(reason)),
+            };
+            let (handler, recorder) = self.dry_run_handler(&phone).await?;
+            log::info!("🧪 Console {} message for {} (dry-run)", webhook.payload.body.msg_type, phone);
+            let result = handle_bird_webhook(handler, MediaSource::Disabled, &scratch_images(), webhook).await;
+            (result, recorder)
+        };
+
+        let (result, recorder) = result;
+        Ok(Ok(SimulationReport {
+            phone,
+            message_id,
+            error: result.err().map(|e| e.to_string()),
+            would_send: recorder.sent_messages(),
+        }))
+    }
+}
+
+/// Dry-run photos go to a scratch directory, never next to the real ones
+fn scratch_images() -> ImageStore {
+    ImageStore::new(std::env::temp_dir().join("tavari_replay_images"))
+}
+
+/// Same steps as an inbound photo after its download, with the file fetched from a public URL
+async fn simulate_image(
+    handler: &MessageHandler,
+    phone: &str,
+    message_id: &str,
+    url: &str,
+    caption: Option<&str>,
+) -> Result<()> {
+    let images = scratch_images();
+    std::fs::create_dir_all(images.dir())?;
+    let path = images.new_image_path(message_id, chrono::Utc::now());
+
+    let response = shared_client().get(url).send().await?.error_for_status()?;
+    stream_to_file(response, Path::new(&path), HttpSettings::global().media_max_bytes).await?;
+
+    handler
+        .handle_message(phone, caption.unwrap_or(""), true, Some(path.to_string_lossy().to_string()))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Language;
+    use crate::services::openrouter::AiGateway;
+
+    fn message(text: Option<&str>, image_url: Option<&str>, button_id: Option<&str>) -> SimulatedMessage {
+        SimulatedMessage {
+            phone: "+90 555 111 22 33".into(),
+            text: text.map(Into::into),
+            image_url: image_url.map(Into::into),
+            caption: None,
+            button_id: button_id.map(Into::into),
+        }
+    }
+
+    #[test]
+    fn test_simulated_message_body() {
+        let body = message(Some("rapor"), None, None).webhook_body("+905551112233", "console_1").unwrap();
+        let ParsedWebhook::Message(webhook) = parse_bird_webhook(&body).unwrap() else {
+            panic!("console body must parse");
+        };
+        assert_eq!(webhook.payload.sender.contact.identifier_value, "+905551112233");
+        assert_eq!(webhook.payload.body.msg_type, "text");
+
+        let body = message(None, None, Some("goalrev_keep")).webhook_body("+905551112233", "console_2").unwrap();
+        let ParsedWebhook::Message(webhook) = parse_bird_webhook(&body).unwrap() else {
+            panic!("console body must parse");
+        };
+        assert_eq!(webhook.payload.body.msg_type, "interactive");
+
+        assert!(message(None, None, None).webhook_body("+905551112233", "console_3").is_err());
+        assert!(message(Some("rapor"), None, Some("goalrev_keep")).webhook_body("+905551112233", "x").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_clients_stay_offline() {
+        // Any request from the dry-run would have to connect here
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let ai = offline_ai().with_gateway(AiGateway { base_url: url.clone(), headers: Vec::new() });
+        assert!(ai.analyze_text_meal("mercimek çorbası", Language::Tr).await.is_err());
+
+        let path = std::env::temp_dir().join(format!("tavari_replay_offline_{}.jpg", std::process::id()));
+        let download = MediaSource::Disabled.download(&format!("{}/media", url), "msg_1", &path, 1024).await;
+        assert!(download.is_err());
+        assert!(!path.exists());
+
+        let connection = tokio::time::timeout(std::time::Duration::from_millis(200), listener.accept()).await;
+        assert!(connection.is_err(), "dry-run made an outbound request");
+    }
+}
@@ -0,0 +1,129 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Inbound webhook message ID, attached to the response by the webhook handler
+/// so the request log line can be correlated with Bird.com deliveries
+#[derive(Debug, Clone)]
+pub struct WebhookMessageId(pub String);
+
+/// Aggregated latency/status numbers for a single route
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RouteStats {
+    pub requests: u64,
+    pub client_errors: u64,
+    pub server_errors: u64,
+    pub total_ms: u64,
+    pub max_ms: u64,
+    pub avg_ms: f64,
+}
+
+impl RouteStats {
+    fn record(&mut self, status: u16, elapsed_ms: u64) {
+        self.requests += 1;
+        self.total_ms += elapsed_ms;
+        self.max_ms = self.max_ms.max(elapsed_ms);
+        self.avg_ms = self.total_ms as f64 / self.requests as f64;
+
+        if (400..500).contains(&status) {
+            self.client_errors += 1;
+        } else if status >= 500 {
+            self.server_errors += 1;
+        }
+    }
+}
+
+/// Per-route latency metrics, keyed by "METHOD /matched/path"
+#[derive(Default)]
+pub struct RouteMetrics {
+    routes: Mutex<HashMap<String, RouteStats>>,
+}
+
+impl RouteMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, route: &str, status: u16, elapsed_ms: u64) {
+        let mut routes = self.routes.lock().unwrap_or_else(|e| e.into_inner());
+        routes.entry(route.to_string()).or_default().record(status, elapsed_ms);
+    }
+
+    /// Snapshot of all route stats (sorted by route for stable output)
+    pub fn snapshot(&self) -> Vec<(String, RouteStats)> {
+        let routes = self.routes.lock().unwrap_or_else(|e| e.into_inner());
+        let mut snapshot: Vec<_> = routes.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        snapshot.sort_by(|a, b| a.0.cmp(&b.0));
+        snapshot
+    }
+}
+
+/// Log method, path, status and latency for every HTTP request
+pub async fn log_requests(
+    State(metrics): State<Arc<RouteMetrics>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let start = Instant::now();
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    // Use the route template (e.g. /admin/api/users/:phone/meals) so phone numbers
+    // don't explode the metrics map
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let response = next.run(request).await;
+
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+    let status = response.status().as_u16();
+    metrics.record(&format!("{} {}", method, route), status, elapsed_ms);
+
+    let message_id = response
+        .extensions()
+        .get::<WebhookMessageId>()
+        .map(|id| format!(" message_id={}", id.0))
+        .unwrap_or_default();
+
+    if status >= 500 {
+        log::error!("🌐 {} {} -> {} ({} ms){}", method, path, status, elapsed_ms, message_id);
+    } else if status >= 400 {
+        log::warn!("🌐 {} {} -> {} ({} ms){}", method, path, status, elapsed_ms, message_id);
+    } else {
+        log::info!("🌐 {} {} -> {} ({} ms){}", method, path, status, elapsed_ms, message_id);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_metrics_aggregation() {
+        let metrics = RouteMetrics::new();
+        metrics.record("POST /webhook/whatsapp", 200, 10);
+        metrics.record("POST /webhook/whatsapp", 422, 30);
+        metrics.record("POST /webhook/whatsapp", 500, 20);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.len(), 1);
+
+        let (route, stats) = &snapshot[0];
+        assert_eq!(route, "POST /webhook/whatsapp");
+        assert_eq!(stats.requests, 3);
+        assert_eq!(stats.client_errors, 1);
+        assert_eq!(stats.server_errors, 1);
+        assert_eq!(stats.max_ms, 30);
+        assert_eq!(stats.avg_ms, 20.0);
+    }
+}
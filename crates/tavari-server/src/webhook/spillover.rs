@@ -0,0 +1,334 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, SystemTime};
+use std::sync::{Arc, Mutex};
+
+use super::server::AppState;
+use super::{handle_bird_webhook, parse_bird_webhook, MediaSource, ParsedWebhook};
+use crate::services::database::is_connection_error;
+use crate::services::image_store::write_atomic;
+
+/// WEBHOOK_SPILLOVER_MAX: bodies kept while Postgres is down (oldest dropped beyond this)
+const DEFAULT_MAX_ENTRIES: usize = 5_000;
+
+/// WEBHOOK_SPILLOVER_RETRY_SECS: how often the buffer checks whether Postgres is back
+const DEFAULT_RETRY_SECS: u64 = 5;
+
+/// A file is being replayed by this (or another) instance sharing the data directory
+const CLAIMED_EXTENSION: &str = "replaying";
+
+/// A claim older than this belongs to an instance that died mid-replay (one replay takes seconds)
+const STALE_CLAIM_AFTER: Duration = Duration::from_secs(10 * 60);
+
+/// An inbound webhook that arrived while the database was unreachable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpilledWebhook {
+    pub received_at: chrono::DateTime<chrono::Utc>,
+    /// Already in `webhook_payloads` (spilled only to keep message order)
+    pub archived: bool,
+    pub body: String,
+}
+
+enum Entry {
+    File(PathBuf),
+    Memory(SpilledWebhook),
+}
+
+/// Keeps webhooks that could not be processed during a Postgres outage (restart, failover) and
+/// replays them in arrival order once the database answers again. Entries go to
+/// `<DATA_DIR>/webhook_spillover` so a restart doesn't lose them; memory is the fallback when
+/// the disk is not writable.
+pub struct WebhookSpillover {
+    dir: PathBuf,
+    memory: Mutex<VecDeque<SpilledWebhook>>,
+    max_entries: usize,
+    retry_secs: u64,
+    sequence: AtomicU64,
+    /// Files on disk, claimed ones included; kept here so the webhook path never lists the directory
+    buffered_files: AtomicUsize,
+}
+
+impl WebhookSpillover {
+    pub fn new(dir: PathBuf, max_entries: usize, retry_secs: u64) -> Self {
+        let spillover = Self {
+            dir,
+            memory: Mutex::new(VecDeque::new()),
+            max_entries,
+            retry_secs,
+            sequence: AtomicU64::new(0),
+            buffered_files: AtomicUsize::new(0),
+        };
+        spillover.reclaim_stale();
+        spillover.recount();
+        spillover
+    }
+
+    pub fn from_env() -> Self {
+        let env_number = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<u64>().ok()).filter(|n| *n > 0);
+        Self::new(
+            crate::services::image_store::data_dir().join("webhook_spillover"),
+            env_number("WEBHOOK_SPILLOVER_MAX").map(|n| n as usize).unwrap_or(DEFAULT_MAX_ENTRIES),
+            env_number("WEBHOOK_SPILLOVER_RETRY_SECS").unwrap_or(DEFAULT_RETRY_SECS),
+        )
+    }
+
+    fn files_with_extension(&self, extension: &str) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(&self.dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|e| e.path()))
+                    .filter(|path| path.extension().is_some_and(|ext| ext == extension))
+                    .collect()
+            })
+            .unwrap_or_default();
+        // File names start with the arrival time, so this is arrival order
+        files.sort();
+        files
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        self.files_with_extension("json")
+    }
+
+    /// Put back files claimed by an instance that crashed or was killed mid-replay; without
+    /// this they would sit in the directory forever and never be replayed
+    fn reclaim_stale(&self) {
+        let now = SystemTime::now();
+        for claimed in self.files_with_extension(CLAIMED_EXTENSION) {
+            let age = std::fs::metadata(&claimed)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .unwrap_or_default();
+            if age >= STALE_CLAIM_AFTER {
+                log::warn!("♻️ Reclaiming abandoned spillover file {}", claimed.display());
+                let _ = std::fs::rename(&claimed, claimed.with_extension("json"));
+            }
+        }
+    }
+
+    /// Re-read the file count from disk (startup, and after a replay in case another instance
+    /// sharing the directory drained files too)
+    fn recount(&self) {
+        let count = self.files().len() + self.files_with_extension(CLAIMED_EXTENSION).len();
+        self.buffered_files.store(count, Ordering::Relaxed);
+    }
+
+    fn forget_file(&self) {
+        let _ = self.buffered_files.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+    }
+
+    /// Buffered webhooks waiting for the database, including ones being replayed right now
+    pub fn len(&self) -> usize {
+        self.buffered_files.load(Ordering::Relaxed) + self.memory.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn write_file(&self, webhook: &SpilledWebhook) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+        // Only list the directory once the limit is reached
+        if self.buffered_files.load(Ordering::Relaxed) >= self.max_entries {
+            let files = self.files();
+            log::error!("❌ Webhook spillover full ({} entries), dropping the oldest", files.len());
+            if let Some(oldest) = files.first() {
+                if std::fs::remove_file(oldest).is_ok() {
+                    self.forget_file();
+                }
+            }
+        }
+        let name = format!(
+            "{}_{:06}.json",
+            webhook.received_at.timestamp_millis(),
+            self.sequence.fetch_add(1, Ordering::Relaxed) % 1_000_000
+        );
+        write_atomic(&self.dir.join(name), &serde_json::to_vec(webhook)?)?;
+        self.buffered_files.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Keep a webhook body for later; never fails (disk first, memory if the disk refuses)
+    pub fn push(&self, body: &str, archived: bool) {
+        let webhook = SpilledWebhook {
+            received_at: chrono::Utc::now(),
+            archived,
+            body: body.to_string(),
+        };
+        if let Err(e) = self.write_file(&webhook) {
+            log::warn!("⚠️ Could not write webhook spillover file, keeping it in memory: {}", e);
+            let mut memory = self.memory.lock().unwrap_or_else(|e| e.into_inner());
+            if memory.len() >= self.max_entries {
+                log::error!("❌ Webhook spillover full ({} entries), dropping the oldest", memory.len());
+                memory.pop_front();
+            }
+            memory.push_back(webhook);
+        }
+    }
+
+    /// Oldest first: files before memory (memory is only used once the disk failed)
+    fn next(&self) -> Option<Entry> {
+        if let Some(path) = self.files().into_iter().next() {
+            return Some(Entry::File(path));
+        }
+        self.memory.lock().unwrap_or_else(|e| e.into_inner()).front().cloned().map(Entry::Memory)
+    }
+
+    fn remove(&self, entry: &Entry) {
+        match entry {
+            Entry::File(path) => {
+                if std::fs::remove_file(path).is_ok() {
+                    self.forget_file();
+                }
+            }
+            Entry::Memory(_) => {
+                self.memory.lock().unwrap_or_else(|e| e.into_inner()).pop_front();
+            }
+        }
+    }
+
+    /// Take a file so another instance sharing the directory doesn't replay it too
+    fn claim(path: &Path) -> Result<(PathBuf, SpilledWebhook)> {
+        let claimed = path.with_extension(CLAIMED_EXTENSION);
+        std::fs::rename(path, &claimed)?;
+        // The claim's age tells a live replay from an abandoned one (see `reclaim_stale`)
+        std::fs::File::options().write(true).open(&claimed)?.set_modified(SystemTime::now())?;
+        let webhook = serde_json::from_slice(&std::fs::read(&claimed)?)
+            .with_context(|| format!("corrupt spillover file {}", claimed.display()))?;
+        Ok((claimed, webhook))
+    }
+
+    /// Process buffered webhooks in order until the buffer is empty or the database drops again.
+    /// Returns how many were processed.
+    pub async fn replay(&self, state: &AppState) -> usize {
+        let mut replayed = 0;
+        while let Some(entry) = self.next() {
+            let (entry, webhook) = match entry {
+                Entry::File(path) => match Self::claim(&path) {
+                    Ok((claimed, webhook)) => (Entry::File(claimed), webhook),
+                    Err(e) => {
+                        log::warn!("⚠️ Skipping spillover file {}: {}", path.display(), e);
+                        let _ = std::fs::rename(&path, path.with_extension("corrupt"));
+                        let _ = std::fs::rename(path.with_extension(CLAIMED_EXTENSION), path.with_extension("corrupt"));
+                        self.forget_file();
+                        continue;
+                    }
+                },
+                Entry::Memory(webhook) => (Entry::Memory(webhook.clone()), webhook),
+            };
+
+            match process(state, &webhook).await {
+                Err(e) if is_connection_error(&e) => {
+                    log::warn!("⚠️ Database unavailable again, {} webhooks stay buffered", self.len());
+                    // Put the claimed file back for the next attempt
+                    if let Entry::File(claimed) = &entry {
+                        let _ = std::fs::rename(claimed, claimed.with_extension("json"));
+                    }
+                    break;
+                }
+                Err(e) => log::error!("❌ Buffered webhook from {} failed: {}", webhook.received_at, e),
+                Ok(()) => {}
+            }
+            self.remove(&entry);
+            replayed += 1;
+        }
+        self.recount();
+        replayed
+    }
+
+    /// Check every `WEBHOOK_SPILLOVER_RETRY_SECS` whether Postgres is back and drain the buffer
+    pub fn spawn_replayer(self: &Arc<Self>, state: Arc<AppState>) {
+        let spillover = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(spillover.retry_secs));
+            loop {
+                interval.tick().await;
+                if spillover.is_empty() || !state.db.ping().await {
+                    continue;
+                }
+                let buffered = spillover.len();
+                log::info!("🔁 Database is back, replaying {} buffered webhooks", buffered);
+                let replayed = spillover.replay(&state).await;
+                log::info!("✅ Replayed {}/{} buffered webhooks", replayed, buffered);
+            }
+        });
+
+        let pending = self.len();
+        if pending > 0 {
+            log::info!("📥 {} webhooks buffered before the restart will be replayed", pending);
+        }
+    }
+}
+
+/// Same steps as the live handler after signature verification (signatures were checked before spilling)
+async fn process(state: &AppState, webhook: &SpilledWebhook) -> Result<()> {
+    let payload = match parse_bird_webhook(&webhook.body)? {
+        ParsedWebhook::Message(payload) => *payload,
+        ParsedWebhook::Unrecognized { reason } => anyhow::bail!("unrecognized payload: {}", reason),
+    };
+    if !webhook.archived {
+        state
+            .db
+            .store_webhook_payload(Some(&payload.payload.id), &webhook.body, None, state.payload_archive_size)
+            .await?;
+    }
+    log::info!("📨 Replaying buffered webhook {} (received {})", payload.payload.id, webhook.received_at);
+    handle_bird_webhook(state.message_handler.clone(), MediaSource::Bird(state.bird_client.clone()), &state.images, payload).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spillover_keeps_order_and_limit() {
+        let dir = std::env::temp_dir().join(format!("tavari_spillover_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let spillover = WebhookSpillover::new(dir.clone(), 2, 1);
+        assert!(spillover.is_empty());
+
+        spillover.push("first", false);
+        spillover.push("second", true);
+        spillover.push("third", false);
+        // Limit 2: the oldest one is dropped
+        assert_eq!(spillover.len(), 2);
+
+        let Some(Entry::File(path)) = spillover.next() else {
+            panic!("entries go to disk first");
+        };
+        let (claimed, webhook) = WebhookSpillover::claim(&path).unwrap();
+        assert_eq!((webhook.body.as_str(), webhook.archived), ("second", true));
+        // Claimed files are not offered again, but still count as buffered until replayed
+        assert!(matches!(spillover.next(), Some(Entry::File(p)) if p != path));
+        assert_eq!(spillover.len(), 2);
+
+        // A fresh claim survives a restart; one left behind by a dead instance is put back
+        assert_eq!(WebhookSpillover::new(dir.clone(), 2, 1).files().len(), 1);
+        std::fs::File::options()
+            .write(true)
+            .open(&claimed)
+            .unwrap()
+            .set_modified(SystemTime::now() - STALE_CLAIM_AFTER)
+            .unwrap();
+        let restarted = WebhookSpillover::new(dir.clone(), 2, 1);
+        assert_eq!((restarted.files().len(), restarted.len()), (2, 2));
+        let Some(Entry::File(path)) = restarted.next() else {
+            panic!("reclaimed file is replayed first");
+        };
+        let (claimed, _) = WebhookSpillover::claim(&path).unwrap();
+        spillover.remove(&Entry::File(claimed));
+        assert_eq!(spillover.len(), 1);
+
+        // Unwritable directory: memory fallback
+        let blocked = WebhookSpillover::new(dir.join("first_file_not_a_dir.json"), 2, 1);
+        std::fs::write(dir.join("first_file_not_a_dir.json"), b"x").unwrap();
+        blocked.push("in memory", false);
+        assert!(matches!(blocked.next(), Some(Entry::Memory(w)) if w.body == "in memory"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
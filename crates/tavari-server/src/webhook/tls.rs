@@ -0,0 +1,145 @@
+use std::net::SocketAddr;
+
+use axum::{
+    http::{header, HeaderValue},
+    Router,
+};
+use tower_http::set_header::SetResponseHeaderLayer;
+
+/// HTTPS settings for deployments without a reverse proxy in front of the bot.
+///
+/// Enabled when both `TLS_CERT_PATH` and `TLS_KEY_PATH` are set (PEM files).
+#[derive(Debug, Clone)]
+pub struct TlsSettings {
+    pub cert_path: String,
+    pub key_path: String,
+    pub hsts_max_age: u64,
+    pub hsts_include_subdomains: bool,
+}
+
+impl TlsSettings {
+    /// Read TLS settings from the environment. Returns None when HTTPS is not configured.
+    pub fn from_env() -> Option<Self> {
+        let cert_path = std::env::var("TLS_CERT_PATH").ok().filter(|v| !v.is_empty())?;
+        let key_path = std::env::var("TLS_KEY_PATH").ok().filter(|v| !v.is_empty())?;
+
+        // Varsayılan: 1 yıl (HSTS preload listeleri için önerilen süre)
+        let hsts_max_age = std::env::var("HSTS_MAX_AGE")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(31_536_000);
+        let hsts_include_subdomains = std::env::var("HSTS_INCLUDE_SUBDOMAINS")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+
+        Some(Self {
+            cert_path,
+            key_path,
+            hsts_max_age,
+            hsts_include_subdomains,
+        })
+    }
+
+    /// Value for the Strict-Transport-Security header
+    pub fn hsts_header_value(&self) -> String {
+        if self.hsts_include_subdomains {
+            format!("max-age={}; includeSubDomains", self.hsts_max_age)
+        } else {
+            format!("max-age={}", self.hsts_max_age)
+        }
+    }
+}
+
+/// Cookie attributes for admin session cookies.
+///
+/// `Secure` is on by default whenever TLS is terminated by the bot itself, and can be
+/// forced with `ADMIN_COOKIE_SECURE=true` when a TLS proxy sits in front of it.
+#[derive(Debug, Clone)]
+pub struct CookieSettings {
+    pub secure: bool,
+}
+
+impl CookieSettings {
+    pub fn from_env(tls: Option<&TlsSettings>) -> Self {
+        let secure = std::env::var("ADMIN_COOKIE_SECURE")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(tls.is_some());
+
+        Self { secure }
+    }
+
+    /// Build a Set-Cookie header value with hardened attributes
+    pub fn session_cookie(&self, name: &str, value: &str, max_age_secs: i64) -> String {
+        let mut cookie = format!(
+            "{}={}; Path=/admin; Max-Age={}; HttpOnly; SameSite=Strict",
+            name, value, max_age_secs
+        );
+        if self.secure {
+            cookie.push_str("; Secure");
+        }
+        cookie
+    }
+}
+
+/// Serve the router over HTTPS (when configured) or plain HTTP
+pub async fn serve(addr: &str, app: Router, tls: Option<TlsSettings>) -> anyhow::Result<()> {
+    let socket_addr: SocketAddr = addr.parse()?;
+
+    match tls {
+        Some(settings) => {
+            let hsts = HeaderValue::from_str(&settings.hsts_header_value())?;
+            let app = app.layer(SetResponseHeaderLayer::if_not_present(
+                header::STRICT_TRANSPORT_SECURITY,
+                hsts,
+            ));
+
+            let config = axum_server::tls_rustls::RustlsConfig::from_pem_file(
+                &settings.cert_path,
+                &settings.key_path,
+            )
+            .await?;
+
+            log::info!("🔒 HTTPS enabled (cert: {}, HSTS: {})", settings.cert_path, settings.hsts_header_value());
+
+            axum_server::bind_rustls(socket_addr, config)
+                .serve(app.into_make_service())
+                .await?;
+        }
+        None => {
+            let listener = tokio::net::TcpListener::bind(socket_addr).await?;
+            axum::serve(listener, app).await?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hsts_header_value() {
+        let mut settings = TlsSettings {
+            cert_path: "cert.pem".to_string(),
+            key_path: "key.pem".to_string(),
+            hsts_max_age: 3600,
+            hsts_include_subdomains: false,
+        };
+        assert_eq!(settings.hsts_header_value(), "max-age=3600");
+
+        settings.hsts_include_subdomains = true;
+        assert_eq!(settings.hsts_header_value(), "max-age=3600; includeSubDomains");
+    }
+
+    #[test]
+    fn test_session_cookie_secure_flag() {
+        let cookies = CookieSettings { secure: true };
+        let cookie = cookies.session_cookie("admin_session", "abc", 3600);
+        assert!(cookie.contains("HttpOnly"));
+        assert!(cookie.ends_with("; Secure"));
+
+        let cookies = CookieSettings { secure: false };
+        assert!(!cookies.session_cookie("admin_session", "abc", 3600).contains("Secure"));
+    }
+}
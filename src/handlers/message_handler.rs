@@ -1,984 +0,0 @@
-use anyhow::Result;
-use chrono::{Utc, Timelike, Datelike};
-use std::sync::Arc;
-
-use crate::models::{ConversationDirection, Meal, MealType, MessageType, User, WaterLog};
-use crate::services::{Database, OpenRouterService, UserIntent, WhatsAppService};
-use crate::handlers::OnboardingHandler;
-
-pub struct MessageHandler {
-    db: Arc<Database>,
-    openai: Arc<OpenRouterService>,  // OpenRouter kullanıyoruz (OpenAI uyumlu)
-    whatsapp: Arc<dyn WhatsAppService>,
-}
-
-impl MessageHandler {
-    pub fn new(
-        db: Arc<Database>,
-        openai: Arc<OpenRouterService>,
-        whatsapp: Arc<dyn WhatsAppService>,
-    ) -> Self {
-        Self {
-            db,
-            openai,
-            whatsapp,
-        }
-    }
-
-    /// Update user's name from WhatsApp profile
-    pub async fn update_user_name(&self, phone: &str, name: Option<&str>) -> Result<()> {
-        self.db.update_user_name(phone, name).await
-    }
-
-    /// Clear 24h window warning status when user sends a message
-    pub async fn clear_window_warning(&self, phone: &str) -> Result<()> {
-        self.db.clear_warning_status(phone).await
-    }
-
-    /// Send message and log to conversation history
-    async fn send_and_log(&self, phone: &str, message: &str) -> Result<()> {
-        // Send the message
-        self.whatsapp.send_message(phone, message).await?;
-
-        // Log to conversation history
-        let _ = self.db.log_conversation(
-            phone,
-            ConversationDirection::Outgoing,
-            MessageType::Text,
-            message,
-            None,
-        ).await;
-
-        Ok(())
-    }
-
-    pub async fn handle_message(
-        &self,
-        from: &str,
-        message: &str,
-        has_media: bool,
-        media_path: Option<String>,
-    ) -> Result<()> {
-        // LOG: Gelen mesajı kaydet
-        log::info!("📨 INCOMING MESSAGE - From: {} | Content: '{}' | Has Media: {} | Media Path: {:?}",
-                   from, message, has_media, media_path);
-
-        // Kullanıcıyı kontrol et veya oluştur
-        self.ensure_user_exists(from).await?;
-
-        // Log incoming message to database
-        let message_type = if has_media { MessageType::Image } else { MessageType::Text };
-        let metadata = if has_media {
-            Some(serde_json::json!({
-                "has_media": true,
-                "media_path": media_path.clone()
-            }))
-        } else {
-            None
-        };
-        let _ = self.db.log_conversation(
-            from,
-            ConversationDirection::Incoming,
-            message_type,
-            message,
-            metadata,
-        ).await;
-
-        // Kullanıcı bilgilerini al
-        let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
-
-        // Kullanıcı deaktif ise, mesajı işleme ama yanıt verme
-        if !user.is_active {
-            log::warn!("⚠️ User {} is inactive, ignoring message", from);
-            return Ok(());
-        }
-
-        // Onboarding tamamlanmamışsa, onboarding handler'a yönlendir
-        if !user.onboarding_completed {
-            log::info!("👤 User {} in onboarding phase (step: {:?})", from, user.onboarding_step);
-
-            // İlk mesajda otomatik olarak onboarding'i başlat
-            // Kullanıcıdan "tekrar mesaj gönder" dememek için direkt başlatıyoruz
-            let onboarding_handler = OnboardingHandler::new(self.db.clone(), self.whatsapp.clone());
-            onboarding_handler.handle_step(&user, message).await?;
-            return Ok(());
-        }
-
-        let message_lower = message.trim().to_lowercase();
-
-        // Resim varsa öncelik ver (komutlardan önce)
-        if has_media {
-            if let Some(image_path) = media_path {
-                self.handle_food_image(from, &image_path).await?;
-                return Ok(());
-            }
-        }
-
-        // Pending command feature removed in v2.1 - fully natural language now
-
-        // Quick water button responses (1, 2, 3) - sadece sayı ise
-        let trimmed = message.trim();
-        if trimmed == "1" {
-            self.handle_water_log_with_amount(from, 200).await?;
-            return Ok(());
-        } else if trimmed == "2" {
-            self.handle_water_log_with_amount(from, 250).await?;
-            return Ok(());
-        } else if trimmed == "3" {
-            self.handle_water_log_with_amount(from, 500).await?;
-            return Ok(());
-        }
-
-        // Önce bilinen komutları dene
-        if self.try_handle_smart_command(from, &message_lower).await? {
-            return Ok(());
-        }
-
-        // Bilinen komut değilse, AI ile kullanıcının ne yapmak istediğini anla
-        log::info!("🧠 Using AI to detect user intent for: '{}'", message);
-        match self.openai.detect_user_intent(message).await {
-            Ok(UserIntent::LogMeal(meal_description)) => {
-                log::info!("🍽️ User wants to log meal: {}", meal_description);
-                self.handle_text_meal(from, &meal_description).await?;
-            }
-            Ok(UserIntent::LogWater(amount)) => {
-                log::info!("💧 User wants to log water: {} ml", amount);
-                self.handle_water_log_with_amount(from, amount).await?;
-            }
-            Ok(UserIntent::SetCalorieGoal(amount)) => {
-                log::info!("🎯 User wants to set calorie goal: {} kcal", amount);
-                self.db.update_calorie_goal(from, amount).await?;
-                self.send_and_log(from, &format!("✅ Kalori hedefin {} kcal olarak ayarlandı!", amount)).await?;
-            }
-            Ok(UserIntent::SetWaterGoal(amount)) => {
-                log::info!("💧 User wants to set water goal: {} ml", amount);
-                self.db.update_water_goal(from, amount).await?;
-                self.send_and_log(from, &format!("✅ Su hedefin {} ml olarak ayarlandı!", amount)).await?;
-            }
-            Ok(UserIntent::SetMealTime(meal_type, time)) => {
-                log::info!("⏰ User wants to set meal time: {} at {}", meal_type, time);
-                let meal_type_normalized = match meal_type.as_str() {
-                    "kahvalti" | "kahvaltı" => "breakfast",
-                    "ogle" | "öğle" => "lunch",
-                    "aksam" | "akşam" => "dinner",
-                    _ => {
-                        self.send_and_log(from, "❌ Geçersiz öğün tipi. Kullan: kahvaltı, öğle, akşam").await?;
-                        return Ok(());
-                    }
-                };
-                self.db.update_meal_time(from, meal_type_normalized, &time).await?;
-                let meal_name_tr = match meal_type_normalized {
-                    "breakfast" => "Kahvaltı",
-                    "lunch" => "Öğle",
-                    "dinner" => "Akşam",
-                    _ => "Öğün",
-                };
-                self.send_and_log(from, &format!("✅ {} saatin {} olarak ayarlandı!", meal_name_tr, time)).await?;
-            }
-            Ok(UserIntent::SetSilentHours(start, end)) => {
-                log::info!("🌙 User wants to set silent hours: {} - {}", start, end);
-                self.db.update_silent_hours(from, &start, &end).await?;
-                self.send_and_log(from, &format!("✅ Sessiz saatler {} - {} olarak ayarlandı!", start, end)).await?;
-            }
-            Ok(UserIntent::RunCommand(command)) => {
-                log::info!("⚙️ User wants to run command: {}", command);
-                if !self.try_handle_smart_command(from, &command).await? {
-                    self.send_help_message(from).await?;
-                }
-            }
-            Ok(UserIntent::Unknown) => {
-                log::info!("❓ AI couldn't determine intent, showing help");
-                self.send_help_message(from).await?;
-            }
-            Err(e) => {
-                log::warn!("⚠️ AI intent detection failed: {}", e);
-                self.send_help_message(from).await?;
-            }
-        }
-
-        Ok(())
-    }
-
-    async fn ensure_user_exists(&self, phone: &str) -> Result<()> {
-        if self.db.get_user(phone).await?.is_none() {
-            let user = User {
-                phone_number: phone.to_string(),
-                name: None,  // Will be updated from WhatsApp later
-                created_at: Utc::now(),
-                onboarding_completed: false,
-                onboarding_step: None,  // Onboarding handler başlatacak
-                breakfast_reminder: true,
-                lunch_reminder: true,
-                dinner_reminder: true,
-                water_reminder: true,
-                breakfast_time: None,
-                lunch_time: None,
-                dinner_time: None,
-                opted_in: true,
-                timezone: "Europe/Istanbul".to_string(),  // Varsayılan Türkiye
-                daily_water_goal: Some(2000),  // Varsayılan: 2 litre (2000 ml)
-                daily_calorie_goal: Some(2000),  // Varsayılan: 2000 kcal
-                silent_hours_start: Some("23:00".to_string()),  // Varsayılan: 23:00
-                silent_hours_end: Some("07:00".to_string()),    // Varsayılan: 07:00
-                is_active: true,  // Varsayılan: aktif
-                pending_command: None,  // Başlangıçta bekleyen komut yok
-            };
-            self.db.create_user(&user).await?;
-            log::info!("✅ New user created: {}", phone);
-        }
-        Ok(())
-    }
-
-    /// Optimized: Detect meal type without fetching user (user already available)
-    async fn detect_meal_type_with_user(&self, user: &User, current_time: chrono::NaiveTime, today: chrono::NaiveDate) -> Result<MealType> {
-        log::debug!("🕐 Detecting meal type for user {} at {} (timezone: {})", user.phone_number, current_time, user.timezone);
-
-        // Bugün kaydedilmiş öğünleri kontrol et
-        let todays_meals = self.db.get_todays_meal_types(&user.phone_number, today).await?;
-
-        let has_breakfast = todays_meals.iter().any(|m| matches!(m, MealType::Breakfast));
-        let has_lunch = todays_meals.iter().any(|m| matches!(m, MealType::Lunch));
-        let has_dinner = todays_meals.iter().any(|m| matches!(m, MealType::Dinner));
-
-        log::debug!("📊 Today's meals - Breakfast: {}, Lunch: {}, Dinner: {}", has_breakfast, has_lunch, has_dinner);
-
-        // Kullanıcının öğün saatlerini parse et
-        let breakfast_time = user.breakfast_time.as_ref()
-            .and_then(|t| chrono::NaiveTime::parse_from_str(t, "%H:%M").ok());
-        let lunch_time = user.lunch_time.as_ref()
-            .and_then(|t| chrono::NaiveTime::parse_from_str(t, "%H:%M").ok());
-        let dinner_time = user.dinner_time.as_ref()
-            .and_then(|t| chrono::NaiveTime::parse_from_str(t, "%H:%M").ok());
-
-        // Eğer öğün saatleri ayarlanmamışsa varsayılan saatler kullan
-        let breakfast = breakfast_time.unwrap_or_else(|| chrono::NaiveTime::from_hms_opt(9, 0, 0).unwrap());
-        let lunch = lunch_time.unwrap_or_else(|| chrono::NaiveTime::from_hms_opt(13, 0, 0).unwrap());
-        let dinner = dinner_time.unwrap_or_else(|| chrono::NaiveTime::from_hms_opt(19, 0, 0).unwrap());
-
-        // Tolerans: ±2 saat
-        let tolerance = chrono::Duration::hours(2);
-
-        // Sıralı öğün kontrolü: Kahvaltı -> Öğle -> Akşam
-        // Kullanıcı önce kahvaltı yapmalı, sonra öğle, sonra akşam
-
-        // Eğer kahvaltı kayıtlı değilse ve kahvaltı saatindeyse
-        if !has_breakfast && Self::is_within_time_range(current_time, breakfast, tolerance) {
-            log::info!("🍳 Detected meal type: Breakfast (current: {}, target: {})", current_time, breakfast);
-            return Ok(MealType::Breakfast);
-        }
-
-        // Eğer kahvaltı kayıtlı ama öğle kayıtlı değilse ve öğle saatindeyse
-        if has_breakfast && !has_lunch && Self::is_within_time_range(current_time, lunch, tolerance) {
-            log::info!("🍱 Detected meal type: Lunch (current: {}, target: {})", current_time, lunch);
-            return Ok(MealType::Lunch);
-        }
-
-        // Eğer kahvaltı ve öğle kayıtlı ama akşam kayıtlı değilse ve akşam saatindeyse
-        if has_breakfast && has_lunch && !has_dinner && Self::is_within_time_range(current_time, dinner, tolerance) {
-            log::info!("🍽️ Detected meal type: Dinner (current: {}, target: {})", current_time, dinner);
-            return Ok(MealType::Dinner);
-        }
-
-        // Eğer sıralı öğün kuralına uymuyorsa ara öğün olarak kaydet
-        log::info!("🍪 Detected meal type: Snack (sequential rule or time doesn't match main meals) at {}", current_time);
-        Ok(MealType::Snack)
-    }
-
-    /// Bir zamanın hedef zaman ± tolerans aralığında olup olmadığını kontrol et
-    fn is_within_time_range(current: chrono::NaiveTime, target: chrono::NaiveTime, tolerance: chrono::Duration) -> bool {
-        // Zamanları dakika cinsine çevir (gece yarısından bu yana)
-        let current_mins = current.num_seconds_from_midnight() as i64 / 60;
-        let target_mins = target.num_seconds_from_midnight() as i64 / 60;
-        let tolerance_mins = tolerance.num_minutes();
-
-        // Fark hesapla (gün sınırını dikkate alarak)
-        let diff = (current_mins - target_mins).abs();
-
-        // Gün sınırı kontrolü (örn: 23:00 ile 01:00 arası)
-        let diff_wrapped = std::cmp::min(diff, 1440 - diff); // 1440 = 24 * 60
-
-        diff_wrapped <= tolerance_mins
-    }
-
-    async fn handle_text_meal(&self, from: &str, description: &str) -> Result<()> {
-        // AI'dan yemek analizi al
-        match self.openai.analyze_text_meal(description).await {
-            Ok(calorie_info) => {
-                // Kullanıcı bilgilerini tek seferde al (hem timezone hem de meal detection için)
-                let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
-                let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
-                let now = Utc::now().with_timezone(&user_tz);
-                let today = now.date_naive();
-
-                // Akıllı öğün tespiti (user'ı tekrar fetch etmeden)
-                let meal_type = self.detect_meal_type_with_user(&user, now.time(), today).await?;
-
-                let meal = Meal {
-                    id: None,
-                    user_phone: from.to_string(),
-                    meal_type: meal_type.clone(),
-                    calories: calorie_info.calories,
-                    description: calorie_info.description.clone(),
-                    image_path: None, // Text-based meal, no image
-                    created_at: Utc::now(),
-                };
-
-                self.db.add_meal(&meal).await?;
-
-                let today = now.date_naive();
-                let stats = self.db.get_daily_stats(from, today).await?;
-
-                let meal_type_name = match meal_type {
-                    MealType::Breakfast => "Kahvaltı",
-                    MealType::Lunch => "Öğle Yemeği",
-                    MealType::Dinner => "Akşam Yemeği",
-                    MealType::Snack => "Ara Öğün",
-                };
-
-                let summary = format!(
-                    "✅ *{} Kaydedildi!*\n\n\
-                     📝 {}\n\
-                     🔥 {:.0} kcal\n\n\
-                     📊 Bugün: {:.0} kcal ({} öğün)",
-                    meal_type_name,
-                    calorie_info.description,
-                    calorie_info.calories,
-                    stats.total_calories,
-                    stats.meals_count
-                );
-
-                self.send_and_log(from, &summary).await?;
-            }
-            Err(e) => {
-                log::error!("❌ Failed to analyze text meal: {}", e);
-                self.whatsapp
-                    .send_message(
-                        from,
-                        "❌ Analiz yapılamadı.\nLütfen daha detaylı açıkla veya fotoğraf gönder.",
-                    )
-                    .await?;
-            }
-        }
-
-        Ok(())
-    }
-
-    async fn handle_food_image(&self, from: &str, image_path: &str) -> Result<()> {
-        // Kullanıcı bilgilerini tek seferde al (hem timezone hem de meal detection için)
-        let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
-        let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
-        let now = Utc::now().with_timezone(&user_tz);
-        let today = now.date_naive();
-
-        // Günlük resim limiti kontrolü (max 20)
-        let daily_image_count = self.db.get_daily_image_count(from, today).await?;
-
-        if daily_image_count >= 20 {
-            log::warn!("📸 User {} reached daily image limit: {}/20", from, daily_image_count);
-            self.whatsapp
-                .send_message(
-                    from,
-                    "⚠️ *Günlük resim limiti* (20/20)\n\n\
-                     Yarın tekrar fotoğraf gönderebilirsin.\n\
-                     Bugün için: ogun tavuk göğsü ve salata"
-                )
-                .await?;
-            return Ok(());
-        }
-
-        match self.openai.analyze_food_image(image_path).await {
-            Ok(calorie_info) => {
-                // Akıllı öğün tespiti (user'ı tekrar fetch etmeden)
-                let meal_type = self.detect_meal_type_with_user(&user, now.time(), today).await?;
-
-                let meal = Meal {
-                    id: None,
-                    user_phone: from.to_string(),
-                    meal_type: meal_type.clone(),
-                    calories: calorie_info.calories,
-                    description: calorie_info.description.clone(),
-                    image_path: Some(image_path.to_string()),
-                    created_at: Utc::now(),
-                };
-
-                self.db.add_meal(&meal).await?;
-
-                let stats = self.db.get_daily_stats(from, today).await?;
-
-                let meal_type_name = match meal_type {
-                    MealType::Breakfast => "Kahvaltı",
-                    MealType::Lunch => "Öğle Yemeği",
-                    MealType::Dinner => "Akşam Yemeği",
-                    MealType::Snack => "Ara Öğün",
-                };
-
-                // Günlük resim sayısını tekrar al (yeni eklenen dahil)
-                let updated_image_count = self.db.get_daily_image_count(from, today).await?;
-
-                let summary = format!(
-                    "✅ *{} Kaydedildi!*\n\n\
-                     📝 {}\n\
-                     🔥 {:.0} kcal\n\n\
-                     📊 Bugün: {:.0} kcal ({} öğün)\n\
-                     📸 Resim: {}/20",
-                    meal_type_name,
-                    calorie_info.description,
-                    calorie_info.calories,
-                    stats.total_calories,
-                    stats.meals_count,
-                    updated_image_count
-                );
-
-                self.send_and_log(from, &summary).await?;
-            }
-            Err(e) => {
-                log::error!("Image analysis error: {}", e);
-                self.whatsapp
-                    .send_message(from, "❌ Resim analiz edilemedi. Tekrar dene.")
-                    .await?;
-            }
-        }
-
-        Ok(())
-    }
-
-    async fn handle_water_log_with_amount(&self, from: &str, amount: i32) -> Result<()> {
-        let water_log = WaterLog {
-            id: None,
-            user_phone: from.to_string(),
-            amount_ml: amount,
-            created_at: Utc::now(),
-        };
-
-        self.db.add_water_log(&water_log).await?;
-
-        // Kullanıcı bilgilerini tek seferde al (hem timezone hem de water_goal için)
-        let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
-
-        let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
-        let today = Utc::now().with_timezone(&user_tz).date_naive();
-
-        let stats = self.db.get_daily_stats(from, today).await?;
-        let water_goal = user.daily_water_goal.unwrap_or(2000);
-
-        let response = format!(
-            "💧 *{} ml kaydedildi!*\n\n\
-             Bugün: {} ml / {} ml\n\
-             Kalan: {} ml",
-            amount,
-            stats.total_water_ml,
-            water_goal,
-            water_goal - stats.total_water_ml as i32
-        );
-
-        self.send_and_log(from, &response).await?;
-
-        Ok(())
-    }
-
-    /// Akıllı komut tespiti - slash olsun olmasın komutları tanır
-    /// Örnek: "rapor", "/rapor", "yardım", "yardim" hepsi çalışır
-    async fn try_handle_smart_command(&self, from: &str, message: &str) -> Result<bool> {
-        // Slash varsa kaldır
-        let clean_msg = message.trim_start_matches('/').trim_start_matches('!');
-        let parts: Vec<&str> = clean_msg.split_whitespace().collect();
-        let main_word = parts.first().unwrap_or(&"");
-
-        // Komut eşleştirmeleri - Türkçe karakterleri normalize et
-        let matched = match *main_word {
-            // Haftalık özet
-            "haftalik" | "haftalık" | "weekly" | "hafta" | "week" => {
-                let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
-                let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
-                let today = Utc::now().with_timezone(&user_tz).date_naive();
-
-                let mut response = "📅 *Haftalık Özet*\n\n".to_string();
-                let mut total_calories = 0.0;
-                let mut total_water = 0;
-
-                for i in 0..7 {
-                    let date = today - chrono::Duration::days(i);
-                    let stats = self.db.get_daily_stats(from, date).await?;
-
-                    total_calories += stats.total_calories;
-                    total_water += stats.total_water_ml as i32;
-
-                    let day_name = match date.weekday() {
-                        chrono::Weekday::Mon => "Pzt",
-                        chrono::Weekday::Tue => "Sal",
-                        chrono::Weekday::Wed => "Çar",
-                        chrono::Weekday::Thu => "Per",
-                        chrono::Weekday::Fri => "Cum",
-                        chrono::Weekday::Sat => "Cmt",
-                        chrono::Weekday::Sun => "Paz",
-                    };
-
-                    response.push_str(&format!(
-                        "{} {}: {:.0} kcal • {} ml\n",
-                        day_name,
-                        date.format("%d.%m"),
-                        stats.total_calories,
-                        stats.total_water_ml
-                    ));
-                }
-
-                let avg_calories = total_calories / 7.0;
-                let avg_water = total_water / 7;
-
-                response.push_str(&format!("\n📊 *Ortalamalar*\n"));
-                response.push_str(&format!("🍽️ Kalori: {:.0} kcal/gün\n", avg_calories));
-                response.push_str(&format!("💧 Su: {} ml/gün\n\n", avg_water));
-                response.push_str("💡 Detaylı tavsiye için 'tavsiye' yaz");
-
-                self.send_and_log(from, &response).await?;
-                true
-            }
-            // Rapor komutları
-            "rapor" | "report" | "özet" | "ozet" | "summary" => {
-                let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
-                let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
-                let today = Utc::now().with_timezone(&user_tz).date_naive();
-                let stats = self.db.get_daily_stats(from, today).await?;
-                let report = crate::services::whatsapp::format_daily_report(
-                    stats.total_calories,
-                    stats.total_water_ml,
-                    stats.meals_count,
-                    stats.water_logs_count,
-                    user.daily_calorie_goal.unwrap_or(2000),
-                    user.daily_water_goal.unwrap_or(2000),
-                );
-                self.send_and_log(from, &report).await?;
-                true
-            }
-            // Yardım komutları
-            "yardim" | "yardım" | "help" | "?" | "komutlar" | "commands" => {
-                self.send_help_message(from).await?;
-                true
-            }
-            // Geçmiş komutları - enhanced with water logs and summary
-            "gecmis" | "geçmiş" | "history" | "tarihçe" | "tarihce" => {
-                let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
-                let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
-                let today = Utc::now().with_timezone(&user_tz).date_naive();
-
-                let meals = self.db.get_recent_meals(from, 5).await?;
-                let stats = self.db.get_daily_stats(from, today).await?;
-                let water_goal = user.daily_water_goal.unwrap_or(2000);
-
-                if meals.is_empty() {
-                    self.send_and_log(from, "📜 Henüz kayıtlı öğün yok.").await?;
-                } else {
-                    let mut response = "📜 *Son Aktiviteler*\n\n".to_string();
-
-                    // Show today's summary first
-                    response.push_str("📊 *Bugün*\n");
-                    response.push_str(&format!("🍽️ Kalori: {:.0} kcal\n", stats.total_calories));
-                    response.push_str(&format!("💧 Su: {} / {} ml\n\n", stats.total_water_ml, water_goal));
-
-                    response.push_str("🍽️ *Son Öğünler*\n\n");
-                    for (i, meal) in meals.iter().enumerate() {
-                        response.push_str(&format!(
-                            "{}. *{}* • {:.0} kcal\n{}\n📅 {}\n\n",
-                            i + 1,
-                            meal.meal_type,
-                            meal.calories,
-                            meal.description.lines().next().unwrap_or(&meal.description),
-                            meal.created_at.format("%d.%m %H:%M")
-                        ));
-                    }
-
-                    response.push_str("💡 *İpucu:* Detaylı rapor için 'rapor' yaz");
-                    self.send_and_log(from, &response).await?;
-                }
-                true
-            }
-            // Tavsiye komutları
-            "tavsiye" | "öneri" | "oneri" | "advice" | "tip" | "tips" => {
-                // Kullanıcı bilgilerini tek seferde al (hem timezone hem de water_goal için)
-                let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
-                let user_tz: chrono_tz::Tz = user.timezone.parse().unwrap_or(chrono_tz::Europe::Istanbul);
-                let today = Utc::now().with_timezone(&user_tz).date_naive();
-                let stats = self.db.get_daily_stats(from, today).await?;
-                let water_goal = user.daily_water_goal.unwrap_or(2000);
-
-                match self
-                    .openai
-                    .get_nutrition_advice(
-                        stats.total_calories,
-                        stats.total_water_ml,
-                        water_goal,
-                        stats.meals_count
-                    )
-                    .await
-                {
-                    Ok(advice) => {
-                        self.send_and_log(from, &advice).await?;
-                    }
-                    Err(e) => {
-                        log::error!("❌ Failed to get nutrition advice: {:?}", e);
-                        log::error!("❌ Error details: {}", e);
-
-                        // Provide more user-friendly error messages
-                        let error_msg = if e.to_string().contains("moderation") {
-                            "⚠️ AI hizmeti geçici olarak kullanılamıyor (içerik moderasyonu hatası). Lütfen daha sonra tekrar deneyin."
-                        } else if e.to_string().contains("Rate limit") {
-                            "⚠️ Çok fazla istek gönderildi. Lütfen birkaç dakika sonra tekrar deneyin."
-                        } else {
-                            "⚠️ Şu anda tavsiye alınamıyor. Lütfen daha sonra tekrar deneyin."
-                        };
-
-                        self.whatsapp
-                            .send_message(from, error_msg)
-                            .await?;
-                    }
-                }
-                true
-            }
-            // Ayarlar komutları
-            "ayarlar" | "settings" | "ayar" | "setting" => {
-                self.handle_settings_command(from).await?;
-                true
-            }
-            // Buton komutları kaldırıldı - text tabanlı su kaydı çalışıyor
-            // Saat komutları
-            "saat" | "time" => {
-                self.handle_time_command(from, &parts).await?;
-                true
-            }
-            // Timezone komutları
-            "timezone" | "tz" | "zamandilimi" => {
-                self.handle_timezone_command(from, &parts).await?;
-                true
-            }
-            // Su hedefi komutları
-            "suhedefi" | "watergoal" | "suhedfi" => {
-                self.handle_water_goal_command(from, &parts).await?;
-                true
-            }
-            // Kalori hedefi komutları
-            "kalorihedefi" | "caloriegoal" | "kalorihedfi" => {
-                self.handle_calorie_goal_command(from, &parts).await?;
-                true
-            }
-            // Sessiz saatler komutları
-            "sessiz" | "silent" | "silentsaatler" => {
-                self.handle_silent_hours_command(from, &parts).await?;
-                true
-            }
-            _ => false,
-        };
-
-        Ok(matched)
-    }
-
-    async fn handle_settings_command(&self, from: &str) -> Result<()> {
-        let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
-
-        let breakfast_time = user.breakfast_time.unwrap_or_else(|| "Ayarlanmamış".to_string());
-        let lunch_time = user.lunch_time.unwrap_or_else(|| "Ayarlanmamış".to_string());
-        let dinner_time = user.dinner_time.unwrap_or_else(|| "Ayarlanmamış".to_string());
-
-        let breakfast_status = if user.breakfast_reminder { "✅" } else { "❌" };
-        let lunch_status = if user.lunch_reminder { "✅" } else { "❌" };
-        let dinner_status = if user.dinner_reminder { "✅" } else { "❌" };
-        let water_status = if user.water_reminder { "✅" } else { "❌" };
-
-        let water_goal = user.daily_water_goal.unwrap_or(2000);
-        let calorie_goal = user.daily_calorie_goal.unwrap_or(2000);
-        let silent_start = user.silent_hours_start.as_deref().unwrap_or("23:00");
-        let silent_end = user.silent_hours_end.as_deref().unwrap_or("07:00");
-
-        let message = format!(
-            "⚙️ *Ayarlarınız*\n\n\
-             🕐 *Öğün Saatleri*\n\
-             Kahvaltı: {} {}\n\
-             Öğle: {} {}\n\
-             Akşam: {} {}\n\n\
-             🎯 *Günlük Hedefler*\n\
-             {} kcal kalori\n\
-             {} ml su ({:.1}L)\n\n\
-             💧 *Su Hatırlatma*\n\
-             {} 2 saatte bir (08:00-22:00)\n\n\
-             🌙 *Sessiz Saatler*\n\
-             {} - {}\n\n\
-             🌍 *Zaman Dilimi*\n\
-             {}\n\n\
-             *Değiştirmek için:*\n\
-             kalorihedefi 2500\n\
-             suhedefi 3000\n\
-             sessiz 23:00 07:00\n\
-             saat kahvalti 09:00\n\
-             timezone Europe/Istanbul",
-            breakfast_time, breakfast_status,
-            lunch_time, lunch_status,
-            dinner_time, dinner_status,
-            calorie_goal,
-            water_goal,
-            water_goal as f64 / 1000.0,
-            water_status,
-            silent_start,
-            silent_end,
-            user.timezone
-        );
-
-        self.send_and_log(from, &message).await?;
-        Ok(())
-    }
-
-    async fn handle_time_command(&self, from: &str, cmd_parts: &[&str]) -> Result<()> {
-        if cmd_parts.len() < 3 {
-            self.send_and_log(
-                from,
-                "❌ Kullanım: saat [kahvalti|ogle|aksam] HH:MM\nÖrnek: saat kahvalti 09:00"
-            ).await?;
-            return Ok(());
-        }
-
-        let meal_type = cmd_parts[1].to_lowercase();
-        let time = cmd_parts[2];
-
-        // Validate time format (HH:MM) with proper hour/minute range checks
-        if !self.validate_time_format(time) {
-            self.send_and_log(
-                from,
-                "❌ Geçersiz saat formatı\nHH:MM olmalı (örn: 09:00, 13:30)"
-            ).await?;
-            return Ok(());
-        }
-
-        let meal_type_db = match meal_type.as_str() {
-            "kahvalti" | "kahvaltı" | "breakfast" => "breakfast",
-            "ogle" | "öğle" | "lunch" => "lunch",
-            "aksam" | "akşam" | "dinner" => "dinner",
-            _ => {
-                self.send_and_log(
-                    from,
-                    "❌ Geçersiz öğün tipi. Kullan: kahvalti, ogle, aksam"
-                ).await?;
-                return Ok(());
-            }
-        };
-
-        self.db.update_meal_time(from, meal_type_db, time).await?;
-
-        let meal_display = match meal_type_db {
-            "breakfast" => "Kahvaltı",
-            "lunch" => "Öğle yemeği",
-            "dinner" => "Akşam yemeği",
-            _ => "Öğün"
-        };
-
-        self.send_and_log(
-            from,
-            &format!("✅ {} saati {} olarak güncellendi!", meal_display, time)
-        ).await?;
-
-        Ok(())
-    }
-
-    async fn handle_timezone_command(&self, from: &str, cmd_parts: &[&str]) -> Result<()> {
-        if cmd_parts.len() < 2 {
-            self.send_and_log(
-                from,
-                "❌ Kullanım: timezone [zaman dilimi]\n\n\
-                 Örnekler:\n\
-                 timezone Europe/Istanbul\n\
-                 timezone America/New_York\n\
-                 timezone Asia/Tokyo"
-            ).await?;
-            return Ok(());
-        }
-
-        let timezone = cmd_parts[1];
-
-        // Validate timezone by trying to parse it
-        match timezone.parse::<chrono_tz::Tz>() {
-            Ok(_) => {
-                // Valid timezone, update in database
-                self.db.update_timezone(from, timezone).await?;
-
-                self.send_and_log(
-                    from,
-                    &format!("✅ Zaman diliminiz {} olarak güncellendi!", timezone)
-                ).await?;
-            }
-            Err(_) => {
-                self.send_and_log(
-                    from,
-                    &format!("❌ Geçersiz zaman dilimi: {}\n\nÖrnek: Europe/Istanbul", timezone)
-                ).await?;
-            }
-        }
-
-        Ok(())
-    }
-
-    async fn handle_water_goal_command(&self, from: &str, cmd_parts: &[&str]) -> Result<()> {
-        if cmd_parts.len() < 2 {
-            self.send_and_log(
-                from,
-                "❌ Kullanım: suhedefi [ml]\nÖrnek: suhedefi 2500"
-            ).await?;
-            return Ok(());
-        }
-
-        let goal_str = cmd_parts[1];
-        match goal_str.parse::<i32>() {
-            Ok(goal) if (500..=10000).contains(&goal) => {
-                self.db.update_water_goal(from, goal).await?;
-
-                self.send_and_log(
-                    from,
-                    &format!("✅ Günlük su hedefiniz {} ml ({} litre) olarak güncellendi!",
-                        goal,
-                        goal as f64 / 1000.0)
-                ).await?;
-            }
-            Ok(goal) => {
-                self.send_and_log(
-                    from,
-                    &format!("❌ Geçersiz hedef: {} ml\nLütfen 500-10000 ml arası bir değer girin.", goal)
-                ).await?;
-            }
-            Err(_) => {
-                self.send_and_log(
-                    from,
-                    &format!("❌ Geçersiz sayı: {}\nLütfen sayı girin (örn: 2000)", goal_str)
-                ).await?;
-            }
-        }
-
-        Ok(())
-    }
-
-    async fn send_help_message(&self, to: &str) -> Result<()> {
-        let help = "📱 *Beslenme Takip Botu*\n\n\
-                   *🍽️ Yemek Kaydet*\n\
-                   Sadece yaz:\n\
-                   • \"kahvaltı yaptım\"\n\
-                   • \"pizza yedim\"\n\
-                   • \"tavuk göğsü ve salata\"\n\
-                   • Fotoğraf gönder\n\n\
-                   *💧 Su Kaydet*\n\
-                   Sadece yaz:\n\
-                   • \"su içtim\"\n\
-                   • \"250 ml içtim\"\n\
-                   • \"1 bardak su\"\n\
-                   • 1, 2, 3 (200/250/500ml)\n\n\
-                   *📊 Raporlar*\n\
-                   rapor - Bugünün özeti\n\
-                   geçmiş - Son aktiviteler\n\
-                   haftalık - 7 günlük trend\n\
-                   tavsiye - AI önerisi\n\n\
-                   *🎯 Hedefler & Ayarlar*\n\
-                   ayarlar - Tüm ayarları gör\n\n\
-                   Doğal dil ile değiştir:\n\
-                   • \"kalori hedefim 2500\"\n\
-                   • \"su hedefim 3 litre\"\n\
-                   • \"kahvaltı saatim 9\"\n\
-                   • \"sessiz saat 23-7\"\n\n\
-                   *💡 İpucu:* Normal konuşarak mesaj at!";
-
-        self.send_and_log(to, help).await?;
-        Ok(())
-    }
-
-    fn validate_time_format(&self, time: &str) -> bool {
-        // HH:MM formatını kontrol et
-        let parts: Vec<&str> = time.split(':').collect();
-        if parts.len() != 2 {
-            return false;
-        }
-
-        let hour = parts[0].parse::<u32>();
-        let minute = parts[1].parse::<u32>();
-
-        match (hour, minute) {
-            (Ok(h), Ok(m)) => h < 24 && m < 60,
-            _ => false,
-        }
-    }
-
-    // ============================================================
-    // New Command Handlers
-    // ============================================================
-
-    async fn handle_calorie_goal_command(&self, from: &str, parts: &[&str]) -> Result<()> {
-        if parts.len() < 2 {
-            let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
-            let current_goal = user.daily_calorie_goal.unwrap_or(2000);
-            self.send_and_log(
-                from,
-                &format!(
-                    "🎯 *Günlük Kalori Hedefi*\n\n\
-                     Mevcut hedefiniz: {} kcal\n\n\
-                     Değiştirmek için:\n\
-                     `kalorihedefi [miktar]`\n\n\
-                     Örnek: kalorihedefi 2500",
-                    current_goal
-                )
-            ).await?;
-            return Ok(());
-        }
-
-        let goal: i32 = parts[1].parse().map_err(|_| anyhow::anyhow!("Geçersiz sayı"))?;
-
-        if !(500..=5000).contains(&goal) {
-            self.send_and_log(
-                from,
-                "❌ Kalori hedefi 500-5000 kcal arasında olmalıdır."
-            ).await?;
-            return Ok(());
-        }
-
-        self.db.update_calorie_goal(from, goal).await?;
-        self.send_and_log(
-            from,
-            &format!("✅ Günlük kalori hedefiniz {} kcal olarak güncellendi!", goal)
-        ).await?;
-
-        Ok(())
-    }
-
-    async fn handle_silent_hours_command(&self, from: &str, parts: &[&str]) -> Result<()> {
-        if parts.len() < 3 {
-            let user = self.db.get_user(from).await?.ok_or_else(|| anyhow::anyhow!("User not found"))?;
-            let start = user.silent_hours_start.as_deref().unwrap_or("23:00");
-            let end = user.silent_hours_end.as_deref().unwrap_or("07:00");
-
-            self.send_and_log(
-                from,
-                &format!(
-                    "🌙 *Sessiz Saatler*\n\n\
-                     Mevcut ayarınız: {} - {}\n\n\
-                     Bu saatler arasında hatırlatma gönderilmez.\n\n\
-                     Değiştirmek için:\n\
-                     `sessiz [başlangıç] [bitiş]`\n\n\
-                     Örnek: sessiz 23:00 07:00",
-                    start, end
-                )
-            ).await?;
-            return Ok(());
-        }
-
-        let start = parts[1];
-        let end = parts[2];
-
-        if !self.validate_time_format(start) || !self.validate_time_format(end) {
-            self.send_and_log(
-                from,
-                "❌ Geçersiz saat formatı. HH:MM formatında girin.\nÖrnek: sessiz 23:00 07:00"
-            ).await?;
-            return Ok(());
-        }
-
-        self.db.update_silent_hours(from, start, end).await?;
-        self.send_and_log(
-            from,
-            &format!("✅ Sessiz saatleriniz {} - {} olarak güncellendi!", start, end)
-        ).await?;
-
-        Ok(())
-    }
-
-}
@@ -1,11 +0,0 @@
-pub mod database;
-pub mod openrouter; // OpenRouter AI service
-pub mod whatsapp;
-pub mod bird; // Bird.com WhatsApp Business API
-pub mod admin; // Admin dashboard service
-
-pub use database::Database;
-pub use openrouter::{OpenRouterService, UserIntent};
-pub use whatsapp::WhatsAppService;
-pub use bird::BirdComClient;
-pub use admin::AdminService;